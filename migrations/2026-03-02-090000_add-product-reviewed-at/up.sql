@@ -0,0 +1,2 @@
+-- Track when a reviewer marked a product as examined.
+ALTER TABLE products ADD COLUMN reviewed_at TIMESTAMP;
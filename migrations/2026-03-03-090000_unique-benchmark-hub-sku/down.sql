@@ -0,0 +1,3 @@
+-- Restore the non-unique lookup index.
+DROP INDEX IF EXISTS idx_benchmarks_hub_sku;
+CREATE INDEX idx_benchmarks_hub_sku ON benchmarks(hub_id, sku);
@@ -0,0 +1,35 @@
+-- Collapse duplicate benchmark SKUs within a hub into the oldest row.
+-- Associations of the duplicates are moved to the surviving benchmark first,
+-- so no product match is lost; the closer distance wins on overlap.
+CREATE TEMPORARY TABLE benchmark_sku_survivor AS
+SELECT b.id AS duplicate_id, keep.id AS survivor_id
+FROM benchmarks b
+JOIN (SELECT hub_id, sku, MIN(id) AS id FROM benchmarks GROUP BY hub_id, sku) keep
+    ON keep.hub_id = b.hub_id AND keep.sku = b.sku
+WHERE b.id <> keep.id;
+
+INSERT INTO product_benchmark (product_id, benchmark_id, distance)
+SELECT pb.product_id, s.survivor_id, MIN(pb.distance)
+FROM product_benchmark pb
+JOIN benchmark_sku_survivor s ON s.duplicate_id = pb.benchmark_id
+WHERE true
+GROUP BY pb.product_id, s.survivor_id
+ON CONFLICT (product_id, benchmark_id)
+    DO UPDATE SET distance = MIN(product_benchmark.distance, excluded.distance);
+
+DELETE FROM product_benchmark
+WHERE benchmark_id IN (SELECT duplicate_id FROM benchmark_sku_survivor);
+
+DELETE FROM benchmarks
+WHERE id IN (SELECT duplicate_id FROM benchmark_sku_survivor);
+
+UPDATE benchmarks
+SET num_products = (
+    SELECT COUNT(*) FROM product_benchmark WHERE benchmark_id = benchmarks.id
+)
+WHERE id IN (SELECT DISTINCT survivor_id FROM benchmark_sku_survivor);
+
+DROP TABLE benchmark_sku_survivor;
+
+DROP INDEX IF EXISTS idx_benchmarks_hub_sku;
+CREATE UNIQUE INDEX idx_benchmarks_hub_sku ON benchmarks(hub_id, sku);
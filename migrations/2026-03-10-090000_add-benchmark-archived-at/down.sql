@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE benchmarks DROP COLUMN archived_at;
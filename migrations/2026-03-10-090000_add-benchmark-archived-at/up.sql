@@ -0,0 +1,2 @@
+-- Mark benchmarks as archived without deleting them or their associations.
+ALTER TABLE benchmarks ADD COLUMN archived_at TIMESTAMP;
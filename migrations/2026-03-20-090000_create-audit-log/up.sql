@@ -0,0 +1,12 @@
+-- Record who created or deleted which resource in a hub.
+CREATE TABLE audit_log (
+    id INTEGER NOT NULL PRIMARY KEY,
+    hub_id INTEGER NOT NULL,
+    user_sub TEXT NOT NULL,
+    action TEXT NOT NULL,
+    resource_type TEXT NOT NULL,
+    resource_id TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_audit_log_hub_id_created_at ON audit_log(hub_id, created_at);
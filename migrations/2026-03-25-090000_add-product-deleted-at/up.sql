@@ -0,0 +1,2 @@
+-- Hide products from listings without dropping their rows.
+ALTER TABLE products ADD COLUMN deleted_at TIMESTAMP;
@@ -0,0 +1,70 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::types::{BenchmarkSku, CategoryId, CrawlerId, HubId};
+
+/// User action recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    Create,
+    Delete,
+}
+
+impl AuditAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Resource an audited action was applied to.
+///
+/// Benchmarks are identified by SKU because creation does not return the new id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditResource {
+    Benchmark(BenchmarkSku),
+    Category(CategoryId),
+    Crawler(CrawlerId),
+}
+
+impl AuditResource {
+    pub const fn resource_type(&self) -> &'static str {
+        match self {
+            Self::Benchmark(_) => "benchmark",
+            Self::Category(_) => "category",
+            Self::Crawler(_) => "crawler",
+        }
+    }
+
+    pub fn resource_id(&self) -> String {
+        match self {
+            Self::Benchmark(sku) => sku.as_str().to_string(),
+            Self::Category(id) => id.get().to_string(),
+            Self::Crawler(id) => id.get().to_string(),
+        }
+    }
+}
+
+/// Stored audit log entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i32,
+    pub hub_id: HubId,
+    pub user_sub: String,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Data required to insert a new [`AuditEntry`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewAuditEntry {
+    pub hub_id: HubId,
+    pub user_sub: String,
+    pub action: AuditAction,
+    pub resource: AuditResource,
+    pub created_at: NaiveDateTime,
+}
@@ -26,6 +26,8 @@ pub struct Benchmark {
     pub embedding: Option<Vec<u8>>,
     pub processing: bool,
     pub num_products: ProductCount,
+    /// When the benchmark was archived; `None` for active benchmarks.
+    pub archived_at: Option<NaiveDateTime>,
 }
 
 /// Data required to insert a new [`Benchmark`].
@@ -17,3 +17,21 @@ pub struct Crawler {
     pub updated_at: NaiveDateTime,
     pub num_products: ProductCount,
 }
+
+/// Data required to insert a new [`Crawler`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NewCrawler {
+    pub hub_id: HubId,
+    pub name: CrawlerName,
+    pub url: CrawlerUrl,
+    pub selector: CrawlerSelectorValue,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Editable fields of an existing [`Crawler`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UpdateCrawlerFields {
+    pub name: CrawlerName,
+    pub url: CrawlerUrl,
+    pub selector: CrawlerSelectorValue,
+}
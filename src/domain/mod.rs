@@ -1,6 +1,8 @@
+pub mod audit;
 pub mod benchmark;
 pub mod category;
 pub mod crawler;
 pub mod product;
+pub mod statistics;
 pub mod types;
 pub mod zmq;
@@ -27,6 +27,8 @@ pub struct Product {
     pub embedding: Option<Vec<u8>>,
     pub category_id: Option<CategoryId>,
     pub category_assignment_source: CategoryAssignmentSource,
+    /// Timestamp when a reviewer marked the product as examined.
+    pub reviewed_at: Option<NaiveDateTime>,
     pub images: Vec<ImageUrl>,
 }
 
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Data-quality and entity counts for a single hub.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HubStatistics {
+    pub crawler_count: usize,
+    pub product_count: usize,
+    /// Benchmarks that are not archived.
+    pub benchmark_count: usize,
+    pub category_count: usize,
+    /// Products without an embedding yet.
+    pub unembedded_product_count: usize,
+    /// Benchmarks that are not archived and have no associated product.
+    pub unmatched_benchmark_count: usize,
+    /// Products without a canonical category.
+    pub uncategorized_product_count: usize,
+}
+
+/// Headline entity totals for a single hub.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HubSummary {
+    pub crawlers: usize,
+    pub products: usize,
+    /// Benchmarks that are not archived.
+    pub benchmarks: usize,
+    pub categories: usize,
+    /// Products without a canonical category.
+    pub uncategorized_products: usize,
+}
+
+impl From<HubStatistics> for HubSummary {
+    fn from(statistics: HubStatistics) -> Self {
+        Self {
+            crawlers: statistics.crawler_count,
+            products: statistics.product_count,
+            benchmarks: statistics.benchmark_count,
+            categories: statistics.category_count,
+            uncategorized_products: statistics.uncategorized_product_count,
+        }
+    }
+}
@@ -661,6 +661,20 @@ impl SimilarityDistance {
         }
     }
 
+    /// Constructs a similarity distance in a `const` context.
+    ///
+    /// Out-of-range values fail const evaluation, so constants are checked at
+    /// compile time.
+    // `RangeInclusive::contains` is not `const`.
+    #[allow(clippy::manual_range_contains)]
+    pub const fn new_const(value: f32) -> Self {
+        assert!(
+            value >= 0.0 && value <= 1.0,
+            "similarity distance must be within [0.0, 1.0]"
+        );
+        Self(value)
+    }
+
     /// Returns the raw `f32` value.
     pub const fn get(self) -> f32 {
         self.0
@@ -732,6 +746,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn const_similarity_distance_matches_validated_one() {
+        const HALF: SimilarityDistance = SimilarityDistance::new_const(0.5);
+
+        assert_eq!(HALF, SimilarityDistance::new(0.5).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "similarity distance must be within [0.0, 1.0]")]
+    fn const_similarity_distance_rejects_out_of_range_values() {
+        SimilarityDistance::new_const(1.5);
+    }
+
     #[test]
     fn product_price_allows_zero() {
         assert_eq!(ProductPrice::new(0.0).unwrap().get(), 0.0);
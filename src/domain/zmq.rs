@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::domain::types::{BenchmarkId, CrawlerSelectorValue, HubId, ProductUrl};
+use crate::domain::types::{BenchmarkId, CrawlerId, CrawlerSelectorValue, HubId, ProductUrl};
 
 /// Messages received over ZMQ to control crawlers or run benchmarks.
 ///
@@ -17,6 +17,13 @@ pub enum ZMQCrawlerMessage {
     /// Worker contract: automatic matching must not overwrite products with
     /// `category_assignment_source = manual`.
     ProductCategoryMatch(HubId),
+    /// Run product-to-category matching for one crawler's products in a hub.
+    ///
+    /// Same worker contract as [`Self::ProductCategoryMatch`]: manual
+    /// assignments are left untouched.
+    ProductCategoryMatchForCrawler(HubId, CrawlerId),
+    /// Generate missing category embeddings for a hub.
+    EmbedCategories(HubId),
 }
 
 /// Selects a crawler and optionally a list of product URLs to crawl.
@@ -31,6 +38,53 @@ pub enum CrawlerSelector {
     SelectorProducts((CrawlerSelectorValue, Vec<ProductUrl>)),
 }
 
+/// Product URLs grouped by crawler selector, each selector listed once.
+///
+/// Crawlers may share a selector; their URLs are merged so the worker gets a
+/// single [`CrawlerSelector::SelectorProducts`] message per selector.
+/// Selectors keep the order they were first added in and duplicate URLs are
+/// dropped.
+#[derive(Debug, Default, PartialEq)]
+pub struct SelectorProductUrls {
+    entries: Vec<(CrawlerSelectorValue, Vec<ProductUrl>)>,
+}
+
+impl SelectorProductUrls {
+    /// Add `urls` under `selector`, merging with an earlier entry for it.
+    pub fn push(&mut self, selector: CrawlerSelectorValue, urls: Vec<ProductUrl>) {
+        let index = match self.entries.iter().position(|(s, _)| *s == selector) {
+            Some(index) => index,
+            None => {
+                self.entries.push((selector, Vec::new()));
+                self.entries.len() - 1
+            }
+        };
+        let merged = &mut self.entries[index].1;
+        for url in urls {
+            if !merged.contains(&url) {
+                merged.push(url);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl IntoIterator for SelectorProductUrls {
+    type Item = (CrawlerSelectorValue, Vec<ProductUrl>);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,4 +107,56 @@ mod tests {
             ZMQCrawlerMessage::ProductCategoryMatch(HubId::new(42).unwrap())
         );
     }
+
+    #[test]
+    fn crawler_category_match_message_round_trips_through_json() {
+        let message = ZMQCrawlerMessage::ProductCategoryMatchForCrawler(
+            HubId::new(42).unwrap(),
+            CrawlerId::new(7).unwrap(),
+        );
+        let value = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({ "ProductCategoryMatchForCrawler": [42, 7] })
+        );
+        let parsed: ZMQCrawlerMessage = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, message);
+    }
+
+    #[test]
+    fn embed_categories_message_round_trips_through_json() {
+        let message = ZMQCrawlerMessage::EmbedCategories(HubId::new(42).unwrap());
+        let value = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(value, serde_json::json!({ "EmbedCategories": 42 }));
+        let parsed: ZMQCrawlerMessage = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, message);
+    }
+
+    #[test]
+    fn selector_product_urls_merges_shared_selectors() {
+        let selector = |value: &str| CrawlerSelectorValue::new(value).unwrap();
+        let url = |value: &str| ProductUrl::new(value).unwrap();
+        let mut batch = SelectorProductUrls::default();
+
+        batch.push(selector("shop"), vec![url("http://a.example")]);
+        batch.push(selector("other"), vec![url("http://c.example")]);
+        batch.push(
+            selector("shop"),
+            vec![url("http://a.example"), url("http://b.example")],
+        );
+
+        assert_eq!(batch.len(), 2);
+        assert_eq!(
+            batch.into_iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    selector("shop"),
+                    vec![url("http://a.example"), url("http://b.example")]
+                ),
+                (selector("other"), vec![url("http://c.example")]),
+            ]
+        );
+    }
 }
@@ -0,0 +1,111 @@
+use serde::Serialize;
+
+use crate::domain::benchmark::Benchmark;
+use crate::domain::product::Product;
+use crate::pagination::Paginated;
+
+/// Response body returned when an API call creates a resource.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CreatedDto {
+    pub id: i32,
+}
+
+/// Response body returned when an API call is rejected.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiErrorDto {
+    pub error: String,
+}
+
+/// One page of a collection returned by a `GET /api/v1/*` endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PaginatedDto<T: Serialize> {
+    pub items: Vec<T>,
+    pub page: usize,
+    pub total_pages: usize,
+    pub total_items: usize,
+}
+
+impl<T: Serialize> From<Paginated<T>> for PaginatedDto<T> {
+    fn from(value: Paginated<T>) -> Self {
+        Self {
+            items: value.items,
+            page: value.page,
+            total_pages: value.total_pages,
+            total_items: value.total_items,
+        }
+    }
+}
+
+/// One page of the product change feed with the cursor for the next request.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProductChangesDto<T: Serialize> {
+    #[serde(flatten)]
+    pub page: PaginatedDto<T>,
+    /// RFC 3339 timestamp to pass as `since` to continue the feed.
+    pub next_since: String,
+}
+
+/// Suggested auto-association distance cutoff for a benchmark.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuggestedThresholdDto {
+    pub threshold: f32,
+}
+
+/// Stored distance of a single benchmark-product association.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssociationDistanceDto {
+    pub distance: f32,
+}
+
+/// Product matched to a benchmark with its similarity distance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkMatchDto {
+    pub product_id: i32,
+    pub name: String,
+    pub sku: String,
+    pub distance: f32,
+}
+
+/// Benchmark together with its matched products, closest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkMatchesDto {
+    pub benchmark: Benchmark,
+    pub matches: Vec<BenchmarkMatchDto>,
+}
+
+/// Product together with the hub benchmarks it is associated with.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductBenchmarksDto {
+    pub product: Product,
+    pub benchmarks: Vec<Benchmark>,
+}
+
+/// Benchmark matched to a product with its embedding distance.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductTopMatchDto {
+    pub benchmark: Benchmark,
+    pub distance: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paginated_dto_serializes_snake_case_keys() {
+        let dto = PaginatedDto::from(Paginated::new(vec!["first"], 1, 1, 20));
+
+        let json = serde_json::to_value(&dto).unwrap();
+
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["items", "page", "total_items", "total_pages"]);
+        assert_eq!(json["total_pages"], 1);
+        assert_eq!(json["total_items"], 1);
+    }
+}
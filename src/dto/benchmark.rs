@@ -0,0 +1,72 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::domain::benchmark::Benchmark;
+
+/// Benchmark row rendered in the benchmark list, without the embedding.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkSummaryDto {
+    pub id: i32,
+    pub name: String,
+    pub sku: String,
+    pub category: String,
+    pub price: f64,
+    pub num_products: i32,
+    pub updated_at: NaiveDateTime,
+    pub processing: bool,
+}
+
+impl From<Benchmark> for BenchmarkSummaryDto {
+    fn from(value: Benchmark) -> Self {
+        Self {
+            id: value.id.get(),
+            name: value.name.into_inner(),
+            sku: value.sku.into_inner(),
+            category: value.category.into_inner(),
+            price: value.price.get(),
+            num_products: value.num_products.get(),
+            updated_at: value.updated_at,
+            processing: value.processing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::types::{
+        BenchmarkId, BenchmarkName, BenchmarkSku, CategoryName, HubId, ProductAmount, ProductCount,
+        ProductDescription, ProductPrice, ProductUnits,
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn summary_omits_the_embedding() {
+        let benchmark = Benchmark {
+            id: BenchmarkId::new(1).unwrap(),
+            hub_id: HubId::new(1).unwrap(),
+            name: BenchmarkName::new("benchmark").unwrap(),
+            sku: BenchmarkSku::new("SKU1").unwrap(),
+            category: CategoryName::new("cat").unwrap(),
+            units: ProductUnits::new("pcs").unwrap(),
+            price: ProductPrice::new(1.0).unwrap(),
+            amount: ProductAmount::new(1.0).unwrap(),
+            description: ProductDescription::new("desc").unwrap(),
+            created_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            embedding: Some(vec![1, 2, 3]),
+            processing: false,
+            num_products: ProductCount::new(2).unwrap(),
+            archived_at: None,
+        };
+
+        let mut context = tera::Context::new();
+        context.insert("benchmarks", &vec![BenchmarkSummaryDto::from(benchmark)]);
+        let json = context.into_json();
+        let row = &json["benchmarks"][0];
+
+        assert!(row.get("embedding").is_none());
+        assert_eq!(row["name"], "benchmark");
+        assert_eq!(row["num_products"], 2);
+    }
+}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+
+use crate::services::import_export::{UploadReport, UploadRowError};
+
+/// Number of row errors included in an API upload report.
+pub const UPLOAD_REPORT_SAMPLE_ERRORS: usize = 10;
+
+/// Row-level upload error returned to API clients.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UploadRowErrorDto {
+    pub row_number: usize,
+    pub sku: Option<String>,
+    pub message: String,
+}
+
+impl From<UploadRowError> for UploadRowErrorDto {
+    fn from(value: UploadRowError) -> Self {
+        Self {
+            row_number: value.row_number,
+            sku: value.sku,
+            message: value.message,
+        }
+    }
+}
+
+/// Upload outcome returned by the API import endpoints.
+///
+/// Carries the full error count but only the first
+/// [`UPLOAD_REPORT_SAMPLE_ERRORS`] row errors.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UploadReportDto {
+    pub total_rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub error_count: usize,
+    pub sample_errors: Vec<UploadRowErrorDto>,
+}
+
+impl From<UploadReport> for UploadReportDto {
+    fn from(value: UploadReport) -> Self {
+        Self {
+            total_rows: value.total_rows,
+            created: value.created,
+            updated: value.updated,
+            skipped: value.skipped,
+            error_count: value.errors.len(),
+            sample_errors: value
+                .errors
+                .into_iter()
+                .take(UPLOAD_REPORT_SAMPLE_ERRORS)
+                .map(UploadRowErrorDto::from)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_all_errors_but_samples_the_first_ten() {
+        let mut report = UploadReport::with_total(15);
+        report.created = 2;
+        for row_number in 2..15 {
+            report.push_error(
+                row_number,
+                Some(format!("SKU{row_number}")),
+                "Invalid price",
+            );
+        }
+        let error_total = report.errors.len();
+
+        let dto = UploadReportDto::from(report);
+
+        assert_eq!(dto.error_count, error_total);
+        assert_eq!(dto.sample_errors.len(), UPLOAD_REPORT_SAMPLE_ERRORS);
+        assert_eq!(dto.sample_errors[0].row_number, 2);
+        assert_eq!(dto.created, 2);
+        assert_eq!(dto.total_rows, 15);
+    }
+}
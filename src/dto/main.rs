@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+use crate::domain::crawler::Crawler;
+
+/// Crawler row rendered on the dashboard.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawlerDto {
+    #[serde(flatten)]
+    pub crawler: Crawler,
+    /// `updated_at` is older than the configured staleness threshold.
+    pub is_stale: bool,
+}
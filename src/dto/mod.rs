@@ -1 +1,6 @@
+pub mod api;
+pub mod benchmark;
 pub mod categories;
+pub mod import_export;
+pub mod main;
+pub mod products;
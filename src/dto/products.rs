@@ -0,0 +1,127 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::domain::product::Product;
+use crate::domain::types::{CategoryAssignmentSource, CategoryName};
+
+/// Product row rendered in product lists.
+///
+/// Leaves out the embedding and images; keeps the fields the list shows and
+/// its review and category controls need.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProductSummaryDto {
+    pub id: i32,
+    pub name: String,
+    pub sku: String,
+    pub price: f64,
+    pub url: Option<String>,
+    pub crawler_id: i32,
+    pub category: Option<String>,
+    pub associated_category: Option<String>,
+    pub units: Option<String>,
+    pub amount: Option<f64>,
+    pub description: Option<String>,
+    pub updated_at: NaiveDateTime,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub category_id: Option<i32>,
+    pub category_assignment_source: CategoryAssignmentSource,
+}
+
+impl From<Product> for ProductSummaryDto {
+    fn from(value: Product) -> Self {
+        Self {
+            id: value.id.get(),
+            name: value.name.into_inner(),
+            sku: value.sku.into_inner(),
+            price: value.price.get(),
+            url: value.url.map(|url| url.into_inner()),
+            crawler_id: value.crawler_id.get(),
+            category: value.category.map(|category| category.into_inner()),
+            associated_category: value
+                .associated_category
+                .map(|category| category.into_inner()),
+            units: value.units.map(|units| units.into_inner()),
+            amount: value.amount.map(|amount| amount.get()),
+            description: value
+                .description
+                .map(|description| description.into_inner()),
+            updated_at: value.updated_at,
+            reviewed_at: value.reviewed_at,
+            category_id: value.category_id.map(|id| id.get()),
+            category_assignment_source: value.category_assignment_source,
+        }
+    }
+}
+
+/// Label of the bucket holding products without a canonical category.
+pub const UNCATEGORIZED_LABEL: &str = "uncategorized";
+
+/// Number of a crawler's products in one canonical category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CrawlerCategoryCountDto {
+    /// Category path, or [`UNCATEGORIZED_LABEL`] for products without one.
+    pub category: String,
+    pub count: usize,
+    /// True for the bucket of products without a canonical category.
+    pub uncategorized: bool,
+}
+
+impl From<(Option<CategoryName>, usize)> for CrawlerCategoryCountDto {
+    fn from((category, count): (Option<CategoryName>, usize)) -> Self {
+        match category {
+            Some(category) => Self {
+                category: category.into_inner(),
+                count,
+                uncategorized: false,
+            },
+            None => Self {
+                category: UNCATEGORIZED_LABEL.to_string(),
+                count,
+                uncategorized: true,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::types::{
+        CrawlerId, ImageUrl, ProductId, ProductName, ProductPrice, ProductSku, ProductUnits,
+        ProductUrl,
+    };
+    use chrono::DateTime;
+
+    #[test]
+    fn summary_omits_the_embedding_and_images() {
+        let product = Product {
+            id: ProductId::new(1).unwrap(),
+            crawler_id: CrawlerId::new(1).unwrap(),
+            name: ProductName::new("product").unwrap(),
+            sku: ProductSku::new("SKU1").unwrap(),
+            category: Some(CategoryName::new("category").unwrap()),
+            associated_category: None,
+            units: Some(ProductUnits::new("pcs").unwrap()),
+            price: ProductPrice::new(1.0).unwrap(),
+            amount: None,
+            description: None,
+            url: Some(ProductUrl::new("http://example.com").unwrap()),
+            created_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            embedding: Some(vec![1, 2, 3]),
+            category_id: None,
+            category_assignment_source: CategoryAssignmentSource::Automatic,
+            reviewed_at: None,
+            images: vec![ImageUrl::new("http://example.com/image.png").unwrap()],
+        };
+
+        let mut context = tera::Context::new();
+        context.insert("products", &vec![ProductSummaryDto::from(product)]);
+        let json = context.into_json();
+        let row = &json["products"][0];
+
+        assert!(row.get("embedding").is_none());
+        assert!(row.get("images").is_none());
+        assert_eq!(row["sku"], "SKU1");
+    }
+}
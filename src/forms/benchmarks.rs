@@ -9,7 +9,8 @@ use validator::{Validate, ValidationErrors};
 use crate::domain::benchmark::NewBenchmark;
 use crate::domain::types::{
     BenchmarkId, BenchmarkName, BenchmarkSku, CategoryName, HubId, ProductAmount,
-    ProductDescription, ProductId, ProductPrice, ProductUnits, TypeConstraintError,
+    ProductDescription, ProductId, ProductPrice, ProductUnits, SimilarityDistance,
+    TypeConstraintError,
 };
 
 /// Form data for creating a single benchmark item via the UI.
@@ -291,6 +292,9 @@ pub struct AssociateForm {
     /// Product identifier.
     #[validate(range(min = 1))]
     pub product_id: i32,
+    /// Optional similarity distance; the configured default is used when absent.
+    #[serde(default)]
+    pub distance: Option<f32>,
 }
 
 /// Strongly-typed payload built from [`AssociateForm`].
@@ -298,6 +302,7 @@ pub struct AssociateForm {
 pub struct AssociateFormPayload {
     pub benchmark_id: BenchmarkId,
     pub product_id: ProductId,
+    pub distance: Option<SimilarityDistance>,
 }
 
 /// Validation and conversion errors for [`AssociateForm`].
@@ -330,6 +335,7 @@ impl TryFrom<AssociateForm> for AssociateFormPayload {
         Ok(Self {
             benchmark_id: BenchmarkId::new(value.benchmark_id)?,
             product_id: ProductId::new(value.product_id)?,
+            distance: value.distance.map(SimilarityDistance::new).transpose()?,
         })
     }
 }
@@ -365,4 +371,40 @@ mod tests {
         let err = UnassociateFormPayload::try_from(form).unwrap_err();
         assert!(matches!(err, UnassociateFormError::Validation(_)));
     }
+
+    #[test]
+    fn unassociate_form_rejects_negative_ids() {
+        let form = UnassociateForm {
+            benchmark_id: 1,
+            product_id: -5,
+        };
+
+        let err = UnassociateFormPayload::try_from(form).unwrap_err();
+        assert!(matches!(err, UnassociateFormError::Validation(_)));
+    }
+
+    #[test]
+    fn associate_form_rejects_negative_ids() {
+        let form = AssociateForm {
+            benchmark_id: -1,
+            product_id: 1,
+            distance: None,
+        };
+
+        let err = AssociateFormPayload::try_from(form).unwrap_err();
+        assert!(matches!(err, AssociateFormError::Validation(_)));
+    }
+
+    #[test]
+    fn associate_form_try_from_builds_typed_ids() {
+        let form = AssociateForm {
+            benchmark_id: 3,
+            product_id: 7,
+            distance: None,
+        };
+
+        let payload = AssociateFormPayload::try_from(form).unwrap();
+        assert_eq!(payload.benchmark_id.get(), 3);
+        assert_eq!(payload.product_id.get(), 7);
+    }
 }
@@ -1,12 +1,64 @@
 use chrono::Utc;
 use serde::Deserialize;
 use thiserror::Error;
-use validator::{Validate, ValidationErrors};
+use validator::{Validate, ValidationError, ValidationErrors};
 
 use crate::domain::category::NewCategory;
 use crate::domain::types::{CategoryId, CategoryName, HubId, ProductId, TypeConstraintError};
+use crate::models::config::{DEFAULT_CATEGORY_MAX_DEPTH, DEFAULT_CATEGORY_MAX_SEGMENT_CHARS};
+
+/// Size bounds for `/`-separated category paths, set by
+/// `ServerConfig.category_max_depth` and `category_max_segment_chars`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryPathLimits {
+    /// Deepest path accepted, counted in segments.
+    pub max_depth: usize,
+    /// Longest single segment accepted, in characters.
+    pub max_segment_chars: usize,
+}
+
+impl CategoryPathLimits {
+    pub fn new(max_depth: usize, max_segment_chars: usize) -> Self {
+        Self {
+            max_depth,
+            max_segment_chars,
+        }
+    }
+
+    /// Reject an already normalized `path` that is too deep or has a too long segment.
+    pub fn check(&self, path: &str) -> Result<(), TypeConstraintError> {
+        let max_segment_chars = self.max_segment_chars;
+        if path
+            .split('/')
+            .any(|part| part.chars().count() > max_segment_chars)
+        {
+            return Err(TypeConstraintError::InvalidValue(format!(
+                "category path segments must be at most {max_segment_chars} characters"
+            )));
+        }
+
+        let max_depth = self.max_depth;
+        if path.split('/').count() > max_depth {
+            return Err(TypeConstraintError::InvalidValue(format!(
+                "category path must have at most {max_depth} levels"
+            )));
+        }
+
+        Ok(())
+    }
+}
 
-fn normalize_category_path(value: String) -> Result<String, TypeConstraintError> {
+impl Default for CategoryPathLimits {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_CATEGORY_MAX_DEPTH,
+            DEFAULT_CATEGORY_MAX_SEGMENT_CHARS,
+        )
+    }
+}
+
+/// Trim the path and its segments, rejecting empty ones.
+fn trim_category_path(value: String) -> Result<String, TypeConstraintError> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
         return Err(TypeConstraintError::EmptyString("category"));
@@ -26,6 +78,16 @@ fn normalize_category_path(value: String) -> Result<String, TypeConstraintError>
     Ok(normalized_parts.join("/"))
 }
 
+/// Trim a category path and check it against `limits`.
+pub(crate) fn normalize_category_path(
+    value: String,
+    limits: &CategoryPathLimits,
+) -> Result<String, TypeConstraintError> {
+    let normalized = trim_category_path(value)?;
+    limits.check(&normalized)?;
+    Ok(normalized)
+}
+
 #[derive(Deserialize, Validate)]
 pub struct AddCategoryForm {
     #[validate(length(min = 1))]
@@ -43,7 +105,7 @@ impl AddCategoryFormPayload {
         NewCategory {
             hub_id,
             name: self.name,
-            // Embedding is generated asynchronously by pushkind-crawlers.
+            // Embedding is generated asynchronously on `EmbedCategories`.
             embedding: None,
             created_at: now,
             updated_at: now,
@@ -76,7 +138,7 @@ impl TryFrom<AddCategoryForm> for AddCategoryFormPayload {
 
     fn try_from(value: AddCategoryForm) -> Result<Self, Self::Error> {
         value.validate()?;
-        let normalized_name = normalize_category_path(value.name)?;
+        let normalized_name = trim_category_path(value.name)?;
 
         Ok(Self {
             name: CategoryName::new(normalized_name)?,
@@ -124,12 +186,12 @@ impl TryFrom<UpdateCategoryForm> for UpdateCategoryFormPayload {
 
     fn try_from(value: UpdateCategoryForm) -> Result<Self, Self::Error> {
         value.validate()?;
-        let normalized_name = normalize_category_path(value.name)?;
+        let normalized_name = trim_category_path(value.name)?;
 
         Ok(Self {
             category_id: CategoryId::new(value.category_id)?,
             name: CategoryName::new(normalized_name)?,
-            // Embedding is regenerated asynchronously by pushkind-crawlers.
+            // Embedding is regenerated asynchronously on `EmbedCategories`.
             embedding: None,
         })
     }
@@ -265,6 +327,119 @@ impl TryFrom<ClearProductCategoryForm> for ClearProductCategoryFormPayload {
     }
 }
 
+#[derive(Deserialize, Validate)]
+#[validate(schema(function = "validate_distinct_categories"))]
+pub struct MergeCategoriesForm {
+    #[validate(range(min = 1))]
+    pub source_id: i32,
+    #[validate(range(min = 1))]
+    pub target_id: i32,
+}
+
+fn validate_distinct_categories(form: &MergeCategoriesForm) -> Result<(), ValidationError> {
+    if form.source_id == form.target_id {
+        return Err(ValidationError::new("same_category")
+            .with_message("source and target categories must differ".into()));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeCategoriesFormPayload {
+    pub source_id: CategoryId,
+    pub target_id: CategoryId,
+}
+
+#[derive(Debug, Error)]
+pub enum MergeCategoriesFormError {
+    #[error("Merge categories form validation failed: {0}")]
+    Validation(String),
+    #[error("Merge categories form contains invalid data: {0}")]
+    TypeConstraint(String),
+}
+
+impl From<ValidationErrors> for MergeCategoriesFormError {
+    fn from(value: ValidationErrors) -> Self {
+        Self::Validation(value.to_string())
+    }
+}
+
+impl From<TypeConstraintError> for MergeCategoriesFormError {
+    fn from(value: TypeConstraintError) -> Self {
+        Self::TypeConstraint(value.to_string())
+    }
+}
+
+impl TryFrom<MergeCategoriesForm> for MergeCategoriesFormPayload {
+    type Error = MergeCategoriesFormError;
+
+    fn try_from(value: MergeCategoriesForm) -> Result<Self, Self::Error> {
+        value.validate()?;
+        Ok(Self {
+            source_id: CategoryId::new(value.source_id)?,
+            target_id: CategoryId::new(value.target_id)?,
+        })
+    }
+}
+
+/// Assign one category to many products at once.
+///
+/// Submitted as repeated `product_ids` fields, so it must be decoded with
+/// `serde_html_form` rather than `web::Form`.
+#[derive(Deserialize, Validate)]
+pub struct BulkSetCategoryForm {
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub product_ids: Vec<i32>,
+    #[validate(range(min = 1))]
+    pub category_id: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkSetCategoryFormPayload {
+    pub product_ids: Vec<ProductId>,
+    pub category_id: CategoryId,
+}
+
+#[derive(Debug, Error)]
+pub enum BulkSetCategoryFormError {
+    #[error("Bulk set category form validation failed: {0}")]
+    Validation(String),
+    #[error("Bulk set category form contains invalid data: {0}")]
+    TypeConstraint(String),
+}
+
+impl From<ValidationErrors> for BulkSetCategoryFormError {
+    fn from(value: ValidationErrors) -> Self {
+        Self::Validation(value.to_string())
+    }
+}
+
+impl From<TypeConstraintError> for BulkSetCategoryFormError {
+    fn from(value: TypeConstraintError) -> Self {
+        Self::TypeConstraint(value.to_string())
+    }
+}
+
+impl TryFrom<BulkSetCategoryForm> for BulkSetCategoryFormPayload {
+    type Error = BulkSetCategoryFormError;
+
+    fn try_from(value: BulkSetCategoryForm) -> Result<Self, Self::Error> {
+        value.validate()?;
+        let mut product_ids = value
+            .product_ids
+            .into_iter()
+            .map(ProductId::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        product_ids.sort_unstable();
+        product_ids.dedup();
+        Ok(Self {
+            product_ids,
+            category_id: CategoryId::new(value.category_id)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,6 +464,55 @@ mod tests {
         assert!(payload.is_err());
     }
 
+    #[test]
+    fn normalize_category_path_limits_depth() {
+        let limits = CategoryPathLimits::default();
+        let deepest = vec!["a"; DEFAULT_CATEGORY_MAX_DEPTH].join("/");
+        let too_deep = vec!["a"; DEFAULT_CATEGORY_MAX_DEPTH + 1].join("/");
+
+        assert_eq!(
+            normalize_category_path(deepest.clone(), &limits).unwrap(),
+            deepest
+        );
+        assert_eq!(
+            normalize_category_path(too_deep, &limits)
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "invalid value: category path must have at most {DEFAULT_CATEGORY_MAX_DEPTH} levels"
+            )
+        );
+    }
+
+    #[test]
+    fn normalize_category_path_limits_segment_length() {
+        let limits = CategoryPathLimits::default();
+        let longest = "ч".repeat(DEFAULT_CATEGORY_MAX_SEGMENT_CHARS);
+        let too_long = format!("Tea/{}", "ч".repeat(DEFAULT_CATEGORY_MAX_SEGMENT_CHARS + 1));
+
+        assert!(normalize_category_path(longest, &limits).is_ok());
+        assert!(matches!(
+            normalize_category_path(too_long, &limits),
+            Err(TypeConstraintError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn normalize_category_path_uses_configured_limits() {
+        let limits = CategoryPathLimits::new(2, 3);
+
+        assert_eq!(
+            normalize_category_path(" Tea / Red ".to_string(), &limits).unwrap(),
+            "Tea/Red"
+        );
+        assert!(normalize_category_path("Tea/Red/Dry".to_string(), &limits).is_err());
+        assert!(normalize_category_path("Teas".to_string(), &limits).is_err());
+        assert!(
+            normalize_category_path(vec!["a"; 8].join("/"), &CategoryPathLimits::new(8, 100))
+                .is_ok()
+        );
+    }
+
     #[test]
     fn set_product_category_form_validates_ids() {
         let form = SetProductCategoryForm {
@@ -299,4 +523,62 @@ mod tests {
         assert_eq!(payload.product_id.get(), 1);
         assert_eq!(payload.category_id.get(), 2);
     }
+
+    #[test]
+    fn merge_categories_rejects_same_source_and_target() {
+        let form = MergeCategoriesForm {
+            source_id: 3,
+            target_id: 3,
+        };
+
+        let payload: Result<MergeCategoriesFormPayload, _> = form.try_into();
+        assert!(matches!(
+            payload,
+            Err(MergeCategoriesFormError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn merge_categories_converts_distinct_ids() {
+        let form = MergeCategoriesForm {
+            source_id: 3,
+            target_id: 4,
+        };
+
+        let payload: MergeCategoriesFormPayload = form.try_into().unwrap();
+        assert_eq!(payload.source_id.get(), 3);
+        assert_eq!(payload.target_id.get(), 4);
+    }
+
+    #[test]
+    fn bulk_set_category_rejects_empty_product_list() {
+        let form = BulkSetCategoryForm {
+            product_ids: vec![],
+            category_id: 1,
+        };
+
+        let payload: Result<BulkSetCategoryFormPayload, _> = form.try_into();
+        assert!(matches!(
+            payload,
+            Err(BulkSetCategoryFormError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn bulk_set_category_drops_non_adjacent_duplicate_products() {
+        let form = BulkSetCategoryForm {
+            product_ids: vec![1, 2, 1, 3, 2],
+            category_id: 1,
+        };
+
+        let payload: BulkSetCategoryFormPayload = form.try_into().unwrap();
+        assert_eq!(
+            payload
+                .product_ids
+                .iter()
+                .map(|id| id.get())
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
 }
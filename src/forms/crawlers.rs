@@ -0,0 +1,227 @@
+use serde::Deserialize;
+use thiserror::Error;
+use validator::{Validate, ValidationErrors};
+
+use crate::domain::types::{
+    CrawlerId, CrawlerName, CrawlerSelectorValue, CrawlerUrl, TypeConstraintError,
+};
+
+/// Form posted by the crawler list to start a crawl.
+#[derive(Deserialize)]
+pub struct ProcessCrawlerForm {
+    pub crawler_id: i32,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct AddCrawlerForm {
+    #[validate(length(min = 1))]
+    pub name: String,
+    #[validate(length(min = 1))]
+    pub url: String,
+    #[validate(length(min = 1))]
+    pub selector: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddCrawlerFormPayload {
+    pub name: CrawlerName,
+    pub url: CrawlerUrl,
+    pub selector: CrawlerSelectorValue,
+}
+
+#[derive(Debug, Error)]
+pub enum AddCrawlerFormError {
+    #[error("Add crawler form validation failed: {0}")]
+    Validation(String),
+    #[error("Add crawler form contains invalid data: {0}")]
+    TypeConstraint(String),
+}
+
+impl From<ValidationErrors> for AddCrawlerFormError {
+    fn from(value: ValidationErrors) -> Self {
+        Self::Validation(value.to_string())
+    }
+}
+
+impl From<TypeConstraintError> for AddCrawlerFormError {
+    fn from(value: TypeConstraintError) -> Self {
+        Self::TypeConstraint(value.to_string())
+    }
+}
+
+impl TryFrom<AddCrawlerForm> for AddCrawlerFormPayload {
+    type Error = AddCrawlerFormError;
+
+    fn try_from(value: AddCrawlerForm) -> Result<Self, Self::Error> {
+        value.validate()?;
+        Ok(Self {
+            name: CrawlerName::new(value.name)?,
+            url: CrawlerUrl::new(value.url)?,
+            selector: CrawlerSelectorValue::new(value.selector)?,
+        })
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct UpdateCrawlerForm {
+    #[validate(length(min = 1))]
+    pub name: String,
+    #[validate(length(min = 1))]
+    pub url: String,
+    #[validate(length(min = 1))]
+    pub selector: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateCrawlerFormPayload {
+    pub name: CrawlerName,
+    pub url: CrawlerUrl,
+    pub selector: CrawlerSelectorValue,
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateCrawlerFormError {
+    #[error("Update crawler form validation failed: {0}")]
+    Validation(String),
+    #[error("Update crawler form contains invalid data: {0}")]
+    TypeConstraint(String),
+}
+
+impl From<ValidationErrors> for UpdateCrawlerFormError {
+    fn from(value: ValidationErrors) -> Self {
+        Self::Validation(value.to_string())
+    }
+}
+
+impl From<TypeConstraintError> for UpdateCrawlerFormError {
+    fn from(value: TypeConstraintError) -> Self {
+        Self::TypeConstraint(value.to_string())
+    }
+}
+
+impl TryFrom<UpdateCrawlerForm> for UpdateCrawlerFormPayload {
+    type Error = UpdateCrawlerFormError;
+
+    fn try_from(value: UpdateCrawlerForm) -> Result<Self, Self::Error> {
+        value.validate()?;
+        Ok(Self {
+            name: CrawlerName::new(value.name)?,
+            url: CrawlerUrl::new(value.url)?,
+            selector: CrawlerSelectorValue::new(value.selector)?,
+        })
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct DeleteCrawlerForm {
+    #[validate(range(min = 1))]
+    pub crawler_id: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteCrawlerFormPayload {
+    pub crawler_id: CrawlerId,
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteCrawlerFormError {
+    #[error("Delete crawler form validation failed: {0}")]
+    Validation(String),
+    #[error("Delete crawler form contains invalid data: {0}")]
+    TypeConstraint(String),
+}
+
+impl From<ValidationErrors> for DeleteCrawlerFormError {
+    fn from(value: ValidationErrors) -> Self {
+        Self::Validation(value.to_string())
+    }
+}
+
+impl From<TypeConstraintError> for DeleteCrawlerFormError {
+    fn from(value: TypeConstraintError) -> Self {
+        Self::TypeConstraint(value.to_string())
+    }
+}
+
+impl TryFrom<DeleteCrawlerForm> for DeleteCrawlerFormPayload {
+    type Error = DeleteCrawlerFormError;
+
+    fn try_from(value: DeleteCrawlerForm) -> Result<Self, Self::Error> {
+        value.validate()?;
+        Ok(Self {
+            crawler_id: CrawlerId::new(value.crawler_id)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_crawler_rejects_empty_name() {
+        let form = AddCrawlerForm {
+            name: "   ".to_string(),
+            url: "https://example.com".to_string(),
+            selector: "example".to_string(),
+        };
+
+        let payload: Result<AddCrawlerFormPayload, _> = form.try_into();
+        assert!(matches!(
+            payload,
+            Err(AddCrawlerFormError::TypeConstraint(_))
+        ));
+    }
+
+    #[test]
+    fn add_crawler_rejects_invalid_url() {
+        let form = AddCrawlerForm {
+            name: "Example".to_string(),
+            url: "not a url".to_string(),
+            selector: "example".to_string(),
+        };
+
+        let payload: Result<AddCrawlerFormPayload, _> = form.try_into();
+        assert!(matches!(
+            payload,
+            Err(AddCrawlerFormError::TypeConstraint(_))
+        ));
+    }
+
+    #[test]
+    fn add_crawler_converts_valid_form() {
+        let form = AddCrawlerForm {
+            name: " Example ".to_string(),
+            url: "https://example.com".to_string(),
+            selector: "example".to_string(),
+        };
+
+        let payload: AddCrawlerFormPayload = form.try_into().unwrap();
+        assert_eq!(payload.name.as_str(), "Example");
+        assert_eq!(payload.url.as_str(), "https://example.com");
+        assert_eq!(payload.selector.as_str(), "example");
+    }
+
+    #[test]
+    fn update_crawler_rejects_invalid_url() {
+        let form = UpdateCrawlerForm {
+            name: "Example".to_string(),
+            url: "example".to_string(),
+            selector: "example".to_string(),
+        };
+
+        let payload: Result<UpdateCrawlerFormPayload, _> = form.try_into();
+        assert!(payload.is_err());
+    }
+
+    #[test]
+    fn delete_crawler_rejects_non_positive_id() {
+        let form = DeleteCrawlerForm { crawler_id: 0 };
+
+        let payload: Result<DeleteCrawlerFormPayload, _> = form.try_into();
+        assert!(matches!(
+            payload,
+            Err(DeleteCrawlerFormError::Validation(_))
+        ));
+    }
+}
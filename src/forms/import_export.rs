@@ -3,6 +3,7 @@ use std::io::{Read, Seek, SeekFrom};
 
 use actix_multipart::form::{MultipartForm, tempfile::TempFile, text::Text};
 use calamine::{Data, Reader, open_workbook_auto};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 const PRODUCTS_HEADERS: [&str; 8] = [
@@ -26,10 +27,40 @@ const BENCHMARK_HEADERS: [&str; 7] = [
     "description",
 ];
 
+/// ZIP local file header signature every XLSX workbook starts with.
+const XLSX_MAGIC: [u8; 4] = *b"PK\x03\x04";
+
+const PRODUCT_CATEGORY_HEADERS: [&str; 2] = ["sku", "category"];
+
+const CATEGORY_HEADERS: [&str; 1] = ["name"];
+
+const CRAWLER_HEADERS: [&str; 3] = ["name", "url", "selector"];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UploadTarget {
     CrawlerProducts,
     Benchmarks,
+    /// SKU to category path mapping used for bulk manual assignments.
+    ProductCategories,
+    /// Category directory paths, one per row.
+    Categories,
+    /// Crawler definitions for bulk hub setup.
+    Crawlers,
+}
+
+impl TryFrom<&str> for UploadTarget {
+    type Error = UploadParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "products" => Ok(Self::CrawlerProducts),
+            "benchmarks" => Ok(Self::Benchmarks),
+            "product_categories" => Ok(Self::ProductCategories),
+            "categories" => Ok(Self::Categories),
+            "crawlers" => Ok(Self::Crawlers),
+            other => Err(UploadParseError::InvalidTarget(other.to_string())),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -50,10 +81,13 @@ impl TryFrom<&str> for UploadFormat {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum UploadMode {
     Full,
     Partial,
+    /// Every row is data; columns map positionally to [`expected_headers`].
+    Headerless,
 }
 
 impl TryFrom<&str> for UploadMode {
@@ -63,6 +97,7 @@ impl TryFrom<&str> for UploadMode {
         match value.trim().to_ascii_lowercase().as_str() {
             "full" => Ok(Self::Full),
             "partial" => Ok(Self::Partial),
+            "headerless" => Ok(Self::Headerless),
             other => Err(UploadParseError::InvalidMode(other.to_string())),
         }
     }
@@ -76,7 +111,7 @@ pub struct UploadImportForm {
     pub mode: Text<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ParsedUploadRow {
     pub row_number: usize,
     pub values: HashMap<String, String>,
@@ -92,12 +127,16 @@ pub struct ParsedUpload {
 
 #[derive(Debug, Error)]
 pub enum UploadParseError {
+    #[error("invalid upload target: {0}")]
+    InvalidTarget(String),
     #[error("invalid upload format: {0}")]
     InvalidFormat(String),
     #[error("invalid upload mode: {0}")]
     InvalidMode(String),
     #[error("uploaded file is missing")]
     MissingFile,
+    #[error("uploaded file is empty")]
+    EmptyFile,
     #[error("uploaded file extension does not match selected format")]
     ExtensionMismatch,
     #[error("uploaded file content type does not match selected format")]
@@ -146,8 +185,24 @@ pub fn parse_upload(
         UploadFormat::Xlsx => parse_xlsx_rows(form)?,
     };
 
-    let normalized_headers = normalize_headers(headers)?;
-    validate_headers(target, mode, &normalized_headers)?;
+    let (normalized_headers, rows, first_row_number) = if mode == UploadMode::Headerless {
+        let expected = expected_headers(target);
+        let rows = std::iter::once(headers).chain(rows).collect::<Vec<_>>();
+        if let Some(idx) = rows.iter().position(|row| row.len() != expected.len()) {
+            return Err(UploadParseError::HeaderValidation(format!(
+                "headerless mode requires {} columns, row {} has {}",
+                expected.len(),
+                idx + 1,
+                rows[idx].len()
+            )));
+        }
+        let headers = expected.iter().map(|header| header.to_string()).collect();
+        (headers, rows, 1)
+    } else {
+        let normalized_headers = normalize_headers(headers)?;
+        validate_headers(target, mode, &normalized_headers)?;
+        (normalized_headers, rows, 2)
+    };
 
     let parsed_rows = rows
         .into_iter()
@@ -159,7 +214,7 @@ pub fn parse_upload(
                 values.insert(header.clone(), value.trim().to_string());
             }
             ParsedUploadRow {
-                row_number: idx + 2,
+                row_number: idx + first_row_number,
                 values,
             }
         })
@@ -173,10 +228,25 @@ pub fn parse_upload(
     })
 }
 
-fn expected_headers(target: UploadTarget) -> &'static [&'static str] {
+/// Canonical header row a full-mode upload for `target` must contain.
+pub fn expected_headers(target: UploadTarget) -> &'static [&'static str] {
     match target {
         UploadTarget::CrawlerProducts => &PRODUCTS_HEADERS,
         UploadTarget::Benchmarks => &BENCHMARK_HEADERS,
+        UploadTarget::ProductCategories => &PRODUCT_CATEGORY_HEADERS,
+        UploadTarget::Categories => &CATEGORY_HEADERS,
+        UploadTarget::Crawlers => &CRAWLER_HEADERS,
+    }
+}
+
+/// Column identifying a row that partial-mode uploads for `target` must include.
+fn key_header(target: UploadTarget) -> &'static str {
+    match target {
+        UploadTarget::Categories => "name",
+        UploadTarget::Crawlers => "url",
+        UploadTarget::CrawlerProducts
+        | UploadTarget::Benchmarks
+        | UploadTarget::ProductCategories => "sku",
     }
 }
 
@@ -220,7 +290,7 @@ fn validate_headers(
     let header_set = headers.iter().map(String::as_str).collect::<HashSet<_>>();
 
     match mode {
-        UploadMode::Full => {
+        UploadMode::Full | UploadMode::Headerless => {
             if header_set != expected_set {
                 return Err(UploadParseError::HeaderValidation(format!(
                     "full mode requires exact headers: {}",
@@ -229,10 +299,11 @@ fn validate_headers(
             }
         }
         UploadMode::Partial => {
-            if !header_set.contains("sku") {
-                return Err(UploadParseError::HeaderValidation(
-                    "partial mode requires sku column".to_string(),
-                ));
+            let key = key_header(target);
+            if !header_set.contains(key) {
+                return Err(UploadParseError::HeaderValidation(format!(
+                    "partial mode requires {key} column"
+                )));
             }
 
             for header in headers {
@@ -265,6 +336,10 @@ fn validate_file_meta(
         return Err(UploadParseError::ExtensionMismatch);
     }
 
+    if form.file.file.as_file().metadata()?.len() == 0 {
+        return Err(UploadParseError::EmptyFile);
+    }
+
     if let Some(content_type) = form.file.content_type.as_ref() {
         let mime = content_type.essence_str();
         let content_type_ok = match format {
@@ -280,11 +355,29 @@ fn validate_file_meta(
         if !content_type_ok {
             return Err(UploadParseError::ContentTypeMismatch);
         }
+    } else if sniff_format(form.file.file.as_file())? != format {
+        return Err(UploadParseError::ContentTypeMismatch);
     }
 
     Ok(())
 }
 
+/// Guess the upload format from leading magic bytes.
+///
+/// XLSX workbooks are ZIP archives; anything else is treated as CSV text.
+fn sniff_format(mut file: &std::fs::File) -> Result<UploadFormat, UploadParseError> {
+    let mut magic = [0_u8; 4];
+    file.seek(SeekFrom::Start(0))?;
+    let read = file.read(&mut magic)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    if read == XLSX_MAGIC.len() && magic == XLSX_MAGIC {
+        Ok(UploadFormat::Xlsx)
+    } else {
+        Ok(UploadFormat::Csv)
+    }
+}
+
 fn parse_csv_rows(
     form: &mut UploadImportForm,
 ) -> Result<(Vec<String>, Vec<Vec<String>>), UploadParseError> {
@@ -417,4 +510,99 @@ mod tests {
             .to_string();
         assert!(err.contains("exact headers"));
     }
+
+    #[test]
+    fn validates_product_category_mapping_headers() {
+        let headers = vec!["category", "sku"]
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        assert!(
+            validate_headers(UploadTarget::ProductCategories, UploadMode::Full, &headers).is_ok()
+        );
+    }
+
+    fn upload_form(file_name: &str, content: &[u8]) -> UploadImportForm {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().expect("temp file should be created");
+        file.write_all(content)
+            .expect("temp file should be writable");
+        UploadImportForm {
+            file: TempFile {
+                file,
+                content_type: None,
+                file_name: Some(file_name.to_string()),
+                size: content.len(),
+            },
+            format: Text("csv".to_string()),
+            mode: Text("full".to_string()),
+        }
+    }
+
+    #[test]
+    fn rejects_xlsx_payload_named_csv_without_content_type() {
+        let form = upload_form("products.csv", b"PK\x03\x04rest-of-zip");
+
+        let err = validate_file_meta(&form, UploadFormat::Csv).unwrap_err();
+        assert!(matches!(err, UploadParseError::ContentTypeMismatch));
+    }
+
+    #[test]
+    fn accepts_text_payload_named_csv_without_content_type() {
+        let form = upload_form("products.csv", b"sku,category\nSKU1,Tea\n");
+
+        assert!(validate_file_meta(&form, UploadFormat::Csv).is_ok());
+    }
+
+    #[test]
+    fn rejects_zero_byte_upload_as_empty_file() {
+        let mut form = upload_form("products.csv", b"");
+
+        let err = parse_upload(&mut form, UploadTarget::ProductCategories).unwrap_err();
+        assert!(matches!(err, UploadParseError::EmptyFile));
+    }
+
+    #[test]
+    fn header_only_upload_parses_to_zero_rows() {
+        let mut form = upload_form("products.csv", b"sku,category\n");
+
+        let parsed = parse_upload(&mut form, UploadTarget::ProductCategories).unwrap();
+        assert_eq!(parsed.headers, vec!["sku", "category"]);
+        assert!(parsed.rows.is_empty());
+    }
+
+    #[test]
+    fn headerless_products_upload_maps_columns_positionally() {
+        let mut form = upload_form(
+            "products.csv",
+            b"SKU1,Tea,Drinks,pcs,10.5,1,Green tea,https://example.com/tea\n\
+              SKU2,Coffee,Drinks,kg,20,2,Arabica,https://example.com/coffee\n",
+        );
+        form.mode = Text("headerless".to_string());
+
+        let parsed = parse_upload(&mut form, UploadTarget::CrawlerProducts).unwrap();
+
+        assert_eq!(parsed.mode, UploadMode::Headerless);
+        assert_eq!(parsed.headers, PRODUCTS_HEADERS.to_vec());
+        assert_eq!(parsed.rows.len(), 2);
+        assert_eq!(parsed.rows[0].row_number, 1);
+        assert_eq!(parsed.rows[0].values["sku"], "SKU1");
+        assert_eq!(parsed.rows[0].values["price"], "10.5");
+        assert_eq!(parsed.rows[1].row_number, 2);
+        assert_eq!(parsed.rows[1].values["units"], "kg");
+        assert_eq!(parsed.rows[1].values["url"], "https://example.com/coffee");
+    }
+
+    #[test]
+    fn headerless_upload_rejects_column_count_mismatch() {
+        let mut form = upload_form("products.csv", b"SKU1,Tea,Drinks\nSKU2,Coffee,Drinks\n");
+        form.mode = Text("headerless".to_string());
+
+        let err = parse_upload(&mut form, UploadTarget::CrawlerProducts).unwrap_err();
+
+        assert!(matches!(err, UploadParseError::HeaderValidation(_)));
+        assert!(err.to_string().contains("requires 8 columns"));
+    }
 }
@@ -1,3 +1,5 @@
 pub mod benchmarks;
 pub mod categories;
+pub mod crawlers;
 pub mod import_export;
+pub mod products;
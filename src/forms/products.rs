@@ -0,0 +1,273 @@
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::domain::product::{NewProduct, Product};
+use crate::domain::types::{
+    CategoryName, CrawlerId, ImageUrl, ProductDescription, ProductName, ProductPrice, ProductSku,
+    ProductUnits, TypeConstraintError,
+};
+
+/// Form data for manual partial edits of a crawler product.
+///
+/// Missing or blank fields are left untouched on the stored product.
+#[derive(Deserialize, Validate, Default)]
+pub struct UpdateProductForm {
+    pub name: Option<String>,
+    pub sku: Option<String>,
+    pub category: Option<String>,
+    pub units: Option<String>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    #[validate(range(min = 0.0))]
+    pub price: Option<f64>,
+    pub description: Option<String>,
+}
+
+/// HTML forms submit blank inputs as empty strings; treat them as absent.
+fn empty_string_as_none<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    match value.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => value.parse().map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Strongly-typed payload built from [`UpdateProductForm`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpdateProductFormPayload {
+    pub name: Option<ProductName>,
+    pub sku: Option<ProductSku>,
+    pub category: Option<CategoryName>,
+    pub units: Option<ProductUnits>,
+    pub price: Option<ProductPrice>,
+    pub description: Option<ProductDescription>,
+}
+
+impl UpdateProductFormPayload {
+    /// Merge the supplied fields over an existing product.
+    pub fn apply_to(self, product: &Product) -> NewProduct {
+        NewProduct {
+            crawler_id: product.crawler_id,
+            name: self.name.unwrap_or_else(|| product.name.clone()),
+            sku: self.sku.unwrap_or_else(|| product.sku.clone()),
+            category: self.category.or_else(|| product.category.clone()),
+            units: self.units.or_else(|| product.units.clone()),
+            price: self.price.unwrap_or(product.price),
+            amount: product.amount,
+            description: self.description.or_else(|| product.description.clone()),
+            url: product.url.clone(),
+            images: product.images.clone(),
+        }
+    }
+}
+
+/// Validation and conversion errors for [`UpdateProductForm`].
+#[derive(Debug, Error)]
+pub enum UpdateProductFormError {
+    #[error("Update product form validation failed: {0}")]
+    Validation(String),
+    #[error("Update product form contains invalid data: {0}")]
+    TypeConstraint(String),
+}
+
+impl From<ValidationErrors> for UpdateProductFormError {
+    fn from(value: ValidationErrors) -> Self {
+        Self::Validation(value.to_string())
+    }
+}
+
+impl From<TypeConstraintError> for UpdateProductFormError {
+    fn from(value: TypeConstraintError) -> Self {
+        Self::TypeConstraint(value.to_string())
+    }
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+impl TryFrom<UpdateProductForm> for UpdateProductFormPayload {
+    type Error = UpdateProductFormError;
+
+    fn try_from(value: UpdateProductForm) -> Result<Self, Self::Error> {
+        value.validate()?;
+        Ok(Self {
+            name: non_blank(value.name).map(ProductName::new).transpose()?,
+            sku: non_blank(value.sku).map(ProductSku::new).transpose()?,
+            category: non_blank(value.category)
+                .map(CategoryName::new)
+                .transpose()?,
+            units: non_blank(value.units).map(ProductUnits::new).transpose()?,
+            price: value.price.map(ProductPrice::new).transpose()?,
+            description: non_blank(value.description)
+                .map(ProductDescription::new)
+                .transpose()?,
+        })
+    }
+}
+
+/// Move every product of one crawler to another crawler of the same hub.
+#[derive(Deserialize, Validate)]
+#[validate(schema(function = "validate_distinct_crawlers"))]
+pub struct MoveProductsForm {
+    #[validate(range(min = 1))]
+    pub source_crawler_id: i32,
+    #[validate(range(min = 1))]
+    pub target_crawler_id: i32,
+}
+
+fn validate_distinct_crawlers(form: &MoveProductsForm) -> Result<(), ValidationError> {
+    if form.source_crawler_id == form.target_crawler_id {
+        return Err(ValidationError::new("same_crawler")
+            .with_message("source and target crawlers must differ".into()));
+    }
+    Ok(())
+}
+
+/// Strongly-typed payload built from [`MoveProductsForm`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveProductsFormPayload {
+    pub source_crawler_id: CrawlerId,
+    pub target_crawler_id: CrawlerId,
+}
+
+/// Validation and conversion errors for [`MoveProductsForm`].
+#[derive(Debug, Error)]
+pub enum MoveProductsFormError {
+    #[error("Move products form validation failed: {0}")]
+    Validation(String),
+    #[error("Move products form contains invalid data: {0}")]
+    TypeConstraint(String),
+}
+
+impl From<ValidationErrors> for MoveProductsFormError {
+    fn from(value: ValidationErrors) -> Self {
+        Self::Validation(value.to_string())
+    }
+}
+
+impl From<TypeConstraintError> for MoveProductsFormError {
+    fn from(value: TypeConstraintError) -> Self {
+        Self::TypeConstraint(value.to_string())
+    }
+}
+
+impl TryFrom<MoveProductsForm> for MoveProductsFormPayload {
+    type Error = MoveProductsFormError;
+
+    fn try_from(value: MoveProductsForm) -> Result<Self, Self::Error> {
+        value.validate()?;
+        Ok(Self {
+            source_crawler_id: CrawlerId::new(value.source_crawler_id)?,
+            target_crawler_id: CrawlerId::new(value.target_crawler_id)?,
+        })
+    }
+}
+
+/// Replacement image list for a crawler product, one URL per line.
+#[derive(Deserialize, Default)]
+pub struct ProductImagesForm {
+    #[serde(default)]
+    pub images: String,
+}
+
+/// Strongly-typed payload built from [`ProductImagesForm`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProductImagesFormPayload {
+    pub images: Vec<ImageUrl>,
+}
+
+/// Validation and conversion errors for [`ProductImagesForm`].
+#[derive(Debug, Error)]
+pub enum ProductImagesFormError {
+    #[error("Product images form contains invalid data: {0}")]
+    TypeConstraint(String),
+}
+
+impl From<TypeConstraintError> for ProductImagesFormError {
+    fn from(value: TypeConstraintError) -> Self {
+        Self::TypeConstraint(value.to_string())
+    }
+}
+
+impl TryFrom<ProductImagesForm> for ProductImagesFormPayload {
+    type Error = ProductImagesFormError;
+
+    fn try_from(value: ProductImagesForm) -> Result<Self, Self::Error> {
+        let images = value
+            .images
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(ImageUrl::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { images })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_product_converts_only_present_fields() {
+        let form = UpdateProductForm {
+            price: Some(12.5),
+            units: Some("  ".to_string()),
+            ..Default::default()
+        };
+
+        let payload: UpdateProductFormPayload = form.try_into().unwrap();
+        assert_eq!(payload.price.map(ProductPrice::get), Some(12.5));
+        assert!(payload.name.is_none());
+        assert!(payload.units.is_none());
+        assert!(payload.description.is_none());
+    }
+
+    #[test]
+    fn update_product_rejects_negative_price() {
+        let form = UpdateProductForm {
+            price: Some(-1.0),
+            ..Default::default()
+        };
+
+        let payload: Result<UpdateProductFormPayload, _> = form.try_into();
+        assert!(payload.is_err());
+    }
+
+    #[test]
+    fn move_products_rejects_same_source_and_target() {
+        let form = MoveProductsForm {
+            source_crawler_id: 1,
+            target_crawler_id: 1,
+        };
+
+        let payload: Result<MoveProductsFormPayload, _> = form.try_into();
+        assert!(matches!(payload, Err(MoveProductsFormError::Validation(_))));
+    }
+
+    #[test]
+    fn product_images_form_skips_blank_lines_and_rejects_invalid_urls() {
+        let form = ProductImagesForm {
+            images: "https://101tea.ru/a.png\n\n  https://101tea.ru/b.png  \n".to_string(),
+        };
+        let payload: ProductImagesFormPayload = form.try_into().unwrap();
+        assert_eq!(
+            payload
+                .images
+                .iter()
+                .map(ImageUrl::as_str)
+                .collect::<Vec<_>>(),
+            ["https://101tea.ru/a.png", "https://101tea.ru/b.png"]
+        );
+
+        let form = ProductImagesForm {
+            images: "not a url".to_string(),
+        };
+        let payload: Result<ProductImagesFormPayload, _> = form.try_into();
+        assert!(payload.is_err());
+    }
+}
@@ -4,6 +4,8 @@
 //! used by the Dantes web application.
 #[cfg(feature = "server")]
 use std::sync::Arc;
+#[cfg(feature = "server")]
+use std::time::Duration;
 
 #[cfg(feature = "server")]
 use actix_files::Files;
@@ -12,9 +14,13 @@ use actix_identity::IdentityMiddleware;
 #[cfg(feature = "server")]
 use actix_session::{SessionMiddleware, storage::CookieSessionStore};
 #[cfg(feature = "server")]
-use actix_web::cookie::Key;
+use actix_web::cookie::{Key, SameSite};
+#[cfg(feature = "server")]
+use actix_web::dev::Server;
+#[cfg(feature = "server")]
+use actix_web::middleware::{Compress, Condition, Logger};
 #[cfg(feature = "server")]
-use actix_web::{App, HttpServer, middleware, web};
+use actix_web::{App, HttpServer, web};
 #[cfg(feature = "server")]
 use actix_web_flash_messages::{FlashMessagesFramework, storage::CookieMessageStore};
 #[cfg(feature = "server")]
@@ -31,28 +37,58 @@ use pushkind_common::zmq::{ZmqSender, ZmqSenderOptions};
 use tera::Tera;
 
 #[cfg(feature = "server")]
-use crate::models::config::ServerConfig;
+use crate::forms::categories::CategoryPathLimits;
+#[cfg(feature = "server")]
+use crate::middleware::templates::TemplateReloadMiddleware;
+#[cfg(feature = "server")]
+use crate::models::config::{CookieSameSite, ServerConfig};
 #[cfg(feature = "server")]
 use crate::repository::DieselRepository;
 #[cfg(feature = "server")]
-use crate::routes::api::api_v1_products;
+use crate::routes::api::{
+    api_v1_association_distance, api_v1_benchmark_matches, api_v1_create_benchmark,
+    api_v1_hub_statistics, api_v1_import_benchmarks, api_v1_import_products,
+    api_v1_product_benchmarks, api_v1_product_by_id, api_v1_product_changes, api_v1_products,
+    api_v1_summary,
+};
+#[cfg(feature = "server")]
+use crate::routes::audit::show_audit_log;
 #[cfg(feature = "server")]
 use crate::routes::benchmarks::{
-    add_benchmark, create_benchmark_product, delete_benchmark_product, download_benchmarks,
-    match_benchmark, show_benchmark, show_benchmarks, update_benchmark_prices, upload_benchmarks,
+    add_benchmark, associate_benchmark_by_sku, create_benchmark_product, delete_benchmark_product,
+    download_benchmarks, export_benchmark, export_benchmark_associations, export_benchmarks,
+    match_benchmark, product_top_matches, recalculate_distances, retry_embeddings, show_benchmark,
+    show_benchmark_fragment, show_benchmarks, suggest_threshold, update_benchmark_prices,
+    upload_benchmarks,
 };
 #[cfg(feature = "server")]
 use crate::routes::categories::{
-    add_category, clear_product_category_manual, delete_category, match_product_categories,
-    set_product_category_manual, show_categories, update_category,
+    add_category, bulk_set_product_categories, clear_product_category_manual, delete_category,
+    import_categories, match_crawler_categories, match_product_categories, merge_categories,
+    recategorize_automatic, set_product_category_manual, show_categories, update_category,
 };
 #[cfg(feature = "server")]
-use crate::routes::main::index;
+use crate::routes::crawlers::import_crawlers;
+#[cfg(feature = "server")]
+use crate::routes::main::{download_upload_template, index, not_found_handler, process_crawler};
 #[cfg(feature = "server")]
 use crate::routes::products::{
-    crawl_crawler, download_crawler_products, show_products, update_crawler_prices,
-    upload_crawler_products,
+    begin_import, commit_import, crawl_all_crawlers, crawl_crawler, crawler_categories,
+    delete_product, download_crawler_products, export_crawler_products, mark_product_reviewed,
+    move_products, preview_crawler_upload, preview_import_products, restore_product,
+    set_product_images, show_deleted_products, show_products, update_crawler_prices,
+    update_product, upload_crawler_products, upload_product_categories,
 };
+#[cfg(feature = "server")]
+use crate::routes::search::show_search;
+#[cfg(feature = "server")]
+use crate::services::import_export::ImportSessionStore;
+#[cfg(feature = "server")]
+use crate::services::rate_limit::RateLimiter;
+#[cfg(feature = "server")]
+use crate::services::retry::SendRetryPolicy;
+#[cfg(feature = "server")]
+use crate::zmq::{DynZmqSender, JobSender, TopicZmqSender};
 
 #[cfg(feature = "data")]
 pub mod domain;
@@ -62,9 +98,15 @@ pub mod dto;
 pub mod error_conversions;
 #[cfg(feature = "server")]
 pub mod forms;
+#[cfg(feature = "server")]
+pub mod logging;
+#[cfg(feature = "server")]
+pub mod middleware;
 #[cfg(feature = "data")]
 pub mod models;
 #[cfg(feature = "server")]
+pub mod pagination;
+#[cfg(feature = "server")]
 pub mod repository;
 #[cfg(feature = "server")]
 pub mod routes;
@@ -72,12 +114,49 @@ pub mod routes;
 pub mod schema;
 #[cfg(feature = "server")]
 pub mod services;
+#[cfg(feature = "server")]
+pub mod zmq;
 
 #[cfg(feature = "server")]
 pub const SERVICE_ACCESS_ROLE: &str = "parser";
 
+/// Role allowed to read the hub audit log.
+#[cfg(feature = "server")]
+pub const AUDIT_ACCESS_ROLE: &str = "admin";
+
+/// Fail startup with a clear error when the static assets directory is missing.
+#[cfg(feature = "server")]
+fn ensure_assets_dir(assets_dir: &str) -> std::io::Result<()> {
+    if std::path::Path::new(assets_dir).is_dir() {
+        Ok(())
+    } else {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Assets directory not found: {assets_dir}"),
+        ))
+    }
+}
+
+/// Parse every template matched by the `templates_dir` glob.
+#[cfg(feature = "server")]
+fn load_templates(templates_dir: &str) -> std::io::Result<Tera> {
+    Tera::new(templates_dir)
+        .map_err(|e| std::io::Error::other(format!("Template parsing error(s): {e}")))
+}
+
 #[cfg(feature = "server")]
 pub async fn run(server_config: ServerConfig) -> std::io::Result<()> {
+    build_server(server_config)?.await?;
+
+    tracing::info!("Server stopped");
+    Ok(())
+}
+
+/// Wire the application from `server_config` and bind it, without awaiting it.
+#[cfg(feature = "server")]
+fn build_server(server_config: ServerConfig) -> std::io::Result<Server> {
+    ensure_assets_dir(&server_config.assets_dir)?;
+
     let common_config = CommonServerConfig {
         auth_service_url: server_config.auth_service_url.to_string(),
         secret: server_config.secret.clone(),
@@ -89,7 +168,11 @@ pub async fn run(server_config: ServerConfig) -> std::io::Result<()> {
     ))
     .map_err(|e| std::io::Error::other(format!("Failed to start ZMQ sender: {e}")))?;
 
-    let zmq_sender = Arc::new(zmq_sender);
+    let zmq_sender: Arc<DynZmqSender> = Arc::new(zmq_sender);
+    let zmq_sender: Arc<JobSender> = Arc::new(TopicZmqSender::new(
+        zmq_sender,
+        server_config.zmq_topic.as_deref(),
+    ));
 
     // Establish Diesel connection pool for the SQLite database.
     let pool = establish_connection_pool(&server_config.database_url).map_err(|e| {
@@ -98,57 +181,125 @@ pub async fn run(server_config: ServerConfig) -> std::io::Result<()> {
 
     let repo = DieselRepository::new(pool);
 
+    // Pending two-step imports, shared by all workers.
+    let import_sessions = web::Data::new(ImportSessionStore::default());
+
+    // Per-hub limit on crawl, match and price update jobs, shared by all workers.
+    let rate_limiter = web::Data::new(RateLimiter::per_minute(
+        server_config.dispatch_rate_limit_per_minute,
+    ));
+
+    // Retry policy for crawl, price update and match job sends.
+    let send_retry = web::Data::new(SendRetryPolicy::new(
+        server_config.zmq_send_retries,
+        Duration::from_millis(server_config.zmq_send_backoff_ms),
+    ));
+
+    // Size bounds for category paths entered in forms and uploads.
+    let category_limits = web::Data::new(CategoryPathLimits::new(
+        server_config.category_max_depth,
+        server_config.category_max_segment_chars,
+    ));
+
     // Keys and stores for identity, sessions, and flash messages.
     let secret_key = Key::from(server_config.secret.as_bytes());
 
     let message_store = CookieMessageStore::builder(secret_key.clone()).build();
     let message_framework = FlashMessagesFramework::builder(message_store).build();
 
-    let tera = Tera::new(&server_config.templates_dir)
-        .map_err(|e| std::io::Error::other(format!("Template parsing error(s): {e}")))?;
+    let tera = load_templates(&server_config.templates_dir)?;
 
     let bind_address = (server_config.address.clone(), server_config.port);
+    let shutdown_timeout = server_config.shutdown_timeout_secs;
 
-    HttpServer::new(move || {
+    let server = HttpServer::new(move || {
         App::new()
             .wrap(message_framework.clone())
             .wrap(IdentityMiddleware::default())
-            .wrap(
-                SessionMiddleware::builder(CookieSessionStore::default(), secret_key.clone())
-                    .cookie_secure(false) // set to true in prod
-                    .cookie_domain(Some(format!(".{}", server_config.domain)))
-                    .build(),
-            )
-            .wrap(middleware::Compress::default())
-            .wrap(middleware::Logger::default())
-            .service(Files::new("/assets", "./assets"))
+            .wrap(session_middleware(&server_config, secret_key.clone()))
+            .wrap(Compress::default())
+            .wrap(Logger::default())
+            // Re-read templates on every request in `dev` builds.
+            .wrap(Condition::new(
+                cfg!(feature = "dev"),
+                TemplateReloadMiddleware::new(tera.clone()),
+            ))
+            .service(Files::new("/assets", &server_config.assets_dir))
             .service(not_assigned)
-            .service(web::scope("/api").service(api_v1_products))
+            .service(
+                web::scope("/api")
+                    .service(api_v1_products)
+                    .service(api_v1_product_changes)
+                    .service(api_v1_create_benchmark)
+                    .service(api_v1_import_benchmarks)
+                    .service(api_v1_import_products)
+                    .service(api_v1_benchmark_matches)
+                    .service(api_v1_association_distance)
+                    .service(api_v1_product_benchmarks)
+                    // After `/v1/products/changes`, which would otherwise match `{product_id}`.
+                    .service(api_v1_product_by_id)
+                    .service(api_v1_hub_statistics)
+                    .service(api_v1_summary),
+            )
             .service(
                 web::scope("")
                     .wrap(RedirectUnauthorized)
                     .service(index)
+                    .service(show_audit_log)
+                    .service(show_search)
+                    .service(download_upload_template)
+                    .service(crawl_all_crawlers)
+                    .service(import_crawlers)
                     .service(crawl_crawler)
+                    .service(process_crawler)
+                    .service(move_products)
                     .service(update_crawler_prices)
                     .service(show_benchmarks)
                     .service(show_benchmark)
+                    .service(show_benchmark_fragment)
+                    .service(suggest_threshold)
+                    .service(product_top_matches)
                     .service(upload_benchmarks)
+                    .service(retry_embeddings)
+                    .service(recalculate_distances)
                     .service(download_benchmarks)
+                    .service(export_benchmarks)
+                    .service(export_benchmark)
+                    .service(export_benchmark_associations)
                     .service(add_benchmark)
                     .service(match_benchmark)
                     .service(update_benchmark_prices)
                     .service(delete_benchmark_product)
                     .service(create_benchmark_product)
+                    .service(associate_benchmark_by_sku)
                     .service(show_products)
+                    .service(show_deleted_products)
+                    .service(mark_product_reviewed)
+                    .service(set_product_images)
+                    .service(update_product)
+                    .service(delete_product)
+                    .service(restore_product)
                     .service(upload_crawler_products)
+                    .service(preview_crawler_upload)
+                    .service(preview_import_products)
+                    .service(begin_import)
+                    .service(commit_import)
+                    .service(upload_product_categories)
                     .service(download_crawler_products)
+                    .service(export_crawler_products)
+                    .service(crawler_categories)
                     .service(show_categories)
                     .service(add_category)
+                    .service(import_categories)
                     .service(update_category)
                     .service(delete_category)
+                    .service(merge_categories)
+                    .service(bulk_set_product_categories)
                     .service(set_product_category_manual)
                     .service(clear_product_category_manual)
                     .service(match_product_categories)
+                    .service(recategorize_automatic)
+                    .service(match_crawler_categories)
                     .service(logout),
             )
             .app_data(web::Data::new(tera.clone()))
@@ -156,8 +307,172 @@ pub async fn run(server_config: ServerConfig) -> std::io::Result<()> {
             .app_data(web::Data::new(server_config.clone()))
             .app_data(web::Data::new(common_config.clone()))
             .app_data(web::Data::new(zmq_sender.clone()))
+            .app_data(import_sessions.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(send_retry.clone())
+            .app_data(category_limits.clone())
+            .default_service(web::route().to(not_found_handler))
     })
+    // SIGTERM stops accepting connections and lets in-flight requests finish
+    // within the timeout. Handlers await their ZMQ sends, so a finished request
+    // has handed its jobs to the sender; nothing else is flushed on shutdown.
+    .shutdown_timeout(shutdown_timeout)
     .bind(bind_address)?
-    .run()
-    .await
+    .run();
+
+    Ok(server)
+}
+
+/// Cookie session middleware configured from `secure_cookies` and `cookie_same_site`.
+#[cfg(feature = "server")]
+fn session_middleware(
+    server_config: &ServerConfig,
+    secret_key: Key,
+) -> SessionMiddleware<CookieSessionStore> {
+    let same_site = match server_config.cookie_same_site {
+        CookieSameSite::Strict => SameSite::Strict,
+        CookieSameSite::Lax => SameSite::Lax,
+        CookieSameSite::None => SameSite::None,
+    };
+
+    SessionMiddleware::builder(CookieSessionStore::default(), secret_key)
+        .cookie_secure(server_config.secure_cookies)
+        .cookie_same_site(same_site)
+        .cookie_domain(Some(format!(".{}", server_config.domain)))
+        .build()
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use actix_session::Session;
+    use actix_web::{HttpResponse, test};
+
+    use super::*;
+
+    fn server_config(overrides: serde_json::Value) -> ServerConfig {
+        let mut value = serde_json::json!({
+            "domain": "example.com",
+            "address": "127.0.0.1",
+            "port": 8080,
+            "database_url": "app.db",
+            "zmq_crawlers_pub": "tcp://127.0.0.1:5550",
+            "templates_dir": "templates/**/*",
+            "secret": "secret",
+            "auth_service_url": "http://auth.example.com",
+        });
+        value
+            .as_object_mut()
+            .unwrap()
+            .extend(overrides.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    async fn session_cookie(server_config: &ServerConfig) -> actix_web::cookie::Cookie<'static> {
+        let app = test::init_service(
+            App::new()
+                .wrap(session_middleware(server_config, Key::from(&[0; 64])))
+                .route(
+                    "/",
+                    web::get().to(|session: Session| async move {
+                        session.insert("user", 1).unwrap();
+                        HttpResponse::Ok().finish()
+                    }),
+                ),
+        )
+        .await;
+
+        let response = test::call_service(&app, test::TestRequest::get().to_request()).await;
+        response
+            .response()
+            .cookies()
+            .next()
+            .expect("session cookie should be set")
+            .into_owned()
+    }
+
+    #[actix_web::test]
+    async fn configured_server_starts_and_stops_gracefully() {
+        let database = tempfile::NamedTempFile::new().expect("temp database should be created");
+        let config = server_config(serde_json::json!({
+            "port": 0,
+            "database_url": database.path().to_str().unwrap(),
+            "zmq_crawlers_pub": "inproc://configured-server-test",
+            "shutdown_timeout_secs": 1,
+        }));
+
+        let server = build_server(config).expect("server should build from config");
+        let handle = server.handle();
+        let running = actix_web::rt::spawn(server);
+
+        handle.stop(true).await;
+        running
+            .await
+            .expect("server task should not panic")
+            .expect("server should stop gracefully");
+    }
+
+    #[test]
+    fn templates_render_from_configured_dir() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        std::fs::write(dir.path().join("hello.html"), "Hello, {{ name }}!")
+            .expect("template should be written");
+        let config = server_config(serde_json::json!({
+            "templates_dir": format!("{}/**/*", dir.path().display()),
+        }));
+
+        let tera = load_templates(&config.templates_dir).expect("templates should load");
+        let mut context = tera::Context::new();
+        context.insert("name", "hub");
+
+        assert_eq!(tera.render("hello.html", &context).unwrap(), "Hello, hub!");
+    }
+
+    #[test]
+    fn category_path_limits_default_and_can_be_configured() {
+        let config = server_config(serde_json::json!({}));
+        assert_eq!(config.category_max_depth, 6);
+        assert_eq!(config.category_max_segment_chars, 100);
+
+        let config = server_config(serde_json::json!({
+            "category_max_depth": 8,
+            "category_max_segment_chars": 40,
+        }));
+        assert_eq!(config.category_max_depth, 8);
+        assert_eq!(config.category_max_segment_chars, 40);
+    }
+
+    #[test]
+    fn assets_dir_defaults_to_repository_assets_and_must_exist() {
+        let config = server_config(serde_json::json!({}));
+
+        assert_eq!(config.assets_dir, "./assets");
+        assert!(ensure_assets_dir(&config.assets_dir).is_ok());
+
+        let err = ensure_assets_dir("./no-such-assets-dir").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        assert!(err.to_string().contains("./no-such-assets-dir"));
+    }
+
+    #[actix_web::test]
+    async fn session_cookie_is_secure_and_lax_by_default() {
+        let config = server_config(serde_json::json!({}));
+
+        let cookie = session_cookie(&config).await;
+
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    }
+
+    #[actix_web::test]
+    async fn session_cookie_follows_configured_flags() {
+        let config = server_config(serde_json::json!({
+            "secure_cookies": false,
+            "cookie_same_site": "strict",
+        }));
+
+        let cookie = session_cookie(&config).await;
+
+        assert_ne!(cookie.secure(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+    }
 }
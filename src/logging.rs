@@ -0,0 +1,72 @@
+//! Tracing subscriber setup selected by the `LOG_FORMAT` environment variable.
+
+use std::env;
+
+use tracing::Subscriber;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::util::{SubscriberInitExt, TryInitError};
+
+/// Environment variable selecting the log output format.
+pub const LOG_FORMAT_ENV: &str = "LOG_FORMAT";
+
+/// Output format of application logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines, the historical default.
+    #[default]
+    Text,
+    /// One JSON object per line for log aggregation in production.
+    Json,
+}
+
+impl LogFormat {
+    /// Parse a `LOG_FORMAT` value; anything other than `json` selects text.
+    pub fn parse(value: &str) -> Self {
+        if value.trim().eq_ignore_ascii_case("json") {
+            Self::Json
+        } else {
+            Self::Text
+        }
+    }
+
+    /// Read the format from `LOG_FORMAT`, defaulting to text when unset.
+    pub fn from_env() -> Self {
+        env::var(LOG_FORMAT_ENV)
+            .map(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+}
+
+/// Build a subscriber writing to `writer` in the given format.
+///
+/// Span creation and closing are logged so `#[tracing::instrument]`-ed
+/// services record their entry, exit and recorded parameters.
+pub fn subscriber<W>(
+    format: LogFormat,
+    filter: EnvFilter,
+    writer: W,
+) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+        .with_writer(writer);
+
+    match format {
+        LogFormat::Json => Box::new(builder.json().finish()),
+        LogFormat::Text => Box::new(builder.finish()),
+    }
+}
+
+/// Install the global subscriber; `RUST_LOG` overrides the default `info` level.
+///
+/// Records emitted through the `log` crate (e.g. actix middleware) are
+/// forwarded to the same subscriber.
+pub fn init(format: LogFormat) -> Result<(), TryInitError> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    subscriber(format, filter, std::io::stderr).try_init()
+}
@@ -7,6 +7,8 @@ use config::Config;
 #[cfg(feature = "server")]
 use dotenvy::dotenv;
 
+#[cfg(feature = "server")]
+use pushkind_dantes::logging::{self, LogFormat};
 #[cfg(feature = "server")]
 use pushkind_dantes::{models::config::ServerConfig, run};
 
@@ -15,8 +17,10 @@ use pushkind_dantes::{models::config::ServerConfig, run};
 async fn main() {
     // Load environment variables from `.env` in local development.
     dotenv().ok();
-    // Initialize logger with default level INFO if not provided.
-    env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
+    // Initialize tracing with default level INFO; `LOG_FORMAT=json` switches to JSON lines.
+    if let Err(err) = logging::init(LogFormat::from_env()) {
+        eprintln!("Failed to initialize logging: {err}");
+    }
 
     // Select config profile (defaults to `local`).
     let app_env = env::var("APP_ENV").unwrap_or_else(|_| "local".into());
@@ -33,7 +37,7 @@ async fn main() {
     let settings = match settings {
         Ok(settings) => settings,
         Err(err) => {
-            log::error!("Error loading settings: {}", err);
+            tracing::error!("Error loading settings: {}", err);
             std::process::exit(1);
         }
     };
@@ -41,17 +45,14 @@ async fn main() {
     let server_config = match settings.try_deserialize::<ServerConfig>() {
         Ok(server_config) => server_config,
         Err(err) => {
-            log::error!("Error loading server config: {}", err);
+            tracing::error!("Error loading server config: {}", err);
             std::process::exit(1);
         }
     };
 
-    match run(server_config).await {
-        Ok(_) => log::info!("Server started"),
-        Err(err) => {
-            log::error!("Error starting server: {}", err);
-            std::process::exit(1);
-        }
+    if let Err(err) = run(server_config).await {
+        tracing::error!("Server error: {}", err);
+        std::process::exit(1);
     }
 }
 
@@ -0,0 +1,120 @@
+//! Per-request template reload for local development.
+
+use std::future::{Ready, ready};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use actix_web::dev::{
+    Extensions, Service, ServiceRequest, ServiceResponse, Transform, forward_ready,
+};
+use actix_web::{Error, web};
+use tera::Tera;
+
+/// Middleware re-reading every template before each request.
+///
+/// The reloaded [`Tera`] is exposed as `web::Data<Tera>` for the request, so
+/// routes and `render_template` pick up edits without a rebuild. A template
+/// that fails to parse is logged and the previous templates stay in use.
+#[derive(Clone)]
+pub struct TemplateReloadMiddleware {
+    tera: Arc<Mutex<Tera>>,
+}
+
+impl TemplateReloadMiddleware {
+    pub fn new(tera: Tera) -> Self {
+        Self {
+            tera: Arc::new(Mutex::new(tera)),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for TemplateReloadMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = TemplateReloadService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TemplateReloadService {
+            service,
+            tera: Arc::clone(&self.tera),
+        }))
+    }
+}
+
+/// Service produced by [`TemplateReloadMiddleware`].
+pub struct TemplateReloadService<S> {
+    service: S,
+    tera: Arc<Mutex<Tera>>,
+}
+
+impl<S, B> Service<ServiceRequest> for TemplateReloadService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = S::Future;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let tera = {
+            let mut tera = self.tera.lock().unwrap_or_else(|e| e.into_inner());
+            if let Err(e) = tera.full_reload() {
+                tracing::error!("Failed to reload templates: {e}");
+            }
+            tera.clone()
+        };
+
+        let mut data = Extensions::new();
+        data.insert(web::Data::new(tera));
+        req.add_data_container(Rc::new(data));
+
+        self.service.call(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, HttpResponse, test};
+
+    use super::*;
+
+    async fn render(tera: web::Data<Tera>) -> HttpResponse {
+        let body = tera
+            .render("page.html", &tera::Context::new())
+            .expect("template should render");
+        HttpResponse::Ok().body(body)
+    }
+
+    #[actix_web::test]
+    async fn template_edits_show_on_the_next_request() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let page = dir.path().join("page.html");
+        std::fs::write(&page, "before").expect("template should be written");
+        let tera =
+            Tera::new(&format!("{}/**/*", dir.path().display())).expect("templates should parse");
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(tera.clone()))
+                .wrap(TemplateReloadMiddleware::new(tera))
+                .route("/", web::get().to(render)),
+        )
+        .await;
+
+        let body = test::call_and_read_body(&app, test::TestRequest::get().to_request()).await;
+        assert_eq!(body, "before");
+
+        std::fs::write(&page, "after").expect("template should be rewritten");
+        let body = test::call_and_read_body(&app, test::TestRequest::get().to_request()).await;
+        assert_eq!(body, "after");
+    }
+}
@@ -0,0 +1,59 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::audit::{AuditEntry as DomainAuditEntry, NewAuditEntry as DomainNewAuditEntry};
+use crate::domain::types::TypeConstraintError;
+
+/// Diesel model representing the `audit_log` table.
+#[derive(Debug, Clone, Identifiable, Queryable)]
+#[diesel(table_name = crate::schema::audit_log)]
+pub struct AuditEntry {
+    pub id: i32,
+    pub hub_id: i32,
+    pub user_sub: String,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Insertable form of [`AuditEntry`].
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::audit_log)]
+pub struct NewAuditEntry {
+    pub hub_id: i32,
+    pub user_sub: String,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl TryFrom<AuditEntry> for DomainAuditEntry {
+    type Error = TypeConstraintError;
+
+    fn try_from(entry: AuditEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: entry.id,
+            hub_id: entry.hub_id.try_into()?,
+            user_sub: entry.user_sub,
+            action: entry.action,
+            resource_type: entry.resource_type,
+            resource_id: entry.resource_id,
+            created_at: entry.created_at,
+        })
+    }
+}
+
+impl From<&DomainNewAuditEntry> for NewAuditEntry {
+    fn from(entry: &DomainNewAuditEntry) -> Self {
+        Self {
+            hub_id: entry.hub_id.get(),
+            user_sub: entry.user_sub.clone(),
+            action: entry.action.as_str().to_string(),
+            resource_type: entry.resource.resource_type().to_string(),
+            resource_id: entry.resource.resource_id(),
+            created_at: entry.created_at,
+        }
+    }
+}
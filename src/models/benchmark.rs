@@ -25,6 +25,7 @@ pub struct Benchmark {
     pub embedding: Option<Vec<u8>>,
     pub processing: bool,
     pub num_products: i32,
+    pub archived_at: Option<NaiveDateTime>,
 }
 
 /// Insertable form of [`Benchmark`] used for creating new rows.
@@ -62,6 +63,7 @@ impl TryFrom<Benchmark> for DomainBenchmark {
             embedding: benchmark.embedding,
             processing: benchmark.processing,
             num_products: ProductCount::new(benchmark.num_products)?,
+            archived_at: benchmark.archived_at,
         })
     }
 }
@@ -1,6 +1,127 @@
 //! Configuration model loaded from external sources.
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+
+use crate::domain::types::SimilarityDistance;
+
+/// Distance stored for manual associations when neither the form nor the config sets one.
+pub const DEFAULT_MANUAL_MATCH_DISTANCE: SimilarityDistance = SimilarityDistance::new_const(1.0);
+
+/// Largest embedding distance accepted for automatic associations when the config omits it.
+pub const DEFAULT_SIMILARITY_THRESHOLD: SimilarityDistance = SimilarityDistance::new_const(0.5);
+
+/// Hours after which a crawler is flagged as stale when the config omits it.
+pub const DEFAULT_CRAWLER_STALENESS_HOURS: u32 = 24;
+
+/// Crawl, match and price update jobs a hub may start per minute when the config omits it.
+pub const DEFAULT_DISPATCH_RATE_LIMIT_PER_MINUTE: u32 = 30;
+
+/// Shortest product search query sent to full-text search when the config omits it.
+pub const DEFAULT_SEARCH_MIN_QUERY_LENGTH: usize = 2;
+
+/// Retries of a failed crawl, price update or match send when the config omits them.
+pub const DEFAULT_ZMQ_SEND_RETRIES: u32 = 2;
+
+/// Pause before the first send retry when the config omits it, in milliseconds.
+pub const DEFAULT_ZMQ_SEND_BACKOFF_MS: u64 = 100;
+
+/// Seconds workers get to finish in-flight requests on shutdown when the config omits it.
+pub const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
+/// Directory served under `/assets` when the config omits it.
+pub const DEFAULT_ASSETS_DIR: &str = "./assets";
+
+/// Deepest category path accepted, in `/`-separated segments, when the config omits it.
+pub const DEFAULT_CATEGORY_MAX_DEPTH: usize = 6;
+
+/// Longest category path segment accepted, in characters, when the config omits it.
+pub const DEFAULT_CATEGORY_MAX_SEGMENT_CHARS: usize = 100;
+
+/// How benchmarks are re-embedded after an update changes their text.
+///
+/// Embeddings are produced by `pushkind-crawlers`; this service can only drop a
+/// stale embedding and decide when the worker is asked for a new one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingRefresh {
+    /// Clear the embedding and leave re-embedding to the next match or retry run.
+    #[default]
+    Clear,
+    /// Clear the embedding and enqueue a benchmark job within the same request.
+    Enqueue,
+}
+
+/// `SameSite` attribute of the session cookie.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CookieSameSite {
+    Strict,
+    /// Sent on top-level navigation from other sites, as browsers do by default.
+    #[default]
+    Lax,
+    /// Sent on every cross-site request; browsers require `secure_cookies` for it.
+    None,
+}
+
+/// How prices are written to CSV and XLSX exports.
+///
+/// The default stays machine-friendly: a dot decimal separator, no thousands
+/// grouping and the shortest representation of the value.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct PriceFormat {
+    /// Character between the integer and fractional digits.
+    pub decimal_separator: char,
+    /// Character inserted between groups of three integer digits, if any.
+    pub thousands_separator: Option<char>,
+    /// Fixed number of fractional digits; `None` keeps the shortest representation.
+    pub decimals: Option<usize>,
+}
+
+impl Default for PriceFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            thousands_separator: None,
+            decimals: None,
+        }
+    }
+}
+
+impl PriceFormat {
+    /// Render `value` with the configured separators and precision.
+    pub fn format(&self, value: f64) -> String {
+        let raw = match self.decimals {
+            Some(decimals) => format!("{value:.decimals$}"),
+            None => value.to_string(),
+        };
+        let (sign, unsigned) = match raw.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", raw.as_str()),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        let mut formatted = String::with_capacity(raw.len() + integer.len() / 3);
+        formatted.push_str(sign);
+        for (idx, digit) in integer.chars().enumerate() {
+            if let Some(separator) = self.thousands_separator
+                && idx > 0
+                && (integer.len() - idx).is_multiple_of(3)
+            {
+                formatted.push(separator);
+            }
+            formatted.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            formatted.push(self.decimal_separator);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+}
 
 #[derive(Clone, Debug, Deserialize)]
 /// Basic configuration shared across handlers.
@@ -13,4 +134,117 @@ pub struct ServerConfig {
     pub templates_dir: String,
     pub secret: String,
     pub auth_service_url: String,
+    /// Distance persisted for manual benchmark associations that omit one.
+    #[serde(
+        default = "default_manual_match_distance",
+        deserialize_with = "deserialize_similarity_distance"
+    )]
+    pub manual_match_distance: SimilarityDistance,
+    /// Largest embedding distance at which a product is associated automatically.
+    #[serde(
+        default = "default_similarity_threshold",
+        deserialize_with = "deserialize_similarity_distance"
+    )]
+    pub similarity_threshold: SimilarityDistance,
+    /// Hours after which a crawler that has not been updated is flagged as stale.
+    #[serde(default = "default_crawler_staleness_hours")]
+    pub crawler_staleness_hours: u32,
+    /// Pause between consecutive crawler messages sent by a bulk crawl, in milliseconds.
+    #[serde(default)]
+    pub crawl_all_send_interval_ms: u64,
+    /// Minimum number of characters a product search needs before it hits full-text search.
+    #[serde(default = "default_search_min_query_length")]
+    pub search_min_query_length: usize,
+    /// Whether benchmark updates only clear a stale embedding or also enqueue re-embedding.
+    #[serde(default)]
+    pub benchmark_embedding_refresh: EmbeddingRefresh,
+    /// Crawl, match and price update jobs a hub may start per minute; `0` disables the limit.
+    #[serde(default = "default_dispatch_rate_limit_per_minute")]
+    pub dispatch_rate_limit_per_minute: u32,
+    /// Times a failed crawl, price update or match send is retried; `0` sends once.
+    #[serde(default = "default_zmq_send_retries")]
+    pub zmq_send_retries: u32,
+    /// Pause before the first send retry in milliseconds; doubles with every retry.
+    #[serde(default = "default_zmq_send_backoff_ms")]
+    pub zmq_send_backoff_ms: u64,
+    /// Topic sent as the first frame of every ZMQ message; single-frame messages when unset.
+    #[serde(default)]
+    pub zmq_topic: Option<String>,
+    /// Separators and precision used for prices in CSV and XLSX exports.
+    #[serde(default)]
+    pub price_format: PriceFormat,
+    /// Whether the session cookie is marked `Secure`; disable only for plain-HTTP development.
+    #[serde(default = "default_secure_cookies")]
+    pub secure_cookies: bool,
+    /// `SameSite` attribute of the session cookie.
+    #[serde(default)]
+    pub cookie_same_site: CookieSameSite,
+    /// Seconds workers get to finish in-flight requests after SIGTERM before they are stopped.
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+    /// Directory served under `/assets` (`APP_ASSETS_DIR`); must exist at startup.
+    #[serde(default = "default_assets_dir")]
+    pub assets_dir: String,
+    /// Deepest category path accepted, counted in `/`-separated segments.
+    #[serde(default = "default_category_max_depth")]
+    pub category_max_depth: usize,
+    /// Longest category path segment accepted, in characters.
+    #[serde(default = "default_category_max_segment_chars")]
+    pub category_max_segment_chars: usize,
+}
+
+fn default_manual_match_distance() -> SimilarityDistance {
+    DEFAULT_MANUAL_MATCH_DISTANCE
+}
+
+fn default_similarity_threshold() -> SimilarityDistance {
+    DEFAULT_SIMILARITY_THRESHOLD
+}
+
+fn default_crawler_staleness_hours() -> u32 {
+    DEFAULT_CRAWLER_STALENESS_HOURS
+}
+
+fn default_search_min_query_length() -> usize {
+    DEFAULT_SEARCH_MIN_QUERY_LENGTH
+}
+
+fn default_dispatch_rate_limit_per_minute() -> u32 {
+    DEFAULT_DISPATCH_RATE_LIMIT_PER_MINUTE
+}
+
+fn default_zmq_send_retries() -> u32 {
+    DEFAULT_ZMQ_SEND_RETRIES
+}
+
+fn default_zmq_send_backoff_ms() -> u64 {
+    DEFAULT_ZMQ_SEND_BACKOFF_MS
+}
+
+fn default_secure_cookies() -> bool {
+    true
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    DEFAULT_SHUTDOWN_TIMEOUT_SECS
+}
+
+fn default_assets_dir() -> String {
+    DEFAULT_ASSETS_DIR.to_string()
+}
+
+fn default_category_max_depth() -> usize {
+    DEFAULT_CATEGORY_MAX_DEPTH
+}
+
+fn default_category_max_segment_chars() -> usize {
+    DEFAULT_CATEGORY_MAX_SEGMENT_CHARS
+}
+
+fn deserialize_similarity_distance<'de, D>(deserializer: D) -> Result<SimilarityDistance, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f32::deserialize(deserializer)?;
+    SimilarityDistance::new(value).map_err(serde::de::Error::custom)
 }
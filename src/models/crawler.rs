@@ -1,7 +1,7 @@
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 
-use crate::domain::crawler::Crawler as DomainCrawler;
+use crate::domain::crawler::{Crawler as DomainCrawler, NewCrawler as DomainNewCrawler};
 use crate::domain::types::{
     CrawlerName, CrawlerSelectorValue, CrawlerUrl, ProductCount, TypeConstraintError,
 };
@@ -20,6 +20,17 @@ pub struct Crawler {
     pub num_products: i32,
 }
 
+/// Insertable form of [`Crawler`] used for creating new rows.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::crawlers)]
+pub struct NewCrawler {
+    pub hub_id: i32,
+    pub name: String,
+    pub url: String,
+    pub selector: String,
+    pub updated_at: NaiveDateTime,
+}
+
 impl TryFrom<Crawler> for DomainCrawler {
     type Error = TypeConstraintError;
 
@@ -36,3 +47,15 @@ impl TryFrom<Crawler> for DomainCrawler {
         })
     }
 }
+
+impl From<DomainNewCrawler> for NewCrawler {
+    fn from(crawler: DomainNewCrawler) -> Self {
+        Self {
+            hub_id: crawler.hub_id.get(),
+            name: crawler.name.into_inner(),
+            url: crawler.url.into_inner(),
+            selector: crawler.selector.into_inner(),
+            updated_at: crawler.updated_at,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod audit;
 pub mod benchmark;
 pub mod category;
 #[cfg(feature = "server")]
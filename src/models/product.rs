@@ -27,6 +27,9 @@ pub struct Product {
     pub embedding: Option<Vec<u8>>,
     pub category_id: Option<i32>,
     pub category_assignment_source: String,
+    pub reviewed_at: Option<NaiveDateTime>,
+    /// Set when the product was soft-deleted; such rows are hidden from listings.
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 /// Insertable/patchable form of [`Product`].
@@ -70,6 +73,7 @@ impl TryFrom<Product> for DomainProduct {
             category_assignment_source: CategoryAssignmentSource::try_from(
                 product.category_assignment_source,
             )?,
+            reviewed_at: product.reviewed_at,
             images: vec![],
         })
     }
@@ -0,0 +1,63 @@
+//! Paginated item lists passed from services to routes.
+
+use pushkind_common::pagination::Paginated as TemplatePaginated;
+use serde::Serialize;
+
+pub use pushkind_common::pagination::DEFAULT_ITEMS_PER_PAGE;
+
+/// One page of items together with its position in the full result set.
+///
+/// Routes convert it into [`pushkind_common::pagination::Paginated`] for
+/// templates, which renders the page links.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub page: usize,
+    pub total_pages: usize,
+    pub total_items: usize,
+}
+
+impl<T> Paginated<T> {
+    /// Build a page from its items and the size of the full result set.
+    pub fn new(items: Vec<T>, page: usize, total_items: usize, per_page: usize) -> Self {
+        Self {
+            items,
+            page,
+            total_pages: total_items.div_ceil(per_page.max(1)),
+            total_items,
+        }
+    }
+
+    /// Transform every item while keeping the pagination metadata.
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Paginated<U> {
+        Paginated {
+            items: self.items.into_iter().map(f).collect(),
+            page: self.page,
+            total_pages: self.total_pages,
+            total_items: self.total_items,
+        }
+    }
+}
+
+impl<T> From<Paginated<T>> for TemplatePaginated<T> {
+    fn from(value: Paginated<T>) -> Self {
+        TemplatePaginated::new(value.items, value.page, value.total_pages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_keeps_page_metadata_and_transforms_all_items() {
+        let paginated = Paginated::new(vec![1, 2, 3], 2, 13, 3);
+
+        let mapped = paginated.map(|value| format!("item-{value}"));
+
+        assert_eq!(mapped.page, 2);
+        assert_eq!(mapped.total_pages, 5);
+        assert_eq!(mapped.total_items, 13);
+        assert_eq!(mapped.items, vec!["item-1", "item-2", "item-3"]);
+    }
+}
@@ -0,0 +1,37 @@
+use diesel::prelude::*;
+use pushkind_common::repository::errors::RepositoryResult;
+
+use crate::domain::audit::{AuditEntry, NewAuditEntry};
+use crate::domain::types::HubId;
+use crate::models::audit::{AuditEntry as DbAuditEntry, NewAuditEntry as DbNewAuditEntry};
+use crate::repository::{AuditLogReader, AuditLogWriter, DieselRepository};
+use crate::schema::audit_log;
+
+impl AuditLogReader for DieselRepository {
+    fn list_audit_entries(&self, hub_id: HubId, limit: usize) -> RepositoryResult<Vec<AuditEntry>> {
+        let mut conn = self.conn()?;
+
+        let entries = audit_log::table
+            .filter(audit_log::hub_id.eq(hub_id.get()))
+            .order((audit_log::created_at.desc(), audit_log::id.desc()))
+            .limit(limit as i64)
+            .load::<DbAuditEntry>(&mut conn)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<AuditEntry>, _>>()?;
+
+        Ok(entries)
+    }
+}
+
+impl AuditLogWriter for DieselRepository {
+    fn log_action(&self, entry: &NewAuditEntry) -> RepositoryResult<usize> {
+        let mut conn = self.conn()?;
+
+        let affected = diesel::insert_into(audit_log::table)
+            .values(DbNewAuditEntry::from(entry))
+            .execute(&mut conn)?;
+
+        Ok(affected)
+    }
+}
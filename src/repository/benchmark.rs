@@ -1,10 +1,87 @@
+use chrono::Utc;
 use diesel::prelude::*;
-use pushkind_common::repository::errors::RepositoryResult;
+use pushkind_common::db::DbConnection;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
 
 use crate::domain::benchmark::{Benchmark, NewBenchmark};
 use crate::domain::types::{BenchmarkId, BenchmarkSku, HubId, ProductId, SimilarityDistance};
 use crate::models::benchmark::{Benchmark as DbBenchmark, NewBenchmark as DbNewBenchmark};
-use crate::repository::{BenchmarkListQuery, BenchmarkReader, BenchmarkWriter, DieselRepository};
+use crate::repository::{
+    BENCHMARK_DISTANCE_BATCH_SIZE, BenchmarkListQuery, BenchmarkReader, BenchmarkWriter,
+    DieselRepository, UpsertOutcome, escape_like, map_unique_violation,
+};
+
+/// Fail with `NotFound` unless the benchmark and the product's crawler belong to `hub_id`.
+fn ensure_association_in_hub(
+    conn: &mut DbConnection,
+    benchmark_id: BenchmarkId,
+    product_id: ProductId,
+    hub_id: HubId,
+) -> RepositoryResult<()> {
+    use crate::schema::{benchmarks, crawlers, products};
+
+    let benchmark_in_hub = diesel::select(diesel::dsl::exists(
+        benchmarks::table
+            .filter(benchmarks::id.eq(benchmark_id.get()))
+            .filter(benchmarks::hub_id.eq(hub_id.get())),
+    ))
+    .get_result::<bool>(conn)?;
+    let product_in_hub = diesel::select(diesel::dsl::exists(
+        products::table
+            .inner_join(crawlers::table)
+            .filter(products::id.eq(product_id.get()))
+            .filter(crawlers::hub_id.eq(hub_id.get())),
+    ))
+    .get_result::<bool>(conn)?;
+
+    if benchmark_in_hub && product_in_hub {
+        Ok(())
+    } else {
+        Err(RepositoryError::NotFound)
+    }
+}
+
+fn delete_association(
+    conn: &mut DbConnection,
+    benchmark_id: BenchmarkId,
+    product_id: ProductId,
+) -> RepositoryResult<usize> {
+    use crate::schema::product_benchmark;
+
+    let affected = diesel::delete(
+        product_benchmark::table
+            .filter(product_benchmark::benchmark_id.eq(benchmark_id.get()))
+            .filter(product_benchmark::product_id.eq(product_id.get())),
+    )
+    .execute(conn)?;
+
+    Ok(affected)
+}
+
+fn insert_association(
+    conn: &mut DbConnection,
+    benchmark_id: BenchmarkId,
+    product_id: ProductId,
+    distance: SimilarityDistance,
+) -> RepositoryResult<usize> {
+    use crate::schema::product_benchmark;
+
+    // Insert association entry with similarity distance
+    let affected = diesel::insert_into(product_benchmark::table)
+        .values((
+            product_benchmark::benchmark_id.eq(benchmark_id.get()),
+            product_benchmark::product_id.eq(product_id.get()),
+            product_benchmark::distance.eq(distance.get()),
+        ))
+        .on_conflict((
+            product_benchmark::product_id,
+            product_benchmark::benchmark_id,
+        ))
+        .do_nothing()
+        .execute(conn)?;
+
+    Ok(affected)
+}
 
 impl BenchmarkReader for DieselRepository {
     fn get_benchmark_by_id(
@@ -30,14 +107,50 @@ impl BenchmarkReader for DieselRepository {
         &self,
         query: BenchmarkListQuery,
     ) -> RepositoryResult<(usize, Vec<Benchmark>)> {
-        use crate::schema::benchmarks;
+        use crate::schema::{benchmarks, product_benchmark};
 
         let mut conn = self.conn()?;
 
         let query_builder = || {
-            benchmarks::table
+            let mut items = benchmarks::table
                 .filter(benchmarks::hub_id.eq(query.hub_id.get()))
-                .into_boxed::<diesel::sqlite::Sqlite>()
+                .into_boxed::<diesel::sqlite::Sqlite>();
+            if !query.include_archived {
+                items = items.filter(benchmarks::archived_at.is_null());
+            }
+            if let Some(category) = &query.category {
+                if query.include_subcategories {
+                    let pattern = format!("{}/%", escape_like(category.as_str()));
+                    items = items.filter(
+                        benchmarks::category
+                            .eq(category.as_str())
+                            .or(benchmarks::category.like(pattern).escape('\\')),
+                    );
+                } else {
+                    items = items.filter(benchmarks::category.eq(category.as_str()));
+                }
+            }
+            if query.only_unmatched {
+                items = items.filter(diesel::dsl::not(diesel::dsl::exists(
+                    product_benchmark::table
+                        .filter(product_benchmark::benchmark_id.eq(benchmarks::id)),
+                )));
+            }
+            if let Some(search) = query
+                .search
+                .as_deref()
+                .map(str::trim)
+                .filter(|search| !search.is_empty())
+            {
+                let pattern = format!("%{}%", escape_like(search));
+                items = items.filter(
+                    benchmarks::name
+                        .like(pattern.clone())
+                        .escape('\\')
+                        .or(benchmarks::sku.like(pattern).escape('\\')),
+                );
+            }
+            items
         };
 
         let total = query_builder().count().get_result::<i64>(&mut conn)? as usize;
@@ -62,6 +175,25 @@ impl BenchmarkReader for DieselRepository {
         Ok((total, items))
     }
 
+    fn get_benchmark_by_sku(
+        &self,
+        sku: &BenchmarkSku,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<Benchmark>> {
+        use crate::schema::benchmarks;
+
+        let mut conn = self.conn()?;
+
+        let benchmark = benchmarks::table
+            .filter(benchmarks::sku.eq(sku.as_str()))
+            .filter(benchmarks::hub_id.eq(hub_id.get()))
+            .first::<DbBenchmark>(&mut conn)
+            .optional()?;
+
+        let benchmark = benchmark.map(TryInto::try_into).transpose()?;
+        Ok(benchmark)
+    }
+
     fn list_benchmarks_by_hub_and_sku(
         &self,
         hub_id: HubId,
@@ -81,23 +213,121 @@ impl BenchmarkReader for DieselRepository {
 
         Ok(items)
     }
-}
-impl BenchmarkWriter for DieselRepository {
-    fn create_benchmark(&self, benchmarks: &[NewBenchmark]) -> RepositoryResult<usize> {
+
+    fn list_benchmarks_missing_embeddings(
+        &self,
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<Benchmark>> {
         use crate::schema::benchmarks;
 
         let mut conn = self.conn()?;
 
-        let db_benchmarks = benchmarks
-            .iter()
-            .map(|benchmark| benchmark.into())
-            .collect::<Vec<DbNewBenchmark>>();
+        let items = benchmarks::table
+            .filter(benchmarks::hub_id.eq(hub_id.get()))
+            .filter(
+                benchmarks::embedding
+                    .is_null()
+                    .or(benchmarks::embedding.eq(Vec::<u8>::new())),
+            )
+            .order(benchmarks::id.asc())
+            .load::<DbBenchmark>(&mut conn)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Benchmark>, _>>()?;
 
-        let affected = diesel::insert_into(benchmarks::table)
-            .values(&db_benchmarks)
-            .execute(&mut conn)?;
+        Ok(items)
+    }
 
-        Ok(affected)
+    fn list_benchmarks_for_product(
+        &self,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<Benchmark>> {
+        use crate::schema::{benchmarks, product_benchmark};
+
+        let mut conn = self.conn()?;
+
+        let items = benchmarks::table
+            .inner_join(product_benchmark::table)
+            .filter(product_benchmark::product_id.eq(product_id.get()))
+            .filter(benchmarks::hub_id.eq(hub_id.get()))
+            .select(benchmarks::all_columns)
+            .order(benchmarks::id.asc())
+            .load::<DbBenchmark>(&mut conn)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Benchmark>, _>>()?;
+
+        Ok(items)
+    }
+
+    fn get_association_distance(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<SimilarityDistance>> {
+        use crate::schema::{benchmarks, crawlers, product_benchmark, products};
+
+        let mut conn = self.conn()?;
+
+        let distance = product_benchmark::table
+            .inner_join(benchmarks::table)
+            .inner_join(products::table.inner_join(crawlers::table))
+            .filter(product_benchmark::benchmark_id.eq(benchmark_id.get()))
+            .filter(product_benchmark::product_id.eq(product_id.get()))
+            .filter(benchmarks::hub_id.eq(hub_id.get()))
+            .filter(crawlers::hub_id.eq(hub_id.get()))
+            .select(product_benchmark::distance)
+            .first::<f32>(&mut conn)
+            .optional()?;
+
+        let distance = distance.map(SimilarityDistance::new).transpose()?;
+        Ok(distance)
+    }
+}
+impl BenchmarkWriter for DieselRepository {
+    fn create_benchmark(
+        &self,
+        benchmarks: &[NewBenchmark],
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<BenchmarkId>> {
+        use crate::schema::benchmarks;
+
+        ensure_benchmarks_in_hub(benchmarks, hub_id)?;
+
+        self.transaction(|conn| {
+            let mut ids = Vec::with_capacity(benchmarks.len());
+            for benchmark in benchmarks {
+                let db_benchmark: DbNewBenchmark = benchmark.into();
+                let id = diesel::insert_into(benchmarks::table)
+                    .values(&db_benchmark)
+                    .returning(benchmarks::id)
+                    .get_result::<i32>(conn)
+                    .map_err(map_unique_violation)?;
+                ids.push(BenchmarkId::new(id)?);
+            }
+            Ok(ids)
+        })
+    }
+
+    fn upsert_benchmark(&self, benchmark: &NewBenchmark) -> RepositoryResult<UpsertOutcome> {
+        self.transaction(|conn| upsert_benchmark_row(conn, benchmark))
+    }
+
+    fn upsert_benchmarks(
+        &self,
+        benchmarks: &[NewBenchmark],
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<UpsertOutcome>> {
+        ensure_benchmarks_in_hub(benchmarks, hub_id)?;
+
+        self.transaction(|conn| {
+            benchmarks
+                .iter()
+                .map(|benchmark| upsert_benchmark_row(conn, benchmark))
+                .collect()
+        })
     }
 
     fn update_benchmark(
@@ -110,8 +340,34 @@ impl BenchmarkWriter for DieselRepository {
         let mut conn = self.conn()?;
         let db_benchmark: DbNewBenchmark = benchmark.into();
 
-        let affected =
-            diesel::update(benchmarks::table.filter(benchmarks::id.eq(benchmark_id.get())))
+        conn.transaction(|conn| {
+            let current = benchmarks::table
+                .filter(benchmarks::id.eq(benchmark_id.get()))
+                .select((
+                    benchmarks::name,
+                    benchmarks::category,
+                    benchmarks::units,
+                    benchmarks::description,
+                ))
+                .first::<(String, String, String, String)>(conn)
+                .optional()?;
+            let Some((name, category, units, description)) = current else {
+                return Ok(0);
+            };
+            // The stored embedding describes the old text; drop it so the worker re-embeds.
+            let text_changed = name != db_benchmark.name
+                || category != db_benchmark.category
+                || units != db_benchmark.units
+                || description != db_benchmark.description;
+
+            let target = benchmarks::table.filter(benchmarks::id.eq(benchmark_id.get()));
+            if text_changed {
+                diesel::update(target)
+                    .set(benchmarks::embedding.eq::<Option<Vec<u8>>>(None))
+                    .execute(conn)?;
+            }
+
+            let affected = diesel::update(target)
                 .set((
                     benchmarks::name.eq(db_benchmark.name),
                     benchmarks::sku.eq(db_benchmark.sku),
@@ -122,9 +378,10 @@ impl BenchmarkWriter for DieselRepository {
                     benchmarks::description.eq(db_benchmark.description),
                     benchmarks::updated_at.eq(db_benchmark.updated_at),
                 ))
-                .execute(&mut conn)?;
+                .execute(conn)?;
 
-        Ok(affected)
+            Ok(affected)
+        })
     }
 
     fn remove_benchmark_association(
@@ -132,44 +389,186 @@ impl BenchmarkWriter for DieselRepository {
         benchmark_id: BenchmarkId,
         product_id: ProductId,
     ) -> RepositoryResult<usize> {
-        use crate::schema::product_benchmark;
-
         let mut conn = self.conn()?;
+        delete_association(&mut conn, benchmark_id, product_id)
+    }
 
-        let affected = diesel::delete(
-            product_benchmark::table
-                .filter(product_benchmark::benchmark_id.eq(benchmark_id.get()))
-                .filter(product_benchmark::product_id.eq(product_id.get())),
-        )
-        .execute(&mut conn)?;
+    fn set_benchmark_association(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        distance: SimilarityDistance,
+    ) -> RepositoryResult<usize> {
+        let mut conn = self.conn()?;
+        insert_association(&mut conn, benchmark_id, product_id, distance)
+    }
 
-        Ok(affected)
+    fn remove_benchmark_association_in_hub(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<usize> {
+        self.transaction(|conn| {
+            ensure_association_in_hub(conn, benchmark_id, product_id, hub_id)?;
+            delete_association(conn, benchmark_id, product_id)
+        })
     }
 
-    fn set_benchmark_association(
+    fn set_benchmark_association_in_hub(
         &self,
         benchmark_id: BenchmarkId,
         product_id: ProductId,
+        hub_id: HubId,
         distance: SimilarityDistance,
+    ) -> RepositoryResult<usize> {
+        self.transaction(|conn| {
+            ensure_association_in_hub(conn, benchmark_id, product_id, hub_id)?;
+            insert_association(conn, benchmark_id, product_id, distance)
+        })
+    }
+
+    fn bulk_update_benchmark_distances(
+        &self,
+        associations: &[(BenchmarkId, ProductId, SimilarityDistance)],
     ) -> RepositoryResult<usize> {
         use crate::schema::product_benchmark;
+        use diesel::upsert::excluded;
+
+        let mut conn = self.conn()?;
+
+        conn.transaction(|conn| {
+            let mut affected = 0;
+            for chunk in associations.chunks(BENCHMARK_DISTANCE_BATCH_SIZE) {
+                let rows = chunk
+                    .iter()
+                    .map(|(benchmark_id, product_id, distance)| {
+                        (
+                            product_benchmark::benchmark_id.eq(benchmark_id.get()),
+                            product_benchmark::product_id.eq(product_id.get()),
+                            product_benchmark::distance.eq(distance.get()),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                affected += diesel::insert_into(product_benchmark::table)
+                    .values(rows)
+                    .on_conflict((
+                        product_benchmark::product_id,
+                        product_benchmark::benchmark_id,
+                    ))
+                    .do_update()
+                    .set(product_benchmark::distance.eq(excluded(product_benchmark::distance)))
+                    .execute(conn)?;
+            }
+            Ok(affected)
+        })
+    }
+
+    fn archive_benchmark(
+        &self,
+        benchmark_id: BenchmarkId,
+        hub_id: HubId,
+    ) -> RepositoryResult<usize> {
+        use crate::schema::benchmarks;
 
         let mut conn = self.conn()?;
 
-        // Insert association entry with similarity distance
-        let affected = diesel::insert_into(product_benchmark::table)
-            .values((
-                product_benchmark::benchmark_id.eq(benchmark_id.get()),
-                product_benchmark::product_id.eq(product_id.get()),
-                product_benchmark::distance.eq(distance.get()),
-            ))
-            .on_conflict((
-                product_benchmark::product_id,
-                product_benchmark::benchmark_id,
-            ))
-            .do_nothing()
-            .execute(&mut conn)?;
+        let now = Utc::now().naive_utc();
+
+        // Associations are left in place so the benchmark can be restored later.
+        let affected = diesel::update(
+            benchmarks::table
+                .filter(benchmarks::id.eq(benchmark_id.get()))
+                .filter(benchmarks::hub_id.eq(hub_id.get()))
+                .filter(benchmarks::archived_at.is_null()),
+        )
+        .set((
+            benchmarks::archived_at.eq(Some(now)),
+            benchmarks::updated_at.eq(now),
+        ))
+        .execute(&mut conn)?;
 
         Ok(affected)
     }
 }
+
+/// Insert `benchmark` or update the row with the same hub and SKU.
+///
+/// Like [`BenchmarkWriter::update_benchmark`], an update that changes the
+/// name, category, units or description clears the stored embedding.
+fn upsert_benchmark_row(
+    conn: &mut DbConnection,
+    benchmark: &NewBenchmark,
+) -> RepositoryResult<UpsertOutcome> {
+    use crate::schema::benchmarks;
+    use diesel::upsert::excluded;
+
+    let db_benchmark: DbNewBenchmark = benchmark.into();
+
+    let current = benchmarks::table
+        .filter(benchmarks::hub_id.eq(db_benchmark.hub_id))
+        .filter(benchmarks::sku.eq(db_benchmark.sku))
+        .select((
+            benchmarks::id,
+            benchmarks::name,
+            benchmarks::category,
+            benchmarks::units,
+            benchmarks::description,
+        ))
+        .first::<(i32, String, String, String, String)>(conn)
+        .optional()?;
+
+    if let Some((id, name, category, units, description)) = &current {
+        // The stored embedding describes the old text; drop it so the worker re-embeds.
+        let text_changed = *name != db_benchmark.name
+            || *category != db_benchmark.category
+            || *units != db_benchmark.units
+            || *description != db_benchmark.description;
+        if text_changed {
+            diesel::update(benchmarks::table.filter(benchmarks::id.eq(*id)))
+                .set(benchmarks::embedding.eq::<Option<Vec<u8>>>(None))
+                .execute(conn)?;
+        }
+    }
+
+    diesel::insert_into(benchmarks::table)
+        .values(&db_benchmark)
+        .on_conflict((benchmarks::hub_id, benchmarks::sku))
+        .do_update()
+        .set((
+            benchmarks::name.eq(excluded(benchmarks::name)),
+            benchmarks::category.eq(excluded(benchmarks::category)),
+            benchmarks::units.eq(excluded(benchmarks::units)),
+            benchmarks::price.eq(excluded(benchmarks::price)),
+            benchmarks::amount.eq(excluded(benchmarks::amount)),
+            benchmarks::description.eq(excluded(benchmarks::description)),
+            benchmarks::updated_at.eq(excluded(benchmarks::updated_at)),
+        ))
+        .execute(conn)?;
+
+    Ok(if current.is_some() {
+        UpsertOutcome::Updated
+    } else {
+        UpsertOutcome::Created
+    })
+}
+
+/// Reject benchmark batches containing records for a hub other than `hub_id`.
+pub(crate) fn ensure_benchmarks_in_hub(
+    benchmarks: &[NewBenchmark],
+    hub_id: HubId,
+) -> RepositoryResult<()> {
+    match benchmarks
+        .iter()
+        .find(|benchmark| benchmark.hub_id != hub_id)
+    {
+        Some(benchmark) => Err(RepositoryError::ValidationError(format!(
+            "benchmark {} belongs to hub {}, expected hub {}",
+            benchmark.sku.as_str(),
+            benchmark.hub_id.get(),
+            hub_id.get()
+        ))),
+        None => Ok(()),
+    }
+}
@@ -1,10 +1,15 @@
 use diesel::prelude::*;
+use diesel::sql_types::Text;
 use pushkind_common::repository::errors::RepositoryResult;
 
 use crate::domain::category::{Category, NewCategory};
 use crate::domain::types::{CategoryAssignmentSource, CategoryId, CategoryName, HubId};
 use crate::models::category::{Category as DbCategory, NewCategory as DbNewCategory};
-use crate::repository::{CategoryListQuery, CategoryReader, CategoryWriter, DieselRepository};
+use crate::repository::{
+    CategoryListQuery, CategoryReader, CategoryWriter, DieselRepository, map_unique_violation,
+};
+
+define_sql_function!(fn lower(x: Text) -> Text);
 
 impl CategoryReader for DieselRepository {
     fn list_categories(
@@ -58,6 +63,25 @@ impl CategoryReader for DieselRepository {
         let category = category.map(TryInto::try_into).transpose()?;
         Ok(category)
     }
+
+    fn get_category_by_path(
+        &self,
+        path: &CategoryName,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<Category>> {
+        use crate::schema::categories;
+
+        let mut conn = self.conn()?;
+
+        let category = categories::table
+            .filter(lower(categories::name).eq(lower(path.as_str())))
+            .filter(categories::hub_id.eq(hub_id.get()))
+            .first::<DbCategory>(&mut conn)
+            .optional()?;
+
+        let category = category.map(TryInto::try_into).transpose()?;
+        Ok(category)
+    }
 }
 
 impl CategoryWriter for DieselRepository {
@@ -69,7 +93,8 @@ impl CategoryWriter for DieselRepository {
 
         let affected = diesel::insert_into(categories::table)
             .values(db_category)
-            .execute(&mut conn)?;
+            .execute(&mut conn)
+            .map_err(map_unique_violation)?;
 
         Ok(affected)
     }
@@ -100,6 +125,27 @@ impl CategoryWriter for DieselRepository {
         Ok(affected)
     }
 
+    fn update_category_embedding(
+        &self,
+        id: CategoryId,
+        hub_id: HubId,
+        embedding: &[u8],
+    ) -> RepositoryResult<usize> {
+        use crate::schema::categories;
+
+        let mut conn = self.conn()?;
+
+        let affected = diesel::update(
+            categories::table
+                .filter(categories::id.eq(id.get()))
+                .filter(categories::hub_id.eq(hub_id.get())),
+        )
+        .set(categories::embedding.eq(embedding))
+        .execute(&mut conn)?;
+
+        Ok(affected)
+    }
+
     fn delete_category(&self, id: CategoryId, hub_id: HubId) -> RepositoryResult<usize> {
         use crate::schema::{categories, crawlers, products};
 
@@ -133,4 +179,42 @@ impl CategoryWriter for DieselRepository {
 
         Ok(affected)
     }
+
+    fn merge_categories(
+        &self,
+        source_id: CategoryId,
+        target_id: CategoryId,
+        hub_id: HubId,
+    ) -> RepositoryResult<usize> {
+        use crate::schema::{categories, crawlers, products};
+
+        let mut conn = self.conn()?;
+
+        let reassigned = conn.transaction(|conn| {
+            let reassigned = diesel::update(
+                products::table
+                    .filter(products::category_id.eq(Some(source_id.get())))
+                    .filter(
+                        products::crawler_id.eq_any(
+                            crawlers::table
+                                .filter(crawlers::hub_id.eq(hub_id.get()))
+                                .select(crawlers::id),
+                        ),
+                    ),
+            )
+            .set(products::category_id.eq(Some(target_id.get())))
+            .execute(conn)?;
+
+            diesel::delete(
+                categories::table
+                    .filter(categories::id.eq(source_id.get()))
+                    .filter(categories::hub_id.eq(hub_id.get())),
+            )
+            .execute(conn)?;
+
+            Ok::<_, diesel::result::Error>(reassigned)
+        })?;
+
+        Ok(reassigned)
+    }
 }
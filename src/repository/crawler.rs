@@ -1,27 +1,64 @@
 use diesel::prelude::*;
-use pushkind_common::repository::errors::RepositoryResult;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
 
-use crate::domain::crawler::Crawler;
+use crate::domain::crawler::{Crawler, NewCrawler, UpdateCrawlerFields};
 use crate::domain::types::{CrawlerId, HubId};
-use crate::models::crawler::Crawler as DbCrawler;
-use crate::repository::{CrawlerReader, DieselRepository};
+use crate::models::crawler::{Crawler as DbCrawler, NewCrawler as DbNewCrawler};
+use crate::repository::{
+    CrawlerListOrder, CrawlerListQuery, CrawlerReader, CrawlerWriter, DieselRepository,
+    escape_like, map_unique_violation,
+};
 
 impl CrawlerReader for DieselRepository {
-    fn list_crawlers(&self, hub_id: HubId) -> RepositoryResult<Vec<Crawler>> {
+    fn list_crawlers(&self, query: CrawlerListQuery) -> RepositoryResult<(usize, Vec<Crawler>)> {
         use crate::schema::crawlers;
 
         let mut conn = self.conn()?;
 
-        let results = crawlers::table
-            .filter(crawlers::hub_id.eq(hub_id.get()))
-            .order(crawlers::id.asc())
-            .get_results::<DbCrawler>(&mut conn)?;
+        let query_builder = || {
+            let mut items = crawlers::table
+                .filter(crawlers::hub_id.eq(query.hub_id.get()))
+                .into_boxed::<diesel::sqlite::Sqlite>();
+            if let Some(search) = query
+                .search
+                .as_deref()
+                .map(str::trim)
+                .filter(|search| !search.is_empty())
+            {
+                let pattern = format!("%{}%", escape_like(search));
+                items = items.filter(
+                    crawlers::name
+                        .like(pattern.clone())
+                        .escape('\\')
+                        .or(crawlers::url.like(pattern).escape('\\')),
+                );
+            }
+            items
+        };
+
+        let total = query_builder().count().get_result::<i64>(&mut conn)? as usize;
+
+        let mut items = query_builder();
+        if let Some(pagination) = &query.pagination {
+            let offset = ((pagination.page.max(1) - 1) * pagination.per_page) as i64;
+            let limit = pagination.per_page as i64;
+            items = items.offset(offset).limit(limit);
+        }
+        let items = match query.order {
+            CrawlerListOrder::ByName => items.order((crawlers::name.asc(), crawlers::id.asc())),
+            CrawlerListOrder::ById => items.order(crawlers::id.asc()),
+            CrawlerListOrder::ByNumProducts => {
+                items.order((crawlers::num_products.desc(), crawlers::id.asc()))
+            }
+        };
+
+        let results = items.get_results::<DbCrawler>(&mut conn)?;
 
         let results = results
             .into_iter()
             .map(TryInto::try_into)
             .collect::<Result<Vec<Crawler>, _>>()?;
-        Ok(results)
+        Ok((total, results))
     }
 
     fn get_crawler_by_id(&self, id: CrawlerId, hub_id: HubId) -> RepositoryResult<Option<Crawler>> {
@@ -38,4 +75,113 @@ impl CrawlerReader for DieselRepository {
         let result = result.map(TryInto::try_into).transpose()?;
         Ok(result)
     }
+
+    fn get_crawler_hub_id(&self, id: CrawlerId) -> RepositoryResult<Option<HubId>> {
+        use crate::schema::crawlers;
+
+        let mut conn = self.conn()?;
+
+        let hub_id = crawlers::table
+            .filter(crawlers::id.eq(id.get()))
+            .select(crawlers::hub_id)
+            .first::<i32>(&mut conn)
+            .optional()?;
+
+        let hub_id = hub_id.map(HubId::new).transpose()?;
+        Ok(hub_id)
+    }
+}
+
+impl CrawlerWriter for DieselRepository {
+    fn create_crawler(&self, crawler: &NewCrawler) -> RepositoryResult<usize> {
+        use crate::schema::crawlers;
+
+        let mut conn = self.conn()?;
+        let db_crawler: DbNewCrawler = crawler.clone().into();
+
+        let affected = diesel::insert_into(crawlers::table)
+            .values(db_crawler)
+            .execute(&mut conn)
+            .map_err(map_unique_violation)?;
+
+        Ok(affected)
+    }
+
+    fn update_crawler(
+        &self,
+        id: CrawlerId,
+        hub_id: HubId,
+        fields: &UpdateCrawlerFields,
+    ) -> RepositoryResult<usize> {
+        use crate::schema::crawlers;
+
+        self.transaction(|conn| {
+            let duplicates = crawlers::table
+                .filter(crawlers::hub_id.eq(hub_id.get()))
+                .filter(crawlers::url.eq(fields.url.as_str()))
+                .filter(crawlers::id.ne(id.get()))
+                .count()
+                .get_result::<i64>(conn)?;
+            if duplicates > 0 {
+                return Err(RepositoryError::ConflictError);
+            }
+
+            let affected = diesel::update(
+                crawlers::table
+                    .filter(crawlers::id.eq(id.get()))
+                    .filter(crawlers::hub_id.eq(hub_id.get())),
+            )
+            .set((
+                crawlers::name.eq(fields.name.as_str()),
+                crawlers::url.eq(fields.url.as_str()),
+                crawlers::selector.eq(fields.selector.as_str()),
+                crawlers::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)?;
+
+            Ok(affected)
+        })
+    }
+
+    fn delete_crawler(&self, id: CrawlerId, hub_id: HubId) -> RepositoryResult<usize> {
+        use crate::schema::{crawlers, product_benchmark, product_images, products};
+
+        self.transaction(|conn| {
+            let in_hub = diesel::select(diesel::dsl::exists(
+                crawlers::table
+                    .filter(crawlers::id.eq(id.get()))
+                    .filter(crawlers::hub_id.eq(hub_id.get())),
+            ))
+            .get_result::<bool>(conn)?;
+            if !in_hub {
+                return Ok(0);
+            }
+
+            let crawler_products = || {
+                products::table
+                    .filter(products::crawler_id.eq(id.get()))
+                    .select(products::id)
+            };
+            diesel::delete(
+                product_benchmark::table
+                    .filter(product_benchmark::product_id.eq_any(crawler_products())),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                product_images::table.filter(product_images::product_id.eq_any(crawler_products())),
+            )
+            .execute(conn)?;
+            diesel::delete(products::table.filter(products::crawler_id.eq(id.get())))
+                .execute(conn)?;
+
+            let affected = diesel::delete(
+                crawlers::table
+                    .filter(crawlers::id.eq(id.get()))
+                    .filter(crawlers::hub_id.eq(hub_id.get())),
+            )
+            .execute(conn)?;
+
+            Ok(affected)
+        })
+    }
 }
@@ -1,23 +1,29 @@
 use std::collections::HashMap;
 
+use chrono::NaiveDateTime;
+
 use pushkind_common::db::{DbConnection, DbPool};
 use pushkind_common::pagination::Pagination;
-use pushkind_common::repository::errors::RepositoryResult;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
 
+use crate::domain::audit::{AuditEntry, NewAuditEntry};
 use crate::domain::benchmark::{Benchmark, NewBenchmark};
 use crate::domain::category::{Category, NewCategory};
-use crate::domain::crawler::Crawler;
+use crate::domain::crawler::{Crawler, NewCrawler, UpdateCrawlerFields};
 use crate::domain::product::{NewProduct, Product};
+use crate::domain::statistics::HubStatistics;
 use crate::domain::types::{
-    BenchmarkId, BenchmarkSku, CategoryId, CategoryName, CrawlerId, HubId, ProductId, ProductSku,
-    SimilarityDistance,
+    BenchmarkId, BenchmarkSku, CategoryId, CategoryName, CrawlerId, HubId, ImageUrl, ProductId,
+    ProductSku, SimilarityDistance,
 };
 
+pub mod audit;
 pub mod benchmark;
 pub mod category;
 pub mod crawler;
 pub mod processing;
 pub mod product;
+pub mod statistics;
 #[cfg(test)]
 pub mod test;
 
@@ -40,6 +46,74 @@ impl DieselRepository {
     fn conn(&self) -> RepositoryResult<DbConnection> {
         Ok(self.pool.get()?)
     }
+
+    /// Run `f` on a single pooled connection inside one database transaction.
+    ///
+    /// Every read and write in the closure sees the same state; returning an
+    /// error rolls all of them back.
+    pub fn transaction<T, F>(&self, f: F) -> RepositoryResult<T>
+    where
+        F: FnOnce(&mut DbConnection) -> RepositoryResult<T>,
+    {
+        use diesel::Connection;
+
+        let mut conn = self.conn()?;
+        conn.transaction::<T, RepositoryError, _>(f)
+    }
+}
+
+/// Convert a Diesel error, reporting UNIQUE constraint violations as
+/// [`RepositoryError::ConflictError`] so callers can tell duplicates apart
+/// from genuine database failures.
+pub(crate) fn map_unique_violation(err: diesel::result::Error) -> RepositoryError {
+    use diesel::result::{DatabaseErrorKind, Error};
+
+    match err {
+        Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+            RepositoryError::ConflictError
+        }
+        err => err.into(),
+    }
+}
+
+/// Escape `LIKE` wildcards so `value` only matches literally (escape char `\`).
+pub(crate) fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Sort direction for ordered repository reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortDirection {
+    /// Smallest values first.
+    #[default]
+    Asc,
+    /// Largest values first.
+    Desc,
+}
+
+/// Result of an insert-or-update keyed by a natural identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    /// A new row was inserted.
+    Created,
+    /// An existing row was updated in place.
+    Updated,
+}
+
+/// Result of moving products from one crawler to another.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProductReassignment {
+    /// Products now owned by the target crawler.
+    pub moved: usize,
+    /// Products left on the source crawler because the target already has their URL.
+    pub skipped_url_conflicts: usize,
 }
 
 /// Query parameters used when listing or searching products.
@@ -53,6 +127,13 @@ pub struct ProductListQuery {
     pub benchmark_id: Option<BenchmarkId>,
     /// Full-text search string.
     pub search: Option<String>,
+    /// Restrict to products not yet marked as reviewed.
+    pub only_unreviewed: bool,
+    /// Restrict to products without any benchmark association.
+    pub only_unassociated: bool,
+    /// Restrict to products updated strictly after this moment; results are
+    /// then ordered by `updated_at` instead of name.
+    pub updated_since: Option<NaiveDateTime>,
     /// Pagination parameters.
     pub pagination: Option<Pagination>,
 }
@@ -64,6 +145,68 @@ pub struct BenchmarkListQuery {
     pub hub_id: HubId,
     /// Pagination parameters.
     pub pagination: Option<Pagination>,
+    /// Whether archived benchmarks are included in the results.
+    pub include_archived: bool,
+    /// Restrict results to benchmarks in this category.
+    pub category: Option<CategoryName>,
+    /// Also match benchmarks in subcategories of [`Self::category`] (`path/...`).
+    pub include_subcategories: bool,
+    /// Restrict to benchmarks without any product association.
+    pub only_unmatched: bool,
+    /// Case-insensitive substring of the benchmark name or SKU.
+    pub search: Option<String>,
+}
+
+/// Ordering applied when listing crawlers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CrawlerListOrder {
+    /// Alphabetical by crawler name.
+    ByName,
+    /// Creation order.
+    #[default]
+    ById,
+    /// Most products first.
+    ByNumProducts,
+}
+
+/// Query parameters for listing crawlers belonging to a hub.
+#[derive(Debug, Clone)]
+pub struct CrawlerListQuery {
+    /// Hub identifier.
+    pub hub_id: HubId,
+    /// Pagination parameters.
+    pub pagination: Option<Pagination>,
+    /// Substring of the crawler name or URL; case-insensitive for ASCII
+    /// letters only, matching SQLite `LIKE`.
+    pub search: Option<String>,
+    /// Result ordering.
+    pub order: CrawlerListOrder,
+}
+
+impl CrawlerListQuery {
+    pub fn new(hub_id: HubId) -> Self {
+        Self {
+            hub_id,
+            pagination: None,
+            search: None,
+            order: CrawlerListOrder::default(),
+        }
+    }
+
+    pub fn paginate(mut self, page: usize, per_page: usize) -> Self {
+        self.pagination = Some(Pagination { page, per_page });
+        self
+    }
+
+    pub fn search(mut self, search: impl Into<String>) -> Self {
+        self.search = Some(search.into());
+        self
+    }
+
+    pub fn order(mut self, order: CrawlerListOrder) -> Self {
+        self.order = order;
+        self
+    }
 }
 
 /// Query parameters for listing categories belonging to a hub.
@@ -93,12 +236,37 @@ impl BenchmarkListQuery {
         Self {
             hub_id,
             pagination: None,
+            include_archived: false,
+            category: None,
+            include_subcategories: false,
+            only_unmatched: false,
+            search: None,
         }
     }
     pub fn paginate(mut self, page: usize, per_page: usize) -> Self {
         self.pagination = Some(Pagination { page, per_page });
         self
     }
+    pub fn include_archived(mut self) -> Self {
+        self.include_archived = true;
+        self
+    }
+    pub fn category(mut self, category: CategoryName) -> Self {
+        self.category = Some(category);
+        self
+    }
+    pub fn include_subcategories(mut self, include_subcategories: bool) -> Self {
+        self.include_subcategories = include_subcategories;
+        self
+    }
+    pub fn only_unmatched(mut self) -> Self {
+        self.only_unmatched = true;
+        self
+    }
+    pub fn search(mut self, search: impl Into<String>) -> Self {
+        self.search = Some(search.into());
+        self
+    }
 }
 
 impl ProductListQuery {
@@ -118,6 +286,18 @@ impl ProductListQuery {
         self.search = Some(search.into());
         self
     }
+    pub fn only_unreviewed(mut self) -> Self {
+        self.only_unreviewed = true;
+        self
+    }
+    pub fn only_unassociated(mut self) -> Self {
+        self.only_unassociated = true;
+        self
+    }
+    pub fn updated_since(mut self, updated_since: NaiveDateTime) -> Self {
+        self.updated_since = Some(updated_since);
+        self
+    }
     pub fn paginate(mut self, page: usize, per_page: usize) -> Self {
         self.pagination = Some(Pagination { page, per_page });
         self
@@ -126,13 +306,36 @@ impl ProductListQuery {
 
 /// Read-only operations for crawler entities.
 pub trait CrawlerReader {
-    /// List all crawlers for a specific hub.
-    fn list_crawlers(&self, hub_id: HubId) -> RepositoryResult<Vec<Crawler>>;
+    /// List crawlers for a hub in the requested order, with the total number
+    /// of matches before pagination.
+    fn list_crawlers(&self, query: CrawlerListQuery) -> RepositoryResult<(usize, Vec<Crawler>)>;
     /// Retrieve a crawler by its identifier.
     fn get_crawler_by_id(&self, id: CrawlerId, hub_id: HubId) -> RepositoryResult<Option<Crawler>>;
+    /// Hub owning the crawler, regardless of the requesting hub.
+    ///
+    /// Only meant for auditing cross-hub access; never return its result to users.
+    fn get_crawler_hub_id(&self, id: CrawlerId) -> RepositoryResult<Option<HubId>>;
 }
 
-pub trait CrawlerWriter {}
+/// Write operations for crawler entities.
+pub trait CrawlerWriter {
+    /// Persist a new crawler.
+    fn create_crawler(&self, crawler: &NewCrawler) -> RepositoryResult<usize>;
+    /// Update name, URL and selector of a hub crawler.
+    ///
+    /// Returns `ConflictError` when another crawler of the hub already uses the URL.
+    fn update_crawler(
+        &self,
+        id: CrawlerId,
+        hub_id: HubId,
+        fields: &UpdateCrawlerFields,
+    ) -> RepositoryResult<usize>;
+    /// Delete a hub crawler together with its products, their images and
+    /// benchmark associations in one transaction.
+    ///
+    /// Returns the number of deleted crawlers; `0` when it is not in the hub.
+    fn delete_crawler(&self, id: CrawlerId, hub_id: HubId) -> RepositoryResult<usize>;
+}
 
 /// Read-only operations exposing processing state for a hub.
 pub trait ProcessingStateReader {
@@ -140,25 +343,93 @@ pub trait ProcessingStateReader {
     fn has_active_processing(&self, hub_id: HubId) -> RepositoryResult<bool>;
 }
 
+/// Read access to the audit log.
+pub trait AuditLogReader {
+    /// Most recent audit entries of the hub, newest first.
+    fn list_audit_entries(&self, hub_id: HubId, limit: usize) -> RepositoryResult<Vec<AuditEntry>>;
+}
+
+/// Append-only writes to the audit log.
+pub trait AuditLogWriter {
+    /// Record one user action.
+    fn log_action(&self, entry: &NewAuditEntry) -> RepositoryResult<usize>;
+}
+
+/// Aggregated counts used for hub data-quality reporting.
+pub trait StatisticsReader {
+    /// Entity and data-quality counts for the hub; archived benchmarks are not counted.
+    fn hub_statistics(&self, hub_id: HubId) -> RepositoryResult<HubStatistics>;
+}
+
+/// Largest number of associations written by one bulk distance statement.
+///
+/// Keeps each statement well below SQLite's bound parameter limit.
+pub const BENCHMARK_DISTANCE_BATCH_SIZE: usize = 500;
+
+/// Lazily loaded products; each item is fetched from storage on demand.
+pub type ProductStream = Box<dyn Iterator<Item = RepositoryResult<Product>> + Send>;
+
 /// Read-only operations for product entities.
 pub trait ProductReader {
     /// List products matching the supplied query parameters.
     fn list_products(&self, query: ProductListQuery) -> RepositoryResult<(usize, Vec<Product>)>;
-    /// Return a mapping of product identifiers to similarity distances for a benchmark.
+    /// Return product similarity distances for a benchmark ordered by distance.
     fn list_distances(
         &self,
         benchmark_id: BenchmarkId,
-    ) -> RepositoryResult<HashMap<ProductId, SimilarityDistance>>;
+        direction: SortDirection,
+    ) -> RepositoryResult<Vec<(ProductId, SimilarityDistance)>>;
+    /// Page through a crawler's products matched to a benchmark with their
+    /// distances, ordered by distance in `direction` (ties by product id).
+    ///
+    /// Returns the total number of matches alongside the requested page.
+    fn list_benchmark_matches(
+        &self,
+        benchmark_id: BenchmarkId,
+        crawler_id: CrawlerId,
+        direction: SortDirection,
+        pagination: Pagination,
+    ) -> RepositoryResult<(usize, Vec<(Product, SimilarityDistance)>)>;
     /// Perform a full-text search for products.
     fn search_products(&self, query: ProductListQuery) -> RepositoryResult<(usize, Vec<Product>)>;
     /// Retrieve a product by its identifier.
     fn get_product_by_id(&self, id: ProductId) -> RepositoryResult<Option<Product>>;
+    /// Retrieve the existing products among `ids` in one query, ordered by id.
+    fn get_products_by_ids(&self, ids: &[ProductId]) -> RepositoryResult<Vec<Product>>;
+    /// Lazily iterate a crawler's products ordered by id, loading `batch_size` rows at a time.
+    fn stream_products(
+        &self,
+        crawler_id: CrawlerId,
+        batch_size: usize,
+    ) -> RepositoryResult<ProductStream>;
     /// Retrieve products in crawler scope by SKU. Multiple rows indicate data conflict.
+    ///
+    /// Soft-deleted products are included, since they still occupy their SKU.
     fn list_products_by_crawler_and_sku(
         &self,
         crawler_id: CrawlerId,
         sku: &ProductSku,
     ) -> RepositoryResult<Vec<Product>>;
+    /// Like [`Self::list_products_by_crawler_and_sku`], without soft-deleted products.
+    fn list_active_products_by_crawler_and_sku(
+        &self,
+        crawler_id: CrawlerId,
+        sku: &ProductSku,
+    ) -> RepositoryResult<Vec<Product>>;
+    /// Count products per crawler in a hub with a single grouped query.
+    fn count_products_by_crawler(&self, hub_id: HubId) -> RepositoryResult<HashMap<i32, usize>>;
+    /// Count a hub crawler's products per canonical category, most products first.
+    ///
+    /// Products without `category_id` are counted under `None`.
+    fn list_product_categories(
+        &self,
+        crawler_id: CrawlerId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<(Option<CategoryName>, usize)>>;
+    /// List a crawler's soft-deleted products, most recently deleted first.
+    fn list_deleted_products(&self, crawler_id: CrawlerId) -> RepositoryResult<Vec<Product>>;
+    /// List a crawler's products that have no URL and so cannot be re-priced.
+    fn list_products_without_url(&self, crawler_id: CrawlerId) -> RepositoryResult<Vec<Product>>;
 }
 
 pub trait ProductWriter {
@@ -178,6 +449,34 @@ pub trait ProductWriter {
     ) -> RepositoryResult<usize>;
     /// Clear manual category assignment and mark source as automatic.
     fn clear_product_category_manual(&self, product_id: ProductId) -> RepositoryResult<usize>;
+    /// Mark a product in hub scope as reviewed.
+    fn mark_reviewed(&self, product_id: ProductId, hub_id: HubId) -> RepositoryResult<usize>;
+    /// Soft-delete a product in hub scope by setting `deleted_at`, removing its
+    /// benchmark associations in the same transaction.
+    ///
+    /// Deleted products are hidden from reads and listings but keep their row,
+    /// so a later crawl or upload of the same SKU updates the hidden row.
+    fn soft_delete_product(&self, product_id: ProductId, hub_id: HubId) -> RepositoryResult<usize>;
+    /// Restore a soft-deleted product in hub scope by clearing `deleted_at`.
+    fn restore_product(&self, product_id: ProductId, hub_id: HubId) -> RepositoryResult<usize>;
+    /// Move all products of `from` to `to` in one transaction.
+    ///
+    /// Both crawlers must belong to `hub_id`, otherwise `NotFound` is returned.
+    /// Products whose URL already exists on `to` stay on `from`.
+    fn reassign_products(
+        &self,
+        from: CrawlerId,
+        to: CrawlerId,
+        hub_id: HubId,
+    ) -> RepositoryResult<ProductReassignment>;
+    /// Replace all images of a product with `urls` in one transaction.
+    ///
+    /// Returns the number of image rows inserted.
+    fn set_product_images(
+        &self,
+        product_id: ProductId,
+        urls: &[ImageUrl],
+    ) -> RepositoryResult<usize>;
 }
 
 /// Read-only operations for category entities.
@@ -191,6 +490,13 @@ pub trait CategoryReader {
         id: CategoryId,
         hub_id: HubId,
     ) -> RepositoryResult<Option<Category>>;
+    /// Retrieve a category by its path within a hub, ignoring case like the
+    /// `(hub_id, LOWER(name))` unique index does.
+    fn get_category_by_path(
+        &self,
+        path: &CategoryName,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<Category>>;
 }
 
 /// Write operations for category entities.
@@ -205,8 +511,27 @@ pub trait CategoryWriter {
         name: &CategoryName,
         embedding: Option<&[u8]>,
     ) -> RepositoryResult<usize>;
+    /// Replace only the stored embedding of a hub category.
+    ///
+    /// Name and `updated_at` are left untouched so background embedding runs do
+    /// not show up as user edits.
+    fn update_category_embedding(
+        &self,
+        id: CategoryId,
+        hub_id: HubId,
+        embedding: &[u8],
+    ) -> RepositoryResult<usize>;
     /// Delete a category by id and hub.
     fn delete_category(&self, id: CategoryId, hub_id: HubId) -> RepositoryResult<usize>;
+    /// Move products from `source_id` to `target_id` and delete the source.
+    ///
+    /// Returns the number of reassigned products.
+    fn merge_categories(
+        &self,
+        source_id: CategoryId,
+        target_id: CategoryId,
+        hub_id: HubId,
+    ) -> RepositoryResult<usize>;
 }
 
 /// Read-only operations for benchmark entities.
@@ -222,19 +547,62 @@ pub trait BenchmarkReader {
         id: BenchmarkId,
         hub_id: HubId,
     ) -> RepositoryResult<Option<Benchmark>>;
+    /// Retrieve the hub benchmark with the given SKU, if any.
+    fn get_benchmark_by_sku(
+        &self,
+        sku: &BenchmarkSku,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<Benchmark>>;
     /// Retrieve benchmarks in hub scope by SKU. Multiple rows indicate data conflict.
     fn list_benchmarks_by_hub_and_sku(
         &self,
         hub_id: HubId,
         sku: &BenchmarkSku,
     ) -> RepositoryResult<Vec<Benchmark>>;
+    /// List hub benchmarks whose embedding is absent or empty.
+    fn list_benchmarks_missing_embeddings(&self, hub_id: HubId)
+    -> RepositoryResult<Vec<Benchmark>>;
+    /// List hub benchmarks associated with a product, ordered by id.
+    fn list_benchmarks_for_product(
+        &self,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<Benchmark>>;
+    /// Stored distance of one association, if both the benchmark and the
+    /// product's crawler belong to `hub_id`.
+    fn get_association_distance(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<SimilarityDistance>>;
 }
 
 /// Write operations for benchmark entities and their associations.
 pub trait BenchmarkWriter {
-    /// Persist new benchmark records.
-    fn create_benchmark(&self, benchmarks: &[NewBenchmark]) -> RepositoryResult<usize>;
+    /// Persist new benchmark records for `hub_id` in one transaction.
+    ///
+    /// Returns the identifiers of the created benchmarks in input order. Fails
+    /// with a validation error when any record belongs to another hub.
+    fn create_benchmark(
+        &self,
+        benchmarks: &[NewBenchmark],
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<BenchmarkId>>;
+    /// Insert a benchmark or update the existing one with the same hub and SKU.
+    fn upsert_benchmark(&self, benchmark: &NewBenchmark) -> RepositoryResult<UpsertOutcome>;
+    /// Upsert every benchmark by SKU inside one transaction.
+    ///
+    /// Either all rows are stored or none are. Fails with a validation error
+    /// when any record belongs to another hub.
+    fn upsert_benchmarks(
+        &self,
+        benchmarks: &[NewBenchmark],
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<UpsertOutcome>>;
     /// Update an existing benchmark row.
+    ///
+    /// Clears the stored embedding when name, category, units or description change.
     fn update_benchmark(
         &self,
         benchmark_id: BenchmarkId,
@@ -253,4 +621,39 @@ pub trait BenchmarkWriter {
         product_id: ProductId,
         distance: SimilarityDistance,
     ) -> RepositoryResult<usize>;
+    /// Remove an association after checking, in the same transaction, that the
+    /// benchmark and the product's crawler belong to `hub_id`.
+    ///
+    /// Returns [`RepositoryError::NotFound`] when either is outside the hub.
+    fn remove_benchmark_association_in_hub(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<usize>;
+    /// Create an association after checking, in the same transaction, that the
+    /// benchmark and the product's crawler belong to `hub_id`.
+    ///
+    /// Returns [`RepositoryError::NotFound`] when either is outside the hub.
+    fn set_benchmark_association_in_hub(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        hub_id: HubId,
+        distance: SimilarityDistance,
+    ) -> RepositoryResult<usize>;
+    /// Insert or update many benchmark associations with their distances.
+    ///
+    /// Rows are written in batches of [`BENCHMARK_DISTANCE_BATCH_SIZE`] inside one
+    /// transaction; existing associations get the new distance.
+    fn bulk_update_benchmark_distances(
+        &self,
+        associations: &[(BenchmarkId, ProductId, SimilarityDistance)],
+    ) -> RepositoryResult<usize>;
+    /// Archive a benchmark, hiding it from default listings while keeping its associations.
+    fn archive_benchmark(
+        &self,
+        benchmark_id: BenchmarkId,
+        hub_id: HubId,
+    ) -> RepositoryResult<usize>;
 }
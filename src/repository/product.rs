@@ -1,17 +1,22 @@
 use std::collections::HashMap;
 
-use chrono::Utc;
+use chrono::{NaiveDateTime, Utc};
 use diesel::prelude::*;
 use diesel::sql_types::{BigInt, Integer, Text};
-use pushkind_common::repository::errors::RepositoryResult;
+use pushkind_common::pagination::Pagination;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
 
 use crate::domain::product::{NewProduct, Product};
 use crate::domain::types::{
-    BenchmarkId, CategoryAssignmentSource, CategoryId, CategoryName, ImageUrl, ProductId,
-    ProductSku, SimilarityDistance,
+    BenchmarkId, CategoryAssignmentSource, CategoryId, CategoryName, CrawlerId, HubId, ImageUrl,
+    ProductId, ProductSku, SimilarityDistance,
 };
 use crate::models::product::{NewProduct as DbNewProduct, Product as DbProduct};
-use crate::repository::{DieselRepository, ProductListQuery, ProductReader, ProductWriter};
+use crate::models::product_image::NewProductImage;
+use crate::repository::{
+    DieselRepository, ProductListQuery, ProductReader, ProductReassignment, ProductStream,
+    ProductWriter, SortDirection, map_unique_violation,
+};
 
 /// Helper struct used to capture the result of a `COUNT(*)` query.
 #[derive(QueryableByName)]
@@ -55,6 +60,105 @@ fn hydrate_associated_categories(
     Ok(())
 }
 
+fn hydrate_images(
+    conn: &mut diesel::sqlite::SqliteConnection,
+    products: &mut [Product],
+) -> RepositoryResult<()> {
+    use crate::schema::product_images;
+
+    if products.is_empty() {
+        return Ok(());
+    }
+
+    let product_ids: Vec<i32> = products.iter().map(|product| product.id.get()).collect();
+    let image_rows = product_images::table
+        .filter(product_images::product_id.eq_any(&product_ids))
+        .select((product_images::product_id, product_images::url))
+        .load::<(i32, String)>(conn)?;
+
+    let mut image_map: HashMap<i32, Vec<String>> = HashMap::new();
+    for (product_id, url) in image_rows {
+        image_map.entry(product_id).or_default().push(url);
+    }
+
+    for product in products {
+        if let Some(images) = image_map.remove(&product.id.get()) {
+            product.images = images
+                .into_iter()
+                .map(ImageUrl::new)
+                .collect::<Result<Vec<ImageUrl>, _>>()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Keyset-paginated iterator over a crawler's products.
+///
+/// Each batch checks a connection out of the pool only for the duration of
+/// the query, so a slow consumer never holds a connection open.
+struct ProductBatches {
+    repo: DieselRepository,
+    crawler_id: CrawlerId,
+    batch_size: i64,
+    last_id: i32,
+    buffer: std::vec::IntoIter<Product>,
+    exhausted: bool,
+}
+
+impl ProductBatches {
+    fn load_next_batch(&self) -> RepositoryResult<Vec<Product>> {
+        use crate::schema::products;
+
+        let mut conn = self.repo.conn()?;
+
+        let mut items = products::table
+            .filter(products::crawler_id.eq(self.crawler_id.get()))
+            .filter(products::id.gt(self.last_id))
+            .filter(products::deleted_at.is_null())
+            .order(products::id.asc())
+            .limit(self.batch_size)
+            .load::<DbProduct>(&mut conn)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Product>, _>>()?;
+
+        hydrate_associated_categories(&mut conn, &mut items)?;
+        hydrate_images(&mut conn, &mut items)?;
+
+        Ok(items)
+    }
+}
+
+impl Iterator for ProductBatches {
+    type Item = RepositoryResult<Product>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(product) = self.buffer.next() {
+                return Some(Ok(product));
+            }
+            if self.exhausted {
+                return None;
+            }
+
+            match self.load_next_batch() {
+                Ok(batch) => {
+                    self.exhausted = (batch.len() as i64) < self.batch_size;
+                    if let Some(last) = batch.last() {
+                        self.last_id = last.id.get();
+                    }
+                    self.buffer = batch.into_iter();
+                }
+                Err(err) => {
+                    self.exhausted = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
 impl ProductReader for DieselRepository {
     fn get_product_by_id(&self, id: ProductId) -> RepositoryResult<Option<Product>> {
         use crate::schema::{product_images, products};
@@ -63,6 +167,7 @@ impl ProductReader for DieselRepository {
 
         let db_product = products::table
             .filter(products::id.eq(id.get()))
+            .filter(products::deleted_at.is_null())
             .first::<DbProduct>(&mut conn)
             .optional()?;
 
@@ -87,6 +192,46 @@ impl ProductReader for DieselRepository {
         Ok(Some(product))
     }
 
+    fn get_products_by_ids(&self, ids: &[ProductId]) -> RepositoryResult<Vec<Product>> {
+        use crate::schema::products;
+
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut conn = self.conn()?;
+        let raw_ids: Vec<i32> = ids.iter().map(|id| id.get()).collect();
+
+        let mut items = products::table
+            .filter(products::id.eq_any(&raw_ids))
+            .filter(products::deleted_at.is_null())
+            .order(products::id.asc())
+            .load::<DbProduct>(&mut conn)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Product>, _>>()?;
+
+        hydrate_associated_categories(&mut conn, &mut items)?;
+        hydrate_images(&mut conn, &mut items)?;
+
+        Ok(items)
+    }
+
+    fn stream_products(
+        &self,
+        crawler_id: CrawlerId,
+        batch_size: usize,
+    ) -> RepositoryResult<ProductStream> {
+        Ok(Box::new(ProductBatches {
+            repo: self.clone(),
+            crawler_id,
+            batch_size: batch_size.max(1) as i64,
+            last_id: 0,
+            buffer: Vec::new().into_iter(),
+            exhausted: false,
+        }))
+    }
+
     fn list_products_by_crawler_and_sku(
         &self,
         crawler_id: crate::domain::types::CrawlerId,
@@ -107,38 +252,131 @@ impl ProductReader for DieselRepository {
         Ok(items)
     }
 
+    fn list_active_products_by_crawler_and_sku(
+        &self,
+        crawler_id: CrawlerId,
+        sku: &ProductSku,
+    ) -> RepositoryResult<Vec<Product>> {
+        use crate::schema::products;
+
+        let mut conn = self.conn()?;
+        let mut items = products::table
+            .filter(products::crawler_id.eq(crawler_id.get()))
+            .filter(products::sku.eq(sku.as_str()))
+            .filter(products::deleted_at.is_null())
+            .load::<DbProduct>(&mut conn)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Product>, _>>()?;
+
+        hydrate_associated_categories(&mut conn, &mut items)?;
+        Ok(items)
+    }
+
+    fn count_products_by_crawler(&self, hub_id: HubId) -> RepositoryResult<HashMap<i32, usize>> {
+        use crate::schema::{crawlers, products};
+
+        let mut conn = self.conn()?;
+
+        let counts: Vec<(i32, i64)> = products::table
+            .inner_join(crawlers::table)
+            .filter(crawlers::hub_id.eq(hub_id.get()))
+            .filter(products::deleted_at.is_null())
+            .group_by(products::crawler_id)
+            .select((products::crawler_id, diesel::dsl::count(products::id)))
+            .load(&mut conn)?;
+
+        Ok(counts
+            .into_iter()
+            .map(|(crawler_id, count)| (crawler_id, count as usize))
+            .collect())
+    }
+
+    fn list_product_categories(
+        &self,
+        crawler_id: CrawlerId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<(Option<CategoryName>, usize)>> {
+        use crate::schema::{categories, crawlers, products};
+
+        let mut conn = self.conn()?;
+
+        let counts: Vec<(Option<i32>, i64)> = products::table
+            .inner_join(crawlers::table)
+            .filter(products::crawler_id.eq(crawler_id.get()))
+            .filter(crawlers::hub_id.eq(hub_id.get()))
+            .filter(products::deleted_at.is_null())
+            .group_by(products::category_id)
+            .select((products::category_id, diesel::dsl::count(products::id)))
+            .load(&mut conn)?;
+
+        let category_ids: Vec<i32> = counts.iter().filter_map(|(id, _)| *id).collect();
+        let names = categories::table
+            .filter(categories::id.eq_any(&category_ids))
+            .select((categories::id, categories::name))
+            .load::<(i32, String)>(&mut conn)?
+            .into_iter()
+            .collect::<HashMap<i32, String>>();
+
+        let mut result = counts
+            .into_iter()
+            .map(|(category_id, count)| {
+                let name = category_id
+                    .and_then(|id| names.get(&id).cloned())
+                    .map(CategoryName::new)
+                    .transpose()?;
+                Ok((name, count as usize))
+            })
+            .collect::<RepositoryResult<Vec<_>>>()?;
+        result.sort_by(|(a_name, a_count), (b_name, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+        });
+
+        Ok(result)
+    }
+
     fn list_distances(
         &self,
         benchmark_id: BenchmarkId,
-    ) -> RepositoryResult<HashMap<ProductId, SimilarityDistance>> {
+        direction: SortDirection,
+    ) -> RepositoryResult<Vec<(ProductId, SimilarityDistance)>> {
         use crate::schema::product_benchmark;
 
         let mut conn = self.conn()?;
 
-        let items: Vec<(i32, f32)> = product_benchmark::table
+        let query = product_benchmark::table
             .filter(product_benchmark::benchmark_id.eq(benchmark_id.get()))
-            .select((product_benchmark::product_id, product_benchmark::distance))
-            .order(product_benchmark::distance.asc())
-            .load(&mut conn)?;
-
-        let mut distances = HashMap::with_capacity(items.len());
-        for (product_id, distance) in items {
-            distances.insert(
-                ProductId::new(product_id)?,
-                SimilarityDistance::new(distance)?,
-            );
-        }
+            .select((product_benchmark::product_id, product_benchmark::distance));
+
+        let items: Vec<(i32, f32)> = match direction {
+            SortDirection::Asc => query
+                .order(product_benchmark::distance.asc())
+                .load(&mut conn)?,
+            SortDirection::Desc => query
+                .order(product_benchmark::distance.desc())
+                .load(&mut conn)?,
+        };
 
-        Ok(distances)
+        items
+            .into_iter()
+            .map(|(product_id, distance)| {
+                Ok((
+                    ProductId::new(product_id)?,
+                    SimilarityDistance::new(distance)?,
+                ))
+            })
+            .collect()
     }
 
     fn list_products(&self, query: ProductListQuery) -> RepositoryResult<(usize, Vec<Product>)> {
-        use crate::schema::{crawlers, product_benchmark, product_images, products};
+        use crate::schema::{crawlers, product_benchmark, products};
 
         let mut conn = self.conn()?;
 
         let query_builder = || {
-            let mut items = products::table.into_boxed::<diesel::sqlite::Sqlite>();
+            let mut items = products::table
+                .filter(products::deleted_at.is_null())
+                .into_boxed::<diesel::sqlite::Sqlite>();
 
             if let Some(crawler_id) = query.crawler_id {
                 items = items.filter(products::crawler_id.eq(crawler_id.get()));
@@ -164,6 +402,20 @@ impl ProductReader for DieselRepository {
                 );
             }
 
+            if query.only_unreviewed {
+                items = items.filter(products::reviewed_at.is_null());
+            }
+
+            if query.only_unassociated {
+                items = items.filter(diesel::dsl::not(diesel::dsl::exists(
+                    product_benchmark::table.filter(product_benchmark::product_id.eq(products::id)),
+                )));
+            }
+
+            if let Some(updated_since) = query.updated_since {
+                items = items.filter(products::updated_at.gt(updated_since));
+            }
+
             items
         };
 
@@ -178,9 +430,15 @@ impl ProductReader for DieselRepository {
             items = items.offset(offset).limit(limit);
         }
 
+        // Change feeds page in modification order; everything else by name.
+        items = if query.updated_since.is_some() {
+            items.order((products::updated_at.asc(), products::id.asc()))
+        } else {
+            items.order(products::name.asc())
+        };
+
         // Final load
         let mut items = items
-            .order(products::name.asc())
             .load::<DbProduct>(&mut conn)?
             .into_iter()
             .map(TryInto::try_into)
@@ -188,30 +446,59 @@ impl ProductReader for DieselRepository {
 
         hydrate_associated_categories(&mut conn, &mut items)?;
 
-        if !items.is_empty() {
-            let product_ids: Vec<i32> = items.iter().map(|product| product.id.get()).collect();
-            let image_rows = product_images::table
-                .filter(product_images::product_id.eq_any(&product_ids))
-                .select((product_images::product_id, product_images::url))
-                .load::<(i32, String)>(&mut conn)?;
+        hydrate_images(&mut conn, &mut items)?;
 
-            let mut image_map: HashMap<i32, Vec<String>> = HashMap::new();
-            for (product_id, url) in image_rows {
-                image_map.entry(product_id).or_default().push(url);
-            }
+        Ok((total, items))
+    }
 
-            for product in &mut items {
-                if let Some(images) = image_map.remove(&product.id.get()) {
-                    product.images = images.into_iter().map(ImageUrl::new).collect::<Result<
-                        Vec<ImageUrl>,
-                        _,
-                    >>(
-                    )?;
-                }
-            }
+    fn list_benchmark_matches(
+        &self,
+        benchmark_id: BenchmarkId,
+        crawler_id: CrawlerId,
+        direction: SortDirection,
+        pagination: Pagination,
+    ) -> RepositoryResult<(usize, Vec<(Product, SimilarityDistance)>)> {
+        use crate::schema::{product_benchmark, products};
+
+        let mut conn = self.conn()?;
+
+        let query_builder = || {
+            products::table
+                .inner_join(product_benchmark::table)
+                .filter(product_benchmark::benchmark_id.eq(benchmark_id.get()))
+                .filter(products::crawler_id.eq(crawler_id.get()))
+                .filter(products::deleted_at.is_null())
+        };
+
+        let total = query_builder().count().get_result::<i64>(&mut conn)? as usize;
+
+        let offset = ((pagination.page.max(1) - 1) * pagination.per_page) as i64;
+        let items = query_builder()
+            .select((products::all_columns, product_benchmark::distance))
+            .offset(offset)
+            .limit(pagination.per_page as i64);
+
+        let rows: Vec<(DbProduct, f32)> = match direction {
+            SortDirection::Asc => items
+                .order((product_benchmark::distance.asc(), products::id.asc()))
+                .load(&mut conn)?,
+            SortDirection::Desc => items
+                .order((product_benchmark::distance.desc(), products::id.asc()))
+                .load(&mut conn)?,
+        };
+
+        let mut distances = Vec::with_capacity(rows.len());
+        let mut items = Vec::with_capacity(rows.len());
+        for (product, distance) in rows {
+            items.push(Product::try_from(product)?);
+            distances.push(SimilarityDistance::new(distance)?);
         }
 
-        Ok((total, items))
+        hydrate_associated_categories(&mut conn, &mut items)?;
+
+        hydrate_images(&mut conn, &mut items)?;
+
+        Ok((total, items.into_iter().zip(distances).collect()))
     }
 
     fn search_products(&self, query: ProductListQuery) -> RepositoryResult<(usize, Vec<Product>)> {
@@ -234,6 +521,7 @@ impl ProductReader for DieselRepository {
             FROM products
             JOIN products_fts ON products.id = products_fts.rowid
             WHERE products_fts MATCH ?
+              AND products.deleted_at IS NULL
             "#,
         );
 
@@ -266,6 +554,22 @@ impl ProductReader for DieselRepository {
             sql.push_str(benchmark_filter);
         }
 
+        if query.only_unreviewed {
+            sql.push_str(" AND products.reviewed_at IS NULL ");
+        }
+
+        if query.only_unassociated {
+            sql.push_str(
+                r#"
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM product_benchmark
+                    WHERE product_benchmark.product_id = products.id
+                )
+            "#,
+            );
+        }
+
         let total_sql = format!("SELECT COUNT(*) as count FROM ({sql})");
 
         // Now add pagination to SQL (but not count)
@@ -317,6 +621,42 @@ impl ProductReader for DieselRepository {
         let total = total_query.get_result::<ProductCount>(&mut conn)?.count as usize;
         Ok((total, items))
     }
+
+    fn list_deleted_products(&self, crawler_id: CrawlerId) -> RepositoryResult<Vec<Product>> {
+        use crate::schema::products;
+
+        let mut conn = self.conn()?;
+        let mut items = products::table
+            .filter(products::crawler_id.eq(crawler_id.get()))
+            .filter(products::deleted_at.is_not_null())
+            .order((products::deleted_at.desc(), products::id.asc()))
+            .load::<DbProduct>(&mut conn)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Product>, _>>()?;
+
+        hydrate_associated_categories(&mut conn, &mut items)?;
+        hydrate_images(&mut conn, &mut items)?;
+        Ok(items)
+    }
+
+    fn list_products_without_url(&self, crawler_id: CrawlerId) -> RepositoryResult<Vec<Product>> {
+        use crate::schema::products;
+
+        let mut conn = self.conn()?;
+        let mut items = products::table
+            .filter(products::crawler_id.eq(crawler_id.get()))
+            .filter(products::url.is_null())
+            .filter(products::deleted_at.is_null())
+            .order((products::name.asc(), products::id.asc()))
+            .load::<DbProduct>(&mut conn)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Product>, _>>()?;
+
+        hydrate_associated_categories(&mut conn, &mut items)?;
+        Ok(items)
+    }
 }
 impl ProductWriter for DieselRepository {
     fn create_product(&self, product: &NewProduct) -> RepositoryResult<usize> {
@@ -327,7 +667,8 @@ impl ProductWriter for DieselRepository {
 
         let affected = diesel::insert_into(products::table)
             .values(&db_product)
-            .execute(&mut conn)?;
+            .execute(&mut conn)
+            .map_err(map_unique_violation)?;
 
         Ok(affected)
     }
@@ -395,4 +736,171 @@ impl ProductWriter for DieselRepository {
 
         Ok(affected)
     }
+
+    fn mark_reviewed(&self, product_id: ProductId, hub_id: HubId) -> RepositoryResult<usize> {
+        use crate::schema::{crawlers, products};
+
+        let mut conn = self.conn()?;
+        let now = Utc::now().naive_utc();
+
+        let affected = diesel::update(
+            products::table
+                .filter(products::id.eq(product_id.get()))
+                .filter(
+                    products::crawler_id.eq_any(
+                        crawlers::table
+                            .filter(crawlers::hub_id.eq(hub_id.get()))
+                            .select(crawlers::id),
+                    ),
+                ),
+        )
+        .set(products::reviewed_at.eq(Some(now)))
+        .execute(&mut conn)?;
+
+        Ok(affected)
+    }
+
+    fn soft_delete_product(&self, product_id: ProductId, hub_id: HubId) -> RepositoryResult<usize> {
+        use crate::schema::{crawlers, product_benchmark, products};
+
+        let now = Utc::now().naive_utc();
+
+        self.transaction(|conn| {
+            let affected = diesel::update(
+                products::table
+                    .filter(products::id.eq(product_id.get()))
+                    .filter(products::deleted_at.is_null())
+                    .filter(
+                        products::crawler_id.eq_any(
+                            crawlers::table
+                                .filter(crawlers::hub_id.eq(hub_id.get()))
+                                .select(crawlers::id),
+                        ),
+                    ),
+            )
+            .set(products::deleted_at.eq(Some(now)))
+            .execute(conn)?;
+
+            if affected > 0 {
+                diesel::delete(
+                    product_benchmark::table
+                        .filter(product_benchmark::product_id.eq(product_id.get())),
+                )
+                .execute(conn)?;
+            }
+
+            Ok(affected)
+        })
+    }
+
+    fn restore_product(&self, product_id: ProductId, hub_id: HubId) -> RepositoryResult<usize> {
+        use crate::schema::{crawlers, products};
+
+        let mut conn = self.conn()?;
+
+        let affected = diesel::update(
+            products::table
+                .filter(products::id.eq(product_id.get()))
+                .filter(products::deleted_at.is_not_null())
+                .filter(
+                    products::crawler_id.eq_any(
+                        crawlers::table
+                            .filter(crawlers::hub_id.eq(hub_id.get()))
+                            .select(crawlers::id),
+                    ),
+                ),
+        )
+        .set(products::deleted_at.eq(None::<NaiveDateTime>))
+        .execute(&mut conn)?;
+
+        Ok(affected)
+    }
+
+    fn set_product_images(
+        &self,
+        product_id: ProductId,
+        urls: &[ImageUrl],
+    ) -> RepositoryResult<usize> {
+        use crate::schema::product_images;
+
+        let rows = urls
+            .iter()
+            .map(|url| NewProductImage {
+                product_id: product_id.get(),
+                url: url.as_str().to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        self.transaction(|conn| {
+            diesel::delete(
+                product_images::table.filter(product_images::product_id.eq(product_id.get())),
+            )
+            .execute(conn)?;
+
+            if rows.is_empty() {
+                return Ok(0);
+            }
+            let inserted = diesel::insert_into(product_images::table)
+                .values(&rows)
+                .execute(conn)?;
+
+            Ok(inserted)
+        })
+    }
+
+    fn reassign_products(
+        &self,
+        from: CrawlerId,
+        to: CrawlerId,
+        hub_id: HubId,
+    ) -> RepositoryResult<ProductReassignment> {
+        use crate::schema::{crawlers, products};
+
+        let mut conn = self.conn()?;
+        let now = Utc::now().naive_utc();
+        let target = diesel::alias!(products as target_products);
+
+        conn.transaction(|conn| {
+            let owned: i64 = crawlers::table
+                .filter(crawlers::id.eq_any([from.get(), to.get()]))
+                .filter(crawlers::hub_id.eq(hub_id.get()))
+                .count()
+                .get_result(conn)?;
+            let expected = if from == to { 1 } else { 2 };
+            if owned != expected {
+                return Err(RepositoryError::NotFound);
+            }
+
+            let target_urls = target
+                .filter(target.field(products::crawler_id).eq(to.get()))
+                .filter(target.field(products::url).is_not_null())
+                .select(target.field(products::url));
+
+            let skipped_url_conflicts: i64 = products::table
+                .filter(products::crawler_id.eq(from.get()))
+                .filter(products::url.eq_any(target_urls))
+                .count()
+                .get_result(conn)?;
+
+            let moved = diesel::update(
+                products::table
+                    .filter(products::crawler_id.eq(from.get()))
+                    .filter(
+                        products::url
+                            .is_null()
+                            .or(products::url.ne_all(target_urls)),
+                    ),
+            )
+            .set((
+                products::crawler_id.eq(to.get()),
+                products::updated_at.eq(now),
+            ))
+            .execute(conn)?;
+
+            Ok(ProductReassignment {
+                moved,
+                skipped_url_conflicts: skipped_url_conflicts as usize,
+            })
+        })
+    }
 }
@@ -0,0 +1,74 @@
+use diesel::dsl::{exists, not};
+use diesel::prelude::*;
+use diesel::sqlite::Sqlite;
+use pushkind_common::repository::errors::RepositoryResult;
+
+use crate::domain::statistics::HubStatistics;
+use crate::domain::types::HubId;
+use crate::repository::{DieselRepository, StatisticsReader};
+use crate::schema::{benchmarks, categories, crawlers, product_benchmark, products};
+
+/// Benchmarks of the hub that are not archived.
+fn hub_benchmarks(hub_id: HubId) -> benchmarks::BoxedQuery<'static, Sqlite> {
+    benchmarks::table
+        .filter(benchmarks::hub_id.eq(hub_id.get()))
+        .filter(benchmarks::archived_at.is_null())
+        .into_boxed()
+}
+
+/// Products belonging to any crawler of the hub.
+fn hub_products(hub_id: HubId) -> products::BoxedQuery<'static, Sqlite> {
+    products::table
+        .filter(
+            products::crawler_id.eq_any(
+                crawlers::table
+                    .filter(crawlers::hub_id.eq(hub_id.get()))
+                    .select(crawlers::id),
+            ),
+        )
+        .filter(products::deleted_at.is_null())
+        .into_boxed()
+}
+
+impl StatisticsReader for DieselRepository {
+    fn hub_statistics(&self, hub_id: HubId) -> RepositoryResult<HubStatistics> {
+        let mut conn = self.conn()?;
+
+        let crawler_count = crawlers::table
+            .filter(crawlers::hub_id.eq(hub_id.get()))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+        let product_count = hub_products(hub_id).count().get_result::<i64>(&mut conn)?;
+        let unembedded_product_count = hub_products(hub_id)
+            .filter(products::embedding.is_null())
+            .count()
+            .get_result::<i64>(&mut conn)?;
+        let uncategorized_product_count = hub_products(hub_id)
+            .filter(products::category_id.is_null())
+            .count()
+            .get_result::<i64>(&mut conn)?;
+        let benchmark_count = hub_benchmarks(hub_id)
+            .count()
+            .get_result::<i64>(&mut conn)?;
+        let unmatched_benchmark_count = hub_benchmarks(hub_id)
+            .filter(not(exists(
+                product_benchmark::table.filter(product_benchmark::benchmark_id.eq(benchmarks::id)),
+            )))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+        let category_count = categories::table
+            .filter(categories::hub_id.eq(hub_id.get()))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+
+        Ok(HubStatistics {
+            crawler_count: crawler_count as usize,
+            product_count: product_count as usize,
+            benchmark_count: benchmark_count as usize,
+            category_count: category_count as usize,
+            unembedded_product_count: unembedded_product_count as usize,
+            unmatched_benchmark_count: unmatched_benchmark_count as usize,
+            uncategorized_product_count: uncategorized_product_count as usize,
+        })
+    }
+}
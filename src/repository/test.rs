@@ -1,28 +1,48 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
 
-use pushkind_common::repository::errors::RepositoryResult;
+use pushkind_common::pagination::Pagination;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
 
+use crate::domain::audit::{AuditEntry, NewAuditEntry};
 use crate::domain::benchmark::NewBenchmark;
 use crate::domain::category::Category;
 use crate::domain::product::NewProduct;
+use crate::domain::statistics::HubStatistics;
 use crate::domain::types::{
-    BenchmarkId, BenchmarkSku, CategoryId, CategoryName, CrawlerId, HubId, ProductId, ProductSku,
-    SimilarityDistance,
+    BenchmarkId, BenchmarkSku, CategoryId, CategoryName, CrawlerId, HubId, ImageUrl, ProductCount,
+    ProductId, ProductSku, SimilarityDistance,
 };
 use crate::domain::{benchmark::Benchmark, crawler::Crawler, product::Product};
+use crate::repository::benchmark::ensure_benchmarks_in_hub;
 use crate::repository::{
-    BenchmarkListQuery, BenchmarkReader, BenchmarkWriter, CategoryListQuery, CategoryReader,
-    CategoryWriter, CrawlerReader, ProcessingStateReader, ProductListQuery, ProductReader,
-    ProductWriter,
+    AuditLogReader, AuditLogWriter, BenchmarkListQuery, BenchmarkReader, BenchmarkWriter,
+    CategoryListQuery, CategoryReader, CategoryWriter, CrawlerListOrder, CrawlerListQuery,
+    CrawlerReader, CrawlerWriter, ProcessingStateReader, ProductListQuery, ProductReader,
+    ProductReassignment, ProductStream, ProductWriter, SortDirection, StatisticsReader,
+    UpsertOutcome,
 };
 
 /// Simple in-memory repository used for unit tests.
+///
+/// Crawlers, products, benchmarks and categories are fixed at construction;
+/// benchmark-product associations, created benchmarks and deletions are kept
+/// in memory so services can be exercised end to end.
 #[derive(Default)]
 pub struct TestRepository {
     crawlers: HashMap<CrawlerId, Crawler>,
     products: Vec<Product>,
     benchmarks: Vec<Benchmark>,
     categories: Vec<Category>,
+    /// Benchmark-product associations with their similarity distance.
+    associations: Mutex<HashMap<(BenchmarkId, ProductId), SimilarityDistance>>,
+    audit_entries: Mutex<Vec<NewAuditEntry>>,
+    /// Benchmarks inserted through [`BenchmarkWriter::create_benchmark`].
+    created_benchmarks: Mutex<Vec<Benchmark>>,
+    /// Products marked as soft-deleted.
+    deleted_products: Mutex<HashSet<ProductId>>,
+    /// Crawlers removed together with their products.
+    deleted_crawlers: Mutex<HashSet<CrawlerId>>,
 }
 
 impl TestRepository {
@@ -32,6 +52,11 @@ impl TestRepository {
             products,
             benchmarks,
             categories: vec![],
+            associations: Mutex::new(HashMap::new()),
+            audit_entries: Mutex::new(vec![]),
+            created_benchmarks: Mutex::new(vec![]),
+            deleted_products: Mutex::new(HashSet::new()),
+            deleted_crawlers: Mutex::new(HashSet::new()),
         }
     }
 
@@ -40,6 +65,75 @@ impl TestRepository {
         self
     }
 
+    pub fn with_associations(
+        self,
+        associations: Vec<(BenchmarkId, ProductId, SimilarityDistance)>,
+    ) -> Self {
+        self.associations.lock().unwrap().extend(
+            associations
+                .into_iter()
+                .map(|(benchmark_id, product_id, distance)| ((benchmark_id, product_id), distance)),
+        );
+        self
+    }
+
+    pub fn with_deleted_products(self, product_ids: Vec<ProductId>) -> Self {
+        self.deleted_products.lock().unwrap().extend(product_ids);
+        self
+    }
+
+    fn crawler(&self, id: CrawlerId) -> Option<&Crawler> {
+        self.crawlers
+            .get(&id)
+            .filter(|_| !self.deleted_crawlers.lock().unwrap().contains(&id))
+    }
+
+    fn stored_crawlers(&self) -> impl Iterator<Item = &Crawler> {
+        let deleted = self.deleted_crawlers.lock().unwrap().clone();
+        self.crawlers
+            .values()
+            .filter(move |c| !deleted.contains(&c.id))
+    }
+
+    /// Products whose crawler has not been deleted.
+    fn stored_products(&self) -> impl Iterator<Item = &Product> {
+        let deleted = self.deleted_crawlers.lock().unwrap().clone();
+        self.products
+            .iter()
+            .filter(move |p| !deleted.contains(&p.crawler_id))
+    }
+
+    fn is_product_deleted(&self, product_id: ProductId) -> bool {
+        self.deleted_products.lock().unwrap().contains(&product_id)
+    }
+
+    fn is_product_in_hub(&self, product_id: ProductId, hub_id: HubId) -> bool {
+        self.stored_products().any(|p| {
+            p.id == product_id
+                && self
+                    .crawler(p.crawler_id)
+                    .is_some_and(|c| c.hub_id == hub_id)
+        })
+    }
+
+    /// Stored associations ordered by benchmark and product id.
+    pub fn associations(&self) -> Vec<(BenchmarkId, ProductId, SimilarityDistance)> {
+        let mut items: Vec<_> = self
+            .associations
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&(benchmark_id, product_id), &distance)| (benchmark_id, product_id, distance))
+            .collect();
+        items.sort_by_key(|&(benchmark_id, product_id, _)| (benchmark_id, product_id));
+        items
+    }
+
+    /// Audit entries recorded through [`AuditLogWriter`].
+    pub fn audit_entries(&self) -> Vec<NewAuditEntry> {
+        self.audit_entries.lock().unwrap().clone()
+    }
+
     fn clone_crawler(c: &Crawler) -> Crawler {
         c.clone()
     }
@@ -52,19 +146,72 @@ impl TestRepository {
         b.clone()
     }
 
+    /// Seeded benchmarks followed by the ones created during the test.
+    fn all_benchmarks(&self) -> Vec<Benchmark> {
+        let mut items: Vec<Benchmark> = self.benchmarks.iter().map(Self::clone_benchmark).collect();
+        items.extend(self.created_benchmarks.lock().unwrap().iter().cloned());
+        items
+    }
+
     fn clone_category(c: &Category) -> Category {
         c.clone()
     }
+
+    fn ensure_association_in_hub(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<()> {
+        let benchmark_in_hub = self
+            .benchmarks
+            .iter()
+            .any(|b| b.id == benchmark_id && b.hub_id == hub_id);
+        if benchmark_in_hub && self.is_product_in_hub(product_id, hub_id) {
+            Ok(())
+        } else {
+            Err(RepositoryError::NotFound)
+        }
+    }
 }
 
 impl CrawlerReader for TestRepository {
-    fn list_crawlers(&self, hub_id: HubId) -> RepositoryResult<Vec<Crawler>> {
-        Ok(self
-            .crawlers
-            .values()
-            .filter(|c| c.hub_id == hub_id)
+    fn list_crawlers(&self, query: CrawlerListQuery) -> RepositoryResult<(usize, Vec<Crawler>)> {
+        let mut items: Vec<Crawler> = self
+            .stored_crawlers()
+            .filter(|c| c.hub_id == query.hub_id)
             .map(Self::clone_crawler)
-            .collect())
+            .collect();
+        match query.order {
+            CrawlerListOrder::ByName => {
+                items.sort_by(|a, b| a.name.as_str().cmp(b.name.as_str()).then(a.id.cmp(&b.id)))
+            }
+            CrawlerListOrder::ById => items.sort_by_key(|c| c.id),
+            CrawlerListOrder::ByNumProducts => items.sort_by(|a, b| {
+                b.num_products
+                    .get()
+                    .cmp(&a.num_products.get())
+                    .then(a.id.cmp(&b.id))
+            }),
+        }
+        if let Some(search) = &query.search {
+            // ASCII-only folding, like SQLite `LIKE`.
+            let search = search.trim().to_ascii_lowercase();
+            items.retain(|c| {
+                c.name.as_str().to_ascii_lowercase().contains(&search)
+                    || c.url.as_str().to_ascii_lowercase().contains(&search)
+            });
+        }
+        let total = items.len();
+        if let Some(pagination) = &query.pagination {
+            let offset = (pagination.page.max(1) - 1) * pagination.per_page;
+            items = items
+                .into_iter()
+                .skip(offset)
+                .take(pagination.per_page)
+                .collect();
+        }
+        Ok((total, items))
     }
 
     fn get_crawler_by_id(
@@ -72,15 +219,107 @@ impl CrawlerReader for TestRepository {
         id: CrawlerId,
         _hub_id: HubId,
     ) -> RepositoryResult<Option<Crawler>> {
-        Ok(self.crawlers.get(&id).map(Self::clone_crawler))
+        Ok(self.crawler(id).map(Self::clone_crawler))
+    }
+
+    fn get_crawler_hub_id(&self, id: CrawlerId) -> RepositoryResult<Option<HubId>> {
+        Ok(self.crawler(id).map(|crawler| crawler.hub_id))
+    }
+}
+
+impl CrawlerWriter for TestRepository {
+    fn create_crawler(
+        &self,
+        _crawler: &crate::domain::crawler::NewCrawler,
+    ) -> RepositoryResult<usize> {
+        Ok(1)
+    }
+
+    fn update_crawler(
+        &self,
+        id: CrawlerId,
+        hub_id: HubId,
+        fields: &crate::domain::crawler::UpdateCrawlerFields,
+    ) -> RepositoryResult<usize> {
+        let duplicate = self.stored_crawlers().any(|crawler| {
+            crawler.hub_id == hub_id && crawler.id != id && crawler.url == fields.url
+        });
+        if duplicate {
+            return Err(RepositoryError::ConflictError);
+        }
+
+        Ok(self
+            .crawler(id)
+            .filter(|crawler| crawler.hub_id == hub_id)
+            .map_or(0, |_| 1))
+    }
+
+    fn delete_crawler(&self, id: CrawlerId, hub_id: HubId) -> RepositoryResult<usize> {
+        if !self
+            .crawler(id)
+            .is_some_and(|crawler| crawler.hub_id == hub_id)
+        {
+            return Ok(0);
+        }
+
+        let product_ids: HashSet<ProductId> = self
+            .stored_products()
+            .filter(|p| p.crawler_id == id)
+            .map(|p| p.id)
+            .collect();
+        self.associations
+            .lock()
+            .unwrap()
+            .retain(|(_, product_id), _| !product_ids.contains(product_id));
+        self.deleted_crawlers.lock().unwrap().insert(id);
+        Ok(1)
+    }
+}
+
+impl StatisticsReader for TestRepository {
+    fn hub_statistics(&self, hub_id: HubId) -> RepositoryResult<HubStatistics> {
+        let products = self
+            .stored_products()
+            .filter(|p| {
+                self.crawler(p.crawler_id)
+                    .is_some_and(|c| c.hub_id == hub_id)
+            })
+            .collect::<Vec<_>>();
+        let benchmarks = self
+            .benchmarks
+            .iter()
+            .filter(|b| b.hub_id == hub_id && b.archived_at.is_none())
+            .collect::<Vec<_>>();
+
+        Ok(HubStatistics {
+            crawler_count: self
+                .stored_crawlers()
+                .filter(|c| c.hub_id == hub_id)
+                .count(),
+            product_count: products.len(),
+            benchmark_count: benchmarks.len(),
+            category_count: self
+                .categories
+                .iter()
+                .filter(|c| c.hub_id == hub_id)
+                .count(),
+            unembedded_product_count: products.iter().filter(|p| p.embedding.is_none()).count(),
+            unmatched_benchmark_count: benchmarks
+                .iter()
+                .filter(|b| b.num_products.get() == 0)
+                .count(),
+            uncategorized_product_count: products
+                .iter()
+                .filter(|p| p.category_id.is_none())
+                .count(),
+        })
     }
 }
 
 impl ProcessingStateReader for TestRepository {
     fn has_active_processing(&self, hub_id: HubId) -> RepositoryResult<bool> {
         let crawler_processing = self
-            .crawlers
-            .values()
+            .stored_crawlers()
             .any(|crawler| crawler.hub_id == hub_id && crawler.processing);
 
         if crawler_processing {
@@ -98,23 +337,96 @@ impl ProcessingStateReader for TestRepository {
 
 impl ProductReader for TestRepository {
     fn list_products(&self, query: ProductListQuery) -> RepositoryResult<(usize, Vec<Product>)> {
-        let mut items: Vec<Product> = self.products.iter().map(Self::clone_product).collect();
+        let mut items: Vec<Product> = self.stored_products().map(Self::clone_product).collect();
         if let Some(crawler_id) = query.crawler_id {
             items.retain(|p| p.crawler_id == crawler_id);
         }
+        if query.only_unreviewed {
+            items.retain(|p| p.reviewed_at.is_none());
+        }
+        if let Some(updated_since) = query.updated_since {
+            items.retain(|p| p.updated_at > updated_since);
+            items.sort_by_key(|p| (p.updated_at, p.id.get()));
+        }
         let total = items.len();
+        if let Some(pagination) = &query.pagination {
+            let offset = (pagination.page.max(1) - 1) * pagination.per_page;
+            items = items
+                .into_iter()
+                .skip(offset)
+                .take(pagination.per_page)
+                .collect();
+        }
         Ok((total, items))
     }
 
     fn list_distances(
         &self,
-        _benchmark_id: BenchmarkId,
-    ) -> RepositoryResult<HashMap<ProductId, SimilarityDistance>> {
-        Ok(HashMap::new())
+        benchmark_id: BenchmarkId,
+        direction: SortDirection,
+    ) -> RepositoryResult<Vec<(ProductId, SimilarityDistance)>> {
+        let mut items: Vec<(ProductId, SimilarityDistance)> = self
+            .associations()
+            .into_iter()
+            .filter(|(b, _, _)| *b == benchmark_id)
+            .map(|(_, product_id, distance)| (product_id, distance))
+            .collect();
+        items.sort_by(|(_, a), (_, b)| a.get().total_cmp(&b.get()));
+        if direction == SortDirection::Desc {
+            items.reverse();
+        }
+        Ok(items)
+    }
+
+    fn list_benchmark_matches(
+        &self,
+        benchmark_id: BenchmarkId,
+        crawler_id: CrawlerId,
+        direction: SortDirection,
+        pagination: Pagination,
+    ) -> RepositoryResult<(usize, Vec<(Product, SimilarityDistance)>)> {
+        let mut items: Vec<(Product, SimilarityDistance)> = self
+            .list_distances(benchmark_id, SortDirection::Asc)?
+            .into_iter()
+            .filter_map(|(product_id, distance)| {
+                self.stored_products()
+                    .find(|p| p.id == product_id && p.crawler_id == crawler_id)
+                    .map(|p| (Self::clone_product(p), distance))
+            })
+            .collect();
+        items.sort_by(|(a, a_distance), (b, b_distance)| {
+            let by_distance = a_distance.get().total_cmp(&b_distance.get());
+            let by_distance = match direction {
+                SortDirection::Asc => by_distance,
+                SortDirection::Desc => by_distance.reverse(),
+            };
+            by_distance.then_with(|| a.id.get().cmp(&b.id.get()))
+        });
+        let total = items.len();
+        let offset = (pagination.page.max(1) - 1) * pagination.per_page;
+        let items = items
+            .into_iter()
+            .skip(offset)
+            .take(pagination.per_page)
+            .collect();
+        Ok((total, items))
+    }
+
+    fn stream_products(
+        &self,
+        crawler_id: CrawlerId,
+        _batch_size: usize,
+    ) -> RepositoryResult<ProductStream> {
+        let items: Vec<Product> = self
+            .stored_products()
+            .filter(|p| p.crawler_id == crawler_id)
+            .map(Self::clone_product)
+            .collect();
+        Ok(Box::new(items.into_iter().map(Ok)))
     }
 
     fn search_products(&self, query: ProductListQuery) -> RepositoryResult<(usize, Vec<Product>)> {
-        let mut items: Vec<Product> = self.products.iter().map(Self::clone_product).collect();
+        let mut items: Vec<Product> = self.stored_products().map(Self::clone_product).collect();
         if let Some(crawler_id) = query.crawler_id {
             items.retain(|p| p.crawler_id == crawler_id);
         }
@@ -128,24 +440,99 @@ impl ProductReader for TestRepository {
 
     fn get_product_by_id(&self, id: ProductId) -> RepositoryResult<Option<Product>> {
         Ok(self
-            .products
-            .iter()
+            .stored_products()
             .find(|p| p.id == id)
             .map(Self::clone_product))
     }
 
+    fn get_products_by_ids(&self, ids: &[ProductId]) -> RepositoryResult<Vec<Product>> {
+        let mut products: Vec<Product> = self
+            .stored_products()
+            .filter(|p| ids.contains(&p.id))
+            .map(Self::clone_product)
+            .collect();
+        products.sort_by_key(|p| p.id.get());
+        Ok(products)
+    }
+
     fn list_products_by_crawler_and_sku(
         &self,
         crawler_id: CrawlerId,
         sku: &ProductSku,
     ) -> RepositoryResult<Vec<Product>> {
         Ok(self
-            .products
-            .iter()
+            .stored_products()
             .filter(|p| p.crawler_id == crawler_id && p.sku == sku.as_str())
             .map(Self::clone_product)
             .collect())
     }
+
+    fn list_active_products_by_crawler_and_sku(
+        &self,
+        crawler_id: CrawlerId,
+        sku: &ProductSku,
+    ) -> RepositoryResult<Vec<Product>> {
+        Ok(self
+            .list_products_by_crawler_and_sku(crawler_id, sku)?
+            .into_iter()
+            .filter(|p| !self.is_product_deleted(p.id))
+            .collect())
+    }
+
+    fn count_products_by_crawler(&self, hub_id: HubId) -> RepositoryResult<HashMap<i32, usize>> {
+        let mut counts = HashMap::new();
+        for product in self.stored_products() {
+            let in_hub = self
+                .crawler(product.crawler_id)
+                .is_some_and(|crawler| crawler.hub_id == hub_id);
+            if in_hub {
+                *counts.entry(product.crawler_id.get()).or_insert(0) += 1;
+            }
+        }
+        Ok(counts)
+    }
+
+    fn list_product_categories(
+        &self,
+        crawler_id: CrawlerId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<(Option<CategoryName>, usize)>> {
+        let in_hub = self
+            .crawler(crawler_id)
+            .is_some_and(|crawler| crawler.hub_id == hub_id);
+        if !in_hub {
+            return Ok(vec![]);
+        }
+
+        let mut counts: HashMap<Option<CategoryName>, usize> = HashMap::new();
+        for product in self
+            .stored_products()
+            .filter(|p| p.crawler_id == crawler_id)
+        {
+            let category = product
+                .category_id
+                .and_then(|_| product.associated_category.clone());
+            *counts.entry(category).or_insert(0) += 1;
+        }
+
+        let mut result: Vec<_> = counts.into_iter().collect();
+        result.sort_by(|(a_name, a_count), (b_name, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+        });
+        Ok(result)
+    }
+
+    fn list_deleted_products(&self, _crawler_id: CrawlerId) -> RepositoryResult<Vec<Product>> {
+        Ok(vec![])
+    }
+
+    fn list_products_without_url(&self, crawler_id: CrawlerId) -> RepositoryResult<Vec<Product>> {
+        Ok(self
+            .stored_products()
+            .filter(|p| p.crawler_id == crawler_id && p.url.is_none())
+            .map(Self::clone_product)
+            .collect())
+    }
 }
 
 impl ProductWriter for TestRepository {
@@ -172,6 +559,54 @@ impl ProductWriter for TestRepository {
     fn clear_product_category_manual(&self, _product_id: ProductId) -> RepositoryResult<usize> {
         Ok(1)
     }
+
+    fn mark_reviewed(&self, _product_id: ProductId, _hub_id: HubId) -> RepositoryResult<usize> {
+        Ok(1)
+    }
+
+    fn soft_delete_product(&self, product_id: ProductId, hub_id: HubId) -> RepositoryResult<usize> {
+        if !self.is_product_in_hub(product_id, hub_id)
+            || !self.deleted_products.lock().unwrap().insert(product_id)
+        {
+            return Ok(0);
+        }
+        self.associations
+            .lock()
+            .unwrap()
+            .retain(|&(_, associated), _| associated != product_id);
+        Ok(1)
+    }
+
+    fn restore_product(&self, product_id: ProductId, hub_id: HubId) -> RepositoryResult<usize> {
+        if !self.is_product_in_hub(product_id, hub_id) {
+            return Ok(0);
+        }
+        let restored = self.deleted_products.lock().unwrap().remove(&product_id);
+        Ok(usize::from(restored))
+    }
+
+    fn set_product_images(
+        &self,
+        _product_id: ProductId,
+        urls: &[ImageUrl],
+    ) -> RepositoryResult<usize> {
+        Ok(urls.len())
+    }
+
+    fn reassign_products(
+        &self,
+        from: CrawlerId,
+        _to: CrawlerId,
+        _hub_id: HubId,
+    ) -> RepositoryResult<ProductReassignment> {
+        Ok(ProductReassignment {
+            moved: self
+                .stored_products()
+                .filter(|p| p.crawler_id == from)
+                .count(),
+            skipped_url_conflicts: 0,
+        })
+    }
 }
 
 impl BenchmarkReader for TestRepository {
@@ -180,7 +615,23 @@ impl BenchmarkReader for TestRepository {
         query: BenchmarkListQuery,
     ) -> RepositoryResult<(usize, Vec<Benchmark>)> {
         let mut items: Vec<Benchmark> = self.benchmarks.iter().map(Self::clone_benchmark).collect();
-        items.retain(|b| b.hub_id == query.hub_id);
+        items.retain(|b| {
+            b.hub_id == query.hub_id && (query.include_archived || b.archived_at.is_none())
+        });
+        if let Some(category) = &query.category {
+            let prefix = format!("{}/", category.as_str());
+            items.retain(|b| {
+                b.category == *category
+                    || (query.include_subcategories && b.category.as_str().starts_with(&prefix))
+            });
+        }
+        if let Some(search) = &query.search {
+            let search = search.trim().to_lowercase();
+            items.retain(|b| {
+                b.name.as_str().to_lowercase().contains(&search)
+                    || b.sku.as_str().to_lowercase().contains(&search)
+            });
+        }
         let total = items.len();
         Ok((total, items))
     }
@@ -197,23 +648,126 @@ impl BenchmarkReader for TestRepository {
             .map(Self::clone_benchmark))
     }
 
+    fn get_benchmark_by_sku(
+        &self,
+        sku: &BenchmarkSku,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<Benchmark>> {
+        Ok(self
+            .all_benchmarks()
+            .into_iter()
+            .find(|b| b.hub_id == hub_id && b.sku == *sku))
+    }
+
     fn list_benchmarks_by_hub_and_sku(
         &self,
         hub_id: HubId,
         sku: &BenchmarkSku,
+    ) -> RepositoryResult<Vec<Benchmark>> {
+        Ok(self
+            .all_benchmarks()
+            .into_iter()
+            .filter(|b| b.hub_id == hub_id && b.sku == *sku)
+            .collect())
+    }
+
+    fn list_benchmarks_missing_embeddings(
+        &self,
+        hub_id: HubId,
     ) -> RepositoryResult<Vec<Benchmark>> {
         Ok(self
             .benchmarks
             .iter()
-            .filter(|b| b.hub_id == hub_id && b.sku == sku.as_str())
+            .filter(|b| b.hub_id == hub_id && b.embedding.as_ref().is_none_or(Vec::is_empty))
             .map(Self::clone_benchmark)
             .collect())
     }
+
+    fn list_benchmarks_for_product(
+        &self,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<Benchmark>> {
+        let associations = self.associations.lock().unwrap();
+        Ok(self
+            .all_benchmarks()
+            .into_iter()
+            .filter(|b| b.hub_id == hub_id && associations.contains_key(&(b.id, product_id)))
+            .collect())
+    }
+
+    fn get_association_distance(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<SimilarityDistance>> {
+        if self
+            .ensure_association_in_hub(benchmark_id, product_id, hub_id)
+            .is_err()
+        {
+            return Ok(None);
+        }
+        Ok(self
+            .associations
+            .lock()
+            .unwrap()
+            .get(&(benchmark_id, product_id))
+            .copied())
+    }
 }
 
 impl BenchmarkWriter for TestRepository {
-    fn create_benchmark(&self, benchmarks: &[NewBenchmark]) -> RepositoryResult<usize> {
-        Ok(benchmarks.len())
+    fn create_benchmark(
+        &self,
+        benchmarks: &[NewBenchmark],
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<BenchmarkId>> {
+        ensure_benchmarks_in_hub(benchmarks, hub_id)?;
+        let mut ids = Vec::with_capacity(benchmarks.len());
+        let mut next_id = self
+            .all_benchmarks()
+            .iter()
+            .map(|b| b.id.get())
+            .max()
+            .unwrap_or(0);
+        let mut created = self.created_benchmarks.lock().unwrap();
+        for benchmark in benchmarks {
+            next_id += 1;
+            let id = BenchmarkId::new(next_id)?;
+            ids.push(id);
+            created.push(Benchmark {
+                id,
+                hub_id: benchmark.hub_id,
+                name: benchmark.name.clone(),
+                sku: benchmark.sku.clone(),
+                category: benchmark.category.clone(),
+                units: benchmark.units.clone(),
+                price: benchmark.price,
+                amount: benchmark.amount,
+                description: benchmark.description.clone(),
+                created_at: benchmark.created_at,
+                updated_at: benchmark.updated_at,
+                embedding: None,
+                processing: false,
+                num_products: ProductCount::new(0)?,
+                archived_at: None,
+            });
+        }
+        Ok(ids)
+    }
+
+    fn upsert_benchmark(&self, _benchmark: &NewBenchmark) -> RepositoryResult<UpsertOutcome> {
+        Ok(UpsertOutcome::Created)
+    }
+
+    fn upsert_benchmarks(
+        &self,
+        benchmarks: &[NewBenchmark],
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<UpsertOutcome>> {
+        ensure_benchmarks_in_hub(benchmarks, hub_id)?;
+        Ok(vec![UpsertOutcome::Created; benchmarks.len()])
     }
 
     fn update_benchmark(
@@ -226,22 +780,88 @@ impl BenchmarkWriter for TestRepository {
 
     fn remove_benchmark_association(
         &self,
-        _benchmark_id: BenchmarkId,
-        _product_id: ProductId,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
     ) -> RepositoryResult<usize> {
-        Ok(1)
+        let removed = self
+            .associations
+            .lock()
+            .unwrap()
+            .remove(&(benchmark_id, product_id));
+        Ok(usize::from(removed.is_some()))
     }
 
     fn set_benchmark_association(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        distance: SimilarityDistance,
+    ) -> RepositoryResult<usize> {
+        self.associations
+            .lock()
+            .unwrap()
+            .insert((benchmark_id, product_id), distance);
+        Ok(1)
+    }
+
+    fn remove_benchmark_association_in_hub(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        hub_id: HubId,
+    ) -> RepositoryResult<usize> {
+        self.ensure_association_in_hub(benchmark_id, product_id, hub_id)?;
+        self.remove_benchmark_association(benchmark_id, product_id)
+    }
+
+    fn set_benchmark_association_in_hub(
+        &self,
+        benchmark_id: BenchmarkId,
+        product_id: ProductId,
+        hub_id: HubId,
+        distance: SimilarityDistance,
+    ) -> RepositoryResult<usize> {
+        self.ensure_association_in_hub(benchmark_id, product_id, hub_id)?;
+        self.set_benchmark_association(benchmark_id, product_id, distance)
+    }
+
+    fn bulk_update_benchmark_distances(
+        &self,
+        associations: &[(BenchmarkId, ProductId, SimilarityDistance)],
+    ) -> RepositoryResult<usize> {
+        let mut stored = self.associations.lock().unwrap();
+        for &(benchmark_id, product_id, distance) in associations {
+            stored.insert((benchmark_id, product_id), distance);
+        }
+        Ok(associations.len())
+    }
+
+    fn archive_benchmark(
         &self,
         _benchmark_id: BenchmarkId,
-        _product_id: ProductId,
-        _distance: SimilarityDistance,
+        _hub_id: HubId,
     ) -> RepositoryResult<usize> {
         Ok(1)
     }
 }
 
+impl AuditLogReader for TestRepository {
+    fn list_audit_entries(
+        &self,
+        _hub_id: HubId,
+        _limit: usize,
+    ) -> RepositoryResult<Vec<AuditEntry>> {
+        Ok(vec![])
+    }
+}
+
+impl AuditLogWriter for TestRepository {
+    fn log_action(&self, entry: &NewAuditEntry) -> RepositoryResult<usize> {
+        self.audit_entries.lock().unwrap().push(entry.clone());
+        Ok(1)
+    }
+}
+
 impl CategoryReader for TestRepository {
     fn list_categories(
         &self,
@@ -276,6 +896,20 @@ impl CategoryReader for TestRepository {
             .find(|c| c.id == id && c.hub_id == hub_id)
             .map(Self::clone_category))
     }
+
+    fn get_category_by_path(
+        &self,
+        path: &CategoryName,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<Category>> {
+        Ok(self
+            .categories
+            .iter()
+            .find(|c| {
+                c.name.as_str().to_lowercase() == path.as_str().to_lowercase() && c.hub_id == hub_id
+            })
+            .map(Self::clone_category))
+    }
 }
 
 impl CategoryWriter for TestRepository {
@@ -296,7 +930,175 @@ impl CategoryWriter for TestRepository {
         Ok(1)
     }
 
+    fn update_category_embedding(
+        &self,
+        _id: CategoryId,
+        _hub_id: HubId,
+        _embedding: &[u8],
+    ) -> RepositoryResult<usize> {
+        Ok(1)
+    }
+
     fn delete_category(&self, _id: CategoryId, _hub_id: HubId) -> RepositoryResult<usize> {
         Ok(1)
     }
+
+    fn merge_categories(
+        &self,
+        _source_id: CategoryId,
+        _target_id: CategoryId,
+        _hub_id: HubId,
+    ) -> RepositoryResult<usize> {
+        Ok(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::domain::types::{
+        BenchmarkName, CategoryAssignmentSource, CrawlerName, CrawlerSelectorValue, CrawlerUrl,
+        ProductAmount, ProductDescription, ProductName, ProductPrice, ProductUnits,
+    };
+
+    fn crawler(id: i32, hub_id: i32) -> Crawler {
+        Crawler {
+            id: CrawlerId::new(id).unwrap(),
+            hub_id: HubId::new(hub_id).unwrap(),
+            name: CrawlerName::new("crawler").unwrap(),
+            url: CrawlerUrl::new(format!("http://example.com/{id}")).unwrap(),
+            selector: CrawlerSelectorValue::new("body").unwrap(),
+            processing: false,
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            num_products: ProductCount::new(0).unwrap(),
+        }
+    }
+
+    fn product(id: i32, crawler_id: i32) -> Product {
+        Product {
+            id: ProductId::new(id).unwrap(),
+            crawler_id: CrawlerId::new(crawler_id).unwrap(),
+            name: ProductName::new("product").unwrap(),
+            sku: ProductSku::new(format!("SKU{id}")).unwrap(),
+            category: None,
+            associated_category: None,
+            units: None,
+            price: ProductPrice::new(1.0).unwrap(),
+            amount: None,
+            description: None,
+            url: None,
+            created_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            embedding: None,
+            category_id: None,
+            category_assignment_source: CategoryAssignmentSource::Automatic,
+            reviewed_at: None,
+            images: vec![],
+        }
+    }
+
+    fn benchmark(id: i32) -> Benchmark {
+        Benchmark {
+            id: BenchmarkId::new(id).unwrap(),
+            hub_id: HubId::new(1).unwrap(),
+            name: BenchmarkName::new("benchmark").unwrap(),
+            sku: BenchmarkSku::new(format!("B{id}")).unwrap(),
+            category: CategoryName::new("cat").unwrap(),
+            units: ProductUnits::new("pcs").unwrap(),
+            price: ProductPrice::new(1.0).unwrap(),
+            amount: ProductAmount::new(1.0).unwrap(),
+            description: ProductDescription::new("desc").unwrap(),
+            created_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            embedding: None,
+            processing: false,
+            num_products: ProductCount::new(0).unwrap(),
+            archived_at: None,
+        }
+    }
+
+    fn distance(value: f32) -> SimilarityDistance {
+        SimilarityDistance::new(value).unwrap()
+    }
+
+    #[test]
+    fn associate_list_distances_and_unassociate_cycle() {
+        let repo = TestRepository::new(
+            vec![crawler(1, 1)],
+            vec![product(1, 1), product(2, 1)],
+            vec![benchmark(1)],
+        );
+        let hub_id = HubId::new(1).unwrap();
+        let benchmark_id = BenchmarkId::new(1).unwrap();
+        let (first, second) = (ProductId::new(1).unwrap(), ProductId::new(2).unwrap());
+
+        repo.set_benchmark_association_in_hub(benchmark_id, first, hub_id, distance(0.4))
+            .unwrap();
+        repo.set_benchmark_association_in_hub(benchmark_id, second, hub_id, distance(0.1))
+            .unwrap();
+
+        let asc = repo
+            .list_distances(benchmark_id, SortDirection::Asc)
+            .unwrap();
+        assert_eq!(asc, vec![(second, distance(0.1)), (first, distance(0.4))]);
+        let desc = repo
+            .list_distances(benchmark_id, SortDirection::Desc)
+            .unwrap();
+        assert_eq!(desc, vec![(first, distance(0.4)), (second, distance(0.1))]);
+        assert_eq!(
+            repo.get_association_distance(benchmark_id, first, hub_id)
+                .unwrap(),
+            Some(distance(0.4))
+        );
+
+        let removed = repo
+            .remove_benchmark_association_in_hub(benchmark_id, first, hub_id)
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(
+            repo.list_distances(benchmark_id, SortDirection::Asc)
+                .unwrap(),
+            vec![(second, distance(0.1))]
+        );
+        assert!(
+            repo.list_benchmarks_for_product(first, hub_id)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn foreign_hub_association_is_rejected_and_seeded_ones_are_kept() {
+        let repo = TestRepository::new(
+            vec![crawler(1, 1), crawler(2, 2)],
+            vec![product(1, 1), product(2, 2)],
+            vec![benchmark(1)],
+        )
+        .with_associations(vec![(
+            BenchmarkId::new(1).unwrap(),
+            ProductId::new(1).unwrap(),
+            distance(0.2),
+        )]);
+        let hub_id = HubId::new(1).unwrap();
+        let benchmark_id = BenchmarkId::new(1).unwrap();
+
+        let result = repo.set_benchmark_association_in_hub(
+            benchmark_id,
+            ProductId::new(2).unwrap(),
+            hub_id,
+            distance(0.3),
+        );
+
+        assert!(matches!(result, Err(RepositoryError::NotFound)));
+        assert_eq!(
+            repo.associations(),
+            vec![(benchmark_id, ProductId::new(1).unwrap(), distance(0.2))]
+        );
+        let benchmarks = repo
+            .list_benchmarks_for_product(ProductId::new(1).unwrap(), hub_id)
+            .unwrap();
+        assert_eq!(benchmarks.len(), 1);
+    }
 }
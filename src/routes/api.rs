@@ -1,22 +1,268 @@
-use actix_web::{HttpResponse, Responder, get, web};
+use std::sync::Arc;
+
+use actix_multipart::form::MultipartForm;
+use actix_web::{HttpResponse, Responder, get, post, web};
+use chrono::SecondsFormat;
 use pushkind_common::domain::auth::AuthenticatedUser;
+use serde::Deserialize;
 
+use crate::dto::api::{ApiErrorDto, CreatedDto, PaginatedDto, ProductChangesDto};
+use crate::dto::import_export::UploadReportDto;
+use crate::forms::import_export::UploadImportForm;
+use crate::models::config::{EmbeddingRefresh, ServerConfig};
 use crate::repository::DieselRepository;
 use crate::services::ServiceError;
-use crate::services::api::{ApiV1ProductsQueryParams, api_v1_products as api_v1_products_service};
+use crate::services::api::{
+    ApiV1ProductChangesQueryParams, ApiV1ProductsQueryParams, NewBenchmarkPayload,
+    api_v1_association_distance as api_v1_association_distance_service,
+    api_v1_benchmark_matches as api_v1_benchmark_matches_service,
+    api_v1_create_benchmark as api_v1_create_benchmark_service,
+    api_v1_product_benchmarks as api_v1_product_benchmarks_service,
+    api_v1_product_by_id as api_v1_product_by_id_service,
+    api_v1_product_changes as api_v1_product_changes_service,
+    api_v1_products as api_v1_products_service,
+};
+use crate::services::benchmarks::{
+    retry_embeddings as retry_embeddings_service,
+    upload_benchmarks_import as upload_benchmarks_import_service,
+};
+use crate::services::products::upload_crawler_products as upload_crawler_products_service;
+use crate::services::rate_limit::RateLimiter;
+use crate::services::retry::SendRetryPolicy;
+use crate::services::statistics::{show_hub_statistics, show_hub_summary};
+use crate::zmq::JobSender;
 
 #[get("/v1/products")]
 pub async fn api_v1_products(
     params: web::Query<ApiV1ProductsQueryParams>,
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
+) -> impl Responder {
+    match api_v1_products_service(
+        params.into_inner(),
+        config.search_min_query_length,
+        &user,
+        repo.get_ref(),
+    ) {
+        Ok(products) => HttpResponse::Ok().json(PaginatedDto::from(products)),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(ServiceError::Form(message)) => {
+            HttpResponse::UnprocessableEntity().json(ApiErrorDto { error: message })
+        }
+        Err(err) => {
+            tracing::error!("Failed to load products via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/products/changes")]
+pub async fn api_v1_product_changes(
+    params: web::Query<ApiV1ProductChangesQueryParams>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    match api_v1_product_changes_service(params.into_inner(), &user, repo.get_ref()) {
+        Ok(changes) => HttpResponse::Ok().json(ProductChangesDto {
+            page: PaginatedDto::from(changes.products),
+            next_since: changes
+                .next_since
+                .and_utc()
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        }),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::Form(message)) => {
+            HttpResponse::UnprocessableEntity().json(ApiErrorDto { error: message })
+        }
+        Err(err) => {
+            tracing::error!("Failed to load product changes via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/v1/benchmarks")]
+pub async fn api_v1_create_benchmark(
+    payload: web::Json<NewBenchmarkPayload>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    match api_v1_create_benchmark_service(payload.into_inner(), &user, repo.get_ref()) {
+        Ok(id) => HttpResponse::Created().json(CreatedDto { id: id.get() }),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::Conflict) => HttpResponse::Conflict().json(ApiErrorDto {
+            error: "Benchmark with this SKU already exists".into(),
+        }),
+        Err(ServiceError::Form(message)) | Err(ServiceError::TypeConstraint(message)) => {
+            HttpResponse::UnprocessableEntity().json(ApiErrorDto { error: message })
+        }
+        Err(err) => {
+            tracing::error!("Failed to create benchmark via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/v1/benchmarks/import")]
+pub async fn api_v1_import_benchmarks(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
+    MultipartForm(mut form): MultipartForm<UploadImportForm>,
+) -> impl Responder {
+    match upload_benchmarks_import_service(&mut form, &user, repo.get_ref()) {
+        Ok(report) => {
+            if report.updated > 0
+                && config.benchmark_embedding_refresh == EmbeddingRefresh::Enqueue
+                && let Err(err) = retry_embeddings_service(
+                    limiter.get_ref(),
+                    retry.get_ref(),
+                    &user,
+                    repo.get_ref(),
+                    zmq_sender.get_ref().as_ref(),
+                )
+                .await
+            {
+                tracing::error!("Failed to enqueue re-embedding after benchmark import: {err}");
+            }
+            HttpResponse::Ok().json(UploadReportDto::from(report))
+        }
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::Form(message)) => {
+            HttpResponse::UnprocessableEntity().json(ApiErrorDto { error: message })
+        }
+        Err(err) => {
+            tracing::error!("Failed to import benchmarks via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ProductImportQuery {
+    pub crawler_id: i32,
+}
+
+#[post("/v1/products/import")]
+pub async fn api_v1_import_products(
+    query: web::Query<ProductImportQuery>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    MultipartForm(mut form): MultipartForm<UploadImportForm>,
+) -> impl Responder {
+    match upload_crawler_products_service(query.crawler_id, &mut form, &user, repo.get_ref()) {
+        Ok(report) => HttpResponse::Ok().json(UploadReportDto::from(report)),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(ServiceError::Form(message)) => {
+            HttpResponse::UnprocessableEntity().json(ApiErrorDto { error: message })
+        }
+        Err(err) => {
+            tracing::error!("Failed to import products via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/benchmarks/{benchmark_id}/matches")]
+pub async fn api_v1_benchmark_matches(
+    benchmark_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    match api_v1_benchmark_matches_service(benchmark_id.into_inner(), &user, repo.get_ref()) {
+        Ok(matches) => HttpResponse::Ok().json(matches),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            tracing::error!("Failed to load benchmark matches via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/benchmarks/{benchmark_id}/products/{product_id}/distance")]
+pub async fn api_v1_association_distance(
+    path: web::Path<(i32, i32)>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
 ) -> impl Responder {
-    match api_v1_products_service(params.into_inner(), &user, repo.get_ref()) {
-        Ok(products) => HttpResponse::Ok().json(products),
+    let (benchmark_id, product_id) = path.into_inner();
+    match api_v1_association_distance_service(benchmark_id, product_id, &user, repo.get_ref()) {
+        Ok(distance) => HttpResponse::Ok().json(distance),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
         Err(err) => {
-            log::error!("Failed to load products via API: {err}");
+            tracing::error!("Failed to load association distance via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/products/{product_id}/benchmarks")]
+pub async fn api_v1_product_benchmarks(
+    product_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    match api_v1_product_benchmarks_service(product_id.into_inner(), &user, repo.get_ref()) {
+        Ok(product_benchmarks) => HttpResponse::Ok().json(product_benchmarks),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            tracing::error!("Failed to load product benchmarks via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/products/{product_id}")]
+pub async fn api_v1_product_by_id(
+    product_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    match api_v1_product_by_id_service(product_id.into_inner(), &user, repo.get_ref()) {
+        Ok(product) => HttpResponse::Ok().json(product),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            tracing::error!("Failed to load product via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/hub/statistics")]
+pub async fn api_v1_hub_statistics(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    match show_hub_statistics(&user, repo.get_ref()) {
+        Ok(statistics) => HttpResponse::Ok().json(statistics),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            tracing::error!("Failed to load hub statistics via API: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/summary")]
+pub async fn api_v1_summary(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    match show_hub_summary(&user, repo.get_ref()) {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            tracing::error!("Failed to load hub summary via API: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
@@ -0,0 +1,37 @@
+use actix_web::{HttpResponse, Responder, get, web};
+use actix_web_flash_messages::IncomingFlashMessages;
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::models::config::CommonServerConfig;
+use pushkind_common::routes::{base_context, redirect, render_template};
+use tera::Tera;
+
+use crate::repository::DieselRepository;
+use crate::services::ServiceError;
+use crate::services::audit::show_audit_log as show_audit_log_service;
+
+#[get("/audit")]
+pub async fn show_audit_log(
+    user: AuthenticatedUser,
+    flash_messages: IncomingFlashMessages,
+    repo: web::Data<DieselRepository>,
+    server_config: web::Data<CommonServerConfig>,
+    tera: web::Data<Tera>,
+) -> impl Responder {
+    match show_audit_log_service(&user, repo.get_ref()) {
+        Ok(entries) => {
+            let mut context = base_context(
+                &flash_messages,
+                &user,
+                "audit",
+                &server_config.auth_service_url,
+            );
+            context.insert("entries", &entries);
+            render_template(&tera, "audit/index.html", &context)
+        }
+        Err(ServiceError::Unauthorized) => redirect("/na"),
+        Err(err) => {
+            tracing::error!("Failed to render audit log: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
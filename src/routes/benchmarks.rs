@@ -5,38 +5,112 @@ use actix_web::{HttpResponse, Responder, get, post, web};
 use actix_web_flash_messages::{FlashMessage, IncomingFlashMessages};
 use pushkind_common::domain::auth::AuthenticatedUser;
 use pushkind_common::models::config::CommonServerConfig;
+use pushkind_common::pagination::Paginated;
 use pushkind_common::routes::{base_context, redirect, render_template};
-use pushkind_common::zmq::ZmqSender;
-use tera::Tera;
+use tera::{Context, Tera};
 
-use crate::forms::benchmarks::{AddBenchmarkForm, AssociateForm, UnassociateForm};
+use crate::domain::benchmark::Benchmark;
+use crate::domain::crawler::Crawler;
+use crate::domain::product::Product;
+use crate::dto::api::{ProductTopMatchDto, SuggestedThresholdDto};
+use crate::dto::benchmark::BenchmarkSummaryDto;
+use crate::forms::benchmarks::{
+    AddBenchmarkForm, AssociateForm, AssociateFormPayload, UnassociateForm, UnassociateFormPayload,
+};
+use crate::forms::categories::CategoryPathLimits;
 use crate::forms::import_export::UploadImportForm;
-use crate::repository::DieselRepository;
+use crate::models::config::{EmbeddingRefresh, ServerConfig};
+use crate::repository::{DieselRepository, SortDirection};
 use crate::services::ServiceError;
 use crate::services::benchmarks::{
-    add_benchmark as add_benchmark_service,
+    BenchmarkFilters, add_benchmark as add_benchmark_service,
+    auto_associate_by_sku as auto_associate_by_sku_service,
     create_benchmark_product as create_benchmark_product_service,
     delete_benchmark_product as delete_benchmark_product_service,
-    download_benchmarks as download_benchmarks_service, match_benchmark as match_benchmark_service,
-    show_benchmark as show_benchmark_service, show_benchmarks as show_benchmarks_service,
+    export_benchmark as export_benchmark_service,
+    export_benchmark_associations as export_benchmark_associations_service,
+    export_benchmarks as export_benchmarks_service, match_benchmark as match_benchmark_service,
+    recalculate_all_distances_for_hub as recalculate_all_distances_for_hub_service,
+    retry_embeddings as retry_embeddings_service, show_benchmark as show_benchmark_service,
+    show_benchmarks as show_benchmarks_service, suggest_threshold as suggest_threshold_service,
+    top_n_matches_for_product as top_n_matches_for_product_service,
     update_benchmark_prices as update_benchmark_prices_service,
     upload_benchmarks_import as upload_benchmarks_import_service,
 };
+use crate::services::rate_limit::RateLimiter;
+use crate::services::retry::SendRetryPolicy;
+use crate::zmq::JobSender;
 
 #[derive(serde::Deserialize)]
 pub struct DownloadQuery {
     pub format: String,
 }
 
+#[derive(serde::Deserialize)]
+pub struct BenchmarkQuery {
+    pub order: Option<String>,
+}
+
+/// Number of benchmarks returned by `/product/{id}/top-matches` by default.
+const DEFAULT_TOP_MATCHES: usize = 10;
+
+#[derive(serde::Deserialize)]
+pub struct TopMatchesQuery {
+    pub n: Option<usize>,
+}
+
+impl BenchmarkQuery {
+    /// Distance ordering requested via `?order=desc`; ascending otherwise.
+    fn direction(&self) -> SortDirection {
+        match self.order.as_deref() {
+            Some(order) if order.eq_ignore_ascii_case("desc") => SortDirection::Desc,
+            _ => SortDirection::Asc,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct BenchmarksQuery {
+    pub category: Option<String>,
+    pub subcategories: Option<bool>,
+    pub unmatched: Option<bool>,
+}
+
+/// Lean benchmark rows for the list view, without embeddings.
+fn benchmark_summaries(benchmarks: Vec<Benchmark>) -> Vec<BenchmarkSummaryDto> {
+    benchmarks
+        .into_iter()
+        .map(BenchmarkSummaryDto::from)
+        .collect()
+}
+
+/// Convert per-crawler product pages into the shape the pagination component renders.
+fn template_crawler_products(
+    products: Vec<(Crawler, crate::pagination::Paginated<Product>)>,
+) -> Vec<(Crawler, Paginated<Product>)> {
+    products
+        .into_iter()
+        .map(|(crawler, page)| (crawler, page.into()))
+        .collect()
+}
+
 #[get("/benchmarks")]
 pub async fn show_benchmarks(
+    params: web::Query<BenchmarksQuery>,
     user: AuthenticatedUser,
     flash_messages: IncomingFlashMessages,
     repo: web::Data<DieselRepository>,
     server_config: web::Data<CommonServerConfig>,
+    limits: web::Data<CategoryPathLimits>,
     tera: web::Data<Tera>,
 ) -> impl Responder {
-    match show_benchmarks_service(&user, repo.get_ref()) {
+    let params = params.into_inner();
+    let filters = BenchmarkFilters {
+        category: params.category,
+        include_subcategories: params.subcategories.unwrap_or(false),
+        only_unmatched: params.unmatched.unwrap_or(false),
+    };
+    match show_benchmarks_service(filters.clone(), limits.get_ref(), &user, repo.get_ref()) {
         Ok(benchmarks) => {
             let mut context = base_context(
                 &flash_messages,
@@ -45,7 +119,13 @@ pub async fn show_benchmarks(
                 &server_config.auth_service_url,
             );
 
-            context.insert("benchmarks", &benchmarks);
+            context.insert("benchmarks", &benchmark_summaries(benchmarks));
+            context.insert(
+                "category_filter",
+                filters.category.as_deref().unwrap_or_default(),
+            );
+            context.insert("include_subcategories", &filters.include_subcategories);
+            context.insert("only_unmatched", &filters.only_unmatched);
 
             render_template(&tera, "benchmarks/index.html", &context)
         }
@@ -56,22 +136,26 @@ pub async fn show_benchmarks(
             redirect("/benchmarks")
         }
         Err(err) => {
-            log::error!("Failed to render benchmarks page: {err}");
+            tracing::error!("Failed to render benchmarks page: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 #[get("/benchmark/{benchmark_id}")]
 pub async fn show_benchmark(
     benchmark_id: web::Path<i32>,
+    params: web::Query<BenchmarkQuery>,
     user: AuthenticatedUser,
     flash_messages: IncomingFlashMessages,
     repo: web::Data<DieselRepository>,
     server_config: web::Data<CommonServerConfig>,
+    config: web::Data<ServerConfig>,
     tera: web::Data<Tera>,
 ) -> impl Responder {
-    match show_benchmark_service(benchmark_id.into_inner(), &user, repo.get_ref()) {
+    let direction = params.direction();
+    match show_benchmark_service(benchmark_id.into_inner(), direction, &user, repo.get_ref()) {
         Ok((benchmark, products, distances)) => {
             let mut context = base_context(
                 &flash_messages,
@@ -80,8 +164,10 @@ pub async fn show_benchmark(
                 &server_config.auth_service_url,
             );
             context.insert("benchmark", &benchmark);
-            context.insert("crawler_products", &products);
+            context.insert("crawler_products", &template_crawler_products(products));
             context.insert("distances", &distances);
+            context.insert("order_desc", &(direction == SortDirection::Desc));
+            context.insert("search_min_query_length", &config.search_min_query_length);
             render_template(&tera, "benchmarks/benchmark.html", &context)
         }
         Err(ServiceError::Unauthorized) => redirect("/na"),
@@ -94,7 +180,84 @@ pub async fn show_benchmark(
             redirect("/benchmarks")
         }
         Err(err) => {
-            log::error!("Failed to render benchmark details: {err}");
+            tracing::error!("Failed to render benchmark details: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/benchmark/{benchmark_id}/fragment")]
+pub async fn show_benchmark_fragment(
+    benchmark_id: web::Path<i32>,
+    params: web::Query<BenchmarkQuery>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    tera: web::Data<Tera>,
+) -> impl Responder {
+    match show_benchmark_service(
+        benchmark_id.into_inner(),
+        params.direction(),
+        &user,
+        repo.get_ref(),
+    ) {
+        Ok((benchmark, products, distances)) => {
+            let mut context = Context::new();
+            context.insert("benchmark", &benchmark);
+            context.insert("crawler_products", &template_crawler_products(products));
+            context.insert("distances", &distances);
+            render_template(&tera, "benchmarks/fragment.html", &context)
+        }
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(ServiceError::Form(message)) => HttpResponse::BadRequest().body(message),
+        Err(err) => {
+            tracing::error!("Failed to render benchmark fragment: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/benchmark/{benchmark_id}/suggest-threshold")]
+pub async fn suggest_threshold(
+    benchmark_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    match suggest_threshold_service(benchmark_id.into_inner(), &user, repo.get_ref()) {
+        Ok(threshold) => HttpResponse::Ok().json(SuggestedThresholdDto {
+            threshold: threshold.get(),
+        }),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            tracing::error!("Failed to suggest threshold: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/product/{product_id}/top-matches")]
+pub async fn product_top_matches(
+    product_id: web::Path<i32>,
+    query: web::Query<TopMatchesQuery>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    let n = query.n.unwrap_or(DEFAULT_TOP_MATCHES);
+    match top_n_matches_for_product_service(product_id.into_inner(), n, &user, repo.get_ref()) {
+        Ok(matches) => HttpResponse::Ok().json(
+            matches
+                .into_iter()
+                .map(|(benchmark, distance)| ProductTopMatchDto {
+                    benchmark,
+                    distance: distance.get(),
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            tracing::error!("Failed to list top matches for product: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
@@ -115,6 +278,9 @@ pub async fn add_benchmark(
         Err(ServiceError::NotFound) => {
             FlashMessage::error("Бенчмарк не существует").send();
         }
+        Err(ServiceError::Conflict) => {
+            FlashMessage::error("Бенчмарк с таким артикулом уже существует").send();
+        }
         Err(ServiceError::Form(message)) => {
             FlashMessage::error(message).send();
         }
@@ -122,7 +288,7 @@ pub async fn add_benchmark(
             return HttpResponse::InternalServerError().finish();
         }
         Err(err) => {
-            log::error!("Failed to add benchmark: {err}");
+            tracing::error!("Failed to add benchmark: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -135,10 +301,14 @@ pub async fn match_benchmark(
     benchmark_id: web::Path<i32>,
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
-    zmq_sender: web::Data<Arc<ZmqSender>>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
 ) -> impl Responder {
     match match_benchmark_service(
         benchmark_id.into_inner(),
+        limiter.get_ref(),
+        retry.get_ref(),
         &user,
         repo.get_ref(),
         zmq_sender.get_ref().as_ref(),
@@ -160,7 +330,87 @@ pub async fn match_benchmark(
             return HttpResponse::InternalServerError().finish();
         }
         Err(err) => {
-            log::error!("Failed to queue benchmark matching: {err}");
+            tracing::error!("Failed to queue benchmark matching: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect("/benchmarks")
+}
+
+#[post("/benchmarks/retry-embeddings")]
+pub async fn retry_embeddings(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
+) -> impl Responder {
+    match retry_embeddings_service(
+        limiter.get_ref(),
+        retry.get_ref(),
+        &user,
+        repo.get_ref(),
+        zmq_sender.get_ref().as_ref(),
+    )
+    .await
+    {
+        Ok(0) => FlashMessage::info("Нет бенчмарков без эмбеддингов.").send(),
+        Ok(requeued) => FlashMessage::success(format!(
+            "Повторно поставлено в очередь бенчмарков: {requeued}."
+        ))
+        .send(),
+        Err(ServiceError::Unauthorized) => {
+            return redirect("/na");
+        }
+        Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
+        Err(ServiceError::Internal) => {
+            return HttpResponse::InternalServerError().finish();
+        }
+        Err(err) => {
+            tracing::error!("Failed to retry benchmark embeddings: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect("/benchmarks")
+}
+
+#[post("/hub/recalculate-distances")]
+pub async fn recalculate_distances(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
+) -> impl Responder {
+    match recalculate_all_distances_for_hub_service(
+        limiter.get_ref(),
+        retry.get_ref(),
+        &user,
+        repo.get_ref(),
+        zmq_sender.get_ref().as_ref(),
+    )
+    .await
+    {
+        Ok(result) if result.failed.is_empty() => FlashMessage::success(format!(
+            "Пересчёт расстояний запущен для бенчмарков: {}, уже в обработке: {}.",
+            result.dispatched, result.skipped
+        ))
+        .send(),
+        Ok(result) => FlashMessage::warning(format!(
+            "Пересчёт расстояний запущен для бенчмарков: {}, уже в обработке: {}, не отправлено: {}.",
+            result.dispatched,
+            result.skipped,
+            result.failed.len()
+        ))
+        .send(),
+        Err(ServiceError::Unauthorized) => {
+            return redirect("/na");
+        }
+        Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
+        Err(err) => {
+            tracing::error!("Failed to recalculate benchmark distances: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -169,16 +419,41 @@ pub async fn match_benchmark(
 }
 
 #[post("/benchmarks/upload")]
+#[allow(clippy::too_many_arguments)]
 pub async fn upload_benchmarks(
     user: AuthenticatedUser,
     flash_messages: IncomingFlashMessages,
     repo: web::Data<DieselRepository>,
     server_config: web::Data<CommonServerConfig>,
+    config: web::Data<ServerConfig>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
+    limits: web::Data<CategoryPathLimits>,
     tera: web::Data<Tera>,
     MultipartForm(mut form): MultipartForm<UploadImportForm>,
 ) -> impl Responder {
     match upload_benchmarks_import_service(&mut form, &user, repo.get_ref()) {
         Ok(report) => {
+            if report.updated > 0
+                && config.benchmark_embedding_refresh == EmbeddingRefresh::Enqueue
+                && let Err(err) = retry_embeddings_service(
+                    limiter.get_ref(),
+                    retry.get_ref(),
+                    &user,
+                    repo.get_ref(),
+                    zmq_sender.get_ref().as_ref(),
+                )
+                .await
+            {
+                tracing::error!("Failed to enqueue re-embedding after benchmark upload: {err}");
+            }
+
+            if report.total_rows == 0 {
+                FlashMessage::warning("Файл не содержит строк данных: обработано 0 строк.").send();
+                return redirect("/benchmarks");
+            }
+
             if report.errors.is_empty() {
                 FlashMessage::success(format!(
                     "Загрузка завершена: создано {}, обновлено {}.",
@@ -188,7 +463,12 @@ pub async fn upload_benchmarks(
                 return redirect("/benchmarks");
             }
 
-            let benchmarks = match show_benchmarks_service(&user, repo.get_ref()) {
+            let benchmarks = match show_benchmarks_service(
+                BenchmarkFilters::default(),
+                limits.get_ref(),
+                &user,
+                repo.get_ref(),
+            ) {
                 Ok(benchmarks) => benchmarks,
                 Err(ServiceError::Unauthorized) => return redirect("/na"),
                 Err(_) => {
@@ -203,7 +483,7 @@ pub async fn upload_benchmarks(
                 "benchmarks",
                 &server_config.auth_service_url,
             );
-            context.insert("benchmarks", &benchmarks);
+            context.insert("benchmarks", &benchmark_summaries(benchmarks));
             context.insert("upload_report", &report);
             return render_template(&tera, "benchmarks/index.html", &context);
         }
@@ -220,7 +500,7 @@ pub async fn upload_benchmarks(
             FlashMessage::error(message).send();
         }
         Err(err) => {
-            log::error!("Failed to upload benchmarks: {err}");
+            tracing::error!("Failed to upload benchmarks: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -233,20 +513,98 @@ pub async fn download_benchmarks(
     params: web::Query<DownloadQuery>,
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
+) -> impl Responder {
+    match export_benchmarks_service(&params.format, &config.price_format, &user, repo.get_ref()) {
+        Ok(file) => HttpResponse::Ok()
+            .append_header(("Content-Type", file.content_type))
+            .append_header(("Content-Disposition", file.content_disposition()))
+            .body(file.bytes),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(ServiceError::Form(message)) => HttpResponse::BadRequest().body(message),
+        Err(err) => {
+            tracing::error!("Failed to download benchmarks: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/benchmarks/export")]
+pub async fn export_benchmarks(
+    params: web::Query<DownloadQuery>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
+) -> impl Responder {
+    match export_benchmarks_service(&params.format, &config.price_format, &user, repo.get_ref()) {
+        Ok(file) => HttpResponse::Ok()
+            .append_header(("Content-Type", file.content_type))
+            .append_header(("Content-Disposition", file.content_disposition()))
+            .body(file.bytes),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(ServiceError::Form(message)) => HttpResponse::BadRequest().body(message),
+        Err(err) => {
+            tracing::error!("Failed to export benchmarks: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/benchmark/{benchmark_id}/export")]
+pub async fn export_benchmark(
+    benchmark_id: web::Path<i32>,
+    params: web::Query<DownloadQuery>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
 ) -> impl Responder {
-    match download_benchmarks_service(&params.format, &user, repo.get_ref()) {
+    match export_benchmark_service(
+        benchmark_id.into_inner(),
+        &params.format,
+        &config.price_format,
+        &user,
+        repo.get_ref(),
+    ) {
         Ok(file) => HttpResponse::Ok()
             .append_header(("Content-Type", file.content_type))
-            .append_header((
-                "Content-Disposition",
-                format!("attachment; filename=\"{}\"", file.file_name),
-            ))
+            .append_header(("Content-Disposition", file.content_disposition()))
             .body(file.bytes),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
         Err(ServiceError::Form(message)) => HttpResponse::BadRequest().body(message),
         Err(err) => {
-            log::error!("Failed to download benchmarks: {err}");
+            tracing::error!("Failed to export benchmark: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/benchmark/{benchmark_id}/associations/export")]
+pub async fn export_benchmark_associations(
+    benchmark_id: web::Path<i32>,
+    params: web::Query<DownloadQuery>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
+) -> impl Responder {
+    match export_benchmark_associations_service(
+        benchmark_id.into_inner(),
+        &params.format,
+        &config.price_format,
+        &user,
+        repo.get_ref(),
+    ) {
+        Ok(file) => HttpResponse::Ok()
+            .append_header(("Content-Type", file.content_type))
+            .append_header(("Content-Disposition", file.content_disposition()))
+            .body(file.bytes),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(ServiceError::Form(message)) => HttpResponse::BadRequest().body(message),
+        Err(err) => {
+            tracing::error!("Failed to export benchmark associations: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
@@ -257,10 +615,12 @@ pub async fn update_benchmark_prices(
     benchmark_id: web::Path<i32>,
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
-    zmq_sender: web::Data<Arc<ZmqSender>>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
 ) -> impl Responder {
     match update_benchmark_prices_service(
         benchmark_id.into_inner(),
+        limiter.get_ref(),
         &user,
         repo.get_ref(),
         zmq_sender.get_ref().as_ref(),
@@ -268,12 +628,21 @@ pub async fn update_benchmark_prices(
     .await
     {
         Ok(results) => {
-            for (selector, sent) in results {
-                if sent {
-                    FlashMessage::success(format!("Обработка запущена для {selector}")).send();
+            for result in results {
+                if result.sent {
+                    FlashMessage::success(format!(
+                        "Обработка запущена для {}: товаров {}",
+                        result.selector, result.product_count
+                    ))
+                    .send();
                 } else {
-                    FlashMessage::error(format!("Не удалось начать обработку для {selector}"))
-                        .send();
+                    FlashMessage::error(format!(
+                        "Не удалось начать обработку для {} (товаров {}): {}",
+                        result.selector,
+                        result.product_count,
+                        result.error.as_deref().unwrap_or("неизвестная ошибка")
+                    ))
+                    .send();
                 }
             }
         }
@@ -290,7 +659,7 @@ pub async fn update_benchmark_prices(
             return HttpResponse::InternalServerError().finish();
         }
         Err(err) => {
-            log::error!("Failed to update benchmark prices: {err}");
+            tracing::error!("Failed to update benchmark prices: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -305,7 +674,15 @@ pub async fn delete_benchmark_product(
     web::Form(form): web::Form<UnassociateForm>,
 ) -> impl Responder {
     let benchmark_id = form.benchmark_id;
-    match delete_benchmark_product_service(form, &user, repo.get_ref()) {
+    let payload: UnassociateFormPayload = match form.try_into() {
+        Ok(payload) => payload,
+        Err(e) => {
+            FlashMessage::error(e.to_string()).send();
+            return redirect(&format!("/benchmark/{benchmark_id}"));
+        }
+    };
+
+    match delete_benchmark_product_service(payload, &user, repo.get_ref()) {
         Ok(true) => FlashMessage::success("Мэтчинг удален.").send(),
         Ok(false) => FlashMessage::error("Ошибка при удалении мэтчинга").send(),
         Err(ServiceError::Unauthorized) => {
@@ -321,7 +698,38 @@ pub async fn delete_benchmark_product(
             return HttpResponse::InternalServerError().finish();
         }
         Err(err) => {
-            log::error!("Failed to remove benchmark association: {err}");
+            tracing::error!("Failed to remove benchmark association: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect(&format!("/benchmark/{benchmark_id}"))
+}
+
+#[post("/benchmark/{benchmark_id}/associate-by-sku")]
+pub async fn associate_benchmark_by_sku(
+    benchmark_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
+) -> impl Responder {
+    let benchmark_id = benchmark_id.into_inner();
+    match auto_associate_by_sku_service(
+        benchmark_id,
+        config.similarity_threshold,
+        &user,
+        repo.get_ref(),
+    ) {
+        Ok(count) => FlashMessage::success(format!("Добавлено мэтчингов по SKU: {count}")).send(),
+        Err(ServiceError::Unauthorized) => {
+            return redirect("/na");
+        }
+        Err(ServiceError::NotFound) => {
+            FlashMessage::error("Бенчмарк не существует").send();
+            return redirect("/benchmarks");
+        }
+        Err(err) => {
+            tracing::error!("Failed to associate benchmark by SKU: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -333,10 +741,24 @@ pub async fn delete_benchmark_product(
 pub async fn create_benchmark_product(
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
     web::Form(form): web::Form<AssociateForm>,
 ) -> impl Responder {
     let benchmark_id = form.benchmark_id;
-    match create_benchmark_product_service(form, &user, repo.get_ref()) {
+    let payload: AssociateFormPayload = match form.try_into() {
+        Ok(payload) => payload,
+        Err(e) => {
+            FlashMessage::error(e.to_string()).send();
+            return redirect(&format!("/benchmark/{benchmark_id}"));
+        }
+    };
+
+    match create_benchmark_product_service(
+        payload,
+        config.manual_match_distance,
+        &user,
+        repo.get_ref(),
+    ) {
         Ok(true) => FlashMessage::success("Мэтчинг добавлен.").send(),
         Ok(false) => FlashMessage::error("Ошибка при добавлении мэтчинга").send(),
         Err(ServiceError::Unauthorized) => {
@@ -352,7 +774,7 @@ pub async fn create_benchmark_product(
             return HttpResponse::InternalServerError().finish();
         }
         Err(err) => {
-            log::error!("Failed to create benchmark association: {err}");
+            tracing::error!("Failed to create benchmark association: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -1,30 +1,40 @@
 use std::sync::Arc;
 
+use actix_multipart::form::MultipartForm;
 use actix_web::{HttpRequest, HttpResponse, Responder, get, post, web};
 use actix_web_flash_messages::{FlashMessage, IncomingFlashMessages};
 use pushkind_common::domain::auth::AuthenticatedUser;
 use pushkind_common::models::config::CommonServerConfig;
 use pushkind_common::routes::{base_context, redirect, render_template};
-use pushkind_common::zmq::ZmqSender;
 use tera::Tera;
 
 use crate::forms::categories::{
-    AddCategoryForm, AddCategoryFormPayload, ClearProductCategoryForm,
-    ClearProductCategoryFormPayload, DeleteCategoryForm, DeleteCategoryFormPayload,
+    AddCategoryForm, AddCategoryFormPayload, BulkSetCategoryForm, BulkSetCategoryFormPayload,
+    CategoryPathLimits, ClearProductCategoryForm, ClearProductCategoryFormPayload,
+    DeleteCategoryForm, DeleteCategoryFormPayload, MergeCategoriesForm, MergeCategoriesFormPayload,
     SetProductCategoryForm, SetProductCategoryFormPayload, UpdateCategoryForm,
     UpdateCategoryFormPayload,
 };
+use crate::forms::import_export::UploadImportForm;
 use crate::repository::DieselRepository;
 use crate::services::ServiceError;
 use crate::services::categories::{
     add_category as add_category_service,
+    bulk_set_product_categories as bulk_set_product_categories_service,
     can_match_product_categories as can_match_product_categories_service,
     clear_product_category_manual as clear_product_category_service,
-    delete_category as delete_category_service,
+    delete_category as delete_category_service, import_categories as import_categories_service,
     match_product_categories as match_product_categories_service,
+    merge_categories as merge_categories_service,
+    request_recategorize_automatic as request_recategorize_automatic_service,
     set_product_category_manual as set_product_category_service,
-    show_categories as show_categories_service, update_category as update_category_service,
+    show_categories as show_categories_service,
+    trigger_category_match_for_crawler as trigger_category_match_for_crawler_service,
+    update_category as update_category_service,
 };
+use crate::services::rate_limit::RateLimiter;
+use crate::services::retry::SendRetryPolicy;
+use crate::zmq::JobSender;
 
 #[get("/categories")]
 pub async fn show_categories(
@@ -46,7 +56,7 @@ pub async fn show_categories(
                         return redirect("/categories");
                     }
                     Err(err) => {
-                        log::error!("Failed to read category matching availability: {err}");
+                        tracing::error!("Failed to read category matching availability: {err}");
                         return HttpResponse::InternalServerError().finish();
                     }
                 };
@@ -68,7 +78,7 @@ pub async fn show_categories(
             redirect("/categories")
         }
         Err(err) => {
-            log::error!("Failed to render categories page: {err}");
+            tracing::error!("Failed to render categories page: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
@@ -78,6 +88,7 @@ pub async fn show_categories(
 pub async fn add_category(
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
+    limits: web::Data<CategoryPathLimits>,
     web::Form(form): web::Form<AddCategoryForm>,
 ) -> impl Responder {
     let payload: AddCategoryFormPayload = match form.try_into() {
@@ -88,7 +99,7 @@ pub async fn add_category(
         }
     };
 
-    match add_category_service(payload, &user, repo.get_ref()) {
+    match add_category_service(payload, limits.get_ref(), &user, repo.get_ref()) {
         Ok(true) => FlashMessage::success("Категория добавлена.").send(),
         Ok(false) => FlashMessage::error("Ошибка при добавлении категории.").send(),
         Err(ServiceError::Unauthorized) => return redirect("/na"),
@@ -96,7 +107,46 @@ pub async fn add_category(
         Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
         Err(ServiceError::Internal) => return HttpResponse::InternalServerError().finish(),
         Err(err) => {
-            log::error!("Failed to add category: {err}");
+            tracing::error!("Failed to add category: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect("/categories")
+}
+
+#[post("/categories/import")]
+pub async fn import_categories(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    limits: web::Data<CategoryPathLimits>,
+    MultipartForm(mut form): MultipartForm<UploadImportForm>,
+) -> impl Responder {
+    match import_categories_service(&mut form, limits.get_ref(), &user, repo.get_ref()) {
+        Ok(report) if report.total_rows == 0 => {
+            FlashMessage::warning("Файл не содержит строк данных: обработано 0 строк.").send()
+        }
+        Ok(report) if report.errors.is_empty() => {
+            FlashMessage::success(format!("Категории добавлены: {}.", report.created)).send()
+        }
+        Ok(report) => {
+            let details = report
+                .errors
+                .iter()
+                .take(5)
+                .map(|error| format!("строка {}: {}", error.row_number, error.message))
+                .collect::<Vec<_>>()
+                .join("; ");
+            FlashMessage::warning(format!(
+                "Категории добавлены: {}, пропущено: {} ({details}).",
+                report.created, report.skipped
+            ))
+            .send()
+        }
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
+        Err(err) => {
+            tracing::error!("Failed to import categories: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -109,6 +159,7 @@ pub async fn update_category(
     category_id: web::Path<i32>,
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
+    limits: web::Data<CategoryPathLimits>,
     web::Form(form): web::Form<UpdateCategoryForm>,
 ) -> impl Responder {
     let mut payload: UpdateCategoryFormPayload = match form.try_into() {
@@ -127,7 +178,7 @@ pub async fn update_category(
         }
     };
 
-    match update_category_service(payload, &user, repo.get_ref()) {
+    match update_category_service(payload, limits.get_ref(), &user, repo.get_ref()) {
         Ok(true) => FlashMessage::success("Категория обновлена.").send(),
         Ok(false) => FlashMessage::error("Ошибка при обновлении категории.").send(),
         Err(ServiceError::Unauthorized) => return redirect("/na"),
@@ -135,7 +186,7 @@ pub async fn update_category(
         Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
         Err(ServiceError::Internal) => return HttpResponse::InternalServerError().finish(),
         Err(err) => {
-            log::error!("Failed to update category: {err}");
+            tracing::error!("Failed to update category: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -174,7 +225,39 @@ pub async fn delete_category(
         Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
         Err(ServiceError::Internal) => return HttpResponse::InternalServerError().finish(),
         Err(err) => {
-            log::error!("Failed to delete category: {err}");
+            tracing::error!("Failed to delete category: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect("/categories")
+}
+
+#[post("/categories/merge")]
+pub async fn merge_categories(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    web::Form(form): web::Form<MergeCategoriesForm>,
+) -> impl Responder {
+    let payload: MergeCategoriesFormPayload = match form.try_into() {
+        Ok(payload) => payload,
+        Err(e) => {
+            FlashMessage::error(e.to_string()).send();
+            return redirect("/categories");
+        }
+    };
+
+    match merge_categories_service(payload, &user, repo.get_ref()) {
+        Ok(reassigned) => FlashMessage::success(format!(
+            "Категории объединены. Перенесено товаров: {reassigned}."
+        ))
+        .send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::NotFound) => FlashMessage::error("Категория не найдена.").send(),
+        Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
+        Err(ServiceError::Internal) => return HttpResponse::InternalServerError().finish(),
+        Err(err) => {
+            tracing::error!("Failed to merge categories: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -222,7 +305,60 @@ pub async fn set_product_category_manual(
         Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
         Err(ServiceError::Internal) => return HttpResponse::InternalServerError().finish(),
         Err(err) => {
-            log::error!("Failed to set manual product category: {err}");
+            tracing::error!("Failed to set manual product category: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect(redirect_to)
+}
+
+#[post("/products/bulk-set-category")]
+pub async fn bulk_set_product_categories(
+    request: HttpRequest,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    body: web::Bytes,
+) -> impl Responder {
+    let redirect_to = request
+        .headers()
+        .get("referer")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("/");
+
+    let form: BulkSetCategoryForm = match serde_html_form::from_bytes(&body) {
+        Ok(form) => form,
+        Err(e) => {
+            FlashMessage::error(e.to_string()).send();
+            return redirect(redirect_to);
+        }
+    };
+
+    let payload: BulkSetCategoryFormPayload = match form.try_into() {
+        Ok(payload) => payload,
+        Err(e) => {
+            FlashMessage::error(e.to_string()).send();
+            return redirect(redirect_to);
+        }
+    };
+
+    match bulk_set_product_categories_service(payload, &user, repo.get_ref()) {
+        Ok(outcome) if outcome.skipped.is_empty() => {
+            FlashMessage::success(format!("Категория назначена товарам: {}.", outcome.updated))
+                .send()
+        }
+        Ok(outcome) => FlashMessage::warning(format!(
+            "Категория назначена товарам: {}. Пропущено: {}.",
+            outcome.updated,
+            outcome.skipped.len()
+        ))
+        .send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::NotFound) => FlashMessage::error("Категория не найдена.").send(),
+        Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
+        Err(ServiceError::Internal) => return HttpResponse::InternalServerError().finish(),
+        Err(err) => {
+            tracing::error!("Failed to bulk set product categories: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -268,7 +404,7 @@ pub async fn clear_product_category_manual(
         Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
         Err(ServiceError::Internal) => return HttpResponse::InternalServerError().finish(),
         Err(err) => {
-            log::error!("Failed to clear manual product category: {err}");
+            tracing::error!("Failed to clear manual product category: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
@@ -280,10 +416,18 @@ pub async fn clear_product_category_manual(
 pub async fn match_product_categories(
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
-    zmq_sender: web::Data<Arc<ZmqSender>>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
 ) -> impl Responder {
-    match match_product_categories_service(&user, repo.get_ref(), zmq_sender.get_ref().as_ref())
-        .await
+    match match_product_categories_service(
+        limiter.get_ref(),
+        retry.get_ref(),
+        &user,
+        repo.get_ref(),
+        zmq_sender.get_ref().as_ref(),
+    )
+    .await
     {
         Ok(true) => FlashMessage::success("Матчинг категорий по товарам запущен.").send(),
         Ok(false) => FlashMessage::error("Не удалось запустить матчинг категорий.").send(),
@@ -292,10 +436,80 @@ pub async fn match_product_categories(
         Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
         Err(ServiceError::Internal) => return HttpResponse::InternalServerError().finish(),
         Err(err) => {
-            log::error!("Failed to enqueue product category matching: {err}");
+            tracing::error!("Failed to enqueue product category matching: {err}");
             return HttpResponse::InternalServerError().finish();
         }
     }
 
     redirect("/categories")
 }
+
+#[post("/categories/recategorize-automatic")]
+pub async fn recategorize_automatic(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
+) -> impl Responder {
+    match request_recategorize_automatic_service(
+        limiter.get_ref(),
+        retry.get_ref(),
+        &user,
+        repo.get_ref(),
+        zmq_sender.get_ref().as_ref(),
+    )
+    .await
+    {
+        Ok(true) => {
+            FlashMessage::success("Повторный матчинг автоматических категорий запущен.").send()
+        }
+        Ok(false) => FlashMessage::error("Не удалось запустить повторный матчинг.").send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
+        Err(ServiceError::Internal) => return HttpResponse::InternalServerError().finish(),
+        Err(err) => {
+            tracing::error!("Failed to enqueue automatic recategorization: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect("/categories")
+}
+
+#[post("/crawler/{crawler_id}/match-categories")]
+pub async fn match_crawler_categories(
+    crawler_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
+) -> impl Responder {
+    let crawler_id = crawler_id.into_inner();
+    match trigger_category_match_for_crawler_service(
+        crawler_id,
+        limiter.get_ref(),
+        retry.get_ref(),
+        &user,
+        repo.get_ref(),
+        zmq_sender.get_ref().as_ref(),
+    )
+    .await
+    {
+        Ok(true) => FlashMessage::success("Матчинг категорий парсера запущен.").send(),
+        Ok(false) => FlashMessage::error("Не удалось запустить матчинг категорий.").send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::NotFound) => {
+            FlashMessage::error("Парсер не найден.").send();
+            return redirect("/");
+        }
+        Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
+        Err(err) => {
+            tracing::error!("Failed to enqueue crawler category matching: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect(&format!("/crawler/{crawler_id}"))
+}
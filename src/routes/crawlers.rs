@@ -0,0 +1,48 @@
+use actix_multipart::form::MultipartForm;
+use actix_web::{HttpResponse, Responder, post, web};
+use actix_web_flash_messages::FlashMessage;
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::routes::redirect;
+
+use crate::forms::import_export::UploadImportForm;
+use crate::repository::DieselRepository;
+use crate::services::ServiceError;
+use crate::services::crawlers::import_crawlers as import_crawlers_service;
+
+#[post("/crawlers/import")]
+pub async fn import_crawlers(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    MultipartForm(mut form): MultipartForm<UploadImportForm>,
+) -> impl Responder {
+    match import_crawlers_service(&mut form, &user, repo.get_ref()) {
+        Ok(report) if report.total_rows == 0 => {
+            FlashMessage::warning("Файл не содержит строк данных: обработано 0 строк.").send()
+        }
+        Ok(report) if report.errors.is_empty() => {
+            FlashMessage::success(format!("Парсеры добавлены: {}.", report.created)).send()
+        }
+        Ok(report) => {
+            let details = report
+                .errors
+                .iter()
+                .take(5)
+                .map(|error| format!("строка {}: {}", error.row_number, error.message))
+                .collect::<Vec<_>>()
+                .join("; ");
+            FlashMessage::warning(format!(
+                "Парсеры добавлены: {}, пропущено: {} ({details}).",
+                report.created, report.skipped
+            ))
+            .send()
+        }
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
+        Err(err) => {
+            tracing::error!("Failed to import crawlers: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect("/")
+}
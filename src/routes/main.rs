@@ -1,13 +1,30 @@
-use actix_web::{HttpResponse, Responder, get, web};
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, get, post, web};
 use actix_web_flash_messages::{FlashMessage, IncomingFlashMessages};
+use chrono::TimeDelta;
 use pushkind_common::domain::auth::AuthenticatedUser;
 use pushkind_common::models::config::CommonServerConfig;
 use pushkind_common::routes::{base_context, redirect, render_template};
+use serde::Deserialize;
 use tera::Tera;
 
+use crate::forms::crawlers::ProcessCrawlerForm;
+use crate::models::config::ServerConfig;
 use crate::repository::DieselRepository;
 use crate::services::ServiceError;
+use crate::services::import_export::download_upload_template as download_upload_template_service;
 use crate::services::main::show_index as show_index_service;
+use crate::services::products::crawl_crawler as crawl_crawler_service;
+use crate::services::rate_limit::RateLimiter;
+use crate::services::retry::SendRetryPolicy;
+use crate::zmq::JobSender;
+
+#[derive(Deserialize)]
+struct TemplateQuery {
+    target: String,
+    format: String,
+}
 
 #[get("/")]
 pub async fn index(
@@ -15,9 +32,11 @@ pub async fn index(
     flash_messages: IncomingFlashMessages,
     repo: web::Data<DieselRepository>,
     server_config: web::Data<CommonServerConfig>,
+    config: web::Data<ServerConfig>,
     tera: web::Data<Tera>,
 ) -> impl Responder {
-    match show_index_service(&user, repo.get_ref()) {
+    let staleness = TimeDelta::hours(i64::from(config.crawler_staleness_hours));
+    match show_index_service(staleness, &user, repo.get_ref()) {
         Ok(crawlers) => {
             let mut context = base_context(
                 &flash_messages,
@@ -37,8 +56,71 @@ pub async fn index(
             redirect("/")
         }
         Err(err) => {
-            log::error!("Failed to render index page: {err}");
+            tracing::error!("Failed to render index page: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/import/template")]
+pub async fn download_upload_template(
+    params: web::Query<TemplateQuery>,
+    user: AuthenticatedUser,
+) -> impl Responder {
+    match download_upload_template_service(&params.target, &params.format, &user) {
+        Ok(file) => HttpResponse::Ok()
+            .append_header(("Content-Type", file.content_type))
+            .append_header(("Content-Disposition", file.content_disposition()))
+            .body(file.bytes),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::Form(message)) => HttpResponse::BadRequest().body(message),
+        Err(err) => {
+            tracing::error!("Failed to render upload template: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
 }
+
+/// Starts a crawl for the crawler selected in the posted form.
+///
+/// Same dispatch as `POST /crawler/{crawler_id}/crawl`, for forms that submit
+/// the crawler id as a field.
+#[post("/crawler/process")]
+pub async fn process_crawler(
+    form: web::Form<ProcessCrawlerForm>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
+) -> impl Responder {
+    match crawl_crawler_service(
+        form.crawler_id,
+        limiter.get_ref(),
+        retry.get_ref(),
+        &user,
+        repo.get_ref(),
+        zmq_sender.get_ref().as_ref(),
+    )
+    .await
+    {
+        Ok(true) => FlashMessage::success("Обработка запущена").send(),
+        Ok(false) => FlashMessage::error("Не удалось начать обработку.").send(),
+        Err(ServiceError::Form(message)) => FlashMessage::error(message).send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::NotFound) => FlashMessage::error("Парсер не существует").send(),
+        Err(err) => {
+            tracing::error!("Failed to process crawler: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect("/")
+}
+
+/// Fallback for requests that match no route.
+pub async fn not_found_handler() -> impl Responder {
+    HttpResponse::NotFound()
+        .content_type("text/plain; charset=utf-8")
+        .body("Страница не найдена")
+}
@@ -1,5 +1,49 @@
+use pushkind_common::pagination::Paginated as TemplatePaginated;
+use serde::Serialize;
+use tera::Context;
+
+use crate::pagination::Paginated;
+
 pub mod api;
+pub mod audit;
 pub mod benchmarks;
 pub mod categories;
+pub mod crawlers;
 pub mod main;
 pub mod products;
+pub mod search;
+
+/// Insert a page of items into a template context.
+///
+/// Sets `{key}` to the items with their page links, as expected by
+/// `components/pagination.html`, plus `{key}_page` and `{key}_total_pages`.
+pub fn pagination_context<T>(context: &mut Context, key: &str, paginated: &Paginated<T>)
+where
+    T: Serialize + Clone,
+{
+    context.insert(key, &TemplatePaginated::from(paginated.clone()));
+    context.insert(format!("{key}_page"), &paginated.page);
+    context.insert(format!("{key}_total_pages"), &paginated.total_pages);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pagination_context_inserts_items_and_page_keys() {
+        let mut context = Context::new();
+        let paginated = Paginated::new(vec!["first", "second"], 2, 4, 2);
+
+        pagination_context(&mut context, "products", &paginated);
+
+        let json = context.into_json();
+        assert_eq!(
+            json["products"]["items"],
+            serde_json::json!(["first", "second"])
+        );
+        assert_eq!(json["products"]["page"], 2);
+        assert_eq!(json["products_page"], 2);
+        assert_eq!(json["products_total_pages"], 2);
+    }
+}
@@ -1,29 +1,69 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use actix_multipart::form::MultipartForm;
-use actix_web::{HttpResponse, Responder, get, post, web};
+use actix_session::Session;
+use actix_web::{HttpRequest, HttpResponse, Responder, get, post, web};
 use actix_web_flash_messages::{FlashMessage, IncomingFlashMessages};
+use futures_util::stream;
 use pushkind_common::domain::auth::AuthenticatedUser;
 use pushkind_common::models::config::CommonServerConfig;
 use pushkind_common::routes::{base_context, redirect, render_template};
-use pushkind_common::zmq::ZmqSender;
 use serde::Deserialize;
 use tera::Tera;
+use uuid::Uuid;
 
+use crate::dto::api::ApiErrorDto;
+use crate::dto::products::{CrawlerCategoryCountDto, ProductSummaryDto};
+use crate::forms::categories::CategoryPathLimits;
 use crate::forms::import_export::UploadImportForm;
+use crate::forms::products::{
+    MoveProductsForm, MoveProductsFormPayload, ProductImagesForm, ProductImagesFormPayload,
+    UpdateProductForm, UpdateProductFormPayload,
+};
+use crate::models::config::ServerConfig;
 use crate::repository::DieselRepository;
+use crate::routes::pagination_context;
 use crate::services::ServiceError;
-use crate::services::categories::show_categories as show_categories_service;
+use crate::services::categories::{
+    show_categories as show_categories_service,
+    upload_product_categories as upload_product_categories_service,
+};
+use crate::services::import_export::{
+    DownloadFormat, IMPORT_SESSION_KEY, ImportSessionStore, UploadReport,
+    begin_import_session as begin_import_session_service,
+    commit_import_session as commit_import_session_service,
+};
 use crate::services::products::{
-    crawl_crawler as crawl_crawler_service,
+    CrawlDispatchStatus, ProductFilters, crawl_all_crawlers as crawl_all_crawlers_service,
+    crawl_crawler as crawl_crawler_service, delete_product as delete_product_service,
     download_crawler_products as download_crawler_products_service,
-    show_products as show_products_service, update_crawler_prices as update_crawler_prices_service,
+    export_products as export_products_service,
+    list_crawler_categories as list_crawler_categories_service,
+    mark_product_reviewed as mark_product_reviewed_service, move_products as move_products_service,
+    preview_crawler_upload as preview_crawler_upload_service,
+    preview_import_products as preview_import_products_service,
+    restore_product as restore_product_service, set_product_images as set_product_images_service,
+    show_deleted_products as show_deleted_products_service, show_products as show_products_service,
+    stream_crawler_products_csv as stream_crawler_products_csv_service,
+    update_crawler_prices as update_crawler_prices_service,
+    update_product as update_product_service,
     upload_crawler_products as upload_crawler_products_service,
 };
+use crate::services::rate_limit::RateLimiter;
+use crate::services::retry::SendRetryPolicy;
+use crate::zmq::JobSender;
 
 #[derive(Deserialize)]
 struct ProductsQueryParams {
     page: Option<usize>,
+    unreviewed: Option<bool>,
+    unassociated: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct CategoryUploadQueryParams {
+    create_missing: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -31,6 +71,11 @@ struct DownloadQueryParams {
     format: String,
 }
 
+#[derive(Deserialize)]
+struct ExportQueryParams {
+    format: Option<String>,
+}
+
 #[get("/crawler/{crawler_id}")]
 pub async fn show_products(
     params: web::Query<ProductsQueryParams>,
@@ -42,8 +87,12 @@ pub async fn show_products(
     tera: web::Data<Tera>,
 ) -> impl Responder {
     let page = params.page.unwrap_or(1);
+    let filters = ProductFilters {
+        only_unreviewed: params.unreviewed.unwrap_or(false),
+        only_unassociated: params.unassociated.unwrap_or(false),
+    };
     let crawler_id = crawler_id.into_inner();
-    match show_products_service(crawler_id, page, &user, repo.get_ref()) {
+    match show_products_service(crawler_id, page, filters, &user, repo.get_ref()) {
         Ok((crawler, products)) => {
             let categories = match show_categories_service(&user, repo.get_ref()) {
                 Ok(categories) => categories,
@@ -54,7 +103,7 @@ pub async fn show_products(
                     vec![]
                 }
                 Err(err) => {
-                    log::error!("Failed to load categories for products page: {err}");
+                    tracing::error!("Failed to load categories for products page: {err}");
                     vec![]
                 }
             };
@@ -64,10 +113,18 @@ pub async fn show_products(
                 "index",
                 &server_config.auth_service_url,
             );
-            context.insert("products", &products);
+            pagination_context(
+                &mut context,
+                "products",
+                &products.map(ProductSummaryDto::from),
+            );
             context.insert("crawler", &crawler);
             context.insert("categories", &categories);
             context.insert("show_category_controls", &true);
+            context.insert("show_review_controls", &true);
+            context.insert("show_edit_controls", &true);
+            context.insert("only_unreviewed", &filters.only_unreviewed);
+            context.insert("only_unassociated", &filters.only_unassociated);
             render_template(&tera, "products/index.html", &context)
         }
         Err(ServiceError::Unauthorized) => redirect("/na"),
@@ -80,21 +137,203 @@ pub async fn show_products(
             redirect(&format!("/crawler/{crawler_id}"))
         }
         Err(err) => {
-            log::error!("Failed to render crawler products: {err}");
+            tracing::error!("Failed to render crawler products: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/crawler/{crawler_id}/deleted-products")]
+pub async fn show_deleted_products(
+    crawler_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    flash_messages: IncomingFlashMessages,
+    repo: web::Data<DieselRepository>,
+    server_config: web::Data<CommonServerConfig>,
+    tera: web::Data<Tera>,
+) -> impl Responder {
+    match show_deleted_products_service(crawler_id.into_inner(), &user, repo.get_ref()) {
+        Ok((crawler, products)) => {
+            let mut context = base_context(
+                &flash_messages,
+                &user,
+                "index",
+                &server_config.auth_service_url,
+            );
+            let products = products
+                .into_iter()
+                .map(ProductSummaryDto::from)
+                .collect::<Vec<_>>();
+            context.insert("crawler", &crawler);
+            context.insert("products", &products);
+            render_template(&tera, "products/deleted.html", &context)
+        }
+        Err(ServiceError::Unauthorized) => redirect("/na"),
+        Err(ServiceError::NotFound) => {
+            FlashMessage::error("Парсер не существует").send();
+            redirect("/")
+        }
+        Err(err) => {
+            tracing::error!("Failed to render deleted products: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
 }
 
+#[post("/crawler/{crawler_id}/product/{product_id}/reviewed")]
+pub async fn mark_product_reviewed(
+    path: web::Path<(i32, i32)>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    let (crawler_id, product_id) = path.into_inner();
+    match mark_product_reviewed_service(crawler_id, product_id, &user, repo.get_ref()) {
+        Ok(true) => FlashMessage::success("Товар отмечен как просмотренный.").send(),
+        Ok(false) => FlashMessage::error("Не удалось отметить товар.").send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::NotFound) => FlashMessage::error("Товар не найден.").send(),
+        Err(err) => {
+            tracing::error!("Failed to mark product as reviewed: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect(&format!("/crawler/{crawler_id}"))
+}
+
+#[post("/crawler/{crawler_id}/product/{product_id}/images")]
+pub async fn set_product_images(
+    path: web::Path<(i32, i32)>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    web::Form(form): web::Form<ProductImagesForm>,
+) -> impl Responder {
+    let (crawler_id, product_id) = path.into_inner();
+    let payload: ProductImagesFormPayload = match form.try_into() {
+        Ok(payload) => payload,
+        Err(e) => {
+            FlashMessage::error(e.to_string()).send();
+            return redirect(&format!("/crawler/{crawler_id}"));
+        }
+    };
+
+    match set_product_images_service(crawler_id, product_id, payload, &user, repo.get_ref()) {
+        Ok(true) => FlashMessage::success("Изображения товара обновлены.").send(),
+        Ok(false) => FlashMessage::error("Не удалось обновить изображения товара.").send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::NotFound) => FlashMessage::error("Товар не найден.").send(),
+        Err(err) => {
+            tracing::error!("Failed to set product images: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect(&format!("/crawler/{crawler_id}"))
+}
+
+#[post("/product/{product_id}/edit")]
+pub async fn update_product(
+    request: HttpRequest,
+    product_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    web::Form(form): web::Form<UpdateProductForm>,
+) -> impl Responder {
+    let redirect_to = request
+        .headers()
+        .get("referer")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("/");
+
+    let payload: UpdateProductFormPayload = match form.try_into() {
+        Ok(payload) => payload,
+        Err(e) => {
+            FlashMessage::error(e.to_string()).send();
+            return redirect(redirect_to);
+        }
+    };
+
+    match update_product_service(product_id.into_inner(), payload, &user, repo.get_ref()) {
+        Ok(true) => FlashMessage::success("Товар обновлён.").send(),
+        Ok(false) => FlashMessage::error("Ошибка при обновлении товара.").send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::NotFound) => FlashMessage::error("Товар не найден.").send(),
+        Err(err) => {
+            tracing::error!("Failed to update product: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect(redirect_to)
+}
+
+#[post("/product/{product_id}/delete")]
+pub async fn delete_product(
+    request: HttpRequest,
+    product_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    let redirect_to = request
+        .headers()
+        .get("referer")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("/");
+
+    match delete_product_service(product_id.into_inner(), &user, repo.get_ref()) {
+        Ok(true) => FlashMessage::success("Товар удалён.").send(),
+        Ok(false) => FlashMessage::error("Ошибка при удалении товара.").send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::NotFound) => FlashMessage::error("Товар не найден.").send(),
+        Err(err) => {
+            tracing::error!("Failed to delete product: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect(redirect_to)
+}
+
+#[post("/products/{product_id}/restore")]
+pub async fn restore_product(
+    request: HttpRequest,
+    product_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    let redirect_to = request
+        .headers()
+        .get("referer")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("/");
+
+    match restore_product_service(product_id.into_inner(), &user, repo.get_ref()) {
+        Ok(true) => FlashMessage::success("Товар восстановлен.").send(),
+        Ok(false) => FlashMessage::error("Ошибка при восстановлении товара.").send(),
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(ServiceError::NotFound) => FlashMessage::error("Товар не найден.").send(),
+        Err(err) => {
+            tracing::error!("Failed to restore product: {err}");
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    redirect(redirect_to)
+}
+
 #[post("/crawler/{crawler_id}/crawl")]
 pub async fn crawl_crawler(
     crawler_id: web::Path<i32>,
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
-    zmq_sender: web::Data<Arc<ZmqSender>>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
 ) -> impl Responder {
     match crawl_crawler_service(
         crawler_id.into_inner(),
+        limiter.get_ref(),
+        retry.get_ref(),
         &user,
         repo.get_ref(),
         zmq_sender.get_ref().as_ref(),
@@ -109,13 +348,107 @@ pub async fn crawl_crawler(
             FlashMessage::error("Не удалось начать обработку.").send();
             redirect("/")
         }
+        Err(ServiceError::Form(message)) => {
+            FlashMessage::error(message).send();
+            redirect("/")
+        }
         Err(ServiceError::Unauthorized) => redirect("/na"),
         Err(ServiceError::NotFound) => {
             FlashMessage::error("Парсер не существует").send();
             redirect("/")
         }
         Err(err) => {
-            log::error!("Failed to start crawler crawl: {err}");
+            tracing::error!("Failed to start crawler crawl: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/crawlers/crawl-all")]
+pub async fn crawl_all_crawlers(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
+    config: web::Data<ServerConfig>,
+) -> impl Responder {
+    let send_interval = Duration::from_millis(config.crawl_all_send_interval_ms);
+    match crawl_all_crawlers_service(
+        send_interval,
+        limiter.get_ref(),
+        retry.get_ref(),
+        &user,
+        repo.get_ref(),
+        zmq_sender.get_ref().as_ref(),
+    )
+    .await
+    {
+        Ok(results) => {
+            let count = |status| results.iter().filter(|r| r.status == status).count();
+            let sent = count(CrawlDispatchStatus::Sent);
+            let skipped = count(CrawlDispatchStatus::Skipped);
+            let failed = count(CrawlDispatchStatus::Failed);
+            if failed > 0 {
+                FlashMessage::error(format!(
+                    "Запущено парсеров: {sent}, уже в обработке: {skipped}, ошибок: {failed}."
+                ))
+                .send();
+            } else {
+                FlashMessage::success(format!(
+                    "Запущено парсеров: {sent}, уже в обработке: {skipped}."
+                ))
+                .send();
+            }
+            redirect("/")
+        }
+        Err(ServiceError::Form(message)) => {
+            FlashMessage::error(message).send();
+            redirect("/")
+        }
+        Err(ServiceError::Unauthorized) => redirect("/na"),
+        Err(err) => {
+            tracing::error!("Failed to start bulk crawl: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/crawlers/move-products")]
+pub async fn move_products(
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    web::Form(form): web::Form<MoveProductsForm>,
+) -> impl Responder {
+    let payload: MoveProductsFormPayload = match form.try_into() {
+        Ok(payload) => payload,
+        Err(e) => {
+            FlashMessage::error(e.to_string()).send();
+            return redirect("/");
+        }
+    };
+    let target_crawler_id = payload.target_crawler_id.get();
+
+    match move_products_service(payload, &user, repo.get_ref()) {
+        Ok(reassignment) if reassignment.skipped_url_conflicts > 0 => {
+            FlashMessage::warning(format!(
+                "Перенесено товаров: {}. Оставлено из-за совпадения URL: {}.",
+                reassignment.moved, reassignment.skipped_url_conflicts
+            ))
+            .send();
+            redirect(&format!("/crawler/{target_crawler_id}"))
+        }
+        Ok(reassignment) => {
+            FlashMessage::success(format!("Перенесено товаров: {}.", reassignment.moved)).send();
+            redirect(&format!("/crawler/{target_crawler_id}"))
+        }
+        Err(ServiceError::Unauthorized) => redirect("/na"),
+        Err(ServiceError::NotFound) => {
+            FlashMessage::error("Парсер не существует").send();
+            redirect("/")
+        }
+        Err(err) => {
+            tracing::error!("Failed to move products: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
@@ -126,10 +459,14 @@ pub async fn update_crawler_prices(
     crawler_id: web::Path<i32>,
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
-    zmq_sender: web::Data<Arc<ZmqSender>>,
+    zmq_sender: web::Data<Arc<JobSender>>,
+    limiter: web::Data<RateLimiter>,
+    retry: web::Data<SendRetryPolicy>,
 ) -> impl Responder {
     match update_crawler_prices_service(
         crawler_id.into_inner(),
+        limiter.get_ref(),
+        retry.get_ref(),
         &user,
         repo.get_ref(),
         zmq_sender.get_ref().as_ref(),
@@ -144,18 +481,74 @@ pub async fn update_crawler_prices(
             FlashMessage::error("Не удалось начать обработку.").send();
             redirect("/")
         }
+        Err(ServiceError::Form(message)) => {
+            FlashMessage::error(message).send();
+            redirect("/")
+        }
         Err(ServiceError::Unauthorized) => redirect("/na"),
         Err(ServiceError::NotFound) => {
             FlashMessage::error("Парсер не существует").send();
             redirect("/")
         }
         Err(err) => {
-            log::error!("Failed to update crawler prices: {err}");
+            tracing::error!("Failed to update crawler prices: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
 }
 
+/// Render the crawler products page with an upload report.
+///
+/// `pending_import` marks the report as a validated import awaiting
+/// confirmation, which shows the commit button.
+#[allow(clippy::too_many_arguments)]
+fn render_upload_report(
+    crawler_id: i32,
+    report: &UploadReport,
+    pending_import: bool,
+    user: &AuthenticatedUser,
+    flash_messages: &IncomingFlashMessages,
+    repo: &DieselRepository,
+    server_config: &CommonServerConfig,
+    tera: &Tera,
+) -> HttpResponse {
+    let (crawler, products) =
+        match show_products_service(crawler_id, 1, ProductFilters::default(), user, repo) {
+            Ok(result) => result,
+            Err(ServiceError::Unauthorized) => return redirect("/na"),
+            Err(ServiceError::NotFound) => {
+                FlashMessage::error("Парсер не существует").send();
+                return redirect("/");
+            }
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        };
+
+    let categories = match show_categories_service(user, repo) {
+        Ok(categories) => categories,
+        Err(ServiceError::Unauthorized) => return redirect("/na"),
+        Err(_) => vec![],
+    };
+
+    let mut context = base_context(
+        flash_messages,
+        user,
+        "index",
+        &server_config.auth_service_url,
+    );
+    pagination_context(
+        &mut context,
+        "products",
+        &products.map(ProductSummaryDto::from),
+    );
+    context.insert("crawler", &crawler);
+    context.insert("categories", &categories);
+    context.insert("show_category_controls", &true);
+    context.insert("show_review_controls", &true);
+    context.insert("upload_report", report);
+    context.insert("pending_import", &pending_import);
+    render_template(tera, "products/index.html", &context)
+}
+
 #[post("/crawler/{crawler_id}/products/upload")]
 pub async fn upload_crawler_products(
     crawler_id: web::Path<i32>,
@@ -169,6 +562,11 @@ pub async fn upload_crawler_products(
     let crawler_id = crawler_id.into_inner();
     match upload_crawler_products_service(crawler_id, &mut form, &user, repo.get_ref()) {
         Ok(report) => {
+            if report.total_rows == 0 {
+                FlashMessage::warning("Файл не содержит строк данных: обработано 0 строк.").send();
+                return redirect(&format!("/crawler/{crawler_id}"));
+            }
+
             if report.errors.is_empty() {
                 FlashMessage::success(format!(
                     "Загрузка завершена: создано {}, обновлено {}.",
@@ -178,35 +576,180 @@ pub async fn upload_crawler_products(
                 return redirect(&format!("/crawler/{crawler_id}"));
             }
 
-            let (crawler, products) =
-                match show_products_service(crawler_id, 1, &user, repo.get_ref()) {
-                    Ok(result) => result,
-                    Err(ServiceError::Unauthorized) => return redirect("/na"),
-                    Err(ServiceError::NotFound) => {
-                        FlashMessage::error("Парсер не существует").send();
-                        return redirect("/");
-                    }
-                    Err(_) => return HttpResponse::InternalServerError().finish(),
-                };
+            render_upload_report(
+                crawler_id,
+                &report,
+                false,
+                &user,
+                &flash_messages,
+                repo.get_ref(),
+                server_config.get_ref(),
+                tera.get_ref(),
+            )
+        }
+        Err(ServiceError::Unauthorized) => redirect("/na"),
+        Err(ServiceError::NotFound) => {
+            FlashMessage::error("Парсер не существует").send();
+            redirect("/")
+        }
+        Err(ServiceError::Form(message)) => {
+            FlashMessage::error(message).send();
+            redirect(&format!("/crawler/{crawler_id}"))
+        }
+        Err(err) => {
+            tracing::error!("Failed to upload crawler products: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
 
-            let categories = match show_categories_service(&user, repo.get_ref()) {
-                Ok(categories) => categories,
-                Err(ServiceError::Unauthorized) => return redirect("/na"),
-                Err(_) => vec![],
-            };
+#[post("/crawler/{crawler_id}/import/begin")]
+#[allow(clippy::too_many_arguments)]
+pub async fn begin_import(
+    crawler_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    session: Session,
+    flash_messages: IncomingFlashMessages,
+    repo: web::Data<DieselRepository>,
+    import_sessions: web::Data<ImportSessionStore>,
+    server_config: web::Data<CommonServerConfig>,
+    tera: web::Data<Tera>,
+    MultipartForm(mut form): MultipartForm<UploadImportForm>,
+) -> impl Responder {
+    let crawler_id = crawler_id.into_inner();
+    match begin_import_session_service(crawler_id, &mut form, &user, repo.get_ref()) {
+        Ok(import) => {
+            if import.upload_report.total_rows == 0 {
+                FlashMessage::warning("Файл не содержит строк данных: обработано 0 строк.").send();
+                return redirect(&format!("/crawler/{crawler_id}"));
+            }
 
-            let mut context = base_context(
+            let report = import.upload_report.clone();
+            let import_id = import_sessions.insert(import);
+            if let Err(err) = session.insert(IMPORT_SESSION_KEY, import_id) {
+                tracing::error!("Failed to store import session id: {err}");
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            render_upload_report(
+                crawler_id,
+                &report,
+                true,
+                &user,
                 &flash_messages,
+                repo.get_ref(),
+                server_config.get_ref(),
+                tera.get_ref(),
+            )
+        }
+        Err(ServiceError::Unauthorized) => redirect("/na"),
+        Err(ServiceError::NotFound) => {
+            FlashMessage::error("Парсер не существует").send();
+            redirect("/")
+        }
+        Err(ServiceError::Form(message)) => {
+            FlashMessage::error(message).send();
+            redirect(&format!("/crawler/{crawler_id}"))
+        }
+        Err(err) => {
+            tracing::error!("Failed to begin import session: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/crawler/{crawler_id}/import/commit")]
+pub async fn commit_import(
+    crawler_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    session: Session,
+    repo: web::Data<DieselRepository>,
+    import_sessions: web::Data<ImportSessionStore>,
+) -> impl Responder {
+    let crawler_id = crawler_id.into_inner();
+    let pending = session
+        .remove_as::<Uuid>(IMPORT_SESSION_KEY)
+        .and_then(Result::ok)
+        .and_then(|import_id| import_sessions.take(import_id));
+    let Some(import) = pending else {
+        FlashMessage::error("Проверенный импорт не найден или устарел. Загрузите файл заново.")
+            .send();
+        return redirect(&format!("/crawler/{crawler_id}"));
+    };
+
+    match commit_import_session_service(crawler_id, import, &user, repo.get_ref()) {
+        Ok(report) => {
+            FlashMessage::success(format!(
+                "Загрузка завершена: создано {}, обновлено {}, пропущено {}.",
+                report.created, report.updated, report.skipped
+            ))
+            .send();
+            redirect(&format!("/crawler/{crawler_id}"))
+        }
+        Err(ServiceError::Unauthorized) => redirect("/na"),
+        Err(ServiceError::NotFound) => {
+            FlashMessage::error("Парсер не существует").send();
+            redirect("/")
+        }
+        Err(ServiceError::Form(message)) => {
+            FlashMessage::error(message).send();
+            redirect(&format!("/crawler/{crawler_id}"))
+        }
+        Err(err) => {
+            tracing::error!("Failed to commit import session: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/crawler/{crawler_id}/products/categories/upload")]
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_product_categories(
+    crawler_id: web::Path<i32>,
+    query: web::Query<CategoryUploadQueryParams>,
+    user: AuthenticatedUser,
+    flash_messages: IncomingFlashMessages,
+    repo: web::Data<DieselRepository>,
+    server_config: web::Data<CommonServerConfig>,
+    limits: web::Data<CategoryPathLimits>,
+    tera: web::Data<Tera>,
+    MultipartForm(mut form): MultipartForm<UploadImportForm>,
+) -> impl Responder {
+    let crawler_id = crawler_id.into_inner();
+    let create_missing = query.create_missing.unwrap_or(false);
+    match upload_product_categories_service(
+        crawler_id,
+        create_missing,
+        &mut form,
+        limits.get_ref(),
+        &user,
+        repo.get_ref(),
+    ) {
+        Ok(report) => {
+            if report.total_rows == 0 {
+                FlashMessage::warning("Файл не содержит строк данных: обработано 0 строк.").send();
+                return redirect(&format!("/crawler/{crawler_id}"));
+            }
+
+            if report.errors.is_empty() {
+                FlashMessage::success(format!(
+                    "Категории назначены: товаров {}, новых категорий {}.",
+                    report.updated, report.created
+                ))
+                .send();
+                return redirect(&format!("/crawler/{crawler_id}"));
+            }
+
+            render_upload_report(
+                crawler_id,
+                &report,
+                false,
                 &user,
-                "index",
-                &server_config.auth_service_url,
-            );
-            context.insert("products", &products);
-            context.insert("crawler", &crawler);
-            context.insert("categories", &categories);
-            context.insert("show_category_controls", &true);
-            context.insert("upload_report", &report);
-            render_template(&tera, "products/index.html", &context)
+                &flash_messages,
+                repo.get_ref(),
+                server_config.get_ref(),
+                tera.get_ref(),
+            )
         }
         Err(ServiceError::Unauthorized) => redirect("/na"),
         Err(ServiceError::NotFound) => {
@@ -218,37 +761,156 @@ pub async fn upload_crawler_products(
             redirect(&format!("/crawler/{crawler_id}"))
         }
         Err(err) => {
-            log::error!("Failed to upload crawler products: {err}");
+            tracing::error!("Failed to upload product categories: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
 }
 
-#[get("/crawler/{crawler_id}/products/download")]
-pub async fn download_crawler_products(
+#[post("/crawler/{crawler_id}/products/upload/preview")]
+pub async fn preview_crawler_upload(
     crawler_id: web::Path<i32>,
-    params: web::Query<DownloadQueryParams>,
     user: AuthenticatedUser,
     repo: web::Data<DieselRepository>,
+    MultipartForm(mut form): MultipartForm<UploadImportForm>,
+) -> impl Responder {
+    match preview_crawler_upload_service(crawler_id.into_inner(), &mut form, &user, repo.get_ref())
+    {
+        Ok(preview) => HttpResponse::Ok().json(preview),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(ServiceError::Form(message)) => {
+            HttpResponse::UnprocessableEntity().json(ApiErrorDto { error: message })
+        }
+        Err(err) => {
+            tracing::error!("Failed to preview crawler products upload: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/crawler/{crawler_id}/import/preview")]
+pub async fn preview_import_products(
+    crawler_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    MultipartForm(mut form): MultipartForm<UploadImportForm>,
+) -> impl Responder {
+    match preview_import_products_service(crawler_id.into_inner(), &mut form, &user, repo.get_ref())
+    {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(ServiceError::Form(message)) => {
+            HttpResponse::UnprocessableEntity().json(ApiErrorDto { error: message })
+        }
+        Err(err) => {
+            tracing::error!("Failed to preview products import: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/crawler/{crawler_id}/export")]
+pub async fn export_crawler_products(
+    crawler_id: web::Path<i32>,
+    params: web::Query<ExportQueryParams>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
 ) -> impl Responder {
-    match download_crawler_products_service(
+    let format = params.format.as_deref().unwrap_or("csv");
+    match export_products_service(
         crawler_id.into_inner(),
-        &params.format,
+        format,
+        &config.price_format,
         &user,
         repo.get_ref(),
     ) {
         Ok(file) => HttpResponse::Ok()
             .append_header(("Content-Type", file.content_type))
-            .append_header((
-                "Content-Disposition",
-                format!("attachment; filename=\"{}\"", file.file_name),
-            ))
+            .append_header(("Content-Disposition", file.content_disposition()))
             .body(file.bytes),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
         Err(ServiceError::Form(message)) => HttpResponse::BadRequest().body(message),
         Err(err) => {
-            log::error!("Failed to download crawler products: {err}");
+            tracing::error!("Failed to export crawler products: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/crawler/{crawler_id}/categories")]
+pub async fn crawler_categories(
+    crawler_id: web::Path<i32>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+) -> impl Responder {
+    match list_crawler_categories_service(crawler_id.into_inner(), &user, repo.get_ref()) {
+        Ok(categories) => HttpResponse::Ok().json(
+            categories
+                .into_iter()
+                .map(CrawlerCategoryCountDto::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            tracing::error!("Failed to list crawler categories: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/crawler/{crawler_id}/products/download")]
+pub async fn download_crawler_products(
+    crawler_id: web::Path<i32>,
+    params: web::Query<DownloadQueryParams>,
+    user: AuthenticatedUser,
+    repo: web::Data<DieselRepository>,
+    config: web::Data<ServerConfig>,
+) -> impl Responder {
+    let crawler_id = crawler_id.into_inner();
+
+    // CSV is streamed row by row; XLSX has to be assembled in memory.
+    let response = if matches!(
+        DownloadFormat::try_from(params.format.as_str()),
+        Ok(DownloadFormat::Csv)
+    ) {
+        stream_crawler_products_csv_service(crawler_id, &config.price_format, &user, repo.get_ref())
+            .map(|download| {
+                let content_disposition = download.content_disposition();
+                HttpResponse::Ok()
+                    .append_header(("Content-Type", download.content_type))
+                    .append_header(("Content-Disposition", content_disposition))
+                    .streaming(stream::iter(
+                        download.chunks.map(|chunk| chunk.map(web::Bytes::from)),
+                    ))
+            })
+    } else {
+        download_crawler_products_service(
+            crawler_id,
+            &params.format,
+            &config.price_format,
+            &user,
+            repo.get_ref(),
+        )
+        .map(|file| {
+            HttpResponse::Ok()
+                .append_header(("Content-Type", file.content_type))
+                .append_header(("Content-Disposition", file.content_disposition()))
+                .body(file.bytes)
+        })
+    };
+
+    match response {
+        Ok(response) => response,
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(ServiceError::Form(message)) => HttpResponse::BadRequest().body(message),
+        Err(err) => {
+            tracing::error!("Failed to download crawler products: {err}");
             HttpResponse::InternalServerError().finish()
         }
     }
@@ -0,0 +1,53 @@
+use actix_web::{HttpResponse, Responder, get, web};
+use actix_web_flash_messages::{FlashMessage, IncomingFlashMessages};
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::models::config::CommonServerConfig;
+use pushkind_common::routes::{base_context, redirect, render_template};
+use tera::Tera;
+
+use crate::models::config::ServerConfig;
+use crate::repository::DieselRepository;
+use crate::services::ServiceError;
+use crate::services::search::{SearchQuery, search_hub};
+
+#[get("/search")]
+pub async fn show_search(
+    params: web::Query<SearchQuery>,
+    user: AuthenticatedUser,
+    flash_messages: IncomingFlashMessages,
+    repo: web::Data<DieselRepository>,
+    server_config: web::Data<CommonServerConfig>,
+    config: web::Data<ServerConfig>,
+    tera: web::Data<Tera>,
+) -> impl Responder {
+    let params = params.into_inner();
+    let search = params.q.clone();
+    match search_hub(
+        params,
+        config.search_min_query_length,
+        &user,
+        repo.get_ref(),
+    ) {
+        Ok(result) => {
+            let mut context = base_context(
+                &flash_messages,
+                &user,
+                "search",
+                &server_config.auth_service_url,
+            );
+            context.insert("search", &search);
+            context.insert("result", &result);
+            context.insert("search_min_query_length", &config.search_min_query_length);
+            render_template(&tera, "search/results.html", &context)
+        }
+        Err(ServiceError::Unauthorized) => redirect("/na"),
+        Err(ServiceError::Form(message)) => {
+            FlashMessage::error(message).send();
+            redirect("/")
+        }
+        Err(err) => {
+            tracing::error!("Failed to render search results: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -1,5 +1,17 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    audit_log (id) {
+        id -> Integer,
+        hub_id -> Integer,
+        user_sub -> Text,
+        action -> Text,
+        resource_type -> Text,
+        resource_id -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     benchmarks (id) {
         id -> Integer,
@@ -16,6 +28,7 @@ diesel::table! {
         embedding -> Nullable<Binary>,
         processing -> Bool,
         num_products -> Integer,
+        archived_at -> Nullable<Timestamp>,
     }
 }
 
@@ -76,6 +89,8 @@ diesel::table! {
         embedding -> Nullable<Binary>,
         category_id -> Nullable<Integer>,
         category_assignment_source -> Text,
+        reviewed_at -> Nullable<Timestamp>,
+        deleted_at -> Nullable<Timestamp>,
     }
 }
 
@@ -128,6 +143,7 @@ diesel::joinable!(products -> categories (category_id));
 diesel::joinable!(products -> crawlers (crawler_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    audit_log,
     benchmarks,
     categories,
     crawlers,
@@ -1,13 +1,24 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime};
 use pushkind_common::domain::auth::AuthenticatedUser;
-use pushkind_common::pagination::DEFAULT_ITEMS_PER_PAGE;
 use pushkind_common::routes::check_role;
 use serde::Deserialize;
 
 use crate::SERVICE_ACCESS_ROLE;
 use crate::domain::product::Product;
-use crate::domain::types::{CrawlerId, HubId};
-use crate::repository::{CrawlerReader, ProductListQuery, ProductReader};
+use crate::domain::types::{BenchmarkId, CrawlerId, HubId, ProductId};
+use crate::dto::api::{
+    AssociationDistanceDto, BenchmarkMatchDto, BenchmarkMatchesDto, ProductBenchmarksDto,
+};
+use crate::forms::benchmarks::AddBenchmarkForm;
+use crate::pagination::{DEFAULT_ITEMS_PER_PAGE, Paginated};
+use crate::repository::{
+    AuditLogWriter, BenchmarkReader, BenchmarkWriter, CrawlerReader, ProductListQuery,
+    ProductReader, SortDirection,
+};
 
+use super::benchmarks::create_benchmark_from_form;
 use super::{ServiceError, ServiceResult};
 
 /// Query parameters accepted by the `api_v1_products` endpoint.
@@ -20,15 +31,18 @@ pub struct ApiV1ProductsQueryParams {
 
 /// Core business logic for the `/v1/products` API endpoint.
 ///
-/// The function returns a list of products for the requested crawler,
+/// The function returns one page of products for the requested crawler,
 /// performing optional search and pagination. All repository interactions and
 /// role checks are handled here so that the HTTP route can remain a thin
-/// wrapper.
+/// wrapper. Search queries shorter than `min_query_length` characters are
+/// rejected with [`ServiceError::Form`] instead of running a broad prefix scan.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn api_v1_products<R>(
     params: ApiV1ProductsQueryParams,
+    min_query_length: usize,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<Vec<Product>>
+) -> ServiceResult<Paginated<Product>>
 where
     R: CrawlerReader + ProductReader,
 {
@@ -39,7 +53,7 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
@@ -51,11 +65,14 @@ where
 
     let crawler = match repo.get_crawler_by_id(crawler_id, hub_id) {
         Ok(Some(crawler)) => crawler,
+        Ok(None) => {
+            log_cross_hub_crawler_access(crawler_id, user, repo);
+            return Err(ServiceError::NotFound);
+        }
         Err(e) => {
-            log::error!("Failed to get crawler: {e}");
+            tracing::error!("Failed to get crawler: {e}");
             return Err(ServiceError::Internal);
         }
-        Ok(None) => return Err(ServiceError::NotFound),
     };
 
     let mut list_query = ProductListQuery::default().crawler(crawler.id);
@@ -65,6 +82,11 @@ where
 
     let result = match &params.query {
         Some(query) if !query.is_empty() => {
+            if query.trim().chars().count() < min_query_length {
+                return Err(ServiceError::Form(format!(
+                    "enter at least {min_query_length} characters"
+                )));
+            }
             list_query = list_query.search(query);
             repo.search_products(list_query)
         }
@@ -72,30 +94,423 @@ where
     };
 
     match result {
-        Ok((_total, products)) => Ok(products
-            .into_iter()
-            .map(|mut p| {
+        Ok((total, products)) => Ok(
+            Paginated::new(products, page, total, DEFAULT_ITEMS_PER_PAGE).map(|mut p| {
                 p.embedding = None;
                 p
+            }),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to list products: {e}");
+            Err(ServiceError::Internal)
+        }
+    }
+}
+
+/// Query parameters accepted by the `api_v1_product_changes` endpoint.
+#[derive(Deserialize, Debug)]
+pub struct ApiV1ProductChangesQueryParams {
+    /// RFC 3339 timestamp; only products updated after it are returned.
+    pub since: String,
+    pub page: Option<usize>,
+}
+
+/// One page of the product change feed.
+#[derive(Debug)]
+pub struct ProductChanges {
+    pub products: Paginated<Product>,
+    /// Cursor for the next request: the `updated_at` of the last product on
+    /// this page, or the requested `since` when the page is empty.
+    pub next_since: NaiveDateTime,
+}
+
+/// Core business logic for the `/v1/products/changes` API endpoint.
+///
+/// Returns hub products updated after `since`, oldest change first, so
+/// integrators can sync incrementally by passing `next_since` back as
+/// `since`. Rows sharing the cursor timestamp with the last returned product
+/// are only guaranteed to be seen by paging with the same `since`.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn api_v1_product_changes<R>(
+    params: ApiV1ProductChangesQueryParams,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ProductChanges>
+where
+    R: ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let since = match DateTime::parse_from_rfc3339(params.since.trim()) {
+        Ok(since) => since.naive_utc(),
+        Err(_) => {
+            return Err(ServiceError::Form(
+                "since must be an RFC 3339 timestamp".to_string(),
+            ));
+        }
+    };
+
+    let page = params.page.unwrap_or(1);
+    let list_query = ProductListQuery::default()
+        .hub_id(hub_id)
+        .updated_since(since)
+        .paginate(page, DEFAULT_ITEMS_PER_PAGE);
+
+    match repo.list_products(list_query) {
+        Ok((total, products)) => {
+            let next_since = products.last().map_or(since, |p| p.updated_at);
+            let products =
+                Paginated::new(products, page, total, DEFAULT_ITEMS_PER_PAGE).map(|mut p| {
+                    p.embedding = None;
+                    p
+                });
+            Ok(ProductChanges {
+                products,
+                next_since,
             })
-            .collect::<Vec<Product>>()),
+        }
         Err(e) => {
-            log::error!("Failed to list products: {e}");
+            tracing::error!("Failed to list changed products: {e}");
             Err(ServiceError::Internal)
         }
     }
 }
 
+/// Warn when a crawler missing from the user's hub exists in another hub.
+///
+/// Repeated hits are a sign of id scraping. The caller still answers with
+/// `NotFound`, so the other hub's crawler is never revealed.
+fn log_cross_hub_crawler_access<R>(crawler_id: CrawlerId, user: &AuthenticatedUser, repo: &R)
+where
+    R: CrawlerReader,
+{
+    match repo.get_crawler_hub_id(crawler_id) {
+        Ok(Some(owner_hub_id)) => tracing::warn!(
+            user = %user.sub,
+            hub_id = user.hub_id,
+            crawler_id = crawler_id.get(),
+            owner_hub_id = owner_hub_id.get(),
+            "Cross-hub crawler access attempt"
+        ),
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to look up crawler owner: {e}"),
+    }
+}
+
+/// Core business logic for the `GET /v1/benchmarks/{id}/matches` API endpoint.
+///
+/// Returns the hub's benchmark, without its embedding, and its matched
+/// products ordered by ascending distance.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn api_v1_benchmark_matches<R>(
+    benchmark_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<BenchmarkMatchesDto>
+where
+    R: BenchmarkReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let benchmark_id = match BenchmarkId::new(benchmark_id) {
+        Ok(benchmark_id) => benchmark_id,
+        Err(_) => return Err(ServiceError::NotFound),
+    };
+
+    let mut benchmark = match repo.get_benchmark_by_id(benchmark_id, hub_id) {
+        Ok(Some(benchmark)) => benchmark,
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get benchmark: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+    benchmark.embedding = None;
+
+    let distances = match repo.list_distances(benchmark_id, SortDirection::Asc) {
+        Ok(distances) => distances,
+        Err(e) => {
+            tracing::error!("Failed to list distances: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let product_ids = distances
+        .iter()
+        .map(|(product_id, _)| *product_id)
+        .collect::<Vec<ProductId>>();
+    let products = match repo.get_products_by_ids(&product_ids) {
+        Ok(products) => products
+            .into_iter()
+            .map(|product| (product.id, product))
+            .collect::<HashMap<_, _>>(),
+        Err(e) => {
+            tracing::error!("Failed to get matched products: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let matches = distances
+        .into_iter()
+        .filter_map(|(product_id, distance)| {
+            products.get(&product_id).map(|product| BenchmarkMatchDto {
+                product_id: product_id.get(),
+                name: product.name.as_str().to_string(),
+                sku: product.sku.as_str().to_string(),
+                distance: distance.get(),
+            })
+        })
+        .collect();
+
+    Ok(BenchmarkMatchesDto { benchmark, matches })
+}
+
+/// Core business logic for the
+/// `GET /v1/benchmarks/{benchmark_id}/products/{product_id}/distance` API endpoint.
+///
+/// Returns [`ServiceError::NotFound`] when the pair is not associated or either
+/// side belongs to another hub.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn api_v1_association_distance<R>(
+    benchmark_id: i32,
+    product_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<AssociationDistanceDto>
+where
+    R: BenchmarkReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let benchmark_id = match BenchmarkId::new(benchmark_id) {
+        Ok(benchmark_id) => benchmark_id,
+        Err(_) => return Err(ServiceError::NotFound),
+    };
+
+    let product_id = match ProductId::new(product_id) {
+        Ok(product_id) => product_id,
+        Err(_) => return Err(ServiceError::NotFound),
+    };
+
+    match repo.get_association_distance(benchmark_id, product_id, hub_id) {
+        Ok(Some(distance)) => Ok(AssociationDistanceDto {
+            distance: distance.get(),
+        }),
+        Ok(None) => Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get association distance: {e}");
+            Err(ServiceError::Internal)
+        }
+    }
+}
+
+/// Core business logic for the `GET /v1/products/{id}` API endpoint.
+///
+/// Returns the hub's product with its images and without the embedding.
+/// Products of other hubs are reported as [`ServiceError::NotFound`].
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn api_v1_product_by_id<R>(
+    product_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Product>
+where
+    R: CrawlerReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let product_id = match ProductId::new(product_id) {
+        Ok(product_id) => product_id,
+        Err(_) => return Err(ServiceError::NotFound),
+    };
+
+    let mut product = match repo.get_product_by_id(product_id) {
+        Ok(Some(product)) => product,
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get product: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    match repo.get_crawler_by_id(product.crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler by id: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    product.embedding = None;
+    Ok(product)
+}
+
+/// Core business logic for the `GET /v1/products/{id}/benchmarks` API endpoint.
+///
+/// Returns the hub's product and the hub benchmarks associated with it, both
+/// without embeddings, so reviewers can see what a deletion would unlink.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn api_v1_product_benchmarks<R>(
+    product_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ProductBenchmarksDto>
+where
+    R: BenchmarkReader + CrawlerReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let product_id = match ProductId::new(product_id) {
+        Ok(product_id) => product_id,
+        Err(_) => return Err(ServiceError::NotFound),
+    };
+
+    let mut product = match repo.get_product_by_id(product_id) {
+        Ok(Some(product)) => product,
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get product: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    match repo.get_crawler_by_id(product.crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler by id: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+    product.embedding = None;
+
+    let benchmarks = match repo.list_benchmarks_for_product(product_id, hub_id) {
+        Ok(benchmarks) => benchmarks
+            .into_iter()
+            .map(|mut benchmark| {
+                benchmark.embedding = None;
+                benchmark
+            })
+            .collect(),
+        Err(e) => {
+            tracing::error!("Failed to list product benchmarks: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    Ok(ProductBenchmarksDto {
+        product,
+        benchmarks,
+    })
+}
+
+/// JSON body accepted by the `POST /v1/benchmarks` endpoint.
+#[derive(Deserialize, Debug)]
+pub struct NewBenchmarkPayload {
+    pub name: String,
+    pub sku: String,
+    pub category: String,
+    pub units: String,
+    pub price: f64,
+    pub amount: f64,
+    pub description: String,
+}
+
+impl From<NewBenchmarkPayload> for AddBenchmarkForm {
+    fn from(value: NewBenchmarkPayload) -> Self {
+        Self {
+            name: value.name,
+            sku: value.sku,
+            category: value.category,
+            units: value.units,
+            price: value.price,
+            amount: value.amount,
+            description: value.description,
+        }
+    }
+}
+
+/// Core business logic for the `POST /v1/benchmarks` API endpoint.
+///
+/// Validation and persistence are shared with
+/// [`add_benchmark`](super::benchmarks::add_benchmark), so the JSON
+/// API and the HTML form follow the same rules. Returns the identifier of the
+/// created benchmark as returned by the insert, and [`ServiceError::Conflict`]
+/// when the SKU is already taken.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn api_v1_create_benchmark<R>(
+    payload: NewBenchmarkPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<BenchmarkId>
+where
+    R: AuditLogWriter + BenchmarkReader + BenchmarkWriter,
+{
+    create_benchmark_from_form(payload.into(), user, repo)?.ok_or(ServiceError::Internal)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::domain::types::{
-        CategoryAssignmentSource, CrawlerId, CrawlerName, CrawlerSelectorValue, CrawlerUrl, HubId,
-        ProductCount, ProductId, ProductName, ProductPrice, ProductSku, ProductUrl,
+        BenchmarkName, BenchmarkSku, CategoryAssignmentSource, CategoryName, CrawlerId,
+        CrawlerName, CrawlerSelectorValue, CrawlerUrl, HubId, ProductAmount, ProductCount,
+        ProductDescription, ProductId, ProductName, ProductPrice, ProductSku, ProductUnits,
+        ProductUrl,
     };
-    use crate::domain::{crawler::Crawler, product::Product};
+    use crate::domain::{benchmark::Benchmark, crawler::Crawler, product::Product};
     use crate::repository::test::TestRepository;
-    use chrono::DateTime;
 
     fn sample_user() -> AuthenticatedUser {
         AuthenticatedUser {
@@ -139,6 +554,7 @@ mod tests {
             embedding: Some(vec![1, 2, 3]),
             category_id: None,
             category_assignment_source: CategoryAssignmentSource::Automatic,
+            reviewed_at: None,
             images: vec![],
         }
     }
@@ -153,9 +569,201 @@ mod tests {
             page: None,
         };
 
-        let result = api_v1_products(params, &user, &repo).unwrap();
+        let result = api_v1_products(params, 2, &user, &repo).unwrap();
+
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.total_items, 1);
+        assert!(result.items[0].embedding.is_none());
+    }
+
+    #[test]
+    fn rejects_search_shorter_than_minimum() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let user = sample_user();
+        let params = ApiV1ProductsQueryParams {
+            crawler_id: 1,
+            query: Some(" a ".into()),
+            page: None,
+        };
+
+        let result = api_v1_products(params, 2, &user, &repo);
+
+        match result {
+            Err(ServiceError::Form(message)) => {
+                assert_eq!(message, "enter at least 2 characters")
+            }
+            other => panic!("expected form error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn product_changes_cursor_advances_past_older_rows() {
+        let at = |secs: i64| DateTime::from_timestamp(secs, 0).unwrap().naive_utc();
+        let product = |id: i32, updated: i64| {
+            let mut product = sample_product();
+            product.id = ProductId::new(id).unwrap();
+            product.sku = ProductSku::new(format!("SKU{id}")).unwrap();
+            product.updated_at = at(updated);
+            product
+        };
+        let repo = TestRepository::new(
+            vec![sample_crawler()],
+            vec![product(1, 300), product(2, 100), product(3, 200)],
+            vec![],
+        );
+        let user = sample_user();
+        let params = |since: &str| ApiV1ProductChangesQueryParams {
+            since: since.into(),
+            page: None,
+        };
+
+        let changes = api_v1_product_changes(params("1970-01-01T00:01:40Z"), &user, &repo).unwrap();
+
+        let ids: Vec<i32> = changes.products.items.iter().map(|p| p.id.get()).collect();
+        assert_eq!(ids, vec![3, 1]);
+        assert!(changes.products.items.iter().all(|p| p.embedding.is_none()));
+        assert_eq!(changes.next_since, at(300));
+
+        let changes = api_v1_product_changes(params("1970-01-01T00:05:00Z"), &user, &repo).unwrap();
+
+        assert!(changes.products.items.is_empty());
+        assert_eq!(changes.next_since, at(300));
+    }
+
+    #[test]
+    fn product_changes_rejects_malformed_since() {
+        let repo = TestRepository::default();
+        let user = sample_user();
+        let params = ApiV1ProductChangesQueryParams {
+            since: "yesterday".into(),
+            page: None,
+        };
+
+        let result = api_v1_product_changes(params, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+    }
+
+    fn sample_benchmark() -> Benchmark {
+        Benchmark {
+            id: BenchmarkId::new(7).unwrap(),
+            hub_id: HubId::new(1).unwrap(),
+            name: BenchmarkName::new("benchmark").unwrap(),
+            sku: BenchmarkSku::new("SKU1").unwrap(),
+            category: CategoryName::new("cat").unwrap(),
+            units: ProductUnits::new("pcs").unwrap(),
+            price: ProductPrice::new(1.0).unwrap(),
+            amount: ProductAmount::new(1.0).unwrap(),
+            description: ProductDescription::new("desc").unwrap(),
+            created_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            embedding: None,
+            processing: false,
+            num_products: ProductCount::new(0).unwrap(),
+            archived_at: None,
+        }
+    }
+
+    #[test]
+    fn association_distance_returns_not_found_without_association() {
+        let repo = TestRepository::new(
+            vec![sample_crawler()],
+            vec![sample_product()],
+            vec![sample_benchmark()],
+        );
+        let user = sample_user();
+
+        let result = api_v1_association_distance(7, 1, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn product_by_id_returns_product_without_embedding() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let user = sample_user();
+
+        let product = api_v1_product_by_id(1, &user, &repo).unwrap();
+
+        assert_eq!(product.id.get(), 1);
+        assert!(product.embedding.is_none());
+    }
+
+    #[test]
+    fn product_by_id_returns_not_found_for_missing_product() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let user = sample_user();
+
+        let result = api_v1_product_by_id(1, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn product_benchmarks_hides_embedding() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let user = sample_user();
+
+        let result = api_v1_product_benchmarks(1, &user, &repo).unwrap();
+
+        assert_eq!(result.product.id.get(), 1);
+        assert!(result.product.embedding.is_none());
+        assert!(result.benchmarks.is_empty());
+    }
+
+    #[test]
+    fn product_benchmarks_returns_not_found_for_missing_product() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let user = sample_user();
+
+        let result = api_v1_product_benchmarks(1, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    fn sample_payload() -> NewBenchmarkPayload {
+        NewBenchmarkPayload {
+            name: "benchmark".into(),
+            sku: "SKU1".into(),
+            category: "cat".into(),
+            units: "pcs".into(),
+            price: 1.0,
+            amount: 1.0,
+            description: "desc".into(),
+        }
+    }
+
+    #[test]
+    fn create_benchmark_returns_created_id() {
+        let mut existing = sample_benchmark();
+        existing.sku = BenchmarkSku::new("SKU0").unwrap();
+        let repo = TestRepository::new(vec![], vec![], vec![existing]);
+        let user = sample_user();
+
+        let id = api_v1_create_benchmark(sample_payload(), &user, &repo).unwrap();
+
+        assert_eq!(id.get(), 8);
+    }
+
+    #[test]
+    fn create_benchmark_rejects_duplicate_sku() {
+        let repo = TestRepository::new(vec![], vec![], vec![sample_benchmark()]);
+        let user = sample_user();
+
+        let result = api_v1_create_benchmark(sample_payload(), &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Conflict)));
+    }
+
+    #[test]
+    fn create_benchmark_rejects_negative_price() {
+        let repo = TestRepository::default();
+        let user = sample_user();
+        let mut payload = sample_payload();
+        payload.price = -1.0;
+
+        let result = api_v1_create_benchmark(payload, &user, &repo);
 
-        assert_eq!(result.len(), 1);
-        assert!(result[0].embedding.is_none());
+        assert!(matches!(result, Err(ServiceError::Form(_))));
     }
 }
@@ -0,0 +1,96 @@
+//! Audit trail of user actions on hub resources.
+
+use chrono::Utc;
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::routes::check_role;
+
+use crate::AUDIT_ACCESS_ROLE;
+use crate::domain::audit::NewAuditEntry;
+use crate::domain::types::HubId;
+use crate::repository::{AuditLogReader, AuditLogWriter};
+
+pub use crate::domain::audit::{AuditAction, AuditEntry, AuditResource};
+
+use super::{ServiceError, ServiceResult};
+
+/// Number of most recent entries shown on the audit page.
+pub const AUDIT_LOG_LIMIT: usize = 200;
+
+/// Record that `user` applied `action` to `resource`.
+///
+/// Called after the action succeeded. A failed write is logged and never fails
+/// the user's request.
+pub fn record_action<R>(
+    action: AuditAction,
+    resource: AuditResource,
+    user: &AuthenticatedUser,
+    repo: &R,
+) where
+    R: AuditLogWriter,
+{
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return;
+        }
+    };
+
+    let entry = NewAuditEntry {
+        hub_id,
+        user_sub: user.sub.clone(),
+        action,
+        resource,
+        created_at: Utc::now().naive_utc(),
+    };
+
+    if let Err(e) = repo.log_action(&entry) {
+        tracing::error!("Failed to record audit entry: {e}");
+    }
+}
+
+/// Return the most recent audit entries of the user's hub.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn show_audit_log<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<AuditEntry>>
+where
+    R: AuditLogReader,
+{
+    if !check_role(AUDIT_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    repo.list_audit_entries(hub_id, AUDIT_LOG_LIMIT)
+        .map_err(|e| {
+            tracing::error!("Failed to load audit log: {e}");
+            ServiceError::Internal
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SERVICE_ACCESS_ROLE;
+    use crate::repository::test::TestRepository;
+
+    #[test]
+    fn audit_log_requires_admin_role() {
+        let repo = TestRepository::default();
+        let user = AuthenticatedUser {
+            sub: "1".into(),
+            email: "test@example.com".into(),
+            hub_id: 1,
+            name: "Test".into(),
+            roles: vec![SERVICE_ACCESS_ROLE.into()],
+            exp: 0,
+        };
+
+        let result = show_audit_log(&user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+}
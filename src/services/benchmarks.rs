@@ -1,29 +1,39 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use pushkind_common::domain::auth::AuthenticatedUser;
-use pushkind_common::pagination::{DEFAULT_ITEMS_PER_PAGE, Paginated};
+use pushkind_common::pagination::Pagination;
+use pushkind_common::repository::errors::RepositoryError;
 use pushkind_common::routes::check_role;
-use pushkind_common::zmq::ZmqSenderExt;
+use pushkind_common::zmq::{ZmqSenderExt, ZmqSenderTrait};
 
 use crate::SERVICE_ACCESS_ROLE;
-use crate::domain::types::{BenchmarkId, HubId, SimilarityDistance};
-use crate::domain::zmq::{CrawlerSelector, ZMQCrawlerMessage};
+use crate::domain::types::{
+    BenchmarkId, CategoryName, HubId, ProductId, ProductSku, SimilarityDistance,
+};
+use crate::domain::zmq::{CrawlerSelector, SelectorProductUrls, ZMQCrawlerMessage};
 use crate::domain::{
     benchmark::Benchmark, benchmark::NewBenchmark, crawler::Crawler, product::Product,
 };
 use crate::forms::benchmarks::{
-    AddBenchmarkForm, AddBenchmarkFormPayload, AssociateForm, AssociateFormPayload,
-    UnassociateForm, UnassociateFormPayload, UploadBenchmarksForm, UploadBenchmarksFormPayload,
+    AddBenchmarkForm, AddBenchmarkFormPayload, AssociateFormPayload, UnassociateFormPayload,
+    UploadBenchmarksForm, UploadBenchmarksFormPayload,
 };
+use crate::forms::categories::{CategoryPathLimits, normalize_category_path};
 use crate::forms::import_export::{UploadImportForm, UploadMode, UploadTarget, parse_upload};
+use crate::models::config::PriceFormat;
+use crate::pagination::{DEFAULT_ITEMS_PER_PAGE, Paginated};
 use crate::repository::{
-    BenchmarkListQuery, BenchmarkReader, BenchmarkWriter, CrawlerReader, ProductListQuery,
-    ProductReader,
+    AuditLogWriter, BenchmarkListQuery, BenchmarkReader, BenchmarkWriter, CrawlerListQuery,
+    CrawlerReader, ProductListQuery, ProductReader, SortDirection, UpsertOutcome,
 };
 use crate::services::import_export::{
     DownloadFile, DownloadFormat, UploadReport, render_download_file,
 };
 
+use super::audit::{AuditAction, AuditResource, record_action};
+use super::rate_limit::{RateLimiter, check_dispatch_rate};
+use super::retry::{SendRetryPolicy, send_json_with_retry};
+use super::similarity::{cosine_distance, decode_embedding, embedding_distance};
 use super::{ServiceError, ServiceResult};
 
 fn parse_f64(value: &str, field: &str) -> Result<f64, String> {
@@ -64,12 +74,29 @@ fn build_benchmark_from_row(
     Ok(payload.into_new_benchmark(hub_id))
 }
 
+/// Optional narrowing applied to the benchmarks page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BenchmarkFilters {
+    /// Category path to restrict the list to; blank values are ignored.
+    pub category: Option<String>,
+    /// Also include benchmarks in subcategories of `category`.
+    pub include_subcategories: bool,
+    /// Only list benchmarks without any matched product.
+    pub only_unmatched: bool,
+}
+
 /// Core business logic for rendering the benchmarks page.
 ///
-/// Validates the `parser` role and fetches paginated benchmarks for the
-/// user's hub. Repository errors are translated into [`ServiceError`] so the
-/// HTTP route can remain a thin wrapper.
-pub fn show_benchmarks<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<Benchmark>>
+/// Validates the `parser` role and fetches benchmarks for the user's hub,
+/// narrowed by `filters`. Repository errors are translated into
+/// [`ServiceError`] so the HTTP route can remain a thin wrapper.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn show_benchmarks<R>(
+    filters: BenchmarkFilters,
+    limits: &CategoryPathLimits,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<Benchmark>>
 where
     R: BenchmarkReader,
 {
@@ -80,22 +107,65 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    match repo.list_benchmarks(BenchmarkListQuery::new(hub_id)) {
+    let mut query = BenchmarkListQuery::new(hub_id);
+    if let Some(category) = filters.category.filter(|c| !c.trim().is_empty()) {
+        let category = normalize_category_path(category, limits)
+            .and_then(CategoryName::new)
+            .map_err(|e| ServiceError::Form(e.to_string()))?;
+        query = query
+            .category(category)
+            .include_subcategories(filters.include_subcategories);
+    }
+    if filters.only_unmatched {
+        query = query.only_unmatched();
+    }
+
+    match repo.list_benchmarks(query) {
         Ok((_total, benchmarks)) => Ok(benchmarks),
         Err(e) => {
-            log::error!("Failed to list benchmarks: {e}");
+            tracing::error!("Failed to list benchmarks: {e}");
             Err(ServiceError::Internal)
         }
     }
 }
 
-pub fn download_benchmarks<R>(
+/// Column headers describing a benchmark in exports.
+const BENCHMARK_EXPORT_HEADERS: [&str; 7] = [
+    "sku",
+    "name",
+    "category",
+    "units",
+    "price",
+    "amount",
+    "description",
+];
+
+/// Extra columns appended per association in a single-benchmark export.
+const BENCHMARK_ASSOCIATION_EXPORT_HEADERS: [&str; 4] =
+    ["product_sku", "product_name", "product_price", "distance"];
+
+fn benchmark_export_row(b: &Benchmark, price_format: &PriceFormat) -> Vec<String> {
+    vec![
+        b.sku.as_str().to_string(),
+        b.name.as_str().to_string(),
+        b.category.as_str().to_string(),
+        b.units.as_str().to_string(),
+        price_format.format(b.price.get()),
+        b.amount.get().to_string(),
+        b.description.as_str().to_string(),
+    ]
+}
+
+/// Export every benchmark of the user's hub.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn export_benchmarks<R>(
     format: &str,
+    price_format: &PriceFormat,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<DownloadFile>
@@ -115,32 +185,73 @@ where
         .1;
 
     let rows = benchmarks
+        .iter()
+        .map(|benchmark| benchmark_export_row(benchmark, price_format))
+        .collect::<Vec<_>>();
+
+    render_download_file("benchmarks", format, &BENCHMARK_EXPORT_HEADERS, &rows)
+        .map_err(|err| ServiceError::Form(err.to_string()))
+}
+
+/// Export a single benchmark with one row per associated product.
+///
+/// Benchmark fields are repeated on every row; a benchmark without
+/// associations yields a single row with empty product columns.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn export_benchmark<R>(
+    benchmark_id: i32,
+    format: &str,
+    price_format: &PriceFormat,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<DownloadFile>
+where
+    R: BenchmarkReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
+    let benchmark_id = BenchmarkId::new(benchmark_id).map_err(|_| ServiceError::NotFound)?;
+    let format =
+        DownloadFormat::try_from(format).map_err(|err| ServiceError::Form(err.to_string()))?;
+
+    let (benchmark, associations) = load_benchmark_associations(benchmark_id, hub_id, repo)?;
+
+    let benchmark_row = benchmark_export_row(&benchmark, price_format);
+    let mut rows = associations
         .into_iter()
-        .map(|b| {
-            vec![
-                b.sku.as_str().to_string(),
-                b.name.as_str().to_string(),
-                b.category.as_str().to_string(),
-                b.units.as_str().to_string(),
-                b.price.get().to_string(),
-                b.amount.get().to_string(),
-                b.description.as_str().to_string(),
-            ]
+        .map(|(product, distance)| {
+            let mut row = benchmark_row.clone();
+            row.extend([
+                product.sku.as_str().to_string(),
+                product.name.as_str().to_string(),
+                price_format.format(product.price.get()),
+                distance.get().to_string(),
+            ]);
+            row
         })
         .collect::<Vec<_>>();
+    if rows.is_empty() {
+        let mut row = benchmark_row;
+        row.resize(
+            BENCHMARK_EXPORT_HEADERS.len() + BENCHMARK_ASSOCIATION_EXPORT_HEADERS.len(),
+            String::new(),
+        );
+        rows.push(row);
+    }
+
+    let headers = BENCHMARK_EXPORT_HEADERS
+        .iter()
+        .chain(BENCHMARK_ASSOCIATION_EXPORT_HEADERS.iter())
+        .copied()
+        .collect::<Vec<_>>();
 
     render_download_file(
-        "benchmarks",
+        &format!("benchmark_{}", benchmark_id.get()),
         format,
-        &[
-            "sku",
-            "name",
-            "category",
-            "units",
-            "price",
-            "amount",
-            "description",
-        ],
+        &headers,
         &rows,
     )
     .map_err(|err| ServiceError::Form(err.to_string()))
@@ -150,11 +261,15 @@ where
 ///
 /// Ensures the user has the `parser` role, verifies that the benchmark belongs
 /// to the user's hub and gathers crawlers with their products and similarity
-/// distances. Repository errors are mapped to [`ServiceError`] variants so the
-/// HTTP route remains a thin wrapper.
+/// distances. Products of each crawler are ordered by distance in the
+/// requested `direction` (closest first by default). Repository errors are
+/// mapped to [`ServiceError`] variants so the HTTP route remains a thin
+/// wrapper.
 #[allow(clippy::type_complexity)]
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn show_benchmark<R>(
     benchmark_id: i32,
+    direction: SortDirection,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<(
@@ -172,7 +287,7 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
@@ -186,63 +301,336 @@ where
         Ok(Some(benchmark)) => benchmark,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get benchmark: {e}");
+            tracing::error!("Failed to get benchmark: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let crawlers = match repo.list_crawlers(hub_id) {
-        Ok(crawlers) => crawlers,
+    let crawlers = match repo.list_crawlers(CrawlerListQuery::new(hub_id)) {
+        Ok((_, crawlers)) => crawlers,
         Err(e) => {
-            log::error!("Failed to list crawlers: {e}");
+            tracing::error!("Failed to list crawlers: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
     let mut products: Vec<(Crawler, Paginated<Product>)> = vec![];
+    let mut distances: HashMap<i32, f32> = HashMap::new();
     for crawler in crawlers {
-        let crawler_products = match repo.list_products(
-            ProductListQuery::default()
-                .benchmark(benchmark_id)
-                .crawler(crawler.id)
-                .paginate(1, DEFAULT_ITEMS_PER_PAGE),
+        let crawler_products = match repo.list_benchmark_matches(
+            benchmark_id,
+            crawler.id,
+            direction,
+            Pagination {
+                page: 1,
+                per_page: DEFAULT_ITEMS_PER_PAGE,
+            },
         ) {
-            Ok((total, items)) => Paginated::new(items, 1, total.div_ceil(DEFAULT_ITEMS_PER_PAGE)),
+            Ok((total, matches)) => {
+                let mut items = Vec::with_capacity(matches.len());
+                for (product, distance) in matches {
+                    distances.insert(product.id.get(), distance.get());
+                    items.push(product);
+                }
+                Paginated::new(items, 1, total, DEFAULT_ITEMS_PER_PAGE)
+            }
             Err(e) => {
-                log::error!("Failed to list products: {e}");
+                tracing::error!("Failed to list benchmark matches: {e}");
                 return Err(ServiceError::Internal);
             }
         };
         products.push((crawler, crawler_products));
     }
 
-    let distances = match repo.list_distances(benchmark_id) {
-        Ok(distances) => distances
-            .into_iter()
-            .map(|(product_id, distance)| (product_id.get(), distance.get()))
-            .collect(),
+    Ok((benchmark, products, distances))
+}
+
+/// Load a hub benchmark with its associated products, closest matches first.
+fn load_benchmark_associations<R>(
+    benchmark_id: BenchmarkId,
+    hub_id: HubId,
+    repo: &R,
+) -> ServiceResult<(Benchmark, Vec<(Product, SimilarityDistance)>)>
+where
+    R: BenchmarkReader + ProductReader,
+{
+    let benchmark = match repo.get_benchmark_by_id(benchmark_id, hub_id) {
+        Ok(Some(benchmark)) => benchmark,
+        Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to list distances: {e}");
+            tracing::error!("Failed to get benchmark: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    Ok((benchmark, products, distances))
+    let products = match repo.list_products(
+        ProductListQuery::default()
+            .benchmark(benchmark_id)
+            .hub_id(hub_id),
+    ) {
+        Ok((_, products)) => products,
+        Err(e) => {
+            tracing::error!("Failed to list associated products: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+    let mut products: HashMap<i32, Product> = products
+        .into_iter()
+        .map(|product| (product.id.get(), product))
+        .collect();
+
+    let distances = match repo.list_distances(benchmark_id, SortDirection::Asc) {
+        Ok(distances) => distances,
+        Err(e) => {
+            tracing::error!("Failed to list distances: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let associations = distances
+        .into_iter()
+        .filter_map(|(product_id, distance)| {
+            products
+                .remove(&product_id.get())
+                .map(|product| (product, distance))
+        })
+        .collect();
+
+    Ok((benchmark, associations))
+}
+
+/// Export every product associated with a benchmark together with its
+/// similarity distance, closest matches first.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn export_benchmark_associations<R>(
+    benchmark_id: i32,
+    format: &str,
+    price_format: &PriceFormat,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<DownloadFile>
+where
+    R: BenchmarkReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
+    let benchmark_id = BenchmarkId::new(benchmark_id).map_err(|_| ServiceError::NotFound)?;
+    let format =
+        DownloadFormat::try_from(format).map_err(|err| ServiceError::Form(err.to_string()))?;
+
+    let (benchmark, associations) = load_benchmark_associations(benchmark_id, hub_id, repo)?;
+
+    let rows = associations
+        .into_iter()
+        .map(|(product, distance)| {
+            vec![
+                benchmark.name.as_str().to_string(),
+                product.sku.as_str().to_string(),
+                product.name.as_str().to_string(),
+                price_format.format(product.price.get()),
+                distance.get().to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    render_download_file(
+        &format!("benchmark_{}_associations", benchmark_id.get()),
+        format,
+        &[
+            "benchmark_name",
+            "product_sku",
+            "product_name",
+            "price",
+            "distance",
+        ],
+        &rows,
+    )
+    .map_err(|err| ServiceError::Form(err.to_string()))
+}
+
+/// Median of an ascending slice of distances; `None` when the slice is empty.
+fn median(sorted: &[f32]) -> Option<f32> {
+    let len = sorted.len();
+    if len == 0 {
+        return None;
+    }
+    let mid = len / 2;
+    if len.is_multiple_of(2) {
+        Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+    } else {
+        Some(sorted[mid])
+    }
+}
+
+/// Split the distance distribution at its median and return the first
+/// distance above the gap that straddles it.
+///
+/// When no distance lies above the median the median itself is returned.
+fn threshold_from_distances(mut distances: Vec<f32>) -> Option<f32> {
+    distances.sort_by(f32::total_cmp);
+    let median = median(&distances)?;
+    Some(
+        distances
+            .iter()
+            .copied()
+            .find(|distance| *distance > median)
+            .unwrap_or(median),
+    )
+}
+
+/// Suggest an auto-association distance cutoff for a benchmark.
+///
+/// The suggestion is derived from the benchmark's current association
+/// distances. Returns `ServiceError::NotFound` when the benchmark is missing
+/// or has fewer than two associations to learn from.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn suggest_threshold<R>(
+    benchmark_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<SimilarityDistance>
+where
+    R: BenchmarkReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+    let benchmark_id = BenchmarkId::new(benchmark_id).map_err(|_| ServiceError::NotFound)?;
+
+    match repo.get_benchmark_by_id(benchmark_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get benchmark: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    let distances = match repo.list_distances(benchmark_id, SortDirection::Asc) {
+        Ok(distances) => distances,
+        Err(e) => {
+            tracing::error!("Failed to list distances: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+    if distances.len() < 2 {
+        return Err(ServiceError::NotFound);
+    }
+
+    let values = distances
+        .into_iter()
+        .map(|(_, distance)| distance.get())
+        .collect();
+    threshold_from_distances(values)
+        .and_then(|value| SimilarityDistance::new(value).ok())
+        .ok_or(ServiceError::Internal)
+}
+
+/// Find the `n` hub benchmarks whose embeddings are closest to a product's.
+///
+/// Benchmarks without an embedding, or whose embedding does not match the
+/// product's dimension, are skipped; a product without an embedding has no
+/// matches. Returns `ServiceError::NotFound` when the product is missing or
+/// belongs to another hub.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn top_n_matches_for_product<R>(
+    product_id: i32,
+    n: usize,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<(Benchmark, SimilarityDistance)>>
+where
+    R: ProductReader + CrawlerReader + BenchmarkReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+    let product_id = ProductId::new(product_id).map_err(|_| ServiceError::NotFound)?;
+
+    let product = match repo.get_product_by_id(product_id) {
+        Ok(Some(product)) => product,
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get product: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    match repo.get_crawler_by_id(product.crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler by id: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    let Some(product_embedding) = product.embedding else {
+        return Ok(vec![]);
+    };
+
+    let benchmarks = match repo.list_benchmarks(BenchmarkListQuery::new(hub_id)) {
+        Ok((_, benchmarks)) => benchmarks,
+        Err(e) => {
+            tracing::error!("Failed to list benchmarks: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let mut matches: Vec<(Benchmark, SimilarityDistance)> = benchmarks
+        .into_iter()
+        .filter_map(|benchmark| {
+            let distance = embedding_distance(&product_embedding, benchmark.embedding.as_deref()?)?;
+            Some((benchmark, distance))
+        })
+        .collect();
+    matches.sort_by(|(_, a), (_, b)| a.get().total_cmp(&b.get()));
+    matches.truncate(n);
+
+    Ok(matches)
 }
 
 /// Adds a new benchmark from the supplied form.
 ///
 /// Validates the `parser` role and the form itself before persisting the
 /// benchmark. Returns `Ok(true)` if the benchmark was created,
-/// `Err(ServiceError::Form(_))` if form validation failed, and `Ok(false)` if
-/// the repository returned an error.
+/// `Err(ServiceError::Form(_))` if form validation failed,
+/// `Err(ServiceError::Conflict)` if the hub already has a benchmark with the
+/// same SKU, and `Ok(false)` if the repository returned an error.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
 pub fn add_benchmark<R>(
     form: AddBenchmarkForm,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<bool>
 where
-    R: BenchmarkWriter,
+    R: BenchmarkReader + BenchmarkWriter + AuditLogWriter,
+{
+    create_benchmark_from_form(form, user, repo).map(|id| id.is_some())
+}
+
+/// Same as [`add_benchmark`], returning the created benchmark's identifier
+/// instead of `true` and `None` instead of `false`.
+pub(crate) fn create_benchmark_from_form<R>(
+    form: AddBenchmarkForm,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Option<BenchmarkId>>
+where
+    R: BenchmarkReader + BenchmarkWriter + AuditLogWriter,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
@@ -251,7 +639,7 @@ where
     let payload: AddBenchmarkFormPayload = match form.try_into() {
         Ok(payload) => payload,
         Err(e) => {
-            log::error!("Failed to parse add benchmark form: {e}");
+            tracing::error!("Failed to parse add benchmark form: {e}");
             return Err(ServiceError::Form(e.to_string()));
         }
     };
@@ -259,27 +647,51 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
-            return Ok(false);
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Ok(None);
         }
     };
 
     let new_benchmark = payload.into_new_benchmark(hub_id);
+    let sku = new_benchmark.sku.clone();
 
-    match repo.create_benchmark(&[new_benchmark]) {
-        Ok(_) => Ok(true),
+    match repo.get_benchmark_by_sku(&sku, hub_id) {
+        Ok(None) => {}
+        Ok(Some(_)) => return Err(ServiceError::Conflict),
         Err(e) => {
-            log::error!("Failed to add a benchmark: {e}");
-            Ok(false)
+            tracing::error!("Failed to check benchmark SKU: {e}");
+            return Ok(None);
+        }
+    }
+
+    match repo.create_benchmark(&[new_benchmark], hub_id) {
+        Ok(ids) => {
+            record_action(
+                AuditAction::Create,
+                AuditResource::Benchmark(sku),
+                user,
+                repo,
+            );
+            Ok(ids.into_iter().next())
+        }
+        Err(RepositoryError::ConflictError) => Err(ServiceError::Conflict),
+        Err(e) => {
+            tracing::error!("Failed to add a benchmark: {e}");
+            Ok(None)
         }
     }
 }
 
 /// Parses and uploads multiple benchmarks.
 ///
-/// Returns `Ok(true)` if benchmarks were created successfully,
+/// Rows are upserted by SKU, so re-uploading the same file updates existing
+/// benchmarks instead of duplicating them. All rows are written in one
+/// transaction: when any of them fails, nothing is stored.
+///
+/// Returns `Ok(true)` if benchmarks were stored successfully,
 /// `Err(ServiceError::Form(_))` if parsing failed, and `Ok(false)` if the
 /// repository returned an error.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
 pub fn upload_benchmarks<R>(
     form: &mut UploadBenchmarksForm,
     user: &AuthenticatedUser,
@@ -295,7 +707,7 @@ where
     let payload: UploadBenchmarksFormPayload = match form.try_into() {
         Ok(payload) => payload,
         Err(e) => {
-            log::error!("Failed to parse upload benchmarks form: {e}");
+            tracing::error!("Failed to parse upload benchmarks form: {e}");
             return Err(ServiceError::Form(e.to_string()));
         }
     };
@@ -303,23 +715,24 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Ok(false);
         }
     };
 
     let benchmarks = payload.into_new_benchmarks(hub_id);
 
-    match repo.create_benchmark(&benchmarks) {
+    match repo.upsert_benchmarks(&benchmarks, hub_id) {
         Ok(_) => Ok(true),
         Err(e) => {
-            log::error!("Failed to add benchmarks: {e}");
+            tracing::error!("Failed to upsert benchmarks: {e}");
             Ok(false)
         }
     }
 }
 
 /// Upload benchmarks using format/mode-aware import parser and SKU upsert semantics.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
 pub fn upload_benchmarks_import<R>(
     form: &mut UploadImportForm,
     user: &AuthenticatedUser,
@@ -377,7 +790,7 @@ where
         let existing = match repo.list_benchmarks_by_hub_and_sku(hub_id, &sku) {
             Ok(items) => items,
             Err(err) => {
-                log::error!("Failed to lookup benchmark by sku: {err}");
+                tracing::error!("Failed to lookup benchmark by sku: {err}");
                 return Err(ServiceError::Internal);
             }
         };
@@ -427,7 +840,7 @@ where
             match repo.update_benchmark(current.id, &new_benchmark) {
                 Ok(_) => report.updated += 1,
                 Err(err) => {
-                    log::error!("Failed to update benchmark: {err}");
+                    tracing::error!("Failed to update benchmark: {err}");
                     report.push_error(
                         row.row_number,
                         Some(sku_value),
@@ -463,10 +876,11 @@ where
             }
         }
 
-        match repo.create_benchmark(&[new_benchmark]) {
-            Ok(_) => report.created += 1,
+        match repo.upsert_benchmark(&new_benchmark) {
+            Ok(UpsertOutcome::Created) => report.created += 1,
+            Ok(UpsertOutcome::Updated) => report.updated += 1,
             Err(err) => {
-                log::error!("Failed to create benchmark: {err}");
+                tracing::error!("Failed to create benchmark: {err}");
                 report.push_error(
                     row.row_number,
                     Some(sku_value),
@@ -483,15 +897,20 @@ where
 ///
 /// Returns `Ok(true)` if the message was sent successfully, `Ok(false)` if
 /// sending failed.
+///
+/// Returns [`ServiceError::Form`] when the hub exceeds its dispatch rate.
+#[tracing::instrument(skip(limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
 pub async fn match_benchmark<R, S>(
     benchmark_id: i32,
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
     user: &AuthenticatedUser,
     repo: &R,
     sender: &S,
 ) -> ServiceResult<bool>
 where
     R: BenchmarkReader,
-    S: ZmqSenderExt + ?Sized,
+    S: ZmqSenderTrait + ?Sized,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
@@ -500,7 +919,7 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
@@ -514,34 +933,40 @@ where
         Ok(Some(benchmark)) => benchmark,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get benchmark: {e}");
+            tracing::error!("Failed to get benchmark: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
+    check_dispatch_rate(limiter, hub_id)?;
+
     let message = ZMQCrawlerMessage::Benchmark(benchmark.id);
-    match sender.send_json(&message).await {
-        Ok(_) => Ok(true),
-        Err(_) => {
-            log::error!("Failed to send ZMQ message");
-            Ok(false)
-        }
+    if send_json_with_retry(sender, &message, retry).await {
+        Ok(true)
+    } else {
+        tracing::error!("Failed to send ZMQ message");
+        Ok(false)
     }
 }
 
-/// Sends ZMQ messages to update prices for all products associated with a benchmark.
+/// Re-enqueues hub benchmarks whose embedding is missing so the worker embeds them again.
 ///
-/// Returns a list of crawler selectors and whether sending the message for that
-/// crawler succeeded.
-pub async fn update_benchmark_prices<R, S>(
-    benchmark_id: i32,
+/// Benchmarks that are currently processing are skipped. Returns the number of
+/// benchmarks successfully re-queued.
+///
+/// The whole batch counts as one dispatch against the hub's rate; returns
+/// [`ServiceError::Form`] when the hub exceeds it.
+#[tracing::instrument(skip(limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
+pub async fn retry_embeddings<R, S>(
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
     user: &AuthenticatedUser,
     repo: &R,
     sender: &S,
-) -> ServiceResult<Vec<(String, bool)>>
+) -> ServiceResult<usize>
 where
-    R: BenchmarkReader + CrawlerReader + ProductReader,
-    S: ZmqSenderExt + ?Sized,
+    R: BenchmarkReader,
+    S: ZmqSenderTrait + ?Sized,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
@@ -550,136 +975,266 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
-            return Err(ServiceError::Internal);
-        }
-    };
-
-    let benchmark_id = match BenchmarkId::new(benchmark_id) {
-        Ok(benchmark_id) => benchmark_id,
-        Err(_) => return Err(ServiceError::NotFound),
-    };
-
-    let benchmark = match repo.get_benchmark_by_id(benchmark_id, hub_id) {
-        Ok(Some(benchmark)) => benchmark,
-        Ok(None) => return Err(ServiceError::NotFound),
-        Err(e) => {
-            log::error!("Failed to get benchmark: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let crawlers = match repo.list_crawlers(hub_id) {
-        Ok(crawlers) => crawlers,
+    let benchmarks = match repo.list_benchmarks_missing_embeddings(hub_id) {
+        Ok(benchmarks) => benchmarks,
         Err(e) => {
-            log::error!("Failed to list crawlers: {e}");
+            tracing::error!("Failed to list benchmarks missing embeddings: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let mut results = Vec::new();
-    for crawler in crawlers {
-        let products = match repo.list_products(
-            ProductListQuery::default()
-                .benchmark(benchmark.id)
-                .crawler(crawler.id),
-        ) {
-            Ok((_total, products)) => products,
-            Err(e) => {
-                log::error!("Failed to list products: {e}");
-                return Err(ServiceError::Internal);
-            }
-        };
+    let idle = benchmarks
+        .into_iter()
+        .filter(|b| !b.processing)
+        .collect::<Vec<_>>();
+    if idle.is_empty() {
+        return Ok(0);
+    }
 
-        if products.is_empty() {
-            continue;
-        }
+    check_dispatch_rate(limiter, hub_id)?;
 
-        let urls = products
-            .into_iter()
-            .filter_map(|p| p.url)
-            .collect::<Vec<_>>();
-        if urls.is_empty() {
-            continue;
+    let mut requeued = 0;
+    for benchmark in idle {
+        let message = ZMQCrawlerMessage::Benchmark(benchmark.id);
+        if send_json_with_retry(sender, &message, retry).await {
+            requeued += 1;
+        } else {
+            tracing::error!("Failed to send ZMQ message for benchmark {}", benchmark.id);
         }
-        let message = ZMQCrawlerMessage::Crawler(CrawlerSelector::SelectorProducts((
-            crawler.selector.clone(),
-            urls,
-        )));
-        let sent = sender.send_json(&message).await.is_ok();
-        if !sent {
-            log::error!("Failed to send ZMQ message");
-        }
-        results.push((crawler.selector.into_inner(), sent));
     }
 
-    Ok(results)
+    Ok(requeued)
 }
 
-/// Removes an association between a benchmark and a product.
+/// Outcome of [`recalculate_all_distances_for_hub`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DistanceRecalculation {
+    /// Benchmarks whose match message was sent.
+    pub dispatched: usize,
+    /// Benchmarks left alone because they were already processing.
+    pub skipped: usize,
+    /// Benchmarks whose match message could not be sent.
+    pub failed: Vec<BenchmarkId>,
+}
+
+/// Re-enqueues matching for every active benchmark of the user's hub.
 ///
-/// Returns `Ok(true)` if the association was removed,
-/// `Err(ServiceError::Form(_))` if form validation failed, and `Ok(false)` if
-/// the repository returned an error or entities were not found.
-pub fn delete_benchmark_product<R>(
-    form: UnassociateForm,
+/// Used after embeddings were rebuilt so the worker recomputes the stored
+/// `product_benchmark` distances. Benchmarks that are already processing are
+/// skipped so repeated requests do not queue duplicate jobs. A failed send is
+/// recorded in [`DistanceRecalculation::failed`] instead of aborting the batch.
+///
+/// The whole batch counts as one dispatch against the hub's rate; returns
+/// [`ServiceError::Form`] when the hub exceeds it.
+#[tracing::instrument(skip(limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
+pub async fn recalculate_all_distances_for_hub<R, S>(
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<bool>
+    sender: &S,
+) -> ServiceResult<DistanceRecalculation>
 where
-    R: BenchmarkReader + ProductReader + CrawlerReader + BenchmarkWriter,
+    R: BenchmarkReader,
+    S: ZmqSenderTrait + ?Sized,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
     }
 
-    let payload: UnassociateFormPayload = match form.try_into() {
-        Ok(payload) => payload,
-        Err(e) => {
-            log::error!("Failed to parse unassociate form: {e}");
-            return Err(ServiceError::Form(e.to_string()));
-        }
-    };
-
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let benchmark = match repo.get_benchmark_by_id(payload.benchmark_id, hub_id) {
-        Ok(Some(b)) => b,
-        Ok(None) => return Err(ServiceError::NotFound),
+    let benchmarks = match repo.list_benchmarks(BenchmarkListQuery::new(hub_id)) {
+        Ok((_total, benchmarks)) => benchmarks,
         Err(e) => {
-            log::error!("Failed to get benchmark: {e}");
+            tracing::error!("Failed to list benchmarks: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let product = match repo.get_product_by_id(payload.product_id) {
-        Ok(Some(p)) => p,
-        Ok(None) => return Err(ServiceError::NotFound),
-        Err(e) => {
-            log::error!("Failed to get product: {e}");
+    let (processing, idle): (Vec<_>, Vec<_>) = benchmarks.into_iter().partition(|b| b.processing);
+    let mut result = DistanceRecalculation {
+        skipped: processing.len(),
+        ..DistanceRecalculation::default()
+    };
+    if idle.is_empty() {
+        return Ok(result);
+    }
+
+    check_dispatch_rate(limiter, hub_id)?;
+
+    for benchmark in idle {
+        let message = ZMQCrawlerMessage::Benchmark(benchmark.id);
+        if send_json_with_retry(sender, &message, retry).await {
+            result.dispatched += 1;
+        } else {
+            tracing::error!("Failed to send ZMQ message for benchmark {}", benchmark.id);
+            result.failed.push(benchmark.id);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Per-selector outcome of [`update_benchmark_prices`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrawlerDispatchResult {
+    /// Crawler selector the message was addressed to.
+    pub selector: String,
+    /// Number of product URLs in the message.
+    pub product_count: usize,
+    /// Whether the message was sent.
+    pub sent: bool,
+    /// Send failure reason when `sent` is `false`.
+    pub error: Option<String>,
+}
+
+/// Sends ZMQ messages to update prices for all products associated with a benchmark.
+///
+/// Crawlers sharing a selector get one message with their product URLs merged.
+/// Returns one [`CrawlerDispatchResult`] per distinct crawler selector.
+///
+/// Returns [`ServiceError::Form`] when the hub exceeds its dispatch rate.
+#[tracing::instrument(skip(limiter, user, repo, sender), fields(hub_id = user.hub_id))]
+pub async fn update_benchmark_prices<R, S>(
+    benchmark_id: i32,
+    limiter: &RateLimiter,
+    user: &AuthenticatedUser,
+    repo: &R,
+    sender: &S,
+) -> ServiceResult<Vec<CrawlerDispatchResult>>
+where
+    R: BenchmarkReader + CrawlerReader + ProductReader,
+    S: ZmqSenderExt + ?Sized,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    match repo.get_crawler_by_id(product.crawler_id, hub_id) {
-        Ok(Some(crawler)) => crawler,
+    let benchmark_id = match BenchmarkId::new(benchmark_id) {
+        Ok(benchmark_id) => benchmark_id,
+        Err(_) => return Err(ServiceError::NotFound),
+    };
+
+    let benchmark = match repo.get_benchmark_by_id(benchmark_id, hub_id) {
+        Ok(Some(benchmark)) => benchmark,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get crawler: {e}");
+            tracing::error!("Failed to get benchmark: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    check_dispatch_rate(limiter, hub_id)?;
+
+    let crawlers = match repo.list_crawlers(CrawlerListQuery::new(hub_id)) {
+        Ok((_, crawlers)) => crawlers,
+        Err(e) => {
+            tracing::error!("Failed to list crawlers: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let mut batch = SelectorProductUrls::default();
+    for crawler in crawlers {
+        let products = match repo.list_products(
+            ProductListQuery::default()
+                .benchmark(benchmark.id)
+                .crawler(crawler.id),
+        ) {
+            Ok((_total, products)) => products,
+            Err(e) => {
+                tracing::error!("Failed to list products: {e}");
+                return Err(ServiceError::Internal);
+            }
+        };
+
+        let urls = products
+            .into_iter()
+            .filter_map(|p| p.url)
+            .collect::<Vec<_>>();
+        if urls.is_empty() {
+            continue;
+        }
+        batch.push(crawler.selector, urls);
+    }
+
+    let mut results = Vec::with_capacity(batch.len());
+    for (selector, urls) in batch {
+        let product_count = urls.len();
+        let message =
+            ZMQCrawlerMessage::Crawler(CrawlerSelector::SelectorProducts((selector.clone(), urls)));
+        let error = match sender.send_json(&message).await {
+            Ok(_) => None,
+            Err(e) => {
+                tracing::error!("Failed to send ZMQ message: {e}");
+                Some(e.to_string())
+            }
+        };
+        results.push(CrawlerDispatchResult {
+            selector: selector.into_inner(),
+            product_count,
+            sent: error.is_none(),
+            error,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Removes an association between a benchmark and a product.
+///
+/// The hub checks and the delete run in one repository transaction.
+///
+/// Returns `Ok(true)` if the association was removed,
+/// `Err(ServiceError::NotFound)` if the benchmark or product is outside the
+/// hub, and `Ok(false)` if the repository returned an error.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn delete_benchmark_product<R>(
+    payload: UnassociateFormPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<bool>
+where
+    R: BenchmarkWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    match repo.remove_benchmark_association(benchmark.id, product.id) {
+    match repo.remove_benchmark_association_in_hub(payload.benchmark_id, payload.product_id, hub_id)
+    {
         Ok(_) => Ok(true),
+        Err(RepositoryError::NotFound) => Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to delete association: {e}");
+            tracing::error!("Failed to delete association: {e}");
             Ok(false)
         }
     }
@@ -687,79 +1242,270 @@ where
 
 /// Creates an association between a benchmark and a product.
 ///
+/// The form distance wins; otherwise `default_distance` (from
+/// `ServerConfig.manual_match_distance`) is stored. The hub checks and the
+/// insert run in one repository transaction.
+///
 /// Returns `Ok(true)` if the association was created,
-/// `Err(ServiceError::Form(_))` if form validation failed, and `Ok(false)` if
-/// the repository returned an error or entities were not found.
+/// `Err(ServiceError::NotFound)` if the benchmark or product is outside the
+/// hub, and `Ok(false)` if the repository returned an error.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn create_benchmark_product<R>(
-    form: AssociateForm,
+    payload: AssociateFormPayload,
+    default_distance: SimilarityDistance,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<bool>
 where
-    R: BenchmarkReader + ProductReader + CrawlerReader + BenchmarkWriter,
+    R: BenchmarkWriter,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
     }
 
-    let payload: AssociateFormPayload = match form.try_into() {
-        Ok(payload) => payload,
-        Err(e) => {
-            log::error!("Failed to parse associate form: {e}");
-            return Err(ServiceError::Form(e.to_string()));
-        }
-    };
-
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let benchmark = match repo.get_benchmark_by_id(payload.benchmark_id, hub_id) {
-        Ok(Some(b)) => b,
-        Ok(None) => return Err(ServiceError::NotFound),
+    let distance = payload.distance.unwrap_or(default_distance);
+
+    match repo.set_benchmark_association_in_hub(
+        payload.benchmark_id,
+        payload.product_id,
+        hub_id,
+        distance,
+    ) {
+        Ok(_) => Ok(true),
+        Err(RepositoryError::NotFound) => Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get benchmark: {e}");
-            return Err(ServiceError::Internal);
+            tracing::error!("Failed to create benchmark association: {e}");
+            Ok(false)
         }
-    };
+    }
+}
+
+/// Associates a benchmark with every hub product that carries the same SKU.
+///
+/// A matching SKU alone is not trusted: the embedding distance between the
+/// benchmark and the product must not exceed `threshold` (from
+/// `ServerConfig.similarity_threshold`). Products already associated with the
+/// benchmark, soft-deleted products and products without a comparable
+/// embedding are skipped, so existing (possibly manual) distances are never
+/// overwritten. Returns the number of new associations.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn auto_associate_by_sku<R>(
+    benchmark_id: i32,
+    threshold: SimilarityDistance,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<usize>
+where
+    R: BenchmarkReader + BenchmarkWriter + CrawlerReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+    let benchmark_id = BenchmarkId::new(benchmark_id).map_err(|_| ServiceError::NotFound)?;
 
-    let product = match repo.get_product_by_id(payload.product_id) {
-        Ok(Some(p)) => p,
+    let benchmark = match repo.get_benchmark_by_id(benchmark_id, hub_id) {
+        Ok(Some(benchmark)) => benchmark,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get product: {e}");
+            tracing::error!("Failed to get benchmark: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    match repo.get_crawler_by_id(product.crawler_id, hub_id) {
-        Ok(Some(crawler)) => crawler,
+    let Some(benchmark_embedding) = benchmark.embedding else {
+        return Ok(0);
+    };
+    let Ok(sku) = ProductSku::new(benchmark.sku.as_str()) else {
+        return Ok(0);
+    };
+
+    let associated = repo
+        .list_distances(benchmark.id, SortDirection::Asc)
+        .map_err(|e| {
+            tracing::error!("Failed to list benchmark distances: {e}");
+            ServiceError::Internal
+        })?
+        .into_iter()
+        .map(|(product_id, _)| product_id)
+        .collect::<HashSet<_>>();
+
+    let (_, crawlers) = repo
+        .list_crawlers(CrawlerListQuery::new(hub_id))
+        .map_err(|e| {
+            tracing::error!("Failed to list crawlers: {e}");
+            ServiceError::Internal
+        })?;
+
+    let mut associations = Vec::new();
+    for crawler in crawlers {
+        let products = repo
+            .list_active_products_by_crawler_and_sku(crawler.id, &sku)
+            .map_err(|e| {
+                tracing::error!("Failed to list products by SKU: {e}");
+                ServiceError::Internal
+            })?;
+        for product in products {
+            if associated.contains(&product.id) {
+                continue;
+            }
+            let Some(distance) = product
+                .embedding
+                .as_deref()
+                .and_then(|embedding| embedding_distance(&benchmark_embedding, embedding))
+            else {
+                continue;
+            };
+            if distance.get() > threshold.get() {
+                tracing::debug!(
+                    product_id = product.id.get(),
+                    distance = distance.get(),
+                    "Skipping SKU match above the similarity threshold"
+                );
+                continue;
+            }
+            associations.push((benchmark.id, product.id, distance));
+        }
+    }
+
+    if associations.is_empty() {
+        return Ok(0);
+    }
+
+    repo.bulk_update_benchmark_distances(&associations)
+        .map_err(|e| {
+            tracing::error!("Failed to store SKU associations: {e}");
+            ServiceError::Internal
+        })
+}
+
+/// Products loaded per query while scanning a hub for embedding matches.
+const EMBEDDING_SCAN_BATCH_SIZE: usize = 500;
+
+/// Associates a benchmark with every hub product whose embedding is within
+/// `threshold` cosine distance of the benchmark's embedding.
+///
+/// Runs locally without a ZMQ round trip. Identical embeddings count as
+/// distance `0.0` and are always associated. Products already associated with
+/// the benchmark and products without a comparable embedding are skipped, so
+/// existing (possibly manual) distances are never overwritten. Returns the
+/// number of new associations.
+///
+/// Each crawler's products are streamed in batches and scored in one linear
+/// pass, so the call is dominated by loading the products (see SPEC FR-11 for
+/// the measured scoring cost).
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn auto_associate_by_embedding<R>(
+    benchmark_id: i32,
+    threshold: SimilarityDistance,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<usize>
+where
+    R: BenchmarkReader + BenchmarkWriter + CrawlerReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+    let benchmark_id = BenchmarkId::new(benchmark_id).map_err(|_| ServiceError::NotFound)?;
+
+    let benchmark = match repo.get_benchmark_by_id(benchmark_id, hub_id) {
+        Ok(Some(benchmark)) => benchmark,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get crawler: {e}");
+            tracing::error!("Failed to get benchmark: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let distance = match SimilarityDistance::new(1.0) {
-        Ok(distance) => distance,
-        Err(e) => {
-            log::error!("Invalid default similarity distance: {e}");
-            return Ok(false);
-        }
+    let Some(benchmark_blob) = benchmark.embedding else {
+        return Ok(0);
+    };
+    let Some(benchmark_embedding) = decode_embedding(&benchmark_blob) else {
+        return Ok(0);
     };
 
-    match repo.set_benchmark_association(benchmark.id, product.id, distance) {
-        Ok(_) => Ok(true),
-        Err(e) => {
-            log::error!("Failed to create benchmark association: {e}");
-            Ok(false)
+    let associated = repo
+        .list_distances(benchmark.id, SortDirection::Asc)
+        .map_err(|e| {
+            tracing::error!("Failed to list benchmark distances: {e}");
+            ServiceError::Internal
+        })?
+        .into_iter()
+        .map(|(product_id, _)| product_id)
+        .collect::<HashSet<_>>();
+
+    let (_, crawlers) = repo
+        .list_crawlers(CrawlerListQuery::new(hub_id))
+        .map_err(|e| {
+            tracing::error!("Failed to list crawlers: {e}");
+            ServiceError::Internal
+        })?;
+
+    let mut associations = Vec::new();
+    for crawler in crawlers {
+        let products = repo
+            .stream_products(crawler.id, EMBEDDING_SCAN_BATCH_SIZE)
+            .map_err(|e| {
+                tracing::error!("Failed to stream products: {e}");
+                ServiceError::Internal
+            })?;
+        for product in products {
+            let product = product.map_err(|e| {
+                tracing::error!("Failed to load products: {e}");
+                ServiceError::Internal
+            })?;
+            if associated.contains(&product.id) {
+                continue;
+            }
+            let Some(product_blob) = product.embedding else {
+                continue;
+            };
+            // Identical blobs are an exact match; rounding must not push them past a zero threshold.
+            let distance = if product_blob == benchmark_blob {
+                Some(0.0)
+            } else {
+                decode_embedding(&product_blob)
+                    .and_then(|embedding| cosine_distance(&benchmark_embedding, &embedding))
+            };
+            let Some(distance) =
+                distance.and_then(|distance| SimilarityDistance::new(distance).ok())
+            else {
+                continue;
+            };
+            if distance.get() > threshold.get() {
+                continue;
+            }
+            associations.push((benchmark.id, product.id, distance));
         }
     }
+
+    if associations.is_empty() {
+        return Ok(0);
+    }
+
+    repo.bulk_update_benchmark_distances(&associations)
+        .map_err(|e| {
+            tracing::error!("Failed to store embedding associations: {e}");
+            ServiceError::Internal
+        })
 }
 
 #[cfg(test)]
@@ -773,8 +1519,9 @@ mod tests {
     };
     use crate::forms::import_export::{ParsedUpload, ParsedUploadRow, UploadFormat, UploadMode};
     use crate::repository::test::TestRepository;
+    use crate::services::similarity::encode_embedding;
+    use crate::zmq::RecordingSender;
     use chrono::DateTime;
-    use pushkind_common::zmq::{SendFuture, ZmqSenderError, ZmqSenderTrait};
     use serde_json::Value;
     use std::collections::HashMap;
 
@@ -820,6 +1567,7 @@ mod tests {
             embedding: None,
             category_id: None,
             category_assignment_source: CategoryAssignmentSource::Automatic,
+            reviewed_at: None,
             images: vec![],
         }
     }
@@ -840,6 +1588,7 @@ mod tests {
             embedding: None,
             processing: false,
             num_products: ProductCount::new(0).unwrap(),
+            archived_at: None,
         }
     }
 
@@ -848,20 +1597,74 @@ mod tests {
         let repo = TestRepository::new(vec![], vec![], vec![sample_benchmark()]);
         let user = sample_user();
 
-        let benchmarks = show_benchmarks(&user, &repo).unwrap();
+        let benchmarks = show_benchmarks(
+            BenchmarkFilters::default(),
+            &CategoryPathLimits::default(),
+            &user,
+            &repo,
+        )
+        .unwrap();
         assert_eq!(benchmarks.len(), 1);
     }
 
+    #[test]
+    fn show_benchmarks_filters_by_category() {
+        let repo = TestRepository::new(vec![], vec![], vec![sample_benchmark()]);
+        let user = sample_user();
+        let filters = |category: &str| BenchmarkFilters {
+            category: Some(category.into()),
+            include_subcategories: false,
+            only_unmatched: false,
+        };
+
+        assert_eq!(
+            show_benchmarks(
+                filters(" cat "),
+                &CategoryPathLimits::default(),
+                &user,
+                &repo
+            )
+            .unwrap()
+            .len(),
+            1
+        );
+        assert!(
+            show_benchmarks(
+                filters("other"),
+                &CategoryPathLimits::default(),
+                &user,
+                &repo
+            )
+            .unwrap()
+            .is_empty()
+        );
+        assert!(matches!(
+            show_benchmarks(
+                filters("cat//sub"),
+                &CategoryPathLimits::default(),
+                &user,
+                &repo
+            ),
+            Err(ServiceError::Form(_))
+        ));
+    }
+
     #[test]
     fn returns_benchmark_details_for_authorized_user() {
         let repo = TestRepository::new(
             vec![sample_crawler()],
             vec![sample_product()],
             vec![sample_benchmark()],
-        );
+        )
+        .with_associations(vec![(
+            BenchmarkId::new(1).unwrap(),
+            ProductId::new(1).unwrap(),
+            SimilarityDistance::new(0.25).unwrap(),
+        )]);
         let user = sample_user();
 
-        let (benchmark, crawler_products, distances) = show_benchmark(1, &user, &repo).unwrap();
+        let (benchmark, crawler_products, distances) =
+            show_benchmark(1, SortDirection::Asc, &user, &repo).unwrap();
 
         assert_eq!(benchmark.id, 1);
         assert_eq!(crawler_products.len(), 1);
@@ -870,7 +1673,265 @@ mod tests {
         let value: Value = serde_json::to_value(paginated).unwrap();
         assert_eq!(value["page"], 1);
         assert_eq!(value["items"].as_array().unwrap().len(), 1);
-        assert!(distances.is_empty());
+        assert_eq!(distances.get(&1), Some(&0.25));
+    }
+
+    #[test]
+    fn show_benchmark_descending_starts_with_furthest_match_beyond_first_page() {
+        let total = DEFAULT_ITEMS_PER_PAGE + 5;
+        let products = (1..=total as i32)
+            .map(|id| {
+                let mut product = sample_product();
+                product.id = ProductId::new(id).unwrap();
+                product.sku = ProductSku::new(format!("SKU{id}")).unwrap();
+                product
+            })
+            .collect();
+        let associations = (1..=total as i32)
+            .map(|id| {
+                (
+                    BenchmarkId::new(1).unwrap(),
+                    ProductId::new(id).unwrap(),
+                    SimilarityDistance::new(id as f32 / 100.0).unwrap(),
+                )
+            })
+            .collect();
+        let repo = TestRepository::new(vec![sample_crawler()], products, vec![sample_benchmark()])
+            .with_associations(associations);
+        let user = sample_user();
+
+        let (_, crawler_products, distances) =
+            show_benchmark(1, SortDirection::Desc, &user, &repo).unwrap();
+
+        let (_, paginated) = &crawler_products[0];
+        let value: Value = serde_json::to_value(paginated).unwrap();
+        let ids: Vec<i64> = value["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ids.len(), DEFAULT_ITEMS_PER_PAGE);
+        assert_eq!(ids[0], total as i64);
+        assert_eq!(distances.len(), DEFAULT_ITEMS_PER_PAGE);
+    }
+
+    #[test]
+    fn top_n_matches_for_product_orders_by_distance() {
+        let mut product = sample_product();
+        product.embedding = Some(encode_embedding(&[1.0, 0.0]));
+        let benchmark = |id: i32, sku: &str, embedding: Option<&[f32]>| {
+            let mut benchmark = sample_benchmark();
+            benchmark.id = BenchmarkId::new(id).unwrap();
+            benchmark.sku = BenchmarkSku::new(sku).unwrap();
+            benchmark.embedding = embedding.map(encode_embedding);
+            benchmark
+        };
+        let repo = TestRepository::new(
+            vec![sample_crawler()],
+            vec![product],
+            vec![
+                benchmark(1, "FAR", Some(&[0.0, 1.0])),
+                benchmark(2, "NEAR", Some(&[1.0, 0.1])),
+                benchmark(3, "NONE", None),
+                benchmark(4, "SAME", Some(&[2.0, 0.0])),
+            ],
+        );
+        let user = sample_user();
+
+        let matches = top_n_matches_for_product(1, 2, &user, &repo).unwrap();
+
+        let ids: Vec<i32> = matches.iter().map(|(b, _)| b.id.get()).collect();
+        assert_eq!(ids, vec![4, 2]);
+        assert_eq!(matches[0].1, 0.0);
+    }
+
+    #[test]
+    fn auto_associate_by_sku_skips_products_above_threshold() {
+        let product = |id: i32, embedding: &[f32]| {
+            let mut product = sample_product();
+            product.id = ProductId::new(id).unwrap();
+            product.embedding = Some(encode_embedding(embedding));
+            product
+        };
+        let mut benchmark = sample_benchmark();
+        benchmark.embedding = Some(encode_embedding(&[1.0, 0.0]));
+        let repo = TestRepository::new(
+            vec![sample_crawler()],
+            vec![product(1, &[1.0, 0.1]), product(2, &[0.0, 1.0])],
+            vec![benchmark],
+        );
+        let user = sample_user();
+        let threshold = SimilarityDistance::new(0.5).unwrap();
+
+        let associated = auto_associate_by_sku(1, threshold, &user, &repo).unwrap();
+
+        assert_eq!(associated, 1);
+    }
+
+    #[test]
+    fn auto_associate_by_sku_keeps_existing_manual_distance() {
+        let product = |id: i32| {
+            let mut product = sample_product();
+            product.id = ProductId::new(id).unwrap();
+            product.embedding = Some(encode_embedding(&[1.0, 0.1]));
+            product
+        };
+        let mut benchmark = sample_benchmark();
+        benchmark.embedding = Some(encode_embedding(&[1.0, 0.0]));
+        let manual = SimilarityDistance::new(0.0).unwrap();
+        let repo = TestRepository::new(
+            vec![sample_crawler()],
+            vec![product(1), product(2)],
+            vec![benchmark],
+        )
+        .with_associations(vec![(
+            BenchmarkId::new(1).unwrap(),
+            ProductId::new(1).unwrap(),
+            manual,
+        )]);
+        let user = sample_user();
+        let threshold = SimilarityDistance::new(0.5).unwrap();
+
+        let associated = auto_associate_by_sku(1, threshold, &user, &repo).unwrap();
+
+        assert_eq!(associated, 1);
+        let distances: HashMap<ProductId, f32> = repo
+            .associations()
+            .into_iter()
+            .map(|(_, product_id, distance)| (product_id, distance.get()))
+            .collect();
+        assert_eq!(distances[&ProductId::new(1).unwrap()], 0.0);
+        assert!(distances[&ProductId::new(2).unwrap()] > 0.0);
+    }
+
+    #[test]
+    fn auto_associate_by_sku_skips_soft_deleted_products() {
+        let product = |id: i32| {
+            let mut product = sample_product();
+            product.id = ProductId::new(id).unwrap();
+            product.embedding = Some(encode_embedding(&[1.0, 0.1]));
+            product
+        };
+        let mut benchmark = sample_benchmark();
+        benchmark.embedding = Some(encode_embedding(&[1.0, 0.0]));
+        let repo = TestRepository::new(
+            vec![sample_crawler()],
+            vec![product(1), product(2)],
+            vec![benchmark],
+        )
+        .with_deleted_products(vec![ProductId::new(2).unwrap()]);
+        let user = sample_user();
+        let threshold = SimilarityDistance::new(0.5).unwrap();
+
+        let associated = auto_associate_by_sku(1, threshold, &user, &repo).unwrap();
+
+        assert_eq!(associated, 1);
+        let stored: Vec<ProductId> = repo
+            .associations()
+            .into_iter()
+            .map(|(_, product_id, _)| product_id)
+            .collect();
+        assert_eq!(stored, vec![ProductId::new(1).unwrap()]);
+    }
+
+    fn embedded_product(id: i32, embedding: &[f32]) -> Product {
+        let mut product = sample_product();
+        product.id = ProductId::new(id).unwrap();
+        product.sku = ProductSku::new(format!("SKU{id}")).unwrap();
+        product.embedding = Some(encode_embedding(embedding));
+        product
+    }
+
+    #[test]
+    fn auto_associate_by_embedding_always_associates_exact_matches() {
+        let mut benchmark = sample_benchmark();
+        benchmark.embedding = Some(encode_embedding(&[0.6, 0.8]));
+        let repo = TestRepository::new(
+            vec![sample_crawler()],
+            vec![
+                embedded_product(1, &[0.6, 0.8]),
+                embedded_product(2, &[0.8, -0.6]),
+            ],
+            vec![benchmark],
+        );
+        let user = sample_user();
+        let threshold = SimilarityDistance::new(0.0).unwrap();
+
+        let associated = auto_associate_by_embedding(1, threshold, &user, &repo).unwrap();
+
+        assert_eq!(associated, 1);
+        assert_eq!(
+            repo.associations(),
+            vec![(
+                BenchmarkId::new(1).unwrap(),
+                ProductId::new(1).unwrap(),
+                SimilarityDistance::new(0.0).unwrap(),
+            )]
+        );
+    }
+
+    #[test]
+    fn auto_associate_by_embedding_skips_already_associated_products() {
+        let mut benchmark = sample_benchmark();
+        benchmark.embedding = Some(encode_embedding(&[1.0, 0.0]));
+        let manual = SimilarityDistance::new(1.0).unwrap();
+        let repo = TestRepository::new(
+            vec![sample_crawler()],
+            vec![
+                embedded_product(1, &[1.0, 0.0]),
+                embedded_product(2, &[1.0, 0.1]),
+            ],
+            vec![benchmark],
+        )
+        .with_associations(vec![(
+            BenchmarkId::new(1).unwrap(),
+            ProductId::new(1).unwrap(),
+            manual,
+        )]);
+        let user = sample_user();
+        let threshold = SimilarityDistance::new(0.5).unwrap();
+
+        let associated = auto_associate_by_embedding(1, threshold, &user, &repo).unwrap();
+
+        assert_eq!(associated, 1);
+        let stored = repo.associations();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].2, manual);
+        assert_eq!(stored[1].1, ProductId::new(2).unwrap());
+    }
+
+    #[test]
+    fn auto_associate_by_embedding_scores_1000_products() {
+        let dimensions = 384;
+        let embedding = |seed: usize| {
+            (0..dimensions)
+                .map(|i| ((seed * 31 + i * 17) % 97) as f32 / 97.0 + 0.01)
+                .collect::<Vec<_>>()
+        };
+        let mut benchmark = sample_benchmark();
+        benchmark.embedding = Some(encode_embedding(&embedding(0)));
+        let products = (1..=1000)
+            .map(|id| embedded_product(id, &embedding(id as usize)))
+            .collect();
+        let repo = TestRepository::new(vec![sample_crawler()], products, vec![benchmark]);
+        let user = sample_user();
+        let threshold = SimilarityDistance::new(1.0).unwrap();
+
+        let associated = auto_associate_by_embedding(1, threshold, &user, &repo).unwrap();
+
+        assert_eq!(associated, 1000);
+        assert_eq!(repo.associations().len(), 1000);
+    }
+
+    #[test]
+    fn top_n_matches_for_product_returns_not_found_without_crawler() {
+        let repo = TestRepository::new(vec![], vec![sample_product()], vec![]);
+        let user = sample_user();
+
+        let result = top_n_matches_for_product(1, 5, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
     }
 
     #[test]
@@ -893,31 +1954,80 @@ mod tests {
     }
 
     #[test]
-    fn delete_benchmark_product_returns_form_error_for_invalid_form() {
+    fn add_benchmark_records_an_audit_entry() {
         let repo = TestRepository::default();
         let user = sample_user();
-        let form = UnassociateForm {
-            benchmark_id: 0,
-            product_id: 1,
+        let form = AddBenchmarkForm {
+            name: "benchmark".into(),
+            sku: "SKU1".into(),
+            category: "cat".into(),
+            units: "pcs".into(),
+            price: 1.0,
+            amount: 1.0,
+            description: "desc".into(),
         };
 
-        let result = delete_benchmark_product(form, &user, &repo);
+        let result = add_benchmark(form, &user, &repo);
 
-        assert!(matches!(result, Err(ServiceError::Form(_))));
+        assert!(matches!(result, Ok(true)));
+        let entries = repo.audit_entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::Create);
+        assert_eq!(
+            entries[0].resource,
+            AuditResource::Benchmark(BenchmarkSku::new("SKU1").unwrap())
+        );
+        assert_eq!(entries[0].user_sub, user.sub);
     }
 
     #[test]
-    fn create_benchmark_product_returns_form_error_for_invalid_form() {
+    fn add_benchmark_rejects_duplicate_sku_in_hub() {
+        let repo = TestRepository::new(vec![], vec![], vec![sample_benchmark()]);
+        let user = sample_user();
+        let form = AddBenchmarkForm {
+            name: "another".into(),
+            sku: "SKU1".into(),
+            category: "cat".into(),
+            units: "pcs".into(),
+            price: 2.0,
+            amount: 1.0,
+            description: "desc".into(),
+        };
+
+        let result = add_benchmark(form, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Conflict)));
+        assert!(repo.audit_entries().is_empty());
+    }
+
+    #[test]
+    fn delete_benchmark_product_returns_not_found_for_missing_benchmark() {
         let repo = TestRepository::default();
         let user = sample_user();
-        let form = AssociateForm {
-            benchmark_id: 1,
-            product_id: 0,
+        let payload = UnassociateFormPayload {
+            benchmark_id: BenchmarkId::new(1).unwrap(),
+            product_id: ProductId::new(1).unwrap(),
         };
 
-        let result = create_benchmark_product(form, &user, &repo);
+        let result = delete_benchmark_product(payload, &user, &repo);
 
-        assert!(matches!(result, Err(ServiceError::Form(_))));
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn create_benchmark_product_returns_not_found_for_missing_benchmark() {
+        let repo = TestRepository::default();
+        let user = sample_user();
+        let payload = AssociateFormPayload {
+            benchmark_id: BenchmarkId::new(1).unwrap(),
+            product_id: ProductId::new(1).unwrap(),
+            distance: None,
+        };
+
+        let result =
+            create_benchmark_product(payload, SimilarityDistance::new(1.0).unwrap(), &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
     }
 
     #[test]
@@ -925,11 +2035,45 @@ mod tests {
         let repo = TestRepository::new(vec![], vec![], vec![sample_benchmark()]);
         let user = sample_user();
 
-        let file = download_benchmarks("csv", &user, &repo).unwrap();
+        let file = export_benchmarks("csv", &PriceFormat::default(), &user, &repo).unwrap();
+        assert_eq!(
+            file.content_disposition(),
+            "attachment; filename=\"benchmarks.csv\""
+        );
         let body = String::from_utf8(file.bytes).unwrap();
         assert!(body.starts_with("sku,name,category,units,price,amount,description"));
     }
 
+    #[test]
+    fn single_benchmark_export_rejects_unknown_benchmark() {
+        let repo = TestRepository::new(vec![], vec![], vec![sample_benchmark()]);
+        let user = sample_user();
+
+        let result = export_benchmark(999, "csv", &PriceFormat::default(), &user, &repo);
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn benchmark_export_applies_configured_price_format() {
+        let mut benchmark = sample_benchmark();
+        benchmark.price = ProductPrice::new(1234.5).unwrap();
+        let repo = TestRepository::new(vec![], vec![], vec![benchmark]);
+        let user = sample_user();
+        let european = PriceFormat {
+            decimal_separator: ',',
+            thousands_separator: Some('.'),
+            decimals: Some(2),
+        };
+
+        let european_file = export_benchmarks("csv", &european, &user, &repo).unwrap();
+        let default_file = export_benchmarks("csv", &PriceFormat::default(), &user, &repo).unwrap();
+
+        let european_body = String::from_utf8(european_file.bytes).unwrap();
+        let default_body = String::from_utf8(default_file.bytes).unwrap();
+        assert!(european_body.contains(",\"1.234,50\","));
+        assert!(default_body.contains(",1234.5,"));
+    }
+
     #[test]
     fn benchmark_upload_reports_db_duplicate_sku_conflict() {
         let mut b1 = sample_benchmark();
@@ -956,33 +2100,208 @@ mod tests {
         assert_eq!(report.errors.len(), 1);
     }
 
-    struct NoopSender;
+    #[actix_web::test]
+    async fn update_benchmark_prices_skips_crawlers_without_urls() {
+        let mut p = sample_product();
+        p.url = None;
+        let repo = TestRepository::new(vec![sample_crawler()], vec![p], vec![sample_benchmark()]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
 
-    impl ZmqSenderTrait for NoopSender {
-        fn send_bytes<'a>(&'a self, _bytes: Vec<u8>) -> SendFuture<'a> {
-            Box::pin(async { Ok(()) })
-        }
+        let results =
+            update_benchmark_prices(1, &RateLimiter::per_minute(0), &user, &repo, &sender)
+                .await
+                .unwrap();
+        assert!(results.is_empty());
+    }
 
-        fn try_send_bytes(&self, _bytes: Vec<u8>) -> Result<(), ZmqSenderError> {
-            Ok(())
-        }
+    #[actix_web::test]
+    async fn update_benchmark_prices_merges_crawlers_sharing_a_selector() {
+        let mut second_crawler = sample_crawler();
+        second_crawler.id = CrawlerId::new(2).unwrap();
+        second_crawler.name = CrawlerName::new("mirror").unwrap();
+        let first = sample_product();
+        let mut second = sample_product();
+        second.id = ProductId::new(2).unwrap();
+        second.crawler_id = second_crawler.id;
+        second.sku = ProductSku::new("SKU2").unwrap();
+        second.url = Some(ProductUrl::new("http://example.com/2").unwrap());
+        let repo = TestRepository::new(
+            vec![sample_crawler(), second_crawler],
+            vec![first, second],
+            vec![sample_benchmark()],
+        );
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let results =
+            update_benchmark_prices(1, &RateLimiter::per_minute(0), &user, &repo, &sender)
+                .await
+                .unwrap();
+
+        assert_eq!(
+            results,
+            vec![CrawlerDispatchResult {
+                selector: "body".to_string(),
+                product_count: 2,
+                sent: true,
+                error: None,
+            }]
+        );
+        let sent = sender.payloads();
+        assert_eq!(sent.len(), 1);
+        let message: ZMQCrawlerMessage = serde_json::from_slice(&sent[0]).unwrap();
+        assert_eq!(
+            message,
+            ZMQCrawlerMessage::Crawler(CrawlerSelector::SelectorProducts((
+                CrawlerSelectorValue::new("body").unwrap(),
+                vec![
+                    ProductUrl::new("http://example.com").unwrap(),
+                    ProductUrl::new("http://example.com/2").unwrap(),
+                ],
+            )))
+        );
+    }
 
-        fn send_multipart<'a>(&'a self, _frames: Vec<Vec<u8>>) -> SendFuture<'a> {
-            Box::pin(async { Ok(()) })
-        }
+    #[actix_web::test]
+    async fn retry_embeddings_requeues_only_idle_benchmarks_without_embedding() {
+        let missing = sample_benchmark();
+        let mut embedded = sample_benchmark();
+        embedded.id = BenchmarkId::new(2).unwrap();
+        embedded.embedding = Some(vec![1, 2, 3]);
+        let mut processing = sample_benchmark();
+        processing.id = BenchmarkId::new(3).unwrap();
+        processing.processing = true;
+        let repo = TestRepository::new(vec![], vec![], vec![missing, embedded, processing]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let requeued = retry_embeddings(
+            &RateLimiter::per_minute(0),
+            &SendRetryPolicy::none(),
+            &user,
+            &repo,
+            &sender,
+        )
+        .await
+        .unwrap();
+        assert_eq!(requeued, 1);
     }
 
     #[actix_web::test]
-    async fn update_benchmark_prices_skips_crawlers_without_urls() {
-        let mut p = sample_product();
-        p.url = None;
-        let repo = TestRepository::new(vec![sample_crawler()], vec![p], vec![sample_benchmark()]);
+    async fn retry_embeddings_is_rejected_over_the_hub_rate() {
+        let repo = TestRepository::new(vec![], vec![], vec![sample_benchmark()]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+        let limiter = RateLimiter::per_minute(1);
+        let retry = SendRetryPolicy::none();
+
+        assert_eq!(
+            retry_embeddings(&limiter, &retry, &user, &repo, &sender)
+                .await
+                .unwrap(),
+            1
+        );
+        let result = retry_embeddings(&limiter, &retry, &user, &repo, &sender).await;
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+    }
+
+    #[actix_web::test]
+    async fn recalculate_distances_dispatches_one_message_per_hub_benchmark() {
+        let first = sample_benchmark();
+        let mut second = sample_benchmark();
+        second.id = BenchmarkId::new(2).unwrap();
+        let mut archived = sample_benchmark();
+        archived.id = BenchmarkId::new(3).unwrap();
+        archived.archived_at = Some(DateTime::from_timestamp(0, 0).unwrap().naive_utc());
+        let mut foreign = sample_benchmark();
+        foreign.id = BenchmarkId::new(4).unwrap();
+        foreign.hub_id = HubId::new(2).unwrap();
+        let repo = TestRepository::new(vec![], vec![], vec![first, second, archived, foreign]);
         let user = sample_user();
-        let sender = NoopSender;
+        let sender = RecordingSender::default();
+
+        let result = recalculate_all_distances_for_hub(
+            &RateLimiter::per_minute(0),
+            &SendRetryPolicy::none(),
+            &user,
+            &repo,
+            &sender,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.dispatched, 2);
+        assert!(result.failed.is_empty());
+        assert_eq!(sender.messages().len(), 2);
+    }
 
-        let results = update_benchmark_prices(1, &user, &repo, &sender)
+    #[actix_web::test]
+    async fn recalculate_distances_skips_processing_benchmarks() {
+        let idle = sample_benchmark();
+        let mut busy = sample_benchmark();
+        busy.id = BenchmarkId::new(2).unwrap();
+        busy.processing = true;
+        let repo = TestRepository::new(vec![], vec![], vec![idle, busy]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let result = recalculate_all_distances_for_hub(
+            &RateLimiter::per_minute(0),
+            &SendRetryPolicy::none(),
+            &user,
+            &repo,
+            &sender,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.dispatched, 1);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(sender.messages().len(), 1);
+    }
+
+    #[actix_web::test]
+    async fn recalculate_distances_is_rejected_over_the_hub_rate() {
+        let repo = TestRepository::new(vec![], vec![], vec![sample_benchmark()]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+        let limiter = RateLimiter::per_minute(1);
+        let retry = SendRetryPolicy::none();
+
+        recalculate_all_distances_for_hub(&limiter, &retry, &user, &repo, &sender)
             .await
             .unwrap();
-        assert!(results.is_empty());
+        let result =
+            recalculate_all_distances_for_hub(&limiter, &retry, &user, &repo, &sender).await;
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+        assert_eq!(sender.messages().len(), 1);
+    }
+
+    #[test]
+    fn median_handles_odd_and_even_distributions() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[0.1, 0.4, 0.9]), Some(0.4));
+        assert_eq!(median(&[0.2, 0.4, 0.6, 0.8]), Some(0.5));
+    }
+
+    #[test]
+    fn threshold_is_first_distance_above_median_gap() {
+        let threshold = threshold_from_distances(vec![0.9, 0.1, 0.3, 0.2, 0.8]);
+        assert_eq!(threshold, Some(0.8));
+
+        let flat = threshold_from_distances(vec![0.4, 0.4]);
+        assert_eq!(flat, Some(0.4));
+    }
+
+    #[test]
+    fn suggest_threshold_requires_two_associations() {
+        let repo = TestRepository::new(vec![], vec![], vec![sample_benchmark()]);
+        let user = sample_user();
+
+        let err = suggest_threshold(1, &user, &repo).unwrap_err();
+        assert!(matches!(err, ServiceError::NotFound));
     }
 }
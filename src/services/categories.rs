@@ -1,25 +1,41 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
 use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::repository::errors::RepositoryError;
 use pushkind_common::routes::check_role;
-use pushkind_common::zmq::ZmqSenderExt;
+use pushkind_common::zmq::ZmqSenderTrait;
 
 use crate::SERVICE_ACCESS_ROLE;
-use crate::domain::types::HubId;
+use crate::domain::category::NewCategory;
+use crate::domain::types::{CategoryId, CategoryName, CrawlerId, HubId, ProductSku};
 use crate::domain::zmq::ZMQCrawlerMessage;
 use crate::dto::categories::CategoryDto;
 use crate::forms::categories::{
-    AddCategoryFormPayload, ClearProductCategoryFormPayload, DeleteCategoryFormPayload,
-    SetProductCategoryFormPayload, UpdateCategoryFormPayload,
+    AddCategoryFormPayload, BulkSetCategoryFormPayload, CategoryPathLimits,
+    ClearProductCategoryFormPayload, DeleteCategoryFormPayload, MergeCategoriesFormPayload,
+    SetProductCategoryFormPayload, UpdateCategoryFormPayload, normalize_category_path,
 };
+use crate::forms::import_export::{ParsedUpload, UploadImportForm, UploadTarget, parse_upload};
 use crate::repository::{
-    CategoryListQuery, CategoryReader, CategoryWriter, CrawlerReader, ProcessingStateReader,
-    ProductReader, ProductWriter,
+    AuditLogWriter, CategoryListQuery, CategoryReader, CategoryWriter, CrawlerReader,
+    ProcessingStateReader, ProductReader, ProductWriter,
 };
+use crate::services::import_export::UploadReport;
 
+use super::audit::{AuditAction, AuditResource, record_action};
+use super::rate_limit::{RateLimiter, check_dispatch_rate};
+use super::retry::{SendRetryPolicy, send_json_with_retry};
 use super::{ServiceError, ServiceResult};
 
-const CATEGORY_MATCH_PROCESSING_MESSAGE: &str =
+/// Flash message shown when category matching is blocked by active processing.
+pub const CATEGORY_MATCH_PROCESSING_MESSAGE: &str =
     "Матчинг категорий недоступен: дождитесь завершения активной обработки парсеров и бенчмарков.";
 
+/// Flash message shown when a crawler's category matching waits for its crawl.
+pub const CRAWLER_CATEGORY_MATCH_PROCESSING_MESSAGE: &str =
+    "Матчинг категорий недоступен: дождитесь завершения обработки парсера.";
+
 fn category_match_available_in_hub<R>(repo: &R, hub_id: HubId) -> ServiceResult<bool>
 where
     R: ProcessingStateReader,
@@ -27,12 +43,13 @@ where
     match repo.has_active_processing(hub_id) {
         Ok(has_active_processing) => Ok(!has_active_processing),
         Err(e) => {
-            log::error!("Failed to read processing state: {e}");
+            tracing::error!("Failed to read processing state: {e}");
             Err(ServiceError::Internal)
         }
     }
 }
 
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn show_categories<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<CategoryDto>>
 where
     R: CategoryReader,
@@ -42,19 +59,20 @@ where
     }
 
     let hub_id = HubId::new(user.hub_id).map_err(|e| {
-        log::error!("Invalid hub id in user context: {e}");
+        tracing::error!("Invalid hub id in user context: {e}");
         ServiceError::Internal
     })?;
 
     match repo.list_categories(CategoryListQuery::new(hub_id)) {
         Ok((_total, categories)) => Ok(categories.into_iter().map(CategoryDto::from).collect()),
         Err(e) => {
-            log::error!("Failed to list categories: {e}");
+            tracing::error!("Failed to list categories: {e}");
             Err(ServiceError::Internal)
         }
     }
 }
 
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn can_match_product_categories<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<bool>
 where
     R: ProcessingStateReader,
@@ -64,42 +82,63 @@ where
     }
 
     let hub_id = HubId::new(user.hub_id).map_err(|e| {
-        log::error!("Invalid hub id in user context: {e}");
+        tracing::error!("Invalid hub id in user context: {e}");
         ServiceError::Internal
     })?;
 
     category_match_available_in_hub(repo, hub_id)
 }
 
+/// Creates a hub category, keeping the submitted casing.
+///
+/// Returns [`ServiceError::Form`] when the path exceeds `limits` or the hub
+/// already has the same path in any letter case, so "Tea/Green" and
+/// "tea/green" cannot coexist.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn add_category<R>(
     payload: AddCategoryFormPayload,
+    limits: &CategoryPathLimits,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<bool>
 where
-    R: CategoryWriter,
+    R: CategoryReader + CategoryWriter,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
     }
 
+    limits
+        .check(payload.name.as_str())
+        .map_err(|e| ServiceError::Form(e.to_string()))?;
+
     let hub_id = HubId::new(user.hub_id).map_err(|e| {
-        log::error!("Invalid hub id in user context: {e}");
+        tracing::error!("Invalid hub id in user context: {e}");
         ServiceError::Internal
     })?;
 
+    let existing = load_category_ids(repo, hub_id)?;
+    if existing.contains_key(&payload.name.as_str().to_lowercase()) {
+        return Err(ServiceError::Form("category already exists".to_string()));
+    }
+
     let category = payload.into_new_category(hub_id);
     match repo.create_category(&category) {
         Ok(_) => Ok(true),
+        Err(RepositoryError::ConflictError) => {
+            Err(ServiceError::Form("category already exists".to_string()))
+        }
         Err(e) => {
-            log::error!("Failed to create category: {e}");
+            tracing::error!("Failed to create category: {e}");
             Ok(false)
         }
     }
 }
 
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn update_category<R>(
     payload: UpdateCategoryFormPayload,
+    limits: &CategoryPathLimits,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<bool>
@@ -110,8 +149,12 @@ where
         return Err(ServiceError::Unauthorized);
     }
 
+    limits
+        .check(payload.name.as_str())
+        .map_err(|e| ServiceError::Form(e.to_string()))?;
+
     let hub_id = HubId::new(user.hub_id).map_err(|e| {
-        log::error!("Invalid hub id in user context: {e}");
+        tracing::error!("Invalid hub id in user context: {e}");
         ServiceError::Internal
     })?;
 
@@ -119,7 +162,7 @@ where
         Ok(Some(_)) => {}
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get category: {e}");
+            tracing::error!("Failed to get category: {e}");
             return Err(ServiceError::Internal);
         }
     }
@@ -132,26 +175,27 @@ where
     ) {
         Ok(_) => Ok(true),
         Err(e) => {
-            log::error!("Failed to update category: {e}");
+            tracing::error!("Failed to update category: {e}");
             Ok(false)
         }
     }
 }
 
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn delete_category<R>(
     payload: DeleteCategoryFormPayload,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<bool>
 where
-    R: CategoryReader + CategoryWriter,
+    R: AuditLogWriter + CategoryReader + CategoryWriter,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
     }
 
     let hub_id = HubId::new(user.hub_id).map_err(|e| {
-        log::error!("Invalid hub id in user context: {e}");
+        tracing::error!("Invalid hub id in user context: {e}");
         ServiceError::Internal
     })?;
 
@@ -159,20 +203,68 @@ where
         Ok(Some(_)) => {}
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get category: {e}");
+            tracing::error!("Failed to get category: {e}");
             return Err(ServiceError::Internal);
         }
     }
 
     match repo.delete_category(payload.category_id, hub_id) {
-        Ok(_) => Ok(true),
+        Ok(_) => {
+            record_action(
+                AuditAction::Delete,
+                AuditResource::Category(payload.category_id),
+                user,
+                repo,
+            );
+            Ok(true)
+        }
         Err(e) => {
-            log::error!("Failed to delete category: {e}");
+            tracing::error!("Failed to delete category: {e}");
             Ok(false)
         }
     }
 }
 
+/// Reassign all products of the source category to the target and delete the source.
+///
+/// Returns the number of reassigned products.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn merge_categories<R>(
+    payload: MergeCategoriesFormPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<usize>
+where
+    R: CategoryReader + CategoryWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    for category_id in [payload.source_id, payload.target_id] {
+        match repo.get_category_by_id(category_id, hub_id) {
+            Ok(Some(_)) => {}
+            Ok(None) => return Err(ServiceError::NotFound),
+            Err(e) => {
+                tracing::error!("Failed to get category: {e}");
+                return Err(ServiceError::Internal);
+            }
+        }
+    }
+
+    repo.merge_categories(payload.source_id, payload.target_id, hub_id)
+        .map_err(|e| {
+            tracing::error!("Failed to merge categories: {e}");
+            ServiceError::Internal
+        })
+}
+
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn set_product_category_manual<R>(
     payload: SetProductCategoryFormPayload,
     user: &AuthenticatedUser,
@@ -186,7 +278,7 @@ where
     }
 
     let hub_id = HubId::new(user.hub_id).map_err(|e| {
-        log::error!("Invalid hub id in user context: {e}");
+        tracing::error!("Invalid hub id in user context: {e}");
         ServiceError::Internal
     })?;
 
@@ -194,7 +286,7 @@ where
         Ok(Some(product)) => product,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get product: {e}");
+            tracing::error!("Failed to get product: {e}");
             return Err(ServiceError::Internal);
         }
     };
@@ -203,7 +295,7 @@ where
         Ok(Some(_)) => {}
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get crawler by id: {e}");
+            tracing::error!("Failed to get crawler by id: {e}");
             return Err(ServiceError::Internal);
         }
     }
@@ -212,7 +304,7 @@ where
         Ok(Some(_)) => {}
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get category by id: {e}");
+            tracing::error!("Failed to get category by id: {e}");
             return Err(ServiceError::Internal);
         }
     }
@@ -220,12 +312,13 @@ where
     match repo.set_product_category_manual(product.id, payload.category_id) {
         Ok(_) => Ok(true),
         Err(e) => {
-            log::error!("Failed to set manual category assignment: {e}");
+            tracing::error!("Failed to set manual category assignment: {e}");
             Ok(false)
         }
     }
 }
 
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn clear_product_category_manual<R>(
     payload: ClearProductCategoryFormPayload,
     user: &AuthenticatedUser,
@@ -239,7 +332,7 @@ where
     }
 
     let hub_id = HubId::new(user.hub_id).map_err(|e| {
-        log::error!("Invalid hub id in user context: {e}");
+        tracing::error!("Invalid hub id in user context: {e}");
         ServiceError::Internal
     })?;
 
@@ -247,7 +340,7 @@ where
         Ok(Some(product)) => product,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get product: {e}");
+            tracing::error!("Failed to get product: {e}");
             return Err(ServiceError::Internal);
         }
     };
@@ -256,7 +349,7 @@ where
         Ok(Some(_)) => {}
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get crawler by id: {e}");
+            tracing::error!("Failed to get crawler by id: {e}");
             return Err(ServiceError::Internal);
         }
     }
@@ -264,27 +357,370 @@ where
     match repo.clear_product_category_manual(product.id) {
         Ok(_) => Ok(true),
         Err(e) => {
-            log::error!("Failed to clear manual category assignment: {e}");
+            tracing::error!("Failed to clear manual category assignment: {e}");
             Ok(false)
         }
     }
 }
 
+/// Outcome of a bulk manual category assignment.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BulkSetCategoryOutcome {
+    pub updated: usize,
+    /// Product ids that do not exist in the user's hub or failed to update.
+    pub skipped: Vec<i32>,
+}
+
+/// Manually assign one category to several products.
+///
+/// The category must belong to the user's hub. Products that are missing or
+/// belong to another hub are skipped instead of failing the whole request.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn bulk_set_product_categories<R>(
+    payload: BulkSetCategoryFormPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<BulkSetCategoryOutcome>
+where
+    R: ProductReader + ProductWriter + CrawlerReader + CategoryReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    match repo.get_category_by_id(payload.category_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get category by id: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    let products = match repo.get_products_by_ids(&payload.product_ids) {
+        Ok(products) => products,
+        Err(e) => {
+            tracing::error!("Failed to get products: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+    let products = products
+        .into_iter()
+        .map(|product| (product.id, product))
+        .collect::<HashMap<_, _>>();
+
+    let mut outcome = BulkSetCategoryOutcome::default();
+    let mut hub_crawlers = HashMap::new();
+    for product_id in payload.product_ids {
+        let Some(product) = products.get(&product_id) else {
+            outcome.skipped.push(product_id.get());
+            continue;
+        };
+
+        let in_hub = match hub_crawlers.get(&product.crawler_id) {
+            Some(in_hub) => *in_hub,
+            None => match repo.get_crawler_by_id(product.crawler_id, hub_id) {
+                Ok(crawler) => *hub_crawlers
+                    .entry(product.crawler_id)
+                    .or_insert(crawler.is_some()),
+                Err(e) => {
+                    tracing::error!("Failed to get crawler by id: {e}");
+                    return Err(ServiceError::Internal);
+                }
+            },
+        };
+        if !in_hub {
+            outcome.skipped.push(product_id.get());
+            continue;
+        }
+
+        match repo.set_product_category_manual(product.id, payload.category_id) {
+            Ok(_) => outcome.updated += 1,
+            Err(e) => {
+                tracing::error!("Failed to set manual category assignment: {e}");
+                outcome.skipped.push(product_id.get());
+            }
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// Upload a `sku,category` mapping file and manually assign categories to the
+/// crawler's products.
+///
+/// Category paths are matched case-insensitively against the hub directory.
+/// Unknown paths are created when `create_missing` is set and reported as row
+/// errors otherwise, as are SKUs without a matching crawler product.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
+pub fn upload_product_categories<R>(
+    crawler_id: i32,
+    create_missing: bool,
+    form: &mut UploadImportForm,
+    limits: &CategoryPathLimits,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<UploadReport>
+where
+    R: CrawlerReader + ProductReader + ProductWriter + CategoryReader + CategoryWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+    match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to load crawler for category upload: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    let parsed = parse_upload(form, UploadTarget::ProductCategories)
+        .map_err(|err| ServiceError::Form(err.to_string()))?;
+    apply_product_category_upload(parsed, hub_id, crawler_id, create_missing, limits, repo)
+}
+
+fn load_category_ids<R>(repo: &R, hub_id: HubId) -> ServiceResult<HashMap<String, CategoryId>>
+where
+    R: CategoryReader,
+{
+    match repo.list_categories(CategoryListQuery::new(hub_id)) {
+        Ok((_, categories)) => Ok(categories
+            .into_iter()
+            .map(|category| (category.name.as_str().to_lowercase(), category.id))
+            .collect()),
+        Err(e) => {
+            tracing::error!("Failed to list categories: {e}");
+            Err(ServiceError::Internal)
+        }
+    }
+}
+
+fn apply_product_category_upload<R>(
+    parsed: ParsedUpload,
+    hub_id: HubId,
+    crawler_id: CrawlerId,
+    create_missing: bool,
+    limits: &CategoryPathLimits,
+    repo: &R,
+) -> ServiceResult<UploadReport>
+where
+    R: ProductReader + ProductWriter + CategoryReader + CategoryWriter,
+{
+    let mut report = UploadReport::with_total(parsed.rows.len());
+    let mut category_ids = load_category_ids(repo, hub_id)?;
+
+    for row in parsed.rows {
+        let sku_value = row.values.get("sku").cloned().unwrap_or_default();
+        let sku = match ProductSku::new(sku_value.clone()) {
+            Ok(sku) => sku,
+            Err(_) => {
+                report.push_error(row.row_number, None, "Missing sku");
+                continue;
+            }
+        };
+
+        let raw_category = row.values.get("category").cloned().unwrap_or_default();
+        let category_path = match normalize_category_path(raw_category, limits) {
+            Ok(path) => path,
+            Err(err) => {
+                report.push_error(row.row_number, Some(sku_value), err.to_string());
+                continue;
+            }
+        };
+
+        let products = match repo.list_products_by_crawler_and_sku(crawler_id, &sku) {
+            Ok(products) => products,
+            Err(e) => {
+                tracing::error!("Failed to lookup product by sku: {e}");
+                return Err(ServiceError::Internal);
+            }
+        };
+        if products.is_empty() {
+            report.push_error(row.row_number, Some(sku_value), "Unknown sku");
+            continue;
+        }
+
+        let key = category_path.to_lowercase();
+        if !category_ids.contains_key(&key) && create_missing {
+            let name = match CategoryName::new(category_path.clone()) {
+                Ok(name) => name,
+                Err(err) => {
+                    report.push_error(row.row_number, Some(sku_value), err.to_string());
+                    continue;
+                }
+            };
+            let now = Utc::now().naive_utc();
+            let category = NewCategory {
+                hub_id,
+                name,
+                embedding: None,
+                created_at: now,
+                updated_at: now,
+            };
+            if let Err(e) = repo.create_category(&category) {
+                tracing::error!("Failed to create category from upload: {e}");
+                return Err(ServiceError::Internal);
+            }
+            report.created += 1;
+            category_ids = load_category_ids(repo, hub_id)?;
+        }
+
+        let Some(category_id) = category_ids.get(&key).copied() else {
+            report.push_error(
+                row.row_number,
+                Some(sku_value),
+                format!("Unknown category: {category_path}"),
+            );
+            continue;
+        };
+
+        for product in products {
+            if let Err(e) = repo.set_product_category_manual(product.id, category_id) {
+                tracing::error!("Failed to set manual category assignment: {e}");
+                return Err(ServiceError::Internal);
+            }
+        }
+        report.updated += 1;
+    }
+
+    Ok(report)
+}
+
+/// Create directory categories from an uploaded file with a `name` column.
+///
+/// Each name is normalized like a manually added category. Paths that already
+/// exist in the hub, or repeat within the file, are reported as skipped rows;
+/// both checks ignore case like the `(hub_id, LOWER(name))` unique index.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
+pub fn import_categories<R>(
+    form: &mut UploadImportForm,
+    limits: &CategoryPathLimits,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<UploadReport>
+where
+    R: CategoryReader + CategoryWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    let parsed = parse_upload(form, UploadTarget::Categories)
+        .map_err(|err| ServiceError::Form(err.to_string()))?;
+    apply_category_upload(parsed, hub_id, limits, repo)
+}
+
+fn apply_category_upload<R>(
+    parsed: ParsedUpload,
+    hub_id: HubId,
+    limits: &CategoryPathLimits,
+    repo: &R,
+) -> ServiceResult<UploadReport>
+where
+    R: CategoryReader + CategoryWriter,
+{
+    let mut report = UploadReport::with_total(parsed.rows.len());
+    let existing = load_category_ids(repo, hub_id)?;
+    let mut seen_paths = HashSet::new();
+
+    for row in parsed.rows {
+        let raw_name = row.values.get("name").cloned().unwrap_or_default();
+        let name = match normalize_category_path(raw_name, limits).and_then(CategoryName::new) {
+            Ok(name) => name,
+            Err(err) => {
+                report.push_error(row.row_number, None, err.to_string());
+                continue;
+            }
+        };
+
+        let key = name.as_str().to_lowercase();
+        if !seen_paths.insert(key.clone()) {
+            report.push_error(
+                row.row_number,
+                None,
+                format!("Duplicate category in file: {}", name.as_str()),
+            );
+            continue;
+        }
+
+        if existing.contains_key(&key) {
+            report.push_error(
+                row.row_number,
+                None,
+                format!("Category already exists: {}", name.as_str()),
+            );
+            continue;
+        }
+
+        let now = Utc::now().naive_utc();
+        let category = NewCategory {
+            hub_id,
+            name: name.clone(),
+            embedding: None,
+            created_at: now,
+            updated_at: now,
+        };
+        match repo.create_category(&category) {
+            Ok(_) => report.created += 1,
+            Err(RepositoryError::ConflictError) => {
+                report.push_error(
+                    row.row_number,
+                    None,
+                    format!("Category already exists: {}", name.as_str()),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Failed to create category from import: {e}");
+                return Err(ServiceError::Internal);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Enqueue category embedding and then product-to-category matching for the hub.
+///
+/// `EmbedCategories` is sent first so newly created categories have embeddings
+/// when the worker matches products. Returns `Ok(false)` when either send fails;
+/// matching is not requested if embedding could not be enqueued. Both sends
+/// count as one dispatch against the hub's rate.
+#[tracing::instrument(skip(limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
 pub async fn match_product_categories<R, S>(
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
     user: &AuthenticatedUser,
     repo: &R,
     sender: &S,
 ) -> ServiceResult<bool>
 where
     R: ProcessingStateReader,
-    S: ZmqSenderExt + ?Sized,
+    S: ZmqSenderTrait + ?Sized,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
     }
 
     let hub_id = HubId::new(user.hub_id).map_err(|e| {
-        log::error!("Invalid hub id in user context: {e}");
+        tracing::error!("Invalid hub id in user context: {e}");
         ServiceError::Internal
     })?;
 
@@ -294,13 +730,122 @@ where
         ));
     }
 
+    check_dispatch_rate(limiter, hub_id)?;
+
+    // Categories are created without embeddings; refresh them before matching.
+    let message = ZMQCrawlerMessage::EmbedCategories(hub_id);
+    if !send_json_with_retry(sender, &message, retry).await {
+        tracing::error!("Failed to send ZMQ message");
+        return Ok(false);
+    }
+
     let message = ZMQCrawlerMessage::ProductCategoryMatch(hub_id);
-    match sender.send_json(&message).await {
-        Ok(_) => Ok(true),
-        Err(_) => {
-            log::error!("Failed to send ZMQ message");
-            Ok(false)
+    if send_json_with_retry(sender, &message, retry).await {
+        Ok(true)
+    } else {
+        tracing::error!("Failed to send ZMQ message");
+        Ok(false)
+    }
+}
+
+/// Ask the worker to re-match automatically categorized products of the hub.
+///
+/// Meant for after category embeddings changed, so only
+/// `ProductCategoryMatch` is sent. By the worker contract on that message,
+/// products with `category_assignment_source = manual` keep their category.
+/// Returns [`ServiceError::Form`] while a crawler or benchmark of the hub is
+/// processing or the hub exceeds its dispatch rate, and `Ok(false)` when the
+/// send fails.
+#[tracing::instrument(skip(limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
+pub async fn request_recategorize_automatic<R, S>(
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
+    user: &AuthenticatedUser,
+    repo: &R,
+    sender: &S,
+) -> ServiceResult<bool>
+where
+    R: ProcessingStateReader,
+    S: ZmqSenderTrait + ?Sized,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    if !category_match_available_in_hub(repo, hub_id)? {
+        return Err(ServiceError::Form(
+            CATEGORY_MATCH_PROCESSING_MESSAGE.to_string(),
+        ));
+    }
+
+    check_dispatch_rate(limiter, hub_id)?;
+
+    let message = ZMQCrawlerMessage::ProductCategoryMatch(hub_id);
+    if send_json_with_retry(sender, &message, retry).await {
+        Ok(true)
+    } else {
+        tracing::error!("Failed to send ZMQ message");
+        Ok(false)
+    }
+}
+
+/// Ask the worker to re-match categories of one crawler's products.
+///
+/// Meant for after a crawl completed. The crawler must belong to the user's
+/// hub and must not be processing; unlike the hub-wide trigger, other
+/// crawlers and benchmarks of the hub do not block it. Returns `Ok(false)`
+/// when the send fails and [`ServiceError::Form`] when the hub exceeds its
+/// dispatch rate.
+#[tracing::instrument(skip(limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
+pub async fn trigger_category_match_for_crawler<R, S>(
+    crawler_id: i32,
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
+    user: &AuthenticatedUser,
+    repo: &R,
+    sender: &S,
+) -> ServiceResult<bool>
+where
+    R: CrawlerReader,
+    S: ZmqSenderTrait + ?Sized,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+
+    let crawler = match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(crawler)) => crawler,
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler: {e}");
+            return Err(ServiceError::Internal);
         }
+    };
+    if crawler.processing {
+        return Err(ServiceError::Form(
+            CRAWLER_CATEGORY_MATCH_PROCESSING_MESSAGE.to_string(),
+        ));
+    }
+
+    check_dispatch_rate(limiter, hub_id)?;
+
+    let message = ZMQCrawlerMessage::ProductCategoryMatchForCrawler(hub_id, crawler_id);
+    if send_json_with_retry(sender, &message, retry).await {
+        Ok(true)
+    } else {
+        tracing::error!("Failed to send ZMQ message");
+        Ok(false)
     }
 }
 
@@ -317,10 +862,11 @@ mod tests {
         ProductAmount, ProductCount, ProductDescription, ProductId, ProductName, ProductPrice,
         ProductSku, ProductUnits, ProductUrl,
     };
-    use crate::forms::categories::SetProductCategoryFormPayload;
+    use crate::forms::categories::{BulkSetCategoryFormPayload, SetProductCategoryFormPayload};
+    use crate::forms::import_export::{ParsedUploadRow, UploadFormat, UploadMode};
     use crate::repository::test::TestRepository;
+    use crate::zmq::RecordingSender;
     use chrono::DateTime;
-    use pushkind_common::zmq::{SendFuture, ZmqSenderError, ZmqSenderTrait};
 
     fn sample_user() -> AuthenticatedUser {
         AuthenticatedUser {
@@ -364,6 +910,7 @@ mod tests {
             embedding: None,
             category_id: None,
             category_assignment_source: CategoryAssignmentSource::Automatic,
+            reviewed_at: None,
             images: vec![],
         }
     }
@@ -395,23 +942,60 @@ mod tests {
             embedding: None,
             processing: false,
             num_products: ProductCount::new(0).unwrap(),
+            archived_at: None,
         }
     }
 
-    struct NoopSender;
+    #[test]
+    fn add_category_rejects_case_variant_of_existing_path() {
+        let repo =
+            TestRepository::new(vec![], vec![], vec![]).with_categories(vec![sample_category()]);
+        let user = sample_user();
+        let payload = AddCategoryFormPayload {
+            name: CategoryName::new("tea/GREEN").unwrap(),
+        };
 
-    impl ZmqSenderTrait for NoopSender {
-        fn send_bytes<'a>(&'a self, _bytes: Vec<u8>) -> SendFuture<'a> {
-            Box::pin(async { Ok(()) })
-        }
+        let result = add_category(payload, &CategoryPathLimits::default(), &user, &repo);
 
-        fn try_send_bytes(&self, _bytes: Vec<u8>) -> Result<(), ZmqSenderError> {
-            Ok(())
+        match result {
+            Err(ServiceError::Form(message)) => assert_eq!(message, "category already exists"),
+            other => panic!("expected form error, got {other:?}"),
         }
+    }
 
-        fn send_multipart<'a>(&'a self, _frames: Vec<Vec<u8>>) -> SendFuture<'a> {
-            Box::pin(async { Ok(()) })
-        }
+    #[test]
+    fn add_category_accepts_new_path() {
+        let repo =
+            TestRepository::new(vec![], vec![], vec![]).with_categories(vec![sample_category()]);
+        let user = sample_user();
+        let payload = AddCategoryFormPayload {
+            name: CategoryName::new("Tea/Black").unwrap(),
+        };
+
+        assert!(matches!(
+            add_category(payload, &CategoryPathLimits::default(), &user, &repo),
+            Ok(true)
+        ));
+    }
+
+    #[test]
+    fn add_category_rejects_path_deeper_than_configured() {
+        let repo = TestRepository::new(vec![], vec![], vec![]);
+        let user = sample_user();
+        let payload = AddCategoryFormPayload {
+            name: CategoryName::new("Tea/Green/Sencha").unwrap(),
+        };
+
+        let result = add_category(
+            payload.clone(),
+            &CategoryPathLimits::new(2, 100),
+            &user,
+            &repo,
+        );
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+
+        let result = add_category(payload, &CategoryPathLimits::new(3, 100), &user, &repo);
+        assert!(matches!(result, Ok(true)));
     }
 
     #[test]
@@ -451,6 +1035,34 @@ mod tests {
         assert!(set_product_category_manual(payload, &user, &repo).unwrap());
     }
 
+    #[test]
+    fn bulk_set_skips_missing_products() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![])
+            .with_categories(vec![sample_category()]);
+        let user = sample_user();
+        let payload = BulkSetCategoryFormPayload {
+            product_ids: vec![ProductId::new(1).unwrap(), ProductId::new(99).unwrap()],
+            category_id: CategoryId::new(1).unwrap(),
+        };
+
+        let outcome = bulk_set_product_categories(payload, &user, &repo).unwrap();
+        assert_eq!(outcome.updated, 1);
+        assert_eq!(outcome.skipped, vec![99]);
+    }
+
+    #[test]
+    fn bulk_set_requires_existing_category_in_hub() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let user = sample_user();
+        let payload = BulkSetCategoryFormPayload {
+            product_ids: vec![ProductId::new(1).unwrap()],
+            category_id: CategoryId::new(999).unwrap(),
+        };
+
+        let err = bulk_set_product_categories(payload, &user, &repo).unwrap_err();
+        assert!(matches!(err, ServiceError::NotFound));
+    }
+
     #[test]
     fn category_match_is_available_without_active_processing() {
         let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![sample_benchmark()]);
@@ -485,11 +1097,318 @@ mod tests {
         benchmark.processing = true;
         let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![benchmark]);
         let user = sample_user();
-        let sender = NoopSender;
+        let sender = RecordingSender::default();
 
-        let result = actix_web::rt::System::new()
-            .block_on(async { match_product_categories(&user, &repo, &sender).await });
+        let result = actix_web::rt::System::new().block_on(async {
+            match_product_categories(
+                &RateLimiter::per_minute(0),
+                &SendRetryPolicy::none(),
+                &user,
+                &repo,
+                &sender,
+            )
+            .await
+        });
 
         assert!(matches!(result, Err(ServiceError::Form(_))));
     }
+
+    /// Messages recorded by `sender`, decoded in send order.
+    fn sent_messages(sender: &RecordingSender) -> Vec<ZMQCrawlerMessage> {
+        sender
+            .payloads()
+            .iter()
+            .map(|payload| serde_json::from_slice(payload).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn match_product_categories_embeds_categories_before_matching() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let result = actix_web::rt::System::new().block_on(async {
+            match_product_categories(
+                &RateLimiter::per_minute(0),
+                &SendRetryPolicy::none(),
+                &user,
+                &repo,
+                &sender,
+            )
+            .await
+        });
+
+        assert!(result.unwrap());
+        let hub_id = HubId::new(1).unwrap();
+        assert_eq!(
+            sent_messages(&sender),
+            vec![
+                ZMQCrawlerMessage::EmbedCategories(hub_id),
+                ZMQCrawlerMessage::ProductCategoryMatch(hub_id),
+            ]
+        );
+    }
+
+    #[test]
+    fn match_product_categories_is_rejected_over_the_hub_rate() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+        let limiter = RateLimiter::per_minute(1);
+        let retry = SendRetryPolicy::none();
+
+        let (first, second) = actix_web::rt::System::new().block_on(async {
+            (
+                match_product_categories(&limiter, &retry, &user, &repo, &sender).await,
+                match_product_categories(&limiter, &retry, &user, &repo, &sender).await,
+            )
+        });
+
+        assert!(first.unwrap());
+        assert!(matches!(second, Err(ServiceError::Form(_))));
+        assert_eq!(sender.messages().len(), 2);
+    }
+
+    #[test]
+    fn recategorize_automatic_is_blocked_while_a_crawler_is_processing() {
+        let mut crawler = sample_crawler();
+        crawler.processing = true;
+        let repo = TestRepository::new(vec![crawler], vec![], vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let result = actix_web::rt::System::new().block_on(async {
+            request_recategorize_automatic(
+                &RateLimiter::per_minute(0),
+                &SendRetryPolicy::none(),
+                &user,
+                &repo,
+                &sender,
+            )
+            .await
+        });
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+        assert!(sender.messages().is_empty());
+    }
+
+    #[test]
+    fn recategorize_automatic_sends_only_product_category_match() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let result = actix_web::rt::System::new().block_on(async {
+            request_recategorize_automatic(
+                &RateLimiter::per_minute(0),
+                &SendRetryPolicy::none(),
+                &user,
+                &repo,
+                &sender,
+            )
+            .await
+        });
+
+        assert!(result.unwrap());
+        assert_eq!(
+            sent_messages(&sender),
+            vec![ZMQCrawlerMessage::ProductCategoryMatch(
+                HubId::new(1).unwrap()
+            )]
+        );
+    }
+
+    #[test]
+    fn crawler_category_match_is_blocked_while_the_crawler_is_processing() {
+        let mut crawler = sample_crawler();
+        crawler.processing = true;
+        let repo = TestRepository::new(vec![crawler], vec![], vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let result = actix_web::rt::System::new().block_on(async {
+            trigger_category_match_for_crawler(
+                1,
+                &RateLimiter::per_minute(0),
+                &SendRetryPolicy::none(),
+                &user,
+                &repo,
+                &sender,
+            )
+            .await
+        });
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+        assert!(sender.messages().is_empty());
+    }
+
+    #[test]
+    fn crawler_category_match_sends_crawler_scoped_message() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let result = actix_web::rt::System::new().block_on(async {
+            trigger_category_match_for_crawler(
+                1,
+                &RateLimiter::per_minute(0),
+                &SendRetryPolicy::none(),
+                &user,
+                &repo,
+                &sender,
+            )
+            .await
+        });
+
+        assert!(result.unwrap());
+        assert_eq!(
+            sent_messages(&sender),
+            vec![ZMQCrawlerMessage::ProductCategoryMatchForCrawler(
+                HubId::new(1).unwrap(),
+                CrawlerId::new(1).unwrap()
+            )]
+        );
+    }
+
+    #[test]
+    fn category_upload_assigns_known_skus_and_flags_unknown_ones() {
+        let mut p1 = sample_product();
+        p1.sku = ProductSku::new("SKU1").unwrap();
+        let mut p2 = sample_product();
+        p2.id = ProductId::new(2).unwrap();
+        p2.sku = ProductSku::new("SKU2").unwrap();
+        let repo = TestRepository::new(vec![sample_crawler()], vec![p1, p2], vec![])
+            .with_categories(vec![sample_category()]);
+        let row = |row_number: usize, sku: &str| ParsedUploadRow {
+            row_number,
+            values: HashMap::from([
+                ("sku".into(), sku.into()),
+                ("category".into(), "tea / green".into()),
+            ]),
+        };
+        let parsed = ParsedUpload {
+            format: UploadFormat::Csv,
+            mode: UploadMode::Full,
+            headers: vec!["sku".into(), "category".into()],
+            rows: vec![row(2, "SKU1"), row(3, "SKU2"), row(4, "MISSING")],
+        };
+
+        let report = apply_product_category_upload(
+            parsed,
+            HubId::new(1).unwrap(),
+            CrawlerId::new(1).unwrap(),
+            false,
+            &CategoryPathLimits::default(),
+            &repo,
+        )
+        .unwrap();
+
+        assert_eq!(report.total_rows, 3);
+        assert_eq!(report.updated, 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.errors[0].sku.as_deref(), Some("MISSING"));
+    }
+
+    #[test]
+    fn category_upload_flags_unknown_category_without_create_missing() {
+        let mut product = sample_product();
+        product.sku = ProductSku::new("SKU1").unwrap();
+        let repo = TestRepository::new(vec![sample_crawler()], vec![product], vec![]);
+        let parsed = ParsedUpload {
+            format: UploadFormat::Csv,
+            mode: UploadMode::Full,
+            headers: vec!["sku".into(), "category".into()],
+            rows: vec![ParsedUploadRow {
+                row_number: 2,
+                values: HashMap::from([
+                    ("sku".into(), "SKU1".into()),
+                    ("category".into(), "Coffee".into()),
+                ]),
+            }],
+        };
+
+        let report = apply_product_category_upload(
+            parsed,
+            HubId::new(1).unwrap(),
+            CrawlerId::new(1).unwrap(),
+            false,
+            &CategoryPathLimits::default(),
+            &repo,
+        )
+        .unwrap();
+
+        assert_eq!(report.updated, 0);
+        assert!(report.errors[0].message.contains("Unknown category"));
+    }
+
+    #[test]
+    fn category_import_skips_existing_and_repeated_paths() {
+        let repo =
+            TestRepository::new(vec![], vec![], vec![]).with_categories(vec![sample_category()]);
+        let row = |row_number: usize, name: &str| ParsedUploadRow {
+            row_number,
+            values: HashMap::from([("name".into(), name.into())]),
+        };
+        let parsed = ParsedUpload {
+            format: UploadFormat::Csv,
+            mode: UploadMode::Full,
+            headers: vec!["name".into()],
+            rows: vec![
+                row(2, " Tea / Green "),
+                row(3, "Tea/Black"),
+                row(4, "Tea / Black"),
+                row(5, "Tea//"),
+            ],
+        };
+
+        let report = apply_category_upload(
+            parsed,
+            HubId::new(1).unwrap(),
+            &CategoryPathLimits::default(),
+            &repo,
+        )
+        .unwrap();
+
+        assert_eq!(report.total_rows, 4);
+        assert_eq!(report.created, 1);
+        assert_eq!(report.skipped, 3);
+        assert!(report.errors[0].message.contains("already exists"));
+        assert!(report.errors[1].message.contains("Duplicate category"));
+        assert_eq!(report.errors[2].row_number, 5);
+    }
+
+    #[test]
+    fn category_import_treats_case_variants_as_the_same_path() {
+        let repo =
+            TestRepository::new(vec![], vec![], vec![]).with_categories(vec![sample_category()]);
+        let row = |row_number: usize, name: &str| ParsedUploadRow {
+            row_number,
+            values: HashMap::from([("name".into(), name.into())]),
+        };
+        let parsed = ParsedUpload {
+            format: UploadFormat::Csv,
+            mode: UploadMode::Full,
+            headers: vec!["name".into()],
+            rows: vec![
+                row(2, "tea / green"),
+                row(3, "Tea/Black"),
+                row(4, "TEA / BLACK"),
+            ],
+        };
+
+        let report = apply_category_upload(
+            parsed,
+            HubId::new(1).unwrap(),
+            &CategoryPathLimits::default(),
+            &repo,
+        )
+        .unwrap();
+
+        assert_eq!(report.created, 1);
+        assert_eq!(report.skipped, 2);
+        assert!(report.errors[0].message.contains("already exists"));
+        assert_eq!(report.errors[1].row_number, 4);
+        assert!(report.errors[1].message.contains("Duplicate category"));
+    }
 }
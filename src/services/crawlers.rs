@@ -0,0 +1,397 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::repository::errors::RepositoryError;
+use pushkind_common::routes::check_role;
+
+use crate::SERVICE_ACCESS_ROLE;
+use crate::domain::crawler::{NewCrawler, UpdateCrawlerFields};
+use crate::domain::types::{CrawlerId, HubId};
+use crate::forms::crawlers::{AddCrawlerForm, AddCrawlerFormPayload, UpdateCrawlerFormPayload};
+use crate::forms::import_export::{ParsedUpload, UploadImportForm, UploadTarget, parse_upload};
+use crate::repository::{AuditLogWriter, CrawlerListQuery, CrawlerReader, CrawlerWriter};
+use crate::services::import_export::UploadReport;
+
+use super::audit::{AuditAction, AuditResource, record_action};
+use super::{ServiceError, ServiceResult};
+
+/// Create crawlers from an uploaded file with `name`, `url` and `selector` columns.
+///
+/// Each row is validated like a manually added crawler. Rows whose URL already
+/// belongs to a hub crawler, or repeats within the file, are reported as
+/// skipped.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
+pub fn import_crawlers<R>(
+    form: &mut UploadImportForm,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<UploadReport>
+where
+    R: CrawlerReader + CrawlerWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    let parsed = parse_upload(form, UploadTarget::Crawlers)
+        .map_err(|err| ServiceError::Form(err.to_string()))?;
+    apply_crawler_import(parsed, hub_id, repo)
+}
+
+/// Update name, URL and selector of a crawler in the user's hub.
+///
+/// Returns [`ServiceError::Conflict`] when another hub crawler already uses the
+/// new URL.
+#[tracing::instrument(skip(payload, user, repo), fields(hub_id = user.hub_id))]
+pub fn update_crawler<R>(
+    crawler_id: CrawlerId,
+    payload: UpdateCrawlerFormPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<()>
+where
+    R: CrawlerReader + CrawlerWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    let fields = UpdateCrawlerFields {
+        name: payload.name,
+        url: payload.url,
+        selector: payload.selector,
+    };
+    match repo.update_crawler(crawler_id, hub_id, &fields) {
+        Ok(_) => Ok(()),
+        Err(RepositoryError::ConflictError) => Err(ServiceError::Conflict),
+        Err(e) => {
+            tracing::error!("Failed to update crawler: {e}");
+            Err(ServiceError::Internal)
+        }
+    }
+}
+
+/// Delete a crawler of the user's hub with all its products and their
+/// benchmark associations.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn delete_crawler<R>(
+    crawler_id: CrawlerId,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<()>
+where
+    R: AuditLogWriter + CrawlerReader + CrawlerWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    match repo.delete_crawler(crawler_id, hub_id) {
+        Ok(0) => Err(ServiceError::NotFound),
+        Ok(_) => {
+            record_action(
+                AuditAction::Delete,
+                AuditResource::Crawler(crawler_id),
+                user,
+                repo,
+            );
+            Ok(())
+        }
+        Err(e) => {
+            tracing::error!("Failed to delete crawler: {e}");
+            Err(ServiceError::Internal)
+        }
+    }
+}
+
+fn apply_crawler_import<R>(
+    parsed: ParsedUpload,
+    hub_id: HubId,
+    repo: &R,
+) -> ServiceResult<UploadReport>
+where
+    R: CrawlerReader + CrawlerWriter,
+{
+    let mut report = UploadReport::with_total(parsed.rows.len());
+
+    let mut seen_urls = match repo.list_crawlers(CrawlerListQuery::new(hub_id)) {
+        Ok((_, crawlers)) => crawlers
+            .into_iter()
+            .map(|crawler| crawler.url.into_inner())
+            .collect::<HashSet<_>>(),
+        Err(e) => {
+            tracing::error!("Failed to list crawlers: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    for row in parsed.rows {
+        let value = |key: &str| row.values.get(key).cloned().unwrap_or_default();
+        let form = AddCrawlerForm {
+            name: value("name"),
+            url: value("url"),
+            selector: value("selector"),
+        };
+        let payload = match AddCrawlerFormPayload::try_from(form) {
+            Ok(payload) => payload,
+            Err(err) => {
+                report.push_error(row.row_number, None, err.to_string());
+                continue;
+            }
+        };
+
+        if !seen_urls.insert(payload.url.as_str().to_string()) {
+            report.push_error(
+                row.row_number,
+                None,
+                format!("Crawler URL already exists: {}", payload.url.as_str()),
+            );
+            continue;
+        }
+
+        let crawler = NewCrawler {
+            hub_id,
+            name: payload.name,
+            url: payload.url,
+            selector: payload.selector,
+            updated_at: Utc::now().naive_utc(),
+        };
+        if let Err(e) = repo.create_crawler(&crawler) {
+            tracing::error!("Failed to create crawler from import: {e}");
+            return Err(ServiceError::Internal);
+        }
+        report.created += 1;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::crawler::Crawler;
+    use crate::domain::product::Product;
+    use crate::domain::types::{
+        BenchmarkId, CategoryAssignmentSource, CrawlerId, CrawlerName, CrawlerSelectorValue,
+        CrawlerUrl, ProductCount, ProductId, ProductName, ProductPrice, ProductSku,
+        SimilarityDistance,
+    };
+    use crate::forms::import_export::{ParsedUploadRow, UploadFormat, UploadMode};
+    use crate::repository::ProductReader;
+    use crate::repository::test::TestRepository;
+    use chrono::DateTime;
+    use std::collections::HashMap;
+
+    fn sample_crawler() -> Crawler {
+        Crawler {
+            id: CrawlerId::new(1).unwrap(),
+            hub_id: HubId::new(1).unwrap(),
+            name: CrawlerName::new("crawler").unwrap(),
+            url: CrawlerUrl::new("https://example.com").unwrap(),
+            selector: CrawlerSelectorValue::new("example").unwrap(),
+            processing: false,
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            num_products: ProductCount::new(0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn crawler_import_skips_existing_and_repeated_urls() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let row = |row_number: usize, name: &str, url: &str| ParsedUploadRow {
+            row_number,
+            values: HashMap::from([
+                ("name".into(), name.into()),
+                ("url".into(), url.into()),
+                ("selector".into(), "shop".into()),
+            ]),
+        };
+        let parsed = ParsedUpload {
+            format: UploadFormat::Csv,
+            mode: UploadMode::Full,
+            headers: vec!["name".into(), "url".into(), "selector".into()],
+            rows: vec![
+                row(2, "Shop", "https://shop.example.com"),
+                row(3, "Existing", "https://example.com"),
+                row(4, "Shop again", "https://shop.example.com"),
+                row(5, "Broken", "not a url"),
+            ],
+        };
+
+        let report = apply_crawler_import(parsed, HubId::new(1).unwrap(), &repo).unwrap();
+
+        assert_eq!(report.total_rows, 4);
+        assert_eq!(report.created, 1);
+        assert_eq!(report.skipped, 3);
+        assert_eq!(report.errors[0].row_number, 3);
+        assert!(report.errors[0].message.contains("already exists"));
+        assert_eq!(report.errors[1].row_number, 4);
+        assert_eq!(report.errors[2].row_number, 5);
+    }
+
+    fn sample_user() -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "1".into(),
+            email: "test@example.com".into(),
+            hub_id: 1,
+            name: "Test".into(),
+            roles: vec![SERVICE_ACCESS_ROLE.into()],
+            exp: 0,
+        }
+    }
+
+    fn update_payload(url: &str) -> UpdateCrawlerFormPayload {
+        UpdateCrawlerFormPayload {
+            name: CrawlerName::new("Renamed").unwrap(),
+            url: CrawlerUrl::new(url).unwrap(),
+            selector: CrawlerSelectorValue::new("example").unwrap(),
+        }
+    }
+
+    #[test]
+    fn update_crawler_rejects_url_of_another_hub_crawler() {
+        let mut other = sample_crawler();
+        other.id = CrawlerId::new(2).unwrap();
+        other.url = CrawlerUrl::new("https://other.example.com").unwrap();
+        let repo = TestRepository::new(vec![sample_crawler(), other], vec![], vec![]);
+
+        let result = update_crawler(
+            CrawlerId::new(1).unwrap(),
+            update_payload("https://other.example.com"),
+            &sample_user(),
+            &repo,
+        );
+
+        assert!(matches!(result, Err(ServiceError::Conflict)));
+    }
+
+    #[test]
+    fn update_crawler_keeps_own_url() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+
+        let result = update_crawler(
+            CrawlerId::new(1).unwrap(),
+            update_payload("https://example.com"),
+            &sample_user(),
+            &repo,
+        );
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn delete_crawler_records_audit_entry() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+
+        delete_crawler(CrawlerId::new(1).unwrap(), &sample_user(), &repo).unwrap();
+
+        let entries = repo.audit_entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::Delete);
+        assert_eq!(
+            entries[0].resource,
+            AuditResource::Crawler(CrawlerId::new(1).unwrap())
+        );
+    }
+
+    fn sample_product(id: i32, crawler_id: i32) -> Product {
+        Product {
+            id: ProductId::new(id).unwrap(),
+            crawler_id: CrawlerId::new(crawler_id).unwrap(),
+            name: ProductName::new("product").unwrap(),
+            sku: ProductSku::new("SKU1").unwrap(),
+            category: None,
+            associated_category: None,
+            units: None,
+            price: ProductPrice::new(1.0).unwrap(),
+            amount: None,
+            description: None,
+            url: None,
+            created_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            embedding: None,
+            category_id: None,
+            category_assignment_source: CategoryAssignmentSource::Automatic,
+            reviewed_at: None,
+            images: vec![],
+        }
+    }
+
+    #[test]
+    fn delete_crawler_removes_its_products_and_their_associations() {
+        let mut other = sample_crawler();
+        other.id = CrawlerId::new(2).unwrap();
+        other.url = CrawlerUrl::new("https://other.example.com").unwrap();
+        let benchmark_id = BenchmarkId::new(1).unwrap();
+        let distance = SimilarityDistance::new(0.5).unwrap();
+        let repo = TestRepository::new(
+            vec![sample_crawler(), other],
+            vec![sample_product(1, 1), sample_product(2, 2)],
+            vec![],
+        )
+        .with_associations(vec![
+            (benchmark_id, ProductId::new(1).unwrap(), distance),
+            (benchmark_id, ProductId::new(2).unwrap(), distance),
+        ]);
+
+        delete_crawler(CrawlerId::new(1).unwrap(), &sample_user(), &repo).unwrap();
+
+        let hub_id = HubId::new(1).unwrap();
+        assert!(
+            repo.get_crawler_by_id(CrawlerId::new(1).unwrap(), hub_id)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            repo.get_product_by_id(ProductId::new(1).unwrap())
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            repo.get_product_by_id(ProductId::new(2).unwrap())
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            repo.associations(),
+            vec![(benchmark_id, ProductId::new(2).unwrap(), distance)]
+        );
+
+        let again = delete_crawler(CrawlerId::new(1).unwrap(), &sample_user(), &repo);
+        assert!(matches!(again, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn delete_crawler_rejects_unknown_crawler() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+
+        let result = delete_crawler(CrawlerId::new(2).unwrap(), &sample_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+}
@@ -1,8 +1,26 @@
-use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::routes::check_role;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use uuid::Uuid;
+
+use crate::SERVICE_ACCESS_ROLE;
+use crate::domain::types::{CrawlerId, HubId};
+use crate::forms::import_export::{
+    ParsedUploadRow, UploadImportForm, UploadMode, UploadTarget, expected_headers, parse_upload,
+};
+use crate::repository::{CrawlerReader, ProductReader, ProductWriter};
+use crate::services::products::{apply_crawler_upload, classify_upload};
+
+use super::{ServiceError, ServiceResult};
 
 /// Row-level upload error used for UI reporting.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UploadRowError {
     pub row_number: usize,
     pub sku: Option<String>,
@@ -10,7 +28,7 @@ pub struct UploadRowError {
 }
 
 /// Aggregated upload outcome report.
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UploadReport {
     pub total_rows: usize,
     pub created: usize,
@@ -42,6 +60,106 @@ impl UploadReport {
     }
 }
 
+/// Outcome an uploaded row would have if the upload were committed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadRowAction {
+    Create,
+    Update,
+    Skip,
+}
+
+/// Per-row classification returned by an upload preview.
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadPreviewRow {
+    pub row_number: usize,
+    pub sku: Option<String>,
+    pub action: UploadRowAction,
+    pub message: Option<String>,
+}
+
+/// Dry-run summary of an upload: nothing is written to the database.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UploadPreview {
+    pub total_rows: usize,
+    pub to_create: usize,
+    pub to_update: usize,
+    pub skipped: usize,
+    pub rows: Vec<UploadPreviewRow>,
+}
+
+impl UploadPreview {
+    pub fn with_total(total_rows: usize) -> Self {
+        Self {
+            total_rows,
+            ..Self::default()
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        row_number: usize,
+        sku: Option<String>,
+        action: UploadRowAction,
+        message: Option<String>,
+    ) {
+        match action {
+            UploadRowAction::Create => self.to_create += 1,
+            UploadRowAction::Update => self.to_update += 1,
+            UploadRowAction::Skip => self.skipped += 1,
+        }
+        self.rows.push(UploadPreviewRow {
+            row_number,
+            sku,
+            action,
+            message,
+        });
+    }
+}
+
+/// Compact dry-run outcome: counts plus the rows that would be rejected.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UploadPreviewResult {
+    pub would_create: usize,
+    pub would_update: usize,
+    pub rows_with_errors: Vec<UploadRowError>,
+}
+
+impl From<UploadPreview> for UploadPreviewResult {
+    fn from(preview: UploadPreview) -> Self {
+        let rows_with_errors = preview
+            .rows
+            .into_iter()
+            .filter(|row| row.action == UploadRowAction::Skip)
+            .map(|row| UploadRowError {
+                row_number: row.row_number,
+                sku: row.sku,
+                message: row.message.unwrap_or_default(),
+            })
+            .collect();
+
+        Self {
+            would_create: preview.to_create,
+            would_update: preview.to_update,
+            rows_with_errors,
+        }
+    }
+}
+
+impl From<UploadPreview> for UploadReport {
+    fn from(preview: UploadPreview) -> Self {
+        let mut report = UploadReport::with_total(preview.total_rows);
+        report.created = preview.to_create;
+        report.updated = preview.to_update;
+        for row in preview.rows {
+            if row.action == UploadRowAction::Skip {
+                report.push_error(row.row_number, row.sku, row.message.unwrap_or_default());
+            }
+        }
+        report
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DownloadFormat {
     Csv,
@@ -67,6 +185,58 @@ pub struct DownloadFile {
     pub bytes: Vec<u8>,
 }
 
+impl DownloadFile {
+    /// Build a `Content-Disposition` header value with a sanitized file name.
+    pub fn content_disposition(&self) -> String {
+        attachment_disposition(&self.file_name)
+    }
+}
+
+/// Rows rendered while lazily consumed by the response body.
+pub type DownloadChunks = Box<dyn Iterator<Item = Result<Vec<u8>, DownloadError>> + Send>;
+
+/// Download whose body is produced one encoded row at a time.
+///
+/// Used instead of [`DownloadFile`] for exports large enough that buffering
+/// the whole file in memory would be wasteful.
+pub struct StreamDownload {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub chunks: DownloadChunks,
+}
+
+impl StreamDownload {
+    /// Build a `Content-Disposition` header value with a sanitized file name.
+    pub fn content_disposition(&self) -> String {
+        attachment_disposition(&self.file_name)
+    }
+}
+
+fn attachment_disposition(file_name: &str) -> String {
+    format!("attachment; filename=\"{}\"", sanitize_filename(file_name))
+}
+
+/// Fallback file name used when sanitizing leaves nothing usable.
+const DEFAULT_DOWNLOAD_FILE_NAME: &str = "download";
+
+/// Make a file name safe for a quoted `Content-Disposition` parameter.
+///
+/// Control characters, quotes and backslashes are removed so the value can
+/// neither terminate the quoted string nor inject additional header lines.
+pub fn sanitize_filename(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
+        .collect();
+    let sanitized = sanitized.trim();
+
+    if sanitized.is_empty() {
+        DEFAULT_DOWNLOAD_FILE_NAME.to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum DownloadError {
     #[error("invalid download format: {0}")]
@@ -75,6 +245,28 @@ pub enum DownloadError {
     CsvRender,
     #[error("failed to render xlsx")]
     XlsxRender,
+    #[error("unknown export column: {0}")]
+    UnknownColumn(String),
+    #[error("failed to load export rows")]
+    RowLoad,
+}
+
+/// Column selection and layout for a rendered download.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Headers to emit, in order; unlisted columns are dropped. `None` keeps all.
+    pub column_order: Option<Vec<String>>,
+    /// Whether the first row carries the column headers.
+    pub include_header: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            column_order: None,
+            include_header: true,
+        }
+    }
 }
 
 pub fn render_download_file(
@@ -83,13 +275,64 @@ pub fn render_download_file(
     headers: &[&str],
     rows: &[Vec<String>],
 ) -> Result<DownloadFile, DownloadError> {
+    render_download_file_with_options(base_name, format, headers, rows, &ExportOptions::default())
+}
+
+/// Headers and rows after applying [`ExportOptions::column_order`].
+type ProjectedColumns<'h, 'r> = (Cow<'h, [&'h str]>, Cow<'r, [Vec<String>]>);
+
+/// Reorder `headers` and every row to `column_order`, dropping unlisted columns.
+fn project_columns<'h, 'r>(
+    headers: &'h [&'h str],
+    rows: &'r [Vec<String>],
+    column_order: Option<&[String]>,
+) -> Result<ProjectedColumns<'h, 'r>, DownloadError> {
+    let Some(column_order) = column_order else {
+        return Ok((Cow::Borrowed(headers), Cow::Borrowed(rows)));
+    };
+
+    let indices = column_order
+        .iter()
+        .map(|column| {
+            headers
+                .iter()
+                .position(|header| header == column)
+                .ok_or_else(|| DownloadError::UnknownColumn(column.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let projected_headers: Vec<&str> = indices.iter().map(|&idx| headers[idx]).collect();
+    let projected_rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            indices
+                .iter()
+                .map(|&idx| row.get(idx).cloned().unwrap_or_default())
+                .collect()
+        })
+        .collect();
+
+    Ok((Cow::Owned(projected_headers), Cow::Owned(projected_rows)))
+}
+
+pub fn render_download_file_with_options(
+    base_name: &str,
+    format: DownloadFormat,
+    headers: &[&str],
+    rows: &[Vec<String>],
+    options: &ExportOptions,
+) -> Result<DownloadFile, DownloadError> {
+    let (headers, rows) = project_columns(headers, rows, options.column_order.as_deref())?;
+    let header_rows = usize::from(options.include_header);
     match format {
         DownloadFormat::Csv => {
             let mut writer = csv::Writer::from_writer(vec![]);
-            writer
-                .write_record(headers)
-                .map_err(|_| DownloadError::CsvRender)?;
-            for row in rows {
+            if options.include_header {
+                writer
+                    .write_record(headers.iter())
+                    .map_err(|_| DownloadError::CsvRender)?;
+            }
+            for row in rows.iter() {
                 let escaped_row: Vec<String> =
                     row.iter().map(|value| escape_csv_cell(value)).collect();
                 writer
@@ -107,14 +350,16 @@ pub fn render_download_file(
             let mut workbook = rust_xlsxwriter::Workbook::new();
             let worksheet = workbook.add_worksheet();
 
-            for (col_idx, header) in headers.iter().enumerate() {
-                worksheet
-                    .write_string(0, col_idx as u16, *header)
-                    .map_err(|_| DownloadError::XlsxRender)?;
+            if options.include_header {
+                for (col_idx, header) in headers.iter().enumerate() {
+                    worksheet
+                        .write_string(0, col_idx as u16, *header)
+                        .map_err(|_| DownloadError::XlsxRender)?;
+                }
             }
 
             for (row_idx, row) in rows.iter().enumerate() {
-                let sheet_row = (row_idx + 1) as u32;
+                let sheet_row = (row_idx + header_rows) as u32;
                 for (col_idx, value) in row.iter().enumerate() {
                     worksheet
                         .write_string(sheet_row, col_idx as u16, value)
@@ -134,6 +379,197 @@ pub fn render_download_file(
     }
 }
 
+/// Stream a CSV download: the header chunk first, then one chunk per row as `rows` yields it.
+pub fn stream_csv_download<I>(base_name: &str, headers: &[&str], rows: I) -> StreamDownload
+where
+    I: Iterator<Item = Result<Vec<String>, DownloadError>> + Send + 'static,
+{
+    let header = encode_csv_record(headers.iter().map(|header| header.to_string()));
+    let body = rows.map(|row| {
+        row.and_then(|row| encode_csv_record(row.iter().map(|value| escape_csv_cell(value))))
+    });
+
+    StreamDownload {
+        file_name: format!("{base_name}.csv"),
+        content_type: "text/csv; charset=utf-8",
+        chunks: Box::new(std::iter::once(header).chain(body)),
+    }
+}
+
+fn encode_csv_record<I>(values: I) -> Result<Vec<u8>, DownloadError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer
+        .write_record(values)
+        .map_err(|_| DownloadError::CsvRender)?;
+    writer.into_inner().map_err(|_| DownloadError::CsvRender)
+}
+
+/// Render an empty upload file containing only the canonical header row.
+#[tracing::instrument(skip(user), fields(hub_id = user.hub_id))]
+pub fn download_upload_template(
+    target: &str,
+    format: &str,
+    user: &AuthenticatedUser,
+) -> ServiceResult<DownloadFile> {
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let target =
+        UploadTarget::try_from(target).map_err(|err| ServiceError::Form(err.to_string()))?;
+    let format =
+        DownloadFormat::try_from(format).map_err(|err| ServiceError::Form(err.to_string()))?;
+    let base_name = match target {
+        UploadTarget::CrawlerProducts => "products_template",
+        UploadTarget::Benchmarks => "benchmarks_template",
+        UploadTarget::ProductCategories => "product_categories_template",
+        UploadTarget::Categories => "categories_template",
+        UploadTarget::Crawlers => "crawlers_template",
+    };
+
+    render_download_file(base_name, format, expected_headers(target), &[])
+        .map_err(|err| ServiceError::Form(err.to_string()))
+}
+
+/// Session key holding the id of the user's pending import.
+pub const IMPORT_SESSION_KEY: &str = "import_session_id";
+
+/// How long a validated import may wait for confirmation.
+const IMPORT_SESSION_TTL: TimeDelta = TimeDelta::minutes(30);
+
+/// Validated crawler products import awaiting confirmation.
+///
+/// Produced by [`begin_import_session`] and persisted by
+/// [`commit_import_session`]; `upload_report` is the dry-run outcome the user
+/// confirms.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportSession {
+    pub id: Uuid,
+    pub crawler_id: i32,
+    pub mode: UploadMode,
+    pub upload_report: UploadReport,
+    pub parsed_rows: Vec<ParsedUploadRow>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ImportSession {
+    fn is_expired(&self, now: NaiveDateTime) -> bool {
+        now - self.created_at > IMPORT_SESSION_TTL
+    }
+}
+
+/// Server-side holder for pending import sessions.
+///
+/// Parsed rows are far larger than a session cookie allows, so the user's
+/// session only carries the [`ImportSession::id`] stored under
+/// [`IMPORT_SESSION_KEY`] while the rows stay here until committed or expired.
+#[derive(Debug, Default)]
+pub struct ImportSessionStore {
+    sessions: Mutex<HashMap<Uuid, ImportSession>>,
+}
+
+impl ImportSessionStore {
+    /// Store a session, dropping any that have expired, and return its id.
+    pub fn insert(&self, session: ImportSession) -> Uuid {
+        let now = Utc::now().naive_utc();
+        let id = session.id;
+        let mut sessions = self
+            .sessions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        sessions.retain(|_, stored| !stored.is_expired(now));
+        sessions.insert(id, session);
+        id
+    }
+
+    /// Remove and return a session unless it is missing or expired.
+    pub fn take(&self, id: Uuid) -> Option<ImportSession> {
+        let now = Utc::now().naive_utc();
+        let mut sessions = self
+            .sessions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        sessions
+            .remove(&id)
+            .filter(|session| !session.is_expired(now))
+    }
+}
+
+/// Parse and validate a crawler products upload without writing it.
+///
+/// The returned session holds the parsed rows and the dry-run report; pass it
+/// to [`commit_import_session`] once the user confirms.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
+pub fn begin_import_session<R>(
+    crawler_id: i32,
+    form: &mut UploadImportForm,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ImportSession>
+where
+    R: CrawlerReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
+    let typed_crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+
+    let parsed = parse_upload(form, UploadTarget::CrawlerProducts)
+        .map_err(|err| ServiceError::Form(err.to_string()))?;
+    let preview = classify_upload(&parsed, hub_id, typed_crawler_id, repo)?;
+
+    Ok(ImportSession {
+        id: Uuid::new_v4(),
+        crawler_id,
+        mode: parsed.mode,
+        upload_report: preview.into(),
+        parsed_rows: parsed.rows,
+        created_at: Utc::now().naive_utc(),
+    })
+}
+
+/// Persist a confirmed import session using SKU upsert semantics.
+///
+/// The session must belong to `crawler_id`, and the crawler must still belong
+/// to the user's hub.
+#[tracing::instrument(skip(session, user, repo), fields(hub_id = user.hub_id))]
+pub fn commit_import_session<R>(
+    crawler_id: i32,
+    session: ImportSession,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<UploadReport>
+where
+    R: CrawlerReader + ProductReader + ProductWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
+    if session.crawler_id != crawler_id {
+        return Err(ServiceError::Form(
+            "Import session belongs to another crawler".to_string(),
+        ));
+    }
+    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+    match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(err) => {
+            tracing::error!("Failed to load crawler for import commit: {err}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    apply_crawler_upload(session.mode, session.parsed_rows, crawler_id, repo)
+}
+
 fn escape_csv_cell(value: &str) -> String {
     let mut chars = value.chars();
     match chars.next() {
@@ -144,7 +580,60 @@ fn escape_csv_cell(value: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{DownloadFormat, render_download_file};
+    use super::{
+        DownloadError, DownloadFormat, ExportOptions, IMPORT_SESSION_TTL, ImportSession,
+        ImportSessionStore, UploadReport, download_upload_template, render_download_file,
+        render_download_file_with_options, sanitize_filename, stream_csv_download,
+    };
+    use crate::SERVICE_ACCESS_ROLE;
+    use crate::forms::import_export::UploadMode;
+    use chrono::Utc;
+    use pushkind_common::domain::auth::AuthenticatedUser;
+    use pushkind_common::services::errors::ServiceError;
+    use uuid::Uuid;
+
+    fn sample_session() -> ImportSession {
+        ImportSession {
+            id: Uuid::new_v4(),
+            crawler_id: 1,
+            mode: UploadMode::Full,
+            upload_report: UploadReport::with_total(0),
+            parsed_rows: vec![],
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    fn sample_user() -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "1".into(),
+            email: "test@example.com".into(),
+            hub_id: 1,
+            name: "Test".into(),
+            roles: vec![SERVICE_ACCESS_ROLE.into()],
+            exp: 0,
+        }
+    }
+
+    #[test]
+    fn csv_template_contains_only_canonical_headers() {
+        let file = download_upload_template("products", "csv", &sample_user())
+            .expect("template render should succeed");
+
+        let csv_output = String::from_utf8(file.bytes).expect("csv output should be utf-8");
+        let mut lines = csv_output.lines();
+        assert_eq!(
+            lines.next(),
+            Some("sku,name,category,units,price,amount,description,url")
+        );
+        assert_eq!(lines.next(), None);
+        assert_eq!(file.file_name, "products_template.csv");
+    }
+
+    #[test]
+    fn template_rejects_unknown_target() {
+        let err = download_upload_template("widgets", "csv", &sample_user()).unwrap_err();
+        assert!(matches!(err, ServiceError::Form(_)));
+    }
 
     #[test]
     fn csv_export_escapes_formula_prefixed_cells() {
@@ -178,4 +667,121 @@ mod tests {
         assert!(csv_output.contains("SKU-123"));
         assert!(csv_output.contains("https://example.com"));
     }
+
+    #[test]
+    fn streamed_csv_matches_buffered_csv() {
+        let headers = ["sku", "url"];
+        let rows = vec![
+            vec!["SKU-1".to_string(), "=cmd".to_string()],
+            vec!["SKU-2".to_string(), "a,b".to_string()],
+        ];
+        let buffered = render_download_file("products", DownloadFormat::Csv, &headers, &rows)
+            .expect("csv render should succeed");
+
+        let streamed = stream_csv_download("products", &headers, rows.into_iter().map(Ok));
+        assert_eq!(streamed.file_name, "products.csv");
+        assert_eq!(streamed.content_type, buffered.content_type);
+        assert_eq!(
+            streamed.content_disposition(),
+            buffered.content_disposition()
+        );
+
+        let chunks = streamed
+            .chunks
+            .collect::<Result<Vec<_>, _>>()
+            .expect("stream should render");
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), buffered.bytes);
+    }
+
+    #[test]
+    fn sanitize_filename_strips_quotes_and_newlines() {
+        assert_eq!(
+            sanitize_filename("bench\"mark\r\nSet-Cookie: x.csv"),
+            "benchmarkSet-Cookie: x.csv"
+        );
+    }
+
+    #[test]
+    fn sanitize_filename_falls_back_to_default() {
+        assert_eq!(sanitize_filename("\"\n\t "), "download");
+    }
+
+    #[test]
+    fn import_session_store_hands_out_a_session_once() {
+        let store = ImportSessionStore::default();
+        let session = sample_session();
+        let id = store.insert(session.clone());
+
+        assert_eq!(store.take(id), Some(session));
+        assert_eq!(store.take(id), None);
+    }
+
+    #[test]
+    fn import_session_store_drops_expired_sessions() {
+        let store = ImportSessionStore::default();
+        let mut session = sample_session();
+        session.created_at -= IMPORT_SESSION_TTL + IMPORT_SESSION_TTL;
+        let id = store.insert(session);
+
+        assert_eq!(store.take(id), None);
+    }
+
+    #[test]
+    fn export_options_reorder_and_drop_columns() {
+        let options = ExportOptions {
+            column_order: Some(vec!["price".to_string(), "sku".to_string()]),
+            ..ExportOptions::default()
+        };
+        let file = render_download_file_with_options(
+            "products",
+            DownloadFormat::Csv,
+            &["sku", "description", "price"],
+            &[vec![
+                "SKU-1".to_string(),
+                "secret".to_string(),
+                "9.5".to_string(),
+            ]],
+            &options,
+        )
+        .expect("csv render should succeed");
+
+        let csv_output = String::from_utf8(file.bytes).expect("csv output should be utf-8");
+        assert_eq!(csv_output, "price,sku\n9.5,SKU-1\n");
+    }
+
+    #[test]
+    fn export_options_can_omit_header_row() {
+        let options = ExportOptions {
+            include_header: false,
+            ..ExportOptions::default()
+        };
+        let file = render_download_file_with_options(
+            "products",
+            DownloadFormat::Csv,
+            &["sku"],
+            &[vec!["SKU-1".to_string()]],
+            &options,
+        )
+        .expect("csv render should succeed");
+
+        assert_eq!(file.bytes, b"SKU-1\n");
+    }
+
+    #[test]
+    fn export_options_reject_unknown_columns() {
+        let options = ExportOptions {
+            column_order: Some(vec!["missing".to_string()]),
+            ..ExportOptions::default()
+        };
+        let result = render_download_file_with_options(
+            "products",
+            DownloadFormat::Csv,
+            &["sku"],
+            &[],
+            &options,
+        );
+
+        assert!(matches!(result, Err(DownloadError::UnknownColumn(column)) if column == "missing"));
+    }
 }
@@ -1,22 +1,31 @@
+use chrono::{NaiveDateTime, TimeDelta, Utc};
 use pushkind_common::domain::auth::AuthenticatedUser;
 use pushkind_common::routes::check_role;
 
 use crate::SERVICE_ACCESS_ROLE;
-use crate::domain::crawler::Crawler;
-use crate::domain::types::HubId;
-use crate::repository::CrawlerReader;
+use crate::domain::types::{HubId, ProductCount};
+use crate::dto::main::CrawlerDto;
+use crate::repository::{CrawlerListOrder, CrawlerListQuery, CrawlerReader, ProductReader};
 
 use super::{ServiceError, ServiceResult};
 
 /// Core business logic for rendering the index page.
 ///
 /// The function validates that the user has the `parser` role and fetches
-/// all crawlers associated with the user's hub. Any repository errors are
+/// all crawlers associated with the user's hub, most active first. Stored
+/// `num_products` values are replaced with live counts from a single grouped
+/// query and the list is re-sorted by them. Crawlers not updated within
+/// `staleness` are flagged as stale. Any repository errors are
 /// translated into `ServiceError` so that the HTTP route can remain a thin
 /// wrapper.
-pub fn show_index<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<Crawler>>
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn show_index<R>(
+    staleness: TimeDelta,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<CrawlerDto>>
 where
-    R: CrawlerReader,
+    R: CrawlerReader + ProductReader,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
@@ -25,26 +34,60 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    match repo.list_crawlers(hub_id) {
-        Ok(crawlers) => Ok(crawlers),
+    let query = CrawlerListQuery::new(hub_id).order(CrawlerListOrder::ByNumProducts);
+    let mut crawlers = match repo.list_crawlers(query) {
+        Ok((_, crawlers)) => crawlers,
         Err(e) => {
-            log::error!("Failed to list crawlers: {e}");
-            Err(ServiceError::Internal)
+            tracing::error!("Failed to list crawlers: {e}");
+            return Err(ServiceError::Internal);
         }
+    };
+
+    let counts = match repo.count_products_by_crawler(hub_id) {
+        Ok(counts) => counts,
+        Err(e) => {
+            tracing::error!("Failed to count products by crawler: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    for crawler in &mut crawlers {
+        let count = counts.get(&crawler.id.get()).copied().unwrap_or(0);
+        crawler.num_products = i32::try_from(count)
+            .ok()
+            .and_then(|count| ProductCount::new(count).ok())
+            .unwrap_or(crawler.num_products);
     }
+    crawlers.sort_by_key(|crawler| std::cmp::Reverse(crawler.num_products.get()));
+
+    let now = Utc::now().naive_utc();
+    Ok(crawlers
+        .into_iter()
+        .map(|crawler| CrawlerDto {
+            is_stale: is_stale(crawler.updated_at, now, staleness),
+            crawler,
+        })
+        .collect())
+}
+
+/// A crawler is stale once strictly more than `staleness` has passed since its update.
+fn is_stale(updated_at: NaiveDateTime, now: NaiveDateTime, staleness: TimeDelta) -> bool {
+    now - updated_at > staleness
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::domain::crawler::Crawler;
+    use crate::domain::product::Product;
     use crate::domain::types::{
-        CrawlerId, CrawlerName, CrawlerSelectorValue, CrawlerUrl, HubId, ProductCount,
+        CategoryAssignmentSource, CrawlerId, CrawlerName, CrawlerSelectorValue, CrawlerUrl, HubId,
+        ProductCount, ProductId, ProductName, ProductPrice, ProductSku,
     };
     use crate::repository::test::TestRepository;
     use chrono::DateTime;
@@ -78,9 +121,90 @@ mod tests {
         let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
         let user = sample_user();
 
-        let result = show_index(&user, &repo).unwrap();
+        let result = show_index(TimeDelta::hours(24), &user, &repo).unwrap();
 
         assert_eq!(result.len(), 1);
-        assert_eq!(result[0].id, 1);
+        assert_eq!(result[0].crawler.id, 1);
+    }
+
+    #[test]
+    fn replaces_stale_product_counts_with_live_counts() {
+        let mut crawler = sample_crawler();
+        crawler.num_products = ProductCount::new(42).unwrap();
+        let repo = TestRepository::new(vec![crawler], vec![], vec![]);
+        let user = sample_user();
+
+        let result = show_index(TimeDelta::hours(24), &user, &repo).unwrap();
+
+        assert_eq!(result[0].crawler.num_products.get(), 0);
+    }
+
+    #[test]
+    fn orders_crawlers_by_live_product_count_descending() {
+        let first = sample_crawler();
+        let mut second = sample_crawler();
+        second.id = CrawlerId::new(2).unwrap();
+        let product = Product {
+            id: ProductId::new(1).unwrap(),
+            crawler_id: second.id,
+            name: ProductName::new("Product").unwrap(),
+            sku: ProductSku::new("SKU").unwrap(),
+            category: None,
+            associated_category: None,
+            units: None,
+            price: ProductPrice::new(1.0).unwrap(),
+            amount: None,
+            description: None,
+            url: None,
+            created_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            embedding: None,
+            category_id: None,
+            category_assignment_source: CategoryAssignmentSource::Automatic,
+            reviewed_at: None,
+            images: vec![],
+        };
+        let repo = TestRepository::new(vec![first, second], vec![product], vec![]);
+        let user = sample_user();
+
+        let result = show_index(TimeDelta::hours(24), &user, &repo).unwrap();
+
+        assert_eq!(result[0].crawler.id, 2);
+        assert_eq!(result[1].crawler.id, 1);
+    }
+
+    #[test]
+    fn flags_crawlers_updated_just_past_the_staleness_threshold() {
+        let threshold = TimeDelta::hours(24);
+        let now = Utc::now().naive_utc();
+        let mut stale = sample_crawler();
+        stale.updated_at = now - threshold - TimeDelta::minutes(1);
+        let mut fresh = sample_crawler();
+        fresh.id = CrawlerId::new(2).unwrap();
+        fresh.updated_at = now - threshold + TimeDelta::minutes(1);
+        let repo = TestRepository::new(vec![stale, fresh], vec![], vec![]);
+        let user = sample_user();
+
+        let result = show_index(threshold, &user, &repo).unwrap();
+
+        let flags = result
+            .iter()
+            .map(|row| (row.crawler.id.get(), row.is_stale))
+            .collect::<Vec<_>>();
+        assert!(flags.contains(&(1, true)));
+        assert!(flags.contains(&(2, false)));
+    }
+
+    #[test]
+    fn staleness_boundary_is_exclusive() {
+        let now = DateTime::from_timestamp(100_000, 0).unwrap().naive_utc();
+        let threshold = TimeDelta::hours(1);
+
+        assert!(!is_stale(now - threshold, now, threshold));
+        assert!(is_stale(
+            now - threshold - TimeDelta::seconds(1),
+            now,
+            threshold
+        ));
     }
 }
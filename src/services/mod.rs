@@ -1,8 +1,15 @@
 pub use pushkind_common::services::errors::{ServiceError, ServiceResult};
 
 pub mod api;
+pub mod audit;
 pub mod benchmarks;
 pub mod categories;
+pub mod crawlers;
 pub mod import_export;
 pub mod main;
 pub mod products;
+pub mod rate_limit;
+pub mod retry;
+pub mod search;
+pub mod similarity;
+pub mod statistics;
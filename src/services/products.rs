@@ -1,20 +1,35 @@
+use std::time::Duration;
+
 use chrono::Utc;
 use pushkind_common::domain::auth::AuthenticatedUser;
-use pushkind_common::pagination::{DEFAULT_ITEMS_PER_PAGE, Paginated};
+use pushkind_common::repository::errors::RepositoryError;
 use pushkind_common::routes::check_role;
-use pushkind_common::zmq::ZmqSenderExt;
+use pushkind_common::zmq::ZmqSenderTrait;
 
 use crate::SERVICE_ACCESS_ROLE;
 use crate::domain::product::NewProduct;
-use crate::domain::types::{CrawlerId, HubId};
+use crate::domain::types::{CategoryName, CrawlerId, CrawlerName, HubId, ProductId};
 use crate::domain::zmq::{CrawlerSelector, ZMQCrawlerMessage};
 use crate::domain::{crawler::Crawler, product::Product};
-use crate::forms::import_export::{UploadImportForm, UploadMode, UploadTarget, parse_upload};
-use crate::repository::{CrawlerReader, ProductListQuery, ProductReader, ProductWriter};
+use crate::forms::import_export::{
+    ParsedUpload, ParsedUploadRow, UploadImportForm, UploadMode, UploadTarget, parse_upload,
+};
+use crate::forms::products::{
+    MoveProductsFormPayload, ProductImagesFormPayload, UpdateProductFormPayload,
+};
+use crate::models::config::PriceFormat;
+use crate::pagination::{DEFAULT_ITEMS_PER_PAGE, Paginated};
+use crate::repository::{
+    CrawlerListQuery, CrawlerReader, ProductListQuery, ProductReader, ProductReassignment,
+    ProductWriter,
+};
 use crate::services::import_export::{
-    DownloadFile, DownloadFormat, UploadReport, render_download_file,
+    DownloadError, DownloadFile, DownloadFormat, StreamDownload, UploadPreview,
+    UploadPreviewResult, UploadReport, UploadRowAction, render_download_file, stream_csv_download,
 };
 
+use super::rate_limit::{RateLimiter, check_dispatch_rate};
+use super::retry::{SendRetryPolicy, send_json_with_retry};
 use super::{ServiceError, ServiceResult};
 
 fn parse_required_f64(value: Option<&String>, field: &str) -> Result<f64, String> {
@@ -100,15 +115,26 @@ fn build_product_from_row(
     })
 }
 
+/// Optional narrowing applied to the crawler products page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProductFilters {
+    /// Skip products already marked as reviewed.
+    pub only_unreviewed: bool,
+    /// Skip products associated with any benchmark.
+    pub only_unassociated: bool,
+}
+
 /// Core business logic for rendering the products page.
 ///
 /// Validates that the user has the `parser` role, ensures the crawler belongs
-/// to the user's hub, and fetches paginated products for the crawler.
-/// Repository errors are converted into `ServiceError` variants so that the
+/// to the user's hub, and fetches paginated products for the crawler narrowed
+/// by `filters`. Repository errors are converted into `ServiceError` variants so that the
 /// HTTP route can remain a thin wrapper.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
 pub fn show_products<R>(
     crawler_id: i32,
     page: usize,
+    filters: ProductFilters,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<(Crawler, Paginated<Product>)>
@@ -122,7 +148,7 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
@@ -136,21 +162,25 @@ where
         Ok(Some(crawler)) => crawler,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get crawler: {e}");
+            tracing::error!("Failed to get crawler: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let products = match repo.list_products(
-        ProductListQuery::default()
-            .crawler(crawler_id)
-            .paginate(page, DEFAULT_ITEMS_PER_PAGE),
-    ) {
-        Ok((total, products)) => {
-            Paginated::new(products, page, total.div_ceil(DEFAULT_ITEMS_PER_PAGE))
-        }
+    let mut query = ProductListQuery::default()
+        .crawler(crawler_id)
+        .paginate(page, DEFAULT_ITEMS_PER_PAGE);
+    if filters.only_unreviewed {
+        query = query.only_unreviewed();
+    }
+    if filters.only_unassociated {
+        query = query.only_unassociated();
+    }
+
+    let products = match repo.list_products(query) {
+        Ok((total, products)) => Paginated::new(products, page, total, DEFAULT_ITEMS_PER_PAGE),
         Err(e) => {
-            log::error!("Failed to list products: {e}");
+            tracing::error!("Failed to list products: {e}");
             return Err(ServiceError::Internal);
         }
     };
@@ -158,12 +188,16 @@ where
     Ok((crawler, products))
 }
 
-pub fn download_crawler_products<R>(
+/// Count a hub crawler's products per canonical category.
+///
+/// Uncategorized products form a single `None` bucket. Unknown crawlers and
+/// crawlers of other hubs yield [`ServiceError::NotFound`].
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn list_crawler_categories<R>(
     crawler_id: i32,
-    format: &str,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<DownloadFile>
+) -> ServiceResult<Vec<(Option<CategoryName>, usize)>>
 where
     R: CrawlerReader + ProductReader,
 {
@@ -171,136 +205,199 @@ where
         return Err(ServiceError::Unauthorized);
     }
 
-    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
     let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
-    let format =
-        DownloadFormat::try_from(format).map_err(|err| ServiceError::Form(err.to_string()))?;
 
     match repo.get_crawler_by_id(crawler_id, hub_id) {
         Ok(Some(_)) => {}
         Ok(None) => return Err(ServiceError::NotFound),
-        Err(_) => return Err(ServiceError::Internal),
+        Err(e) => {
+            tracing::error!("Failed to get crawler: {e}");
+            return Err(ServiceError::Internal);
+        }
     }
 
-    let products = repo
-        .list_products(ProductListQuery::default().crawler(crawler_id))
-        .map_err(|_| ServiceError::Internal)?
-        .1;
-
-    let rows = products
-        .into_iter()
-        .map(|p| {
-            vec![
-                p.sku.as_str().to_string(),
-                p.name.as_str().to_string(),
-                p.category
-                    .as_ref()
-                    .map(|v| v.as_str().to_string())
-                    .unwrap_or_default(),
-                p.units
-                    .as_ref()
-                    .map(|v| v.as_str().to_string())
-                    .unwrap_or_default(),
-                p.price.get().to_string(),
-                p.amount.map(|v| v.get().to_string()).unwrap_or_default(),
-                p.description
-                    .as_ref()
-                    .map(|v| v.as_str().to_string())
-                    .unwrap_or_default(),
-                p.url
-                    .as_ref()
-                    .map(|v| v.as_str().to_string())
-                    .unwrap_or_default(),
-            ]
+    repo.list_product_categories(crawler_id, hub_id)
+        .map_err(|e| {
+            tracing::error!("Failed to list crawler categories: {e}");
+            ServiceError::Internal
         })
-        .collect::<Vec<_>>();
+}
 
-    render_download_file(
-        &format!("crawler-{}-products", crawler_id.get()),
-        format,
-        &[
-            "sku",
-            "name",
-            "category",
-            "units",
-            "price",
-            "amount",
-            "description",
-            "url",
-        ],
-        &rows,
-    )
-    .map_err(|err| ServiceError::Form(err.to_string()))
+/// Marks a crawler product as reviewed.
+///
+/// The product must belong to the given crawler and the crawler must belong
+/// to the user's hub. Returns `Ok(true)` when the flag was stored and
+/// `Ok(false)` if the repository returned an error.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn mark_product_reviewed<R>(
+    crawler_id: i32,
+    product_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<bool>
+where
+    R: CrawlerReader + ProductReader + ProductWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+    let product_id = ProductId::new(product_id).map_err(|_| ServiceError::NotFound)?;
+
+    match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    match repo.get_product_by_id(product_id) {
+        Ok(Some(product)) if product.crawler_id == crawler_id => {}
+        Ok(_) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get product: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    match repo.mark_reviewed(product_id, hub_id) {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            tracing::error!("Failed to mark product as reviewed: {e}");
+            Ok(false)
+        }
+    }
 }
 
-/// Starts crawling for the specified crawler.
+/// Replaces the images of a crawler product.
 ///
-/// Validates the `parser` role, ensures the crawler belongs to the user's hub
-/// and sends a ZMQ message to trigger crawling. Returns `Ok(true)` if the
-/// message was sent successfully, `Ok(false)` if sending failed, or an error if
-/// the crawler was not found or a repository error occurred.
-pub async fn crawl_crawler<R, S>(
+/// The product must belong to the given crawler and the crawler must belong
+/// to the user's hub. Returns `Ok(true)` when the images were stored and
+/// `Ok(false)` if the repository returned an error.
+#[tracing::instrument(skip(payload, user, repo), fields(hub_id = user.hub_id))]
+pub fn set_product_images<R>(
     crawler_id: i32,
+    product_id: i32,
+    payload: ProductImagesFormPayload,
     user: &AuthenticatedUser,
     repo: &R,
-    sender: &S,
 ) -> ServiceResult<bool>
 where
-    R: CrawlerReader,
-    S: ZmqSenderExt + ?Sized,
+    R: CrawlerReader + ProductReader + ProductWriter,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
     }
 
-    let hub_id = match HubId::new(user.hub_id) {
-        Ok(hub_id) => hub_id,
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+    let product_id = ProductId::new(product_id).map_err(|_| ServiceError::NotFound)?;
+
+    match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Failed to get crawler: {e}");
             return Err(ServiceError::Internal);
         }
-    };
+    }
 
-    let crawler_id = match CrawlerId::new(crawler_id) {
-        Ok(crawler_id) => crawler_id,
-        Err(_) => return Err(ServiceError::NotFound),
-    };
+    match repo.get_product_by_id(product_id) {
+        Ok(Some(product)) if product.crawler_id == crawler_id => {}
+        Ok(_) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get product: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
 
-    let crawler = match repo.get_crawler_by_id(crawler_id, hub_id) {
-        Ok(Some(crawler)) => crawler,
+    match repo.set_product_images(product_id, &payload.images) {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            tracing::error!("Failed to set product images: {e}");
+            Ok(false)
+        }
+    }
+}
+
+/// Soft-deletes a crawler product and unlinks it from its benchmarks.
+///
+/// The product's crawler must belong to the user's hub. The benchmark
+/// associations are removed in the same transaction as the soft delete, so
+/// matching results never point at a hidden product. Returns `Ok(true)` when
+/// the product was deleted and `Ok(false)` if the repository returned an error.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn delete_product<R>(product_id: i32, user: &AuthenticatedUser, repo: &R) -> ServiceResult<bool>
+where
+    R: CrawlerReader + ProductReader + ProductWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    let product_id = ProductId::new(product_id).map_err(|_| ServiceError::NotFound)?;
+
+    let product = match repo.get_product_by_id(product_id) {
+        Ok(Some(product)) => product,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get crawler by id: {e}");
+            tracing::error!("Failed to get product: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let message = ZMQCrawlerMessage::Crawler(CrawlerSelector::Selector(crawler.selector));
-    match sender.send_json(&message).await {
+    match repo.get_crawler_by_id(product.crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler: {e}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    match repo.soft_delete_product(product_id, hub_id) {
+        Ok(0) => Err(ServiceError::NotFound),
         Ok(_) => Ok(true),
-        Err(_) => {
-            log::error!("Failed to send ZMQ message");
+        Err(e) => {
+            tracing::error!("Failed to delete product: {e}");
             Ok(false)
         }
     }
 }
 
-/// Updates prices for all products of the specified crawler.
+/// Lists the soft-deleted products of a hub crawler.
 ///
-/// Performs the same validations as [`crawl_crawler`] but also fetches all
-/// product URLs for the crawler before sending a ZMQ message. Returns
-/// `Ok(true)` if the message was sent successfully, `Ok(false)` if sending
-/// failed, or an error if the crawler was not found or a repository error
-/// occurred.
-pub async fn update_crawler_prices<R, S>(
+/// Unknown crawlers and crawlers of other hubs yield [`ServiceError::NotFound`].
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn show_deleted_products<R>(
     crawler_id: i32,
     user: &AuthenticatedUser,
     repo: &R,
-    sender: &S,
-) -> ServiceResult<bool>
+) -> ServiceResult<(Crawler, Vec<Product>)>
 where
     R: CrawlerReader + ProductReader,
-    S: ZmqSenderExt + ?Sized,
 {
     if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
         return Err(ServiceError::Unauthorized);
@@ -309,7 +406,7 @@ where
     let hub_id = match HubId::new(user.hub_id) {
         Ok(hub_id) => hub_id,
         Err(e) => {
-            log::error!("Invalid hub id in user context: {e}");
+            tracing::error!("Invalid hub id in user context: {e}");
             return Err(ServiceError::Internal);
         }
     };
@@ -323,46 +420,71 @@ where
         Ok(Some(crawler)) => crawler,
         Ok(None) => return Err(ServiceError::NotFound),
         Err(e) => {
-            log::error!("Failed to get crawler by id: {e}");
+            tracing::error!("Failed to get crawler: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let products = match repo.list_products(ProductListQuery::default().crawler(crawler_id)) {
-        Ok((_total, products)) => products,
+    let products = match repo.list_deleted_products(crawler_id) {
+        Ok(products) => products,
         Err(e) => {
-            log::error!("Failed to get products: {e}");
+            tracing::error!("Failed to list deleted products: {e}");
             return Err(ServiceError::Internal);
         }
     };
 
-    let urls = products
-        .into_iter()
-        .filter_map(|p| p.url)
-        .collect::<Vec<_>>();
-    if urls.is_empty() {
-        return Ok(false);
+    Ok((crawler, products))
+}
+
+/// Restores a soft-deleted product of the user's hub.
+///
+/// Benchmark associations removed on delete are not recreated; the product
+/// shows up again in listings and is picked up by the next matching run.
+/// Returns `Ok(true)` when the product was restored and `Ok(false)` if the
+/// repository returned an error.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn restore_product<R>(
+    product_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<bool>
+where
+    R: ProductWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
     }
 
-    let message =
-        ZMQCrawlerMessage::Crawler(CrawlerSelector::SelectorProducts((crawler.selector, urls)));
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
 
-    match sender.send_json(&message).await {
+    let product_id = ProductId::new(product_id).map_err(|_| ServiceError::NotFound)?;
+
+    match repo.restore_product(product_id, hub_id) {
+        Ok(0) => Err(ServiceError::NotFound),
         Ok(_) => Ok(true),
-        Err(_) => {
-            log::error!("Failed to send ZMQ message");
+        Err(e) => {
+            tracing::error!("Failed to restore product: {e}");
             Ok(false)
         }
     }
 }
 
-/// Upload crawler products using format/mode-aware import parser and SKU upsert semantics.
-pub fn upload_crawler_products<R>(
-    crawler_id: i32,
-    form: &mut UploadImportForm,
+/// Applies a manual partial edit to a crawler product.
+///
+/// Only fields present in the payload are changed; the remaining product data
+/// is preserved. The product's crawler must belong to the user's hub. Returns
+/// `Ok(true)` when the product was updated and `Ok(false)` if the repository
+/// returned an error.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn update_product<R>(
+    product_id: i32,
+    payload: UpdateProductFormPayload,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<UploadReport>
+) -> ServiceResult<bool>
 where
     R: CrawlerReader + ProductReader + ProductWriter,
 {
@@ -370,181 +492,936 @@ where
         return Err(ServiceError::Unauthorized);
     }
 
-    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
-    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
-    match repo.get_crawler_by_id(crawler_id, hub_id) {
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    let product_id = ProductId::new(product_id).map_err(|_| ServiceError::NotFound)?;
+
+    let product = match repo.get_product_by_id(product_id) {
+        Ok(Some(product)) => product,
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get product: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    match repo.get_crawler_by_id(product.crawler_id, hub_id) {
         Ok(Some(_)) => {}
         Ok(None) => return Err(ServiceError::NotFound),
-        Err(err) => {
-            log::error!("Failed to load crawler for upload: {err}");
+        Err(e) => {
+            tracing::error!("Failed to get crawler: {e}");
             return Err(ServiceError::Internal);
         }
     }
 
-    let parsed = parse_upload(form, UploadTarget::CrawlerProducts)
-        .map_err(|err| ServiceError::Form(err.to_string()))?;
-    apply_crawler_upload(parsed, crawler_id, repo)
+    let updated = payload.apply_to(&product);
+
+    match repo.update_product(product.id, &updated) {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            tracing::error!("Failed to update product: {e}");
+            Ok(false)
+        }
+    }
 }
 
-fn apply_crawler_upload<R>(
-    parsed: crate::forms::import_export::ParsedUpload,
-    crawler_id: CrawlerId,
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn download_crawler_products<R>(
+    crawler_id: i32,
+    format: &str,
+    price_format: &PriceFormat,
+    user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<UploadReport>
+) -> ServiceResult<DownloadFile>
 where
-    R: ProductReader + ProductWriter,
+    R: CrawlerReader + ProductReader,
 {
-    let mut report = UploadReport::with_total(parsed.rows.len());
-    let mut seen_skus = std::collections::HashSet::new();
-
-    for row in parsed.rows {
-        let sku_value = row
-            .values
-            .get("sku")
-            .cloned()
-            .unwrap_or_default()
-            .trim()
-            .to_string();
-        if sku_value.is_empty() {
-            report.push_error(row.row_number, None, "Missing sku");
-            continue;
-        }
-        if !seen_skus.insert(sku_value.clone()) {
-            report.push_error(
-                row.row_number,
-                Some(sku_value),
-                "Duplicate sku in uploaded file",
-            );
-            continue;
-        }
+    render_crawler_products(
+        crawler_id,
+        format,
+        |id| format!("crawler-{id}-products"),
+        price_format,
+        user,
+        repo,
+    )
+}
 
-        let sku = match crate::domain::types::ProductSku::new(sku_value.clone()) {
-            Ok(sku) => sku,
-            Err(err) => {
-                report.push_error(row.row_number, Some(sku_value), err.to_string());
-                continue;
-            }
-        };
+/// Export a crawler's product catalogue as `crawler_{id}_products.{ext}`.
+///
+/// Verifies the crawler belongs to the user's hub; unknown crawlers yield
+/// [`ServiceError::NotFound`] and unsupported formats [`ServiceError::Form`].
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn export_products<R>(
+    crawler_id: i32,
+    format: &str,
+    price_format: &PriceFormat,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<DownloadFile>
+where
+    R: CrawlerReader + ProductReader,
+{
+    render_crawler_products(
+        crawler_id,
+        format,
+        |id| format!("crawler_{id}_products"),
+        price_format,
+        user,
+        repo,
+    )
+}
 
-        let existing = match repo.list_products_by_crawler_and_sku(crawler_id, &sku) {
-            Ok(items) => items,
-            Err(err) => {
-                log::error!("Failed to lookup products by sku: {err}");
-                return Err(ServiceError::Internal);
-            }
-        };
-        if existing.len() > 1 {
-            report.push_error(
-                row.row_number,
-                Some(sku_value),
-                "Multiple existing products found for sku",
-            );
-            continue;
-        }
+fn render_crawler_products<R>(
+    crawler_id: i32,
+    format: &str,
+    base_name: fn(i32) -> String,
+    price_format: &PriceFormat,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<DownloadFile>
+where
+    R: CrawlerReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
 
-        let mut merged = row.values.clone();
-        if parsed.mode == UploadMode::Partial
-            && let Some(current) = existing.first()
-        {
-            merged
-                .entry("name".to_string())
-                .or_insert_with(|| current.name.as_str().to_string());
-            merged
-                .entry("price".to_string())
-                .or_insert_with(|| current.price.get().to_string());
-            if !merged.contains_key("category") {
-                merged.insert(
-                    "category".to_string(),
-                    current
-                        .category
-                        .as_ref()
-                        .map(|v| v.as_str().to_string())
-                        .unwrap_or_default(),
-                );
-            }
-            if !merged.contains_key("units") {
-                merged.insert(
-                    "units".to_string(),
-                    current
-                        .units
-                        .as_ref()
-                        .map(|v| v.as_str().to_string())
-                        .unwrap_or_default(),
-                );
-            }
-            if !merged.contains_key("amount") {
-                merged.insert(
-                    "amount".to_string(),
-                    current
-                        .amount
-                        .map(|v| v.get().to_string())
-                        .unwrap_or_default(),
-                );
-            }
-            if !merged.contains_key("description") {
-                merged.insert(
-                    "description".to_string(),
-                    current
-                        .description
-                        .as_ref()
-                        .map(|v| v.as_str().to_string())
-                        .unwrap_or_default(),
-                );
-            }
-            if !merged.contains_key("url") {
-                merged.insert(
-                    "url".to_string(),
-                    current
-                        .url
-                        .as_ref()
-                        .map(|v| v.as_str().to_string())
-                        .unwrap_or_default(),
-                );
-            }
-        }
+    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
+    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+    let format =
+        DownloadFormat::try_from(format).map_err(|err| ServiceError::Form(err.to_string()))?;
 
-        let new_product = match build_product_from_row(&merged, crawler_id) {
-            Ok(item) => item,
-            Err(err) => {
-                report.push_error(row.row_number, Some(sku_value), err);
-                continue;
-            }
-        };
+    match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(_) => return Err(ServiceError::Internal),
+    }
 
-        if let Some(current) = existing.first() {
-            match repo.update_product(current.id, &new_product) {
-                Ok(_) => report.updated += 1,
-                Err(err) => {
-                    log::error!("Failed to update product: {err}");
-                    report.push_error(row.row_number, Some(sku_value), "Failed to update product");
-                }
-            }
+    let products = repo
+        .list_products(ProductListQuery::default().crawler(crawler_id))
+        .map_err(|_| ServiceError::Internal)?
+        .1;
+
+    let rows = products
+        .into_iter()
+        .map(|product| product_download_row(product, price_format))
+        .collect::<Vec<_>>();
+
+    render_download_file(
+        &base_name(crawler_id.get()),
+        format,
+        &PRODUCT_DOWNLOAD_HEADERS,
+        &rows,
+    )
+    .map_err(|err| ServiceError::Form(err.to_string()))
+}
+
+/// Stream a crawler's products as CSV without buffering the whole file.
+///
+/// Rows are loaded from the repository in batches as the response body is
+/// consumed and are ordered by product id.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn stream_crawler_products_csv<R>(
+    crawler_id: i32,
+    price_format: &PriceFormat,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<StreamDownload>
+where
+    R: CrawlerReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
+    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+
+    match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(_) => return Err(ServiceError::Internal),
+    }
+
+    let products = repo
+        .stream_products(crawler_id, PRODUCT_STREAM_BATCH_SIZE)
+        .map_err(|_| ServiceError::Internal)?;
+    let price_format = price_format.clone();
+    let rows = products.map(move |product| {
+        product
+            .map(|product| product_download_row(product, &price_format))
+            .map_err(|err| {
+                tracing::error!("Failed to load products for streamed download: {err}");
+                DownloadError::RowLoad
+            })
+    });
+
+    Ok(stream_csv_download(
+        &format!("crawler-{}-products", crawler_id.get()),
+        &PRODUCT_DOWNLOAD_HEADERS,
+        rows,
+    ))
+}
+
+/// Column headers of crawler product downloads.
+const PRODUCT_DOWNLOAD_HEADERS: [&str; 8] = [
+    "sku",
+    "name",
+    "category",
+    "units",
+    "price",
+    "amount",
+    "description",
+    "url",
+];
+
+/// Rows loaded per query when streaming a crawler product download.
+const PRODUCT_STREAM_BATCH_SIZE: usize = 500;
+
+fn product_download_row(p: Product, price_format: &PriceFormat) -> Vec<String> {
+    vec![
+        p.sku.as_str().to_string(),
+        p.name.as_str().to_string(),
+        p.category
+            .as_ref()
+            .map(|v| v.as_str().to_string())
+            .unwrap_or_default(),
+        p.units
+            .as_ref()
+            .map(|v| v.as_str().to_string())
+            .unwrap_or_default(),
+        price_format.format(p.price.get()),
+        p.amount.map(|v| v.get().to_string()).unwrap_or_default(),
+        p.description
+            .as_ref()
+            .map(|v| v.as_str().to_string())
+            .unwrap_or_default(),
+        p.url
+            .as_ref()
+            .map(|v| v.as_str().to_string())
+            .unwrap_or_default(),
+    ]
+}
+
+/// Starts crawling for the specified crawler.
+///
+/// Validates the `parser` role, ensures the crawler belongs to the user's hub
+/// and sends a ZMQ message to trigger crawling. Returns `Ok(true)` if the
+/// message was sent successfully, `Ok(false)` if sending failed, or an error if
+/// the crawler was not found or a repository error occurred.
+///
+/// Returns [`ServiceError::Form`] when the hub exceeds its dispatch rate.
+#[tracing::instrument(skip(limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
+pub async fn crawl_crawler<R, S>(
+    crawler_id: i32,
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
+    user: &AuthenticatedUser,
+    repo: &R,
+    sender: &S,
+) -> ServiceResult<bool>
+where
+    R: CrawlerReader,
+    S: ZmqSenderTrait + ?Sized,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let crawler_id = match CrawlerId::new(crawler_id) {
+        Ok(crawler_id) => crawler_id,
+        Err(_) => return Err(ServiceError::NotFound),
+    };
+
+    let crawler = match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(crawler)) => crawler,
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler by id: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    check_dispatch_rate(limiter, hub_id)?;
+
+    let message = ZMQCrawlerMessage::Crawler(CrawlerSelector::Selector(crawler.selector));
+    if send_json_with_retry(sender, &message, retry).await {
+        Ok(true)
+    } else {
+        tracing::error!("Failed to send ZMQ message");
+        Ok(false)
+    }
+}
+
+/// Outcome of dispatching a single crawler during a bulk crawl.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrawlDispatchStatus {
+    /// The crawl message was sent.
+    Sent,
+    /// The crawler was already processing and was left alone.
+    Skipped,
+    /// Sending the crawl message failed.
+    Failed,
+}
+
+/// Per-crawler result of [`crawl_all_crawlers`].
+#[derive(Debug, Clone)]
+pub struct CrawlDispatch {
+    pub crawler_id: CrawlerId,
+    pub name: CrawlerName,
+    pub status: CrawlDispatchStatus,
+}
+
+/// Starts crawling for every crawler of the user's hub.
+///
+/// Crawlers that are already `processing` are skipped. Messages are sent one
+/// at a time with `send_interval` between them so the crawler workers are not
+/// flooded. A failed send is reported per crawler instead of aborting the
+/// whole batch.
+///
+/// The whole batch counts as one dispatch against the hub's rate; returns
+/// [`ServiceError::Form`] when the hub exceeds it.
+#[tracing::instrument(skip(limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
+pub async fn crawl_all_crawlers<R, S>(
+    send_interval: Duration,
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
+    user: &AuthenticatedUser,
+    repo: &R,
+    sender: &S,
+) -> ServiceResult<Vec<CrawlDispatch>>
+where
+    R: CrawlerReader,
+    S: ZmqSenderTrait + ?Sized,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let crawlers = match repo.list_crawlers(CrawlerListQuery::new(hub_id)) {
+        Ok((_, crawlers)) => crawlers,
+        Err(e) => {
+            tracing::error!("Failed to list crawlers: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    if crawlers.iter().any(|crawler| !crawler.processing) {
+        check_dispatch_rate(limiter, hub_id)?;
+    }
+
+    let mut results = Vec::with_capacity(crawlers.len());
+    let mut sent_any = false;
+    for crawler in crawlers {
+        if crawler.processing {
+            results.push(CrawlDispatch {
+                crawler_id: crawler.id,
+                name: crawler.name,
+                status: CrawlDispatchStatus::Skipped,
+            });
             continue;
         }
 
-        if parsed.mode == UploadMode::Partial {
-            let has_required = ["name", "price"].iter().all(|field| {
-                merged
-                    .get(*field)
-                    .map(|value| !value.trim().is_empty())
-                    .unwrap_or(false)
+        if sent_any && !send_interval.is_zero() {
+            actix_web::rt::time::sleep(send_interval).await;
+        }
+        sent_any = true;
+
+        let message = ZMQCrawlerMessage::Crawler(CrawlerSelector::Selector(crawler.selector));
+        let status = if send_json_with_retry(sender, &message, retry).await {
+            CrawlDispatchStatus::Sent
+        } else {
+            tracing::error!("Failed to send ZMQ message for crawler {}", crawler.id);
+            CrawlDispatchStatus::Failed
+        };
+        results.push(CrawlDispatch {
+            crawler_id: crawler.id,
+            name: crawler.name,
+            status,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Updates prices for all products of the specified crawler.
+///
+/// Performs the same validations as [`crawl_crawler`] but also fetches all
+/// product URLs for the crawler before sending a ZMQ message. Products without
+/// a URL are left out of the message. Returns
+/// `Ok(true)` if the message was sent successfully, `Ok(false)` if sending
+/// failed, or an error if the crawler was not found or a repository error
+/// occurred.
+///
+/// Returns [`ServiceError::Form`] when the hub exceeds its dispatch rate.
+#[tracing::instrument(skip(limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
+pub async fn update_crawler_prices<R, S>(
+    crawler_id: i32,
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
+    user: &AuthenticatedUser,
+    repo: &R,
+    sender: &S,
+) -> ServiceResult<bool>
+where
+    R: CrawlerReader + ProductReader,
+    S: ZmqSenderTrait + ?Sized,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let crawler_id = match CrawlerId::new(crawler_id) {
+        Ok(crawler_id) => crawler_id,
+        Err(_) => return Err(ServiceError::NotFound),
+    };
+
+    let crawler = match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(crawler)) => crawler,
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler by id: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    check_dispatch_rate(limiter, hub_id)?;
+
+    let products = match repo.list_products(ProductListQuery::default().crawler(crawler_id)) {
+        Ok((_total, products)) => products,
+        Err(e) => {
+            tracing::error!("Failed to get products: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let urls = products
+        .into_iter()
+        .filter_map(|p| p.url)
+        .collect::<Vec<_>>();
+    if urls.is_empty() {
+        return Ok(false);
+    }
+
+    let message =
+        ZMQCrawlerMessage::Crawler(CrawlerSelector::SelectorProducts((crawler.selector, urls)));
+
+    if send_json_with_retry(sender, &message, retry).await {
+        Ok(true)
+    } else {
+        tracing::error!("Failed to send ZMQ message");
+        Ok(false)
+    }
+}
+
+/// Refreshes prices for a selection of the crawler's products.
+///
+/// Like [`update_crawler_prices`], but the message only carries the URLs of
+/// `product_ids`, in the given order; products without a URL are left out.
+/// Returns [`ServiceError::NotFound`] when the crawler is outside the user's
+/// hub or any product does not belong to it, `Ok(false)` when no selected
+/// product has a URL or sending failed, and [`ServiceError::Form`] when the
+/// hub exceeds its dispatch rate.
+#[tracing::instrument(skip(product_ids, limiter, retry, user, repo, sender), fields(hub_id = user.hub_id))]
+pub async fn sync_product_urls<R, S>(
+    crawler_id: i32,
+    product_ids: Vec<i32>,
+    limiter: &RateLimiter,
+    retry: &SendRetryPolicy,
+    user: &AuthenticatedUser,
+    repo: &R,
+    sender: &S,
+) -> ServiceResult<bool>
+where
+    R: CrawlerReader + ProductReader,
+    S: ZmqSenderTrait + ?Sized,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let crawler_id = match CrawlerId::new(crawler_id) {
+        Ok(crawler_id) => crawler_id,
+        Err(_) => return Err(ServiceError::NotFound),
+    };
+
+    let crawler = match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(crawler)) => crawler,
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to get crawler by id: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let mut urls = Vec::with_capacity(product_ids.len());
+    for product_id in product_ids {
+        let product_id = ProductId::new(product_id).map_err(|_| ServiceError::NotFound)?;
+        let product = match repo.get_product_by_id(product_id) {
+            Ok(Some(product)) if product.crawler_id == crawler.id => product,
+            Ok(_) => return Err(ServiceError::NotFound),
+            Err(e) => {
+                tracing::error!("Failed to get product: {e}");
+                return Err(ServiceError::Internal);
+            }
+        };
+        if let Some(url) = product.url
+            && !urls.contains(&url)
+        {
+            urls.push(url);
+        }
+    }
+    if urls.is_empty() {
+        return Ok(false);
+    }
+
+    check_dispatch_rate(limiter, hub_id)?;
+
+    let message =
+        ZMQCrawlerMessage::Crawler(CrawlerSelector::SelectorProducts((crawler.selector, urls)));
+
+    if send_json_with_retry(sender, &message, retry).await {
+        Ok(true)
+    } else {
+        tracing::error!("Failed to send ZMQ message");
+        Ok(false)
+    }
+}
+
+/// Move all products of the source crawler to the target crawler.
+///
+/// Both crawlers must belong to the user's hub. Products whose URL already
+/// exists on the target are left on the source and counted as conflicts.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn move_products<R>(
+    payload: MoveProductsFormPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ProductReassignment>
+where
+    R: CrawlerReader + ProductWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    for crawler_id in [payload.source_crawler_id, payload.target_crawler_id] {
+        match repo.get_crawler_by_id(crawler_id, hub_id) {
+            Ok(Some(_)) => {}
+            Ok(None) => return Err(ServiceError::NotFound),
+            Err(e) => {
+                tracing::error!("Failed to get crawler by id: {e}");
+                return Err(ServiceError::Internal);
+            }
+        }
+    }
+
+    match repo.reassign_products(payload.source_crawler_id, payload.target_crawler_id, hub_id) {
+        Ok(reassignment) => Ok(reassignment),
+        Err(RepositoryError::NotFound) => Err(ServiceError::NotFound),
+        Err(e) => {
+            tracing::error!("Failed to reassign products: {e}");
+            Err(ServiceError::Internal)
+        }
+    }
+}
+
+/// Upload crawler products using format/mode-aware import parser and SKU upsert semantics.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
+pub fn upload_crawler_products<R>(
+    crawler_id: i32,
+    form: &mut UploadImportForm,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<UploadReport>
+where
+    R: CrawlerReader + ProductReader + ProductWriter,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
+    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+    match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(err) => {
+            tracing::error!("Failed to load crawler for upload: {err}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    let parsed = parse_upload(form, UploadTarget::CrawlerProducts)
+        .map_err(|err| ServiceError::Form(err.to_string()))?;
+    apply_crawler_upload(parsed.mode, parsed.rows, crawler_id, repo)
+}
+
+/// Upsert target for a single uploaded row, keyed by SKU within the crawler.
+enum UpsertKey {
+    Create {
+        sku: String,
+    },
+    Update {
+        sku: String,
+        current: Box<Product>,
+    },
+    Invalid {
+        sku: Option<String>,
+        message: String,
+    },
+}
+
+fn resolve_upsert_key<R>(
+    row: &ParsedUploadRow,
+    crawler_id: CrawlerId,
+    seen_skus: &mut std::collections::HashSet<String>,
+    repo: &R,
+) -> ServiceResult<UpsertKey>
+where
+    R: ProductReader,
+{
+    let sku_value = row
+        .values
+        .get("sku")
+        .cloned()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if sku_value.is_empty() {
+        return Ok(UpsertKey::Invalid {
+            sku: None,
+            message: "Missing sku".to_string(),
+        });
+    }
+    if !seen_skus.insert(sku_value.clone()) {
+        return Ok(UpsertKey::Invalid {
+            sku: Some(sku_value),
+            message: "Duplicate sku in uploaded file".to_string(),
+        });
+    }
+
+    let sku = match crate::domain::types::ProductSku::new(sku_value.clone()) {
+        Ok(sku) => sku,
+        Err(err) => {
+            return Ok(UpsertKey::Invalid {
+                sku: Some(sku_value),
+                message: err.to_string(),
+            });
+        }
+    };
+
+    let mut existing = match repo.list_products_by_crawler_and_sku(crawler_id, &sku) {
+        Ok(items) => items,
+        Err(err) => {
+            tracing::error!("Failed to lookup products by sku: {err}");
+            return Err(ServiceError::Internal);
+        }
+    };
+    if existing.len() > 1 {
+        return Ok(UpsertKey::Invalid {
+            sku: Some(sku_value),
+            message: "Multiple existing products found for sku".to_string(),
+        });
+    }
+
+    Ok(match existing.pop() {
+        Some(current) => UpsertKey::Update {
+            sku: sku_value,
+            current: Box::new(current),
+        },
+        None => UpsertKey::Create { sku: sku_value },
+    })
+}
+
+fn has_required_create_fields(values: &std::collections::HashMap<String, String>) -> bool {
+    ["name", "price"].iter().all(|field| {
+        values
+            .get(*field)
+            .map(|value| !value.trim().is_empty())
+            .unwrap_or(false)
+    })
+}
+
+/// Fill columns missing from a Partial-mode row with the current product's values.
+fn merge_partial_row(
+    values: &std::collections::HashMap<String, String>,
+    current: &Product,
+) -> std::collections::HashMap<String, String> {
+    let mut merged = values.clone();
+    merged
+        .entry("name".to_string())
+        .or_insert_with(|| current.name.as_str().to_string());
+    merged
+        .entry("price".to_string())
+        .or_insert_with(|| current.price.get().to_string());
+    merged.entry("category".to_string()).or_insert_with(|| {
+        current
+            .category
+            .as_ref()
+            .map(|v| v.as_str().to_string())
+            .unwrap_or_default()
+    });
+    merged.entry("units".to_string()).or_insert_with(|| {
+        current
+            .units
+            .as_ref()
+            .map(|v| v.as_str().to_string())
+            .unwrap_or_default()
+    });
+    merged.entry("amount".to_string()).or_insert_with(|| {
+        current
+            .amount
+            .map(|v| v.get().to_string())
+            .unwrap_or_default()
+    });
+    merged.entry("description".to_string()).or_insert_with(|| {
+        current
+            .description
+            .as_ref()
+            .map(|v| v.as_str().to_string())
+            .unwrap_or_default()
+    });
+    merged.entry("url".to_string()).or_insert_with(|| {
+        current
+            .url
+            .as_ref()
+            .map(|v| v.as_str().to_string())
+            .unwrap_or_default()
+    });
+    merged
+}
+
+/// Fully validated outcome of a single uploaded row.
+enum PreparedRow {
+    Create {
+        sku: String,
+        product: NewProduct,
+    },
+    Update {
+        sku: String,
+        current: Box<Product>,
+        product: NewProduct,
+    },
+    Invalid {
+        sku: Option<String>,
+        message: String,
+    },
+}
+
+/// Resolve the SKU key of a row, merge Partial-mode values and build the
+/// product, so previews reject exactly the rows the commit would reject.
+fn prepare_upload_row<R>(
+    mode: UploadMode,
+    row: &ParsedUploadRow,
+    crawler_id: CrawlerId,
+    seen_skus: &mut std::collections::HashSet<String>,
+    repo: &R,
+) -> ServiceResult<PreparedRow>
+where
+    R: ProductReader,
+{
+    let (sku, existing) = match resolve_upsert_key(row, crawler_id, seen_skus, repo)? {
+        UpsertKey::Invalid { sku, message } => return Ok(PreparedRow::Invalid { sku, message }),
+        UpsertKey::Update { sku, current } => (sku, Some(current)),
+        UpsertKey::Create { sku } => (sku, None),
+    };
+
+    let merged = match existing.as_deref() {
+        Some(current) if mode == UploadMode::Partial => merge_partial_row(&row.values, current),
+        _ => row.values.clone(),
+    };
+
+    let product = match build_product_from_row(&merged, crawler_id) {
+        Ok(product) => product,
+        Err(message) => {
+            return Ok(PreparedRow::Invalid {
+                sku: Some(sku),
+                message,
             });
-            if !has_required {
-                report.push_error(
-                    row.row_number,
-                    Some(sku_value),
-                    "Partial mode create requires name and price",
-                );
-                continue;
+        }
+    };
+
+    Ok(match existing {
+        Some(current) => PreparedRow::Update {
+            sku,
+            current,
+            product,
+        },
+        None if mode == UploadMode::Partial && !has_required_create_fields(&merged) => {
+            PreparedRow::Invalid {
+                sku: Some(sku),
+                message: "Partial mode create requires name and price".to_string(),
+            }
+        }
+        None => PreparedRow::Create { sku, product },
+    })
+}
+
+/// Classify parsed upload rows as create, update or skip without writing.
+///
+/// Rows go through the same SKU key, Partial-mode merge and validation as
+/// [`upload_crawler_products`], so a row previewed as create or update does
+/// not fail on commit for invalid values.
+#[tracing::instrument(skip(parsed, repo))]
+pub fn classify_upload<R>(
+    parsed: &ParsedUpload,
+    hub_id: HubId,
+    crawler_id: CrawlerId,
+    repo: &R,
+) -> ServiceResult<UploadPreview>
+where
+    R: CrawlerReader + ProductReader,
+{
+    match repo.get_crawler_by_id(crawler_id, hub_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ServiceError::NotFound),
+        Err(err) => {
+            tracing::error!("Failed to load crawler for upload preview: {err}");
+            return Err(ServiceError::Internal);
+        }
+    }
+
+    let mut preview = UploadPreview::with_total(parsed.rows.len());
+    let mut seen_skus = std::collections::HashSet::new();
+
+    for row in &parsed.rows {
+        match prepare_upload_row(parsed.mode, row, crawler_id, &mut seen_skus, repo)? {
+            PreparedRow::Invalid { sku, message } => {
+                preview.push(row.row_number, sku, UploadRowAction::Skip, Some(message));
+            }
+            PreparedRow::Update { sku, .. } => {
+                preview.push(row.row_number, Some(sku), UploadRowAction::Update, None);
+            }
+            PreparedRow::Create { sku, .. } => {
+                preview.push(row.row_number, Some(sku), UploadRowAction::Create, None);
             }
         }
+    }
+
+    Ok(preview)
+}
+
+/// Parse an uploaded crawler products file and return its dry-run classification.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
+pub fn preview_crawler_upload<R>(
+    crawler_id: i32,
+    form: &mut UploadImportForm,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<UploadPreview>
+where
+    R: CrawlerReader + ProductReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|_| ServiceError::Internal)?;
+    let crawler_id = CrawlerId::new(crawler_id).map_err(|_| ServiceError::NotFound)?;
+
+    let parsed = parse_upload(form, UploadTarget::CrawlerProducts)
+        .map_err(|err| ServiceError::Form(err.to_string()))?;
+    classify_upload(&parsed, hub_id, crawler_id, repo)
+}
+
+/// Dry-run a crawler products import and summarize it as an
+/// [`UploadPreviewResult`]. Only reader methods are used, so nothing is written.
+///
+/// Rows with values the commit would reject (bad price, URL, amount, ...)
+/// are reported in `rows_with_errors` and excluded from the counts.
+#[tracing::instrument(skip(form, user, repo), fields(hub_id = user.hub_id))]
+pub fn preview_import_products<R>(
+    crawler_id: i32,
+    form: &mut UploadImportForm,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<UploadPreviewResult>
+where
+    R: CrawlerReader + ProductReader,
+{
+    preview_crawler_upload(crawler_id, form, user, repo).map(Into::into)
+}
+
+/// Upsert already parsed crawler product rows by SKU.
+pub(crate) fn apply_crawler_upload<R>(
+    mode: UploadMode,
+    rows: Vec<ParsedUploadRow>,
+    crawler_id: CrawlerId,
+    repo: &R,
+) -> ServiceResult<UploadReport>
+where
+    R: ProductReader + ProductWriter,
+{
+    let mut report = UploadReport::with_total(rows.len());
+    let mut seen_skus = std::collections::HashSet::new();
 
-        match repo.create_product(&new_product) {
-            Ok(_) => report.created += 1,
-            Err(err) => {
-                log::error!("Failed to create product: {err}");
-                report.push_error(row.row_number, Some(sku_value), "Failed to create product");
+    for row in rows {
+        match prepare_upload_row(mode, &row, crawler_id, &mut seen_skus, repo)? {
+            PreparedRow::Invalid { sku, message } => {
+                report.push_error(row.row_number, sku, message);
             }
+            PreparedRow::Update {
+                sku,
+                current,
+                product,
+            } => match repo.update_product(current.id, &product) {
+                Ok(_) => report.updated += 1,
+                Err(err) => {
+                    tracing::error!("Failed to update product: {err}");
+                    report.push_error(row.row_number, Some(sku), "Failed to update product");
+                }
+            },
+            PreparedRow::Create { sku, product } => match repo.create_product(&product) {
+                Ok(_) => report.created += 1,
+                Err(err) => {
+                    tracing::error!("Failed to create product: {err}");
+                    report.push_error(row.row_number, Some(sku), "Failed to create product");
+                }
+            },
         }
     }
 
@@ -555,15 +1432,15 @@ where
 mod tests {
     use super::*;
     use crate::domain::types::{
-        CategoryAssignmentSource, CategoryName, CrawlerId, CrawlerName, CrawlerSelectorValue,
-        CrawlerUrl, HubId, ProductCount, ProductId, ProductName, ProductPrice, ProductSku,
-        ProductUnits, ProductUrl,
+        BenchmarkId, CategoryAssignmentSource, CategoryName, CrawlerId, CrawlerName,
+        CrawlerSelectorValue, CrawlerUrl, HubId, ProductCount, ProductId, ProductName,
+        ProductPrice, ProductSku, ProductUnits, ProductUrl, SimilarityDistance,
     };
     use crate::forms::import_export::{ParsedUpload, ParsedUploadRow, UploadFormat, UploadMode};
     use crate::repository::test::TestRepository;
+    use crate::zmq::RecordingSender;
     use chrono::DateTime;
     use pushkind_common::domain::auth::AuthenticatedUser;
-    use pushkind_common::zmq::{SendFuture, ZmqSenderError, ZmqSenderTrait};
     use serde_json::Value;
     use std::collections::HashMap;
 
@@ -609,16 +1486,82 @@ mod tests {
             embedding: None,
             category_id: None,
             category_assignment_source: CategoryAssignmentSource::Automatic,
+            reviewed_at: None,
             images: vec![],
         }
     }
 
+    #[test]
+    fn delete_product_requires_hub_crawler() {
+        let repo = TestRepository::new(vec![], vec![sample_product()], vec![]);
+        let user = sample_user();
+
+        let result = delete_product(1, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn delete_product_soft_deletes_hub_product() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let user = sample_user();
+
+        assert!(delete_product(1, &user, &repo).unwrap());
+        assert!(matches!(
+            delete_product(1, &user, &repo),
+            Err(ServiceError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn delete_product_removes_associations_within_the_hub_only() {
+        let product_id = ProductId::new(1).unwrap();
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![])
+            .with_associations(vec![(
+                BenchmarkId::new(1).unwrap(),
+                product_id,
+                SimilarityDistance::new(0.2).unwrap(),
+            )]);
+        let other_hub = AuthenticatedUser {
+            hub_id: 2,
+            ..sample_user()
+        };
+
+        assert!(matches!(
+            delete_product(1, &other_hub, &repo),
+            Err(ServiceError::NotFound)
+        ));
+        assert_eq!(
+            repo.soft_delete_product(product_id, HubId::new(2).unwrap())
+                .unwrap(),
+            0
+        );
+        assert_eq!(repo.associations().len(), 1);
+
+        assert!(delete_product(1, &sample_user(), &repo).unwrap());
+        assert!(repo.associations().is_empty());
+    }
+
+    #[test]
+    fn restore_product_restores_known_product_only() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![])
+            .with_deleted_products(vec![ProductId::new(1).unwrap()]);
+        let user = sample_user();
+
+        assert!(restore_product(1, &user, &repo).unwrap());
+        assert!(matches!(
+            restore_product(2, &user, &repo),
+            Err(ServiceError::NotFound)
+        ));
+    }
+
     #[test]
     fn returns_products_for_authorized_user() {
         let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
         let user = sample_user();
 
-        let (crawler, paginated) = show_products(1, 1, &user, &repo).unwrap();
+        let (crawler, paginated) =
+            show_products(1, 1, ProductFilters::default(), &user, &repo).unwrap();
 
         assert_eq!(crawler.id, 1);
         let value: Value = serde_json::to_value(&paginated).unwrap();
@@ -626,16 +1569,109 @@ mod tests {
         assert_eq!(value["items"].as_array().unwrap().len(), 1);
     }
 
+    #[test]
+    fn show_products_skips_reviewed_when_requested() {
+        let mut reviewed = sample_product();
+        reviewed.id = ProductId::new(2).unwrap();
+        reviewed.reviewed_at = Some(DateTime::from_timestamp(0, 0).unwrap().naive_utc());
+        let repo = TestRepository::new(
+            vec![sample_crawler()],
+            vec![sample_product(), reviewed],
+            vec![],
+        );
+        let user = sample_user();
+
+        let (_, paginated) = show_products(
+            1,
+            1,
+            ProductFilters {
+                only_unreviewed: true,
+                ..ProductFilters::default()
+            },
+            &user,
+            &repo,
+        )
+        .unwrap();
+
+        let value: Value = serde_json::to_value(&paginated).unwrap();
+        let items = value["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], 1);
+    }
+
+    #[test]
+    fn mark_product_reviewed_rejects_product_from_other_crawler() {
+        let mut product = sample_product();
+        product.crawler_id = CrawlerId::new(2).unwrap();
+        let repo = TestRepository::new(vec![sample_crawler()], vec![product], vec![]);
+        let user = sample_user();
+
+        let result = mark_product_reviewed(1, 1, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
     #[test]
     fn crawler_download_csv_contains_expected_headers() {
         let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
         let user = sample_user();
 
-        let file = download_crawler_products(1, "csv", &user, &repo).unwrap();
+        let file =
+            download_crawler_products(1, "csv", &PriceFormat::default(), &user, &repo).unwrap();
         let body = String::from_utf8(file.bytes).unwrap();
         assert!(body.starts_with("sku,name,category,units,price,amount,description,url"));
     }
 
+    #[test]
+    fn export_products_names_file_after_crawler() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let user = sample_user();
+
+        let file = export_products(1, "xlsx", &PriceFormat::default(), &user, &repo).unwrap();
+        assert_eq!(
+            file.content_disposition(),
+            "attachment; filename=\"crawler_1_products.xlsx\""
+        );
+    }
+
+    #[test]
+    fn export_products_rejects_unknown_crawler() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let user = sample_user();
+
+        let result = export_products(42, "csv", &PriceFormat::default(), &user, &repo);
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn streamed_crawler_download_sets_csv_headers() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let user = sample_user();
+
+        let download =
+            stream_crawler_products_csv(1, &PriceFormat::default(), &user, &repo).unwrap();
+        assert_eq!(download.content_type, "text/csv; charset=utf-8");
+        assert_eq!(
+            download.content_disposition(),
+            "attachment; filename=\"crawler-1-products.csv\""
+        );
+
+        let chunks = download.chunks.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(chunks.len(), 2);
+        let body = String::from_utf8(chunks.concat()).unwrap();
+        assert!(body.starts_with("sku,name,category,units,price,amount,description,url"));
+        assert!(body.contains("SKU1,product"));
+    }
+
+    #[test]
+    fn streamed_crawler_download_rejects_unknown_crawler() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let user = sample_user();
+
+        let result = stream_crawler_products_csv(2, &PriceFormat::default(), &user, &repo);
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
     #[test]
     fn crawler_upload_reports_db_duplicate_sku_conflict() {
         let mut p1 = sample_product();
@@ -656,25 +1692,131 @@ mod tests {
             }],
         };
 
-        let report = apply_crawler_upload(parsed, CrawlerId::new(1).unwrap(), &repo).unwrap();
+        let report =
+            apply_crawler_upload(parsed.mode, parsed.rows, CrawlerId::new(1).unwrap(), &repo)
+                .unwrap();
         assert_eq!(report.skipped, 1);
         assert_eq!(report.errors.len(), 1);
     }
 
-    struct NoopSender;
+    #[test]
+    fn classify_upload_counts_new_and_existing_skus() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let row = |row_number: usize, sku: &str| ParsedUploadRow {
+            row_number,
+            values: HashMap::from([
+                ("sku".into(), sku.into()),
+                ("name".into(), "Product".into()),
+                ("price".into(), "10.0".into()),
+            ]),
+        };
+        let parsed = ParsedUpload {
+            format: UploadFormat::Csv,
+            mode: UploadMode::Full,
+            headers: vec!["sku".into(), "name".into(), "price".into()],
+            rows: vec![
+                row(2, "SKU1"),
+                row(3, "SKU2"),
+                row(4, "SKU3"),
+                row(5, "SKU2"),
+            ],
+        };
 
-    impl ZmqSenderTrait for NoopSender {
-        fn send_bytes<'a>(&'a self, _bytes: Vec<u8>) -> SendFuture<'a> {
-            Box::pin(async { Ok(()) })
-        }
+        let preview = classify_upload(
+            &parsed,
+            HubId::new(1).unwrap(),
+            CrawlerId::new(1).unwrap(),
+            &repo,
+        )
+        .unwrap();
 
-        fn try_send_bytes(&self, _bytes: Vec<u8>) -> Result<(), ZmqSenderError> {
-            Ok(())
-        }
+        assert_eq!(preview.total_rows, 4);
+        assert_eq!(preview.to_update, 1);
+        assert_eq!(preview.to_create, 2);
+        assert_eq!(preview.skipped, 1);
+        let actions: Vec<UploadRowAction> = preview.rows.iter().map(|row| row.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                UploadRowAction::Update,
+                UploadRowAction::Create,
+                UploadRowAction::Create,
+                UploadRowAction::Skip,
+            ]
+        );
+    }
 
-        fn send_multipart<'a>(&'a self, _frames: Vec<Vec<u8>>) -> SendFuture<'a> {
-            Box::pin(async { Ok(()) })
-        }
+    #[test]
+    fn classify_upload_skips_rows_that_fail_validation() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let row = |row_number: usize, sku: &str, price: &str| ParsedUploadRow {
+            row_number,
+            values: HashMap::from([
+                ("sku".into(), sku.into()),
+                ("name".into(), "Product".into()),
+                ("price".into(), price.into()),
+            ]),
+        };
+        let parsed = ParsedUpload {
+            format: UploadFormat::Csv,
+            mode: UploadMode::Partial,
+            headers: vec!["sku".into(), "name".into(), "price".into()],
+            rows: vec![row(2, "SKU1", "12.5"), row(3, "SKU2", "abc")],
+        };
+        let mut invalid_update = parsed.clone();
+        invalid_update.rows = vec![row(2, "SKU1", "abc")];
+
+        let preview = classify_upload(
+            &parsed,
+            HubId::new(1).unwrap(),
+            CrawlerId::new(1).unwrap(),
+            &repo,
+        )
+        .unwrap();
+        assert_eq!(preview.to_update, 1);
+        assert_eq!(preview.to_create, 0);
+        assert_eq!(preview.skipped, 1);
+        assert_eq!(preview.rows[1].action, UploadRowAction::Skip);
+        assert!(preview.rows[1].message.is_some());
+
+        let preview = classify_upload(
+            &invalid_update,
+            HubId::new(1).unwrap(),
+            CrawlerId::new(1).unwrap(),
+            &repo,
+        )
+        .unwrap();
+        assert_eq!(preview.to_update, 0);
+        assert_eq!(preview.skipped, 1);
+
+        let report = apply_crawler_upload(
+            invalid_update.mode,
+            invalid_update.rows,
+            CrawlerId::new(1).unwrap(),
+            &repo,
+        )
+        .unwrap();
+        assert_eq!(report.updated, 0);
+        assert_eq!(report.errors.len(), 1);
+    }
+
+    #[test]
+    fn classify_upload_rejects_unknown_crawler() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let parsed = ParsedUpload {
+            format: UploadFormat::Csv,
+            mode: UploadMode::Full,
+            headers: vec![],
+            rows: vec![],
+        };
+
+        let result = classify_upload(
+            &parsed,
+            HubId::new(1).unwrap(),
+            CrawlerId::new(2).unwrap(),
+            &repo,
+        );
+        assert!(matches!(result, Err(ServiceError::NotFound)));
     }
 
     #[actix_web::test]
@@ -683,11 +1825,218 @@ mod tests {
         p.url = None;
         let repo = TestRepository::new(vec![sample_crawler()], vec![p], vec![]);
         let user = sample_user();
-        let sender = NoopSender;
+        let sender = RecordingSender::default();
 
-        let sent = update_crawler_prices(1, &user, &repo, &sender)
-            .await
-            .unwrap();
+        let sent = update_crawler_prices(
+            1,
+            &RateLimiter::per_minute(0),
+            &SendRetryPolicy::none(),
+            &user,
+            &repo,
+            &sender,
+        )
+        .await
+        .unwrap();
         assert!(!sent);
     }
+
+    #[actix_web::test]
+    async fn crawl_all_skips_processing_crawlers_and_dispatches_the_rest() {
+        let idle = sample_crawler();
+        let mut busy = sample_crawler();
+        busy.id = CrawlerId::new(2).unwrap();
+        busy.processing = true;
+        let mut other = sample_crawler();
+        other.id = CrawlerId::new(3).unwrap();
+        let repo = TestRepository::new(vec![idle, busy, other], vec![], vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let results = crawl_all_crawlers(
+            Duration::from_millis(1),
+            &RateLimiter::per_minute(0),
+            &SendRetryPolicy::none(),
+            &user,
+            &repo,
+            &sender,
+        )
+        .await
+        .unwrap();
+
+        let statuses = results
+            .iter()
+            .map(|result| (result.crawler_id.get(), result.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                (1, CrawlDispatchStatus::Sent),
+                (2, CrawlDispatchStatus::Skipped),
+                (3, CrawlDispatchStatus::Sent),
+            ]
+        );
+        assert_eq!(sender.messages().len(), 2);
+    }
+
+    #[actix_web::test]
+    async fn crawl_all_counts_as_one_dispatch_against_the_hub_rate() {
+        let mut other = sample_crawler();
+        other.id = CrawlerId::new(2).unwrap();
+        let repo = TestRepository::new(vec![sample_crawler(), other], vec![], vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+        let limiter = RateLimiter::per_minute(1);
+        let crawl_all = || {
+            crawl_all_crawlers(
+                Duration::ZERO,
+                &limiter,
+                &SendRetryPolicy::none(),
+                &user,
+                &repo,
+                &sender,
+            )
+        };
+
+        let results = crawl_all().await.unwrap();
+        assert_eq!(results.len(), 2);
+        let result = crawl_all().await;
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+        assert_eq!(sender.messages().len(), 2);
+    }
+
+    #[actix_web::test]
+    async fn crawl_crawler_rejects_calls_over_the_hub_rate() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![], vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+        let limiter = RateLimiter::per_minute(2);
+
+        for _ in 0..2 {
+            let sent = crawl_crawler(1, &limiter, &SendRetryPolicy::none(), &user, &repo, &sender)
+                .await
+                .unwrap();
+            assert!(sent);
+        }
+        let result =
+            crawl_crawler(1, &limiter, &SendRetryPolicy::none(), &user, &repo, &sender).await;
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+        assert_eq!(sender.messages().len(), 2);
+    }
+
+    fn crawler_products(count: i32) -> Vec<Product> {
+        (1..=count)
+            .map(|id| {
+                let mut product = sample_product();
+                product.id = ProductId::new(id).unwrap();
+                product.sku = ProductSku::new(format!("SKU{id}")).unwrap();
+                product.url = Some(ProductUrl::new(format!("http://example.com/{id}")).unwrap());
+                product
+            })
+            .collect()
+    }
+
+    #[actix_web::test]
+    async fn sync_product_urls_sends_urls_of_selected_products() {
+        let repo = TestRepository::new(vec![sample_crawler()], crawler_products(3), vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let sent = sync_product_urls(
+            1,
+            vec![3, 1],
+            &RateLimiter::per_minute(0),
+            &SendRetryPolicy::none(),
+            &user,
+            &repo,
+            &sender,
+        )
+        .await
+        .unwrap();
+
+        assert!(sent);
+        let messages = sender.payloads();
+        assert_eq!(messages.len(), 1);
+        let message: ZMQCrawlerMessage = serde_json::from_slice(&messages[0]).unwrap();
+        assert_eq!(
+            message,
+            ZMQCrawlerMessage::Crawler(CrawlerSelector::SelectorProducts((
+                CrawlerSelectorValue::new("body").unwrap(),
+                vec![
+                    ProductUrl::new("http://example.com/3").unwrap(),
+                    ProductUrl::new("http://example.com/1").unwrap(),
+                ],
+            )))
+        );
+    }
+
+    #[actix_web::test]
+    async fn update_crawler_prices_skips_products_without_url() {
+        let mut products = crawler_products(3);
+        products[1].url = None;
+        let repo = TestRepository::new(vec![sample_crawler()], products, vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let sent = update_crawler_prices(
+            1,
+            &RateLimiter::per_minute(0),
+            &SendRetryPolicy::none(),
+            &user,
+            &repo,
+            &sender,
+        )
+        .await
+        .unwrap();
+
+        assert!(sent);
+        let messages = sender.payloads();
+        let message: ZMQCrawlerMessage = serde_json::from_slice(&messages[0]).unwrap();
+        assert_eq!(
+            message,
+            ZMQCrawlerMessage::Crawler(CrawlerSelector::SelectorProducts((
+                CrawlerSelectorValue::new("body").unwrap(),
+                vec![
+                    ProductUrl::new("http://example.com/1").unwrap(),
+                    ProductUrl::new("http://example.com/3").unwrap(),
+                ],
+            )))
+        );
+    }
+
+    #[actix_web::test]
+    async fn sync_product_urls_rejects_products_of_other_crawlers() {
+        let mut products = crawler_products(2);
+        products[1].crawler_id = CrawlerId::new(2).unwrap();
+        let repo = TestRepository::new(vec![sample_crawler()], products, vec![]);
+        let user = sample_user();
+        let sender = RecordingSender::default();
+
+        let result = sync_product_urls(
+            1,
+            vec![1, 2],
+            &RateLimiter::per_minute(0),
+            &SendRetryPolicy::none(),
+            &user,
+            &repo,
+            &sender,
+        )
+        .await;
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+        assert!(sender.messages().is_empty());
+    }
+
+    #[test]
+    fn move_products_requires_both_crawlers_in_hub() {
+        let repo = TestRepository::new(vec![sample_crawler()], vec![sample_product()], vec![]);
+        let payload = MoveProductsFormPayload {
+            source_crawler_id: CrawlerId::new(1).unwrap(),
+            target_crawler_id: CrawlerId::new(2).unwrap(),
+        };
+
+        let result = move_products(payload, &sample_user(), &repo);
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
 }
@@ -0,0 +1,125 @@
+//! Per-hub throttling of background job dispatches.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::domain::types::HubId;
+
+use super::{ServiceError, ServiceResult};
+
+/// Message returned when a hub dispatches jobs faster than the limiter allows.
+pub const RATE_LIMITED_MESSAGE: &str = "too many requests, try again shortly";
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// In-memory token bucket per hub, shared by all workers.
+///
+/// Each hub may start `capacity` jobs at once; spent tokens refill evenly over
+/// `window`. A limiter with zero capacity lets every request through.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: u32,
+    window: Duration,
+    buckets: Mutex<HashMap<HubId, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Allow `capacity` dispatches per hub within `window`.
+    pub fn new(capacity: u32, window: Duration) -> Self {
+        Self {
+            capacity,
+            window,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Allow `limit` dispatches per hub per minute; `0` disables limiting.
+    pub fn per_minute(limit: u32) -> Self {
+        Self::new(limit, Duration::from_secs(60))
+    }
+
+    /// Take one token from the hub's bucket, returning `false` when it is empty.
+    pub fn try_acquire(&self, hub_id: HubId) -> bool {
+        self.try_acquire_at(hub_id, Instant::now())
+    }
+
+    fn try_acquire_at(&self, hub_id: HubId, now: Instant) -> bool {
+        if self.capacity == 0 || self.window.is_zero() {
+            return true;
+        }
+
+        let capacity = f64::from(self.capacity);
+        let refill_per_second = capacity / self.window.as_secs_f64();
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let bucket = buckets.entry(hub_id).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * refill_per_second).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+}
+
+/// Reject the dispatch with a form error when the hub is over its rate.
+pub(crate) fn check_dispatch_rate(limiter: &RateLimiter, hub_id: HubId) -> ServiceResult<()> {
+    if limiter.try_acquire(hub_id) {
+        Ok(())
+    } else {
+        tracing::warn!("Job dispatch rate limit exceeded for hub {}", hub_id.get());
+        Err(ServiceError::Form(RATE_LIMITED_MESSAGE.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_call_beyond_capacity_within_window() {
+        let limiter = RateLimiter::per_minute(3);
+        let hub_id = HubId::new(1).unwrap();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at(hub_id, now));
+        }
+        assert!(!limiter.try_acquire_at(hub_id, now + Duration::from_secs(1)));
+        assert!(limiter.try_acquire_at(HubId::new(2).unwrap(), now));
+    }
+
+    #[test]
+    fn refills_tokens_over_the_window() {
+        let limiter = RateLimiter::per_minute(2);
+        let hub_id = HubId::new(1).unwrap();
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(hub_id, now));
+        assert!(limiter.try_acquire_at(hub_id, now));
+        assert!(!limiter.try_acquire_at(hub_id, now));
+        assert!(limiter.try_acquire_at(hub_id, now + Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn zero_capacity_disables_limiting() {
+        let limiter = RateLimiter::per_minute(0);
+        let hub_id = HubId::new(1).unwrap();
+
+        assert!((0..100).all(|_| limiter.try_acquire(hub_id)));
+    }
+}
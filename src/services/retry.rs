@@ -0,0 +1,265 @@
+//! Retries with exponential backoff for background job dispatches.
+
+use std::future::Future;
+use std::time::Duration;
+
+use pushkind_common::zmq::{ZmqSenderError, ZmqSenderTrait};
+use serde::Serialize;
+
+use crate::models::config::{DEFAULT_ZMQ_SEND_BACKOFF_MS, DEFAULT_ZMQ_SEND_RETRIES};
+
+/// How often and how patiently a failed ZMQ send is repeated.
+///
+/// Retry `n` (starting at zero) waits `initial_backoff * 2^n` first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendRetryPolicy {
+    /// Attempts made after the first one fails.
+    pub retries: u32,
+    /// Pause before the first retry.
+    pub initial_backoff: Duration,
+}
+
+impl SendRetryPolicy {
+    pub fn new(retries: u32, initial_backoff: Duration) -> Self {
+        Self {
+            retries,
+            initial_backoff,
+        }
+    }
+
+    /// Policy that sends exactly once.
+    pub fn none() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+
+    /// Pause before retry `retry` (zero-based).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+    }
+}
+
+impl Default for SendRetryPolicy {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_ZMQ_SEND_RETRIES,
+            Duration::from_millis(DEFAULT_ZMQ_SEND_BACKOFF_MS),
+        )
+    }
+}
+
+/// Run `attempt` until it succeeds, `is_transient` rejects its error, or the
+/// policy's retries are used up. Returns the last result.
+pub async fn retry_with_backoff<T, E, F, Fut>(
+    policy: &SendRetryPolicy,
+    is_transient: impl Fn(&E) -> bool,
+    mut attempt: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut retry = 0;
+    loop {
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(err) if retry < policy.retries && is_transient(&err) => {
+                let backoff = policy.backoff(retry);
+                if !backoff.is_zero() {
+                    actix_web::rt::time::sleep(backoff).await;
+                }
+                retry += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Whether a failed send may succeed when repeated.
+///
+/// Socket errors and a full send queue clear up once the worker is reachable
+/// again; a closed sender or an unserializable message fails every time.
+pub fn is_transient(err: &ZmqSenderError) -> bool {
+    match err {
+        ZmqSenderError::Zmq(_) | ZmqSenderError::QueueFull => true,
+        ZmqSenderError::Serialization(_) | ZmqSenderError::ChannelClosed => false,
+    }
+}
+
+/// Serialize `message` as JSON and send it, retrying transient send failures.
+///
+/// A message that cannot be serialized is never sent; sender errors are
+/// retried only when [`is_transient`] accepts them. Returns `true` once a
+/// send succeeds.
+pub async fn send_json_with_retry<S, M>(sender: &S, message: &M, policy: &SendRetryPolicy) -> bool
+where
+    S: ZmqSenderTrait + ?Sized,
+    M: Serialize,
+{
+    let bytes = match serde_json::to_vec(message) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to serialize ZMQ message: {e}");
+            return false;
+        }
+    };
+
+    match retry_with_backoff(policy, is_transient, || sender.send_bytes(bytes.clone())).await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::error!("Failed to send ZMQ message: {e}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::sync::Mutex;
+
+    use pushkind_common::zmq::SendFuture;
+
+    use super::*;
+
+    /// Sender failing with the queued errors in order, then succeeding.
+    struct FailingSender {
+        errors: Mutex<Vec<ZmqSenderError>>,
+        calls: Mutex<usize>,
+    }
+
+    impl FailingSender {
+        fn new(mut errors: Vec<ZmqSenderError>) -> Self {
+            errors.reverse();
+            Self {
+                errors: Mutex::new(errors),
+                calls: Mutex::new(0),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            *self.calls.lock().unwrap()
+        }
+    }
+
+    impl ZmqSenderTrait for FailingSender {
+        fn send_bytes<'a>(&'a self, _bytes: Vec<u8>) -> SendFuture<'a> {
+            *self.calls.lock().unwrap() += 1;
+            let result = match self.errors.lock().unwrap().pop() {
+                Some(err) => Err(err),
+                None => Ok(()),
+            };
+            Box::pin(async move { result })
+        }
+
+        fn try_send_bytes(&self, _bytes: Vec<u8>) -> Result<(), ZmqSenderError> {
+            Ok(())
+        }
+
+        fn send_multipart<'a>(&'a self, _frames: Vec<Vec<u8>>) -> SendFuture<'a> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    fn serialization_error() -> ZmqSenderError {
+        ZmqSenderError::from(serde_json::from_str::<u8>("not json").unwrap_err())
+    }
+
+    #[test]
+    fn classifies_sender_errors() {
+        assert!(is_transient(&ZmqSenderError::QueueFull));
+        assert!(!is_transient(&ZmqSenderError::ChannelClosed));
+        assert!(!is_transient(&serialization_error()));
+    }
+
+    #[actix_web::test]
+    async fn sender_retries_transient_failures_until_success() {
+        let sender = FailingSender::new(vec![ZmqSenderError::QueueFull, ZmqSenderError::QueueFull]);
+        let policy = SendRetryPolicy::new(2, Duration::ZERO);
+
+        assert!(send_json_with_retry(&sender, &"job", &policy).await);
+        assert_eq!(sender.calls(), 3);
+    }
+
+    #[actix_web::test]
+    async fn sender_returns_immediately_on_permanent_failure() {
+        let sender = FailingSender::new(vec![ZmqSenderError::ChannelClosed]);
+        let policy = SendRetryPolicy::new(3, Duration::from_secs(60));
+
+        assert!(!send_json_with_retry(&sender, &"job", &policy).await);
+        assert_eq!(sender.calls(), 1);
+    }
+
+    #[test]
+    fn backoff_doubles_per_retry() {
+        let policy = SendRetryPolicy::new(3, Duration::from_millis(100));
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+    }
+
+    #[actix_web::test]
+    async fn succeeds_after_two_transient_failures() {
+        let policy = SendRetryPolicy::new(2, Duration::from_millis(1));
+        let calls = Cell::new(0);
+
+        let result = retry_with_backoff(
+            &policy,
+            |_: &&str| true,
+            || {
+                calls.set(calls.get() + 1);
+                let call = calls.get();
+                async move {
+                    if call <= 2 {
+                        Err("unavailable")
+                    } else {
+                        Ok(call)
+                    }
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[actix_web::test]
+    async fn gives_up_when_retries_are_exhausted() {
+        let policy = SendRetryPolicy::new(1, Duration::ZERO);
+        let calls = Cell::new(0);
+
+        let result: Result<(), &str> = retry_with_backoff(
+            &policy,
+            |_| true,
+            || {
+                calls.set(calls.get() + 1);
+                async { Err("unavailable") }
+            },
+        )
+        .await;
+
+        assert_eq!(result, Err("unavailable"));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[actix_web::test]
+    async fn does_not_retry_permanent_errors() {
+        let policy = SendRetryPolicy::new(3, Duration::ZERO);
+        let calls = Cell::new(0);
+
+        let result: Result<(), &str> = retry_with_backoff(
+            &policy,
+            |err: &&str| *err != "invalid",
+            || {
+                calls.set(calls.get() + 1);
+                async { Err("invalid") }
+            },
+        )
+        .await;
+
+        assert_eq!(result, Err("invalid"));
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+//! Hub-wide search across crawler products and benchmarks.
+
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::routes::check_role;
+use serde::{Deserialize, Serialize};
+
+use crate::SERVICE_ACCESS_ROLE;
+use crate::domain::types::HubId;
+use crate::dto::benchmark::BenchmarkSummaryDto;
+use crate::dto::products::ProductSummaryDto;
+use crate::repository::{BenchmarkListQuery, BenchmarkReader, ProductListQuery, ProductReader};
+
+use super::{ServiceError, ServiceResult};
+
+/// Number of matches of each kind shown on the search page.
+pub const SEARCH_RESULTS_LIMIT: usize = 10;
+
+/// Query string of `GET /search`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchQuery {
+    /// Text to look for; blank renders an empty page.
+    #[serde(default)]
+    pub q: String,
+}
+
+/// First page of products and benchmarks matching a search, with total counts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HubSearchResult {
+    pub products: Vec<ProductSummaryDto>,
+    pub benchmarks: Vec<BenchmarkSummaryDto>,
+    pub product_total: usize,
+    pub benchmark_total: usize,
+}
+
+/// Search the user's hub for products (full-text) and benchmarks (name or SKU).
+///
+/// A blank query returns an empty result. Queries shorter than
+/// `min_query_length` characters are rejected with [`ServiceError::Form`].
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn search_hub<R>(
+    query: SearchQuery,
+    min_query_length: usize,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<HubSearchResult>
+where
+    R: ProductReader + BenchmarkReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = match HubId::new(user.hub_id) {
+        Ok(hub_id) => hub_id,
+        Err(e) => {
+            tracing::error!("Invalid hub id in user context: {e}");
+            return Err(ServiceError::Internal);
+        }
+    };
+
+    let search = query.q.trim();
+    if search.is_empty() {
+        return Ok(HubSearchResult::default());
+    }
+    if search.chars().count() < min_query_length {
+        return Err(ServiceError::Form(format!(
+            "enter at least {min_query_length} characters"
+        )));
+    }
+
+    let (product_total, products) = repo
+        .search_products(
+            ProductListQuery::default()
+                .hub_id(hub_id)
+                .search(search)
+                .paginate(1, SEARCH_RESULTS_LIMIT),
+        )
+        .map_err(|e| {
+            tracing::error!("Failed to search products: {e}");
+            ServiceError::Internal
+        })?;
+
+    let (benchmark_total, benchmarks) = repo
+        .list_benchmarks(
+            BenchmarkListQuery::new(hub_id)
+                .search(search)
+                .paginate(1, SEARCH_RESULTS_LIMIT),
+        )
+        .map_err(|e| {
+            tracing::error!("Failed to search benchmarks: {e}");
+            ServiceError::Internal
+        })?;
+
+    Ok(HubSearchResult {
+        products: products.into_iter().map(ProductSummaryDto::from).collect(),
+        benchmarks: benchmarks
+            .into_iter()
+            .map(BenchmarkSummaryDto::from)
+            .collect(),
+        product_total,
+        benchmark_total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::domain::benchmark::Benchmark;
+    use crate::domain::types::{
+        BenchmarkId, BenchmarkName, BenchmarkSku, CategoryName, ProductAmount, ProductCount,
+        ProductDescription, ProductPrice, ProductUnits,
+    };
+    use crate::repository::test::TestRepository;
+
+    fn sample_user() -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "1".into(),
+            email: "test@example.com".into(),
+            hub_id: 1,
+            name: "Test".into(),
+            roles: vec![SERVICE_ACCESS_ROLE.into()],
+            exp: 0,
+        }
+    }
+
+    fn benchmark(id: i32, name: &str, sku: &str) -> Benchmark {
+        Benchmark {
+            id: BenchmarkId::new(id).unwrap(),
+            hub_id: HubId::new(1).unwrap(),
+            name: BenchmarkName::new(name).unwrap(),
+            sku: BenchmarkSku::new(sku).unwrap(),
+            category: CategoryName::new("cat").unwrap(),
+            units: ProductUnits::new("pcs").unwrap(),
+            price: ProductPrice::new(1.0).unwrap(),
+            amount: ProductAmount::new(1.0).unwrap(),
+            description: ProductDescription::new("desc").unwrap(),
+            created_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            updated_at: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            embedding: Some(vec![1, 2, 3]),
+            processing: false,
+            num_products: ProductCount::new(0).unwrap(),
+            archived_at: None,
+        }
+    }
+
+    fn query(q: &str) -> SearchQuery {
+        SearchQuery { q: q.into() }
+    }
+
+    #[test]
+    fn finds_benchmarks_by_name_or_sku() {
+        let repo = TestRepository::new(
+            vec![],
+            vec![],
+            vec![
+                benchmark(1, "Green tea", "TEA-1"),
+                benchmark(2, "Coffee", "COF-1"),
+                benchmark(3, "Herbal", "TEA-2"),
+            ],
+        );
+
+        let result = search_hub(query("tea"), 2, &sample_user(), &repo).unwrap();
+
+        assert_eq!(result.benchmark_total, 2);
+        let ids: Vec<i32> = result.benchmarks.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+    }
+
+    #[test]
+    fn blank_query_returns_empty_result() {
+        let repo = TestRepository::new(vec![], vec![], vec![benchmark(1, "Tea", "TEA-1")]);
+
+        let result = search_hub(query("  "), 2, &sample_user(), &repo).unwrap();
+
+        assert_eq!(result, HubSearchResult::default());
+    }
+
+    #[test]
+    fn short_query_is_rejected() {
+        let repo = TestRepository::default();
+
+        let result = search_hub(query("t"), 2, &sample_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+    }
+}
@@ -0,0 +1,83 @@
+//! Cosine distance between stored embeddings.
+//!
+//! Embeddings are written by `pushkind-crawlers` as blobs of little-endian
+//! `f32` values.
+
+use crate::domain::types::SimilarityDistance;
+
+/// Decode an embedding blob; `None` when it is empty or not a whole number of `f32`s.
+pub fn decode_embedding(bytes: &[u8]) -> Option<Vec<f32>> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect(),
+    )
+}
+
+/// Cosine distance `1 - cos(a, b)`, clamped to the `[0.0, 1.0]` range of
+/// [`SimilarityDistance`] so opposite vectors are as far apart as unrelated ones.
+///
+/// Returns `None` when the vectors differ in length or either has zero norm.
+pub fn cosine_distance(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.len() != b.len() || a.is_empty() {
+        return None;
+    }
+
+    let (dot, norm_a, norm_b) = a.iter().zip(b).fold(
+        (0.0_f32, 0.0_f32, 0.0_f32),
+        |(dot, norm_a, norm_b), (x, y)| (dot + x * y, norm_a + x * x, norm_b + y * y),
+    );
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+
+    let distance = 1.0 - dot / (norm_a.sqrt() * norm_b.sqrt());
+    distance.is_finite().then(|| distance.clamp(0.0, 1.0))
+}
+
+/// Cosine distance between two embedding blobs.
+pub fn embedding_distance(a: &[u8], b: &[u8]) -> Option<SimilarityDistance> {
+    let distance = cosine_distance(&decode_embedding(a)?, &decode_embedding(b)?)?;
+    SimilarityDistance::new(distance).ok()
+}
+
+/// Encode `values` the way embedding blobs are stored.
+#[cfg(test)]
+pub(crate) fn encode_embedding(values: &[f32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_little_endian_floats() {
+        let bytes = encode_embedding(&[1.0, -0.5]);
+
+        assert_eq!(decode_embedding(&bytes), Some(vec![1.0, -0.5]));
+        assert_eq!(decode_embedding(&[]), None);
+        assert_eq!(decode_embedding(&[0, 0, 0]), None);
+    }
+
+    #[test]
+    fn cosine_distance_of_identical_and_orthogonal_vectors() {
+        assert_eq!(cosine_distance(&[1.0, 0.0], &[3.0, 0.0]), Some(0.0));
+        assert_eq!(cosine_distance(&[1.0, 0.0], &[0.0, 1.0]), Some(1.0));
+        assert_eq!(cosine_distance(&[1.0, 0.0], &[-1.0, 0.0]), Some(1.0));
+    }
+
+    #[test]
+    fn cosine_distance_rejects_mismatched_or_zero_vectors() {
+        assert_eq!(cosine_distance(&[1.0], &[1.0, 0.0]), None);
+        assert_eq!(cosine_distance(&[0.0, 0.0], &[1.0, 0.0]), None);
+    }
+}
@@ -0,0 +1,95 @@
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::routes::check_role;
+
+use crate::SERVICE_ACCESS_ROLE;
+use crate::domain::types::HubId;
+use crate::repository::StatisticsReader;
+
+pub use crate::domain::statistics::{HubStatistics, HubSummary};
+
+use super::{ServiceError, ServiceResult};
+
+/// Return data-quality and entity counts for the user's hub.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn show_hub_statistics<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<HubStatistics>
+where
+    R: StatisticsReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    repo.hub_statistics(hub_id).map_err(|e| {
+        tracing::error!("Failed to load hub statistics: {e}");
+        ServiceError::Internal
+    })
+}
+
+/// Return headline entity totals for the user's hub.
+///
+/// The totals are taken from [`show_hub_statistics`]' counts, so both
+/// endpoints always agree.
+#[tracing::instrument(skip(user, repo), fields(hub_id = user.hub_id))]
+pub fn show_hub_summary<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<HubSummary>
+where
+    R: StatisticsReader,
+{
+    if !check_role(SERVICE_ACCESS_ROLE, &user.roles) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id).map_err(|e| {
+        tracing::error!("Invalid hub id in user context: {e}");
+        ServiceError::Internal
+    })?;
+
+    repo.hub_statistics(hub_id)
+        .map(HubSummary::from)
+        .map_err(|e| {
+            tracing::error!("Failed to load hub summary: {e}");
+            ServiceError::Internal
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::test::TestRepository;
+
+    #[test]
+    fn hub_statistics_require_parser_role() {
+        let repo = TestRepository::default();
+        let user = AuthenticatedUser {
+            sub: "1".into(),
+            email: "test@example.com".into(),
+            hub_id: 1,
+            name: "Test".into(),
+            roles: vec![],
+            exp: 0,
+        };
+
+        let result = show_hub_statistics(&user, &repo);
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn hub_summary_requires_parser_role() {
+        let repo = TestRepository::default();
+        let user = AuthenticatedUser {
+            sub: "1".into(),
+            email: "test@example.com".into(),
+            hub_id: 1,
+            name: "Test".into(),
+            roles: vec![],
+            exp: 0,
+        };
+
+        let result = show_hub_summary(&user, &repo);
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+}
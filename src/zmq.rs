@@ -0,0 +1,170 @@
+//! ZeroMQ sender that prefixes every message with a configured topic frame.
+
+use std::sync::Arc;
+
+use pushkind_common::zmq::{SendFuture, ZmqSender, ZmqSenderError, ZmqSenderTrait};
+
+/// Sender used by routes to publish worker jobs.
+///
+/// When a topic is configured every message goes out as two frames,
+/// `[topic, payload]`, through `send_multipart`, so workers shared by several
+/// deployments can subscribe to their own topic. Without a topic messages keep
+/// the single-frame format.
+pub struct TopicZmqSender<S: ?Sized = ZmqSender> {
+    inner: Arc<S>,
+    topic: Option<Vec<u8>>,
+}
+
+impl<S: ?Sized> TopicZmqSender<S> {
+    /// Wrap `inner`; a blank `topic` is treated as no topic.
+    pub fn new(inner: Arc<S>, topic: Option<&str>) -> Self {
+        let topic = topic
+            .map(str::trim)
+            .filter(|topic| !topic.is_empty())
+            .map(|topic| topic.as_bytes().to_vec());
+        Self { inner, topic }
+    }
+
+    /// The topic frame prepended to every message, if any.
+    pub fn topic(&self) -> Option<&[u8]> {
+        self.topic.as_deref()
+    }
+
+    fn with_topic(&self, mut frames: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+        if let Some(topic) = &self.topic {
+            frames.insert(0, topic.clone());
+        }
+        frames
+    }
+}
+
+impl<S> ZmqSenderTrait for TopicZmqSender<S>
+where
+    S: ZmqSenderTrait + ?Sized + 'static,
+{
+    fn send_bytes<'a>(&'a self, bytes: Vec<u8>) -> SendFuture<'a> {
+        match self.topic {
+            Some(_) => self.inner.send_multipart(self.with_topic(vec![bytes])),
+            None => self.inner.send_bytes(bytes),
+        }
+    }
+
+    /// Without a topic this is the inner non-blocking send. With a topic the
+    /// two frames are queued on the current runtime and failures are logged.
+    fn try_send_bytes(&self, bytes: Vec<u8>) -> Result<(), ZmqSenderError> {
+        if self.topic.is_none() {
+            return self.inner.try_send_bytes(bytes);
+        }
+
+        let frames = self.with_topic(vec![bytes]);
+        let inner = Arc::clone(&self.inner);
+        actix_web::rt::spawn(async move {
+            if inner.send_multipart(frames).await.is_err() {
+                tracing::error!("Failed to send ZMQ message with topic frame");
+            }
+        });
+        Ok(())
+    }
+
+    fn send_multipart<'a>(&'a self, frames: Vec<Vec<u8>>) -> SendFuture<'a> {
+        self.inner.send_multipart(self.with_topic(frames))
+    }
+}
+
+/// Transport behind [`JobSender`]: the live [`ZmqSender`] in production, any
+/// other [`ZmqSenderTrait`] implementation (for example a recording mock) in tests.
+pub type DynZmqSender = dyn ZmqSenderTrait + Send + Sync;
+
+/// Topic-aware sender injected into routes as `web::Data<Arc<JobSender>>`.
+///
+/// The transport is a trait object so route tests can register a mock sender
+/// instead of binding a live ZMQ socket.
+pub type JobSender = TopicZmqSender<DynZmqSender>;
+
+/// Test double standing in for the live ZMQ socket.
+///
+/// Records each message as the list of frames it was sent with; every send
+/// succeeds. Available to integration tests through the `test-utils` feature.
+#[cfg(any(test, feature = "test-utils"))]
+#[derive(Default)]
+pub struct RecordingSender {
+    messages: std::sync::Mutex<Vec<Vec<Vec<u8>>>>,
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl RecordingSender {
+    /// Every message sent so far, as its frames.
+    pub fn messages(&self) -> Vec<Vec<Vec<u8>>> {
+        self.messages.lock().unwrap().clone()
+    }
+
+    /// The payload (last frame) of every message sent so far.
+    pub fn payloads(&self) -> Vec<Vec<u8>> {
+        self.messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|frames| frames.last().cloned())
+            .collect()
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl ZmqSenderTrait for RecordingSender {
+    fn send_bytes<'a>(&'a self, bytes: Vec<u8>) -> SendFuture<'a> {
+        self.messages.lock().unwrap().push(vec![bytes]);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn try_send_bytes(&self, bytes: Vec<u8>) -> Result<(), ZmqSenderError> {
+        self.messages.lock().unwrap().push(vec![bytes]);
+        Ok(())
+    }
+
+    fn send_multipart<'a>(&'a self, frames: Vec<Vec<u8>>) -> SendFuture<'a> {
+        self.messages.lock().unwrap().push(frames);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pushkind_common::zmq::ZmqSenderExt;
+
+    use super::*;
+    use crate::domain::types::HubId;
+    use crate::domain::zmq::ZMQCrawlerMessage;
+
+    fn message() -> ZMQCrawlerMessage {
+        ZMQCrawlerMessage::ProductCategoryMatch(HubId::new(7).unwrap())
+    }
+
+    #[actix_web::test]
+    async fn prepends_topic_frame_when_configured() {
+        let inner = Arc::new(RecordingSender::default());
+        let sender = TopicZmqSender::new(Arc::clone(&inner), Some("dantes"));
+
+        sender.send_json(&message()).await.unwrap();
+
+        let messages = inner.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages[0],
+            vec![b"dantes".to_vec(), serde_json::to_vec(&message()).unwrap()]
+        );
+    }
+
+    #[actix_web::test]
+    async fn keeps_single_frame_without_topic() {
+        let inner = Arc::new(RecordingSender::default());
+        let sender = TopicZmqSender::new(Arc::clone(&inner), Some("  "));
+
+        sender.send_json(&message()).await.unwrap();
+
+        assert!(sender.topic().is_none());
+        assert_eq!(
+            inner.messages(),
+            vec![vec![serde_json::to_vec(&message()).unwrap()]]
+        );
+    }
+}
@@ -0,0 +1,587 @@
+use std::sync::Arc;
+
+use actix_web::http::{StatusCode, header};
+use actix_web::{test, web};
+use diesel::prelude::*;
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_dantes::domain::types::{BenchmarkId, HubId, ProductId, SimilarityDistance};
+use pushkind_dantes::domain::zmq::ZMQCrawlerMessage;
+use pushkind_dantes::forms::benchmarks::{AssociateForm, AssociateFormPayload};
+use pushkind_dantes::models::config::PriceFormat;
+use pushkind_dantes::repository::{DieselRepository, ProductWriter};
+use pushkind_dantes::routes::api::api_v1_create_benchmark;
+use pushkind_dantes::routes::benchmarks::match_benchmark;
+use pushkind_dantes::schema::{benchmarks, product_benchmark, products};
+use pushkind_dantes::services::ServiceError;
+use pushkind_dantes::services::api::{api_v1_association_distance, api_v1_benchmark_matches};
+use pushkind_dantes::services::benchmarks::{
+    auto_associate_by_sku, create_benchmark_product, export_benchmark,
+    export_benchmark_associations,
+};
+use pushkind_dantes::services::rate_limit::RateLimiter;
+use pushkind_dantes::services::retry::SendRetryPolicy;
+use pushkind_dantes::zmq::{DynZmqSender, JobSender, RecordingSender, TopicZmqSender};
+
+mod common;
+
+#[test]
+fn manual_association_persists_configured_default_distance() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .execute(&mut conn)
+        .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+
+    diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Product"),
+            products::sku.eq("SKU-1"),
+            products::price.eq(10.0_f64),
+        ))
+        .execute(&mut conn)
+        .expect("should create product");
+    let product_id: i32 = products::table
+        .select(products::id)
+        .first(&mut conn)
+        .expect("inserted product id should be readable");
+
+    let form = AssociateForm {
+        benchmark_id,
+        product_id,
+        distance: None,
+    };
+    let payload = AssociateFormPayload::try_from(form).expect("valid associate form");
+    let configured = SimilarityDistance::new(0.0).expect("valid distance");
+
+    let created = create_benchmark_product(payload, configured, &common::sample_user(), &repo)
+        .expect("association should be created");
+    assert!(created);
+
+    let stored: f32 = product_benchmark::table
+        .filter(product_benchmark::benchmark_id.eq(benchmark_id))
+        .filter(product_benchmark::product_id.eq(product_id))
+        .select(product_benchmark::distance)
+        .first(&mut conn)
+        .expect("association should be stored");
+    assert_eq!(stored, 0.0);
+}
+
+#[test]
+fn association_export_includes_seeded_distance() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .execute(&mut conn)
+        .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+
+    diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Product"),
+            products::sku.eq("SKU-1"),
+            products::price.eq(12.5_f64),
+        ))
+        .execute(&mut conn)
+        .expect("should create product");
+    let product_id: i32 = products::table
+        .select(products::id)
+        .first(&mut conn)
+        .expect("inserted product id should be readable");
+
+    diesel::insert_into(product_benchmark::table)
+        .values((
+            product_benchmark::benchmark_id.eq(benchmark_id),
+            product_benchmark::product_id.eq(product_id),
+            product_benchmark::distance.eq(0.25_f32),
+        ))
+        .execute(&mut conn)
+        .expect("should create association");
+
+    let file = export_benchmark_associations(
+        benchmark_id,
+        "csv",
+        &PriceFormat::default(),
+        &common::sample_user(),
+        &repo,
+    )
+    .expect("export should succeed");
+
+    let csv_output = String::from_utf8(file.bytes).expect("csv output should be utf-8");
+    let lines: Vec<&str> = csv_output.lines().collect();
+    assert_eq!(
+        lines[0],
+        "benchmark_name,product_sku,product_name,price,distance"
+    );
+    assert_eq!(lines[1], "Benchmark,SKU-1,Product,12.5,0.25");
+}
+
+#[test]
+fn single_benchmark_export_lists_fields_and_associations() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    let benchmark_id: i32 = diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .returning(benchmarks::id)
+        .get_result(&mut conn)
+        .expect("should create benchmark");
+
+    let file = export_benchmark(
+        benchmark_id,
+        "csv",
+        &PriceFormat::default(),
+        &common::sample_user(),
+        &repo,
+    )
+    .expect("export should succeed");
+    assert_eq!(
+        file.content_disposition(),
+        format!("attachment; filename=\"benchmark_{benchmark_id}.csv\"")
+    );
+    let csv_output = String::from_utf8(file.bytes).expect("csv output should be utf-8");
+    let lines: Vec<&str> = csv_output.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "sku,name,category,units,price,amount,description,product_sku,product_name,product_price,distance",
+            "BENCH-1,Benchmark,Tea,g,10,100,Benchmark tea,,,,",
+        ]
+    );
+
+    let product_id: i32 = diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Product"),
+            products::sku.eq("SKU-1"),
+            products::price.eq(12.5_f64),
+        ))
+        .returning(products::id)
+        .get_result(&mut conn)
+        .expect("should create product");
+    diesel::insert_into(product_benchmark::table)
+        .values((
+            product_benchmark::benchmark_id.eq(benchmark_id),
+            product_benchmark::product_id.eq(product_id),
+            product_benchmark::distance.eq(0.25_f32),
+        ))
+        .execute(&mut conn)
+        .expect("should create association");
+
+    let file = export_benchmark(
+        benchmark_id,
+        "csv",
+        &PriceFormat::default(),
+        &common::sample_user(),
+        &repo,
+    )
+    .expect("export should succeed");
+    let csv_output = String::from_utf8(file.bytes).expect("csv output should be utf-8");
+    let lines: Vec<&str> = csv_output.lines().collect();
+    assert_eq!(
+        lines[1],
+        "BENCH-1,Benchmark,Tea,g,10,100,Benchmark tea,SKU-1,Product,12.5,0.25"
+    );
+    assert_eq!(lines.len(), 2);
+}
+
+#[test]
+fn benchmark_matches_list_products_with_their_distances() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+            benchmarks::embedding.eq(Some(vec![1_u8, 2, 3])),
+        ))
+        .execute(&mut conn)
+        .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+
+    let mut product_ids = vec![];
+    for (sku, distance) in [("SKU-FAR", 0.75_f32), ("SKU-NEAR", 0.25)] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(1),
+                products::name.eq(format!("Product {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(12.5_f64),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+        let product_id: i32 = products::table
+            .filter(products::sku.eq(sku))
+            .select(products::id)
+            .first(&mut conn)
+            .expect("inserted product id should be readable");
+        diesel::insert_into(product_benchmark::table)
+            .values((
+                product_benchmark::benchmark_id.eq(benchmark_id),
+                product_benchmark::product_id.eq(product_id),
+                product_benchmark::distance.eq(distance),
+            ))
+            .execute(&mut conn)
+            .expect("should create association");
+        product_ids.push(product_id);
+    }
+
+    let result = api_v1_benchmark_matches(benchmark_id, &common::sample_user(), &repo)
+        .expect("matches should load");
+
+    assert_eq!(result.benchmark.id.get(), benchmark_id);
+    assert!(result.benchmark.embedding.is_none());
+    let matches = result
+        .matches
+        .iter()
+        .map(|item| (item.product_id, item.sku.as_str(), item.distance))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        matches,
+        vec![
+            (product_ids[1], "SKU-NEAR", 0.25),
+            (product_ids[0], "SKU-FAR", 0.75),
+        ]
+    );
+
+    let mut other_hub = common::sample_user();
+    other_hub.hub_id = 2;
+    assert!(matches!(
+        api_v1_benchmark_matches(benchmark_id, &other_hub, &repo),
+        Err(ServiceError::NotFound)
+    ));
+}
+
+#[actix_web::test]
+async fn match_benchmark_publishes_through_injected_job_sender() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .execute(&mut conn)
+        .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+
+    // Register the app data the way `run` does, with a recording transport.
+    let recorder = Arc::new(RecordingSender::default());
+    let transport: Arc<DynZmqSender> = recorder.clone();
+    let zmq_sender: Arc<JobSender> = Arc::new(TopicZmqSender::new(transport, Some("dantes")));
+    let app = test::init_service(
+        common::test_app()
+            .service(match_benchmark)
+            .app_data(web::Data::new(repo))
+            .app_data(web::Data::new(zmq_sender))
+            .app_data(web::Data::new(RateLimiter::per_minute(10)))
+            .app_data(web::Data::new(SendRetryPolicy::none())),
+    )
+    .await;
+
+    let login =
+        test::call_service(&app, test::TestRequest::post().uri("/login").to_request()).await;
+    let request = common::signed_in(
+        test::TestRequest::post().uri(&format!("/benchmark/{benchmark_id}/match")),
+        &login,
+    );
+    let response = test::call_service(&app, request.to_request()).await;
+
+    assert!(response.status().is_redirection());
+    assert_eq!(
+        response.headers().get(header::LOCATION).unwrap(),
+        "/benchmarks"
+    );
+    let expected = ZMQCrawlerMessage::Benchmark(BenchmarkId::new(benchmark_id).unwrap());
+    assert_eq!(
+        recorder.messages(),
+        vec![vec![
+            b"dantes".to_vec(),
+            serde_json::to_vec(&expected).unwrap()
+        ]]
+    );
+}
+
+#[test]
+fn association_distance_returns_stored_distance_or_not_found() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    let benchmark_id: i32 = diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .returning(benchmarks::id)
+        .get_result(&mut conn)
+        .expect("should create benchmark");
+
+    let mut product_ids = Vec::new();
+    for sku in ["SKU-1", "SKU-2"] {
+        let id: i32 = diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(1),
+                products::name.eq("Product"),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+            ))
+            .returning(products::id)
+            .get_result(&mut conn)
+            .expect("should create product");
+        product_ids.push(id);
+    }
+    diesel::insert_into(product_benchmark::table)
+        .values((
+            product_benchmark::benchmark_id.eq(benchmark_id),
+            product_benchmark::product_id.eq(product_ids[0]),
+            product_benchmark::distance.eq(0.25_f32),
+        ))
+        .execute(&mut conn)
+        .expect("should create association");
+
+    let user = common::sample_user();
+    let present = api_v1_association_distance(benchmark_id, product_ids[0], &user, &repo)
+        .expect("associated pair should have a distance");
+    assert_eq!(present.distance, 0.25);
+
+    let absent = api_v1_association_distance(benchmark_id, product_ids[1], &user, &repo);
+    assert!(matches!(absent, Err(ServiceError::NotFound)));
+
+    let other_hub = AuthenticatedUser {
+        hub_id: 2,
+        ..common::sample_user()
+    };
+    let foreign = api_v1_association_distance(benchmark_id, product_ids[0], &other_hub, &repo);
+    assert!(matches!(foreign, Err(ServiceError::NotFound)));
+}
+
+#[test]
+fn sku_auto_association_skips_soft_deleted_products() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+    let embedding: Vec<u8> = [1.0_f32, 0.0]
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+
+    diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("SKU-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+            benchmarks::embedding.eq(Some(embedding.clone())),
+        ))
+        .execute(&mut conn)
+        .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+
+    diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Product"),
+            products::sku.eq("SKU-1"),
+            products::price.eq(10.0_f64),
+            products::embedding.eq(Some(embedding)),
+        ))
+        .execute(&mut conn)
+        .expect("should create product");
+    let product_id: i32 = products::table
+        .select(products::id)
+        .first(&mut conn)
+        .expect("inserted product id should be readable");
+
+    let deleted = repo
+        .soft_delete_product(
+            ProductId::new(product_id).expect("valid product id"),
+            HubId::new(1).expect("valid hub id"),
+        )
+        .expect("should soft-delete product");
+    assert_eq!(deleted, 1);
+
+    let threshold = SimilarityDistance::new(0.5).expect("valid distance");
+    let associated = auto_associate_by_sku(benchmark_id, threshold, &common::sample_user(), &repo)
+        .expect("auto-association should succeed");
+    assert_eq!(associated, 0);
+
+    let associations: i64 = product_benchmark::table
+        .filter(product_benchmark::benchmark_id.eq(benchmark_id))
+        .count()
+        .get_result(&mut conn)
+        .expect("should count associations");
+    assert_eq!(associations, 0);
+}
+
+fn benchmark_payload(sku: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": "Benchmark",
+        "sku": sku,
+        "category": "Tea",
+        "units": "g",
+        "price": 10.0,
+        "amount": 100.0,
+        "description": "Benchmark tea",
+    })
+}
+
+#[actix_web::test]
+async fn create_benchmark_route_maps_service_results_to_statuses() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let app = test::init_service(
+        common::test_app()
+            .service(web::scope("/api").service(api_v1_create_benchmark))
+            .app_data(web::Data::new(repo)),
+    )
+    .await;
+    let login =
+        test::call_service(&app, test::TestRequest::post().uri("/login").to_request()).await;
+
+    let created = test::call_service(
+        &app,
+        common::signed_in(test::TestRequest::post().uri("/api/v1/benchmarks"), &login)
+            .set_json(benchmark_payload("BENCH-1"))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(created.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(created).await;
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for verification");
+    let stored_id: i32 = benchmarks::table
+        .filter(benchmarks::sku.eq("BENCH-1"))
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("created benchmark should be stored");
+    assert_eq!(body, serde_json::json!({ "id": stored_id }));
+
+    let duplicate = test::call_service(
+        &app,
+        common::signed_in(test::TestRequest::post().uri("/api/v1/benchmarks"), &login)
+            .set_json(benchmark_payload("BENCH-1"))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+
+    let mut invalid_payload = benchmark_payload("BENCH-2");
+    invalid_payload["name"] = "".into();
+    let invalid = test::call_service(
+        &app,
+        common::signed_in(test::TestRequest::post().uri("/api/v1/benchmarks"), &login)
+            .set_json(invalid_payload)
+            .to_request(),
+    )
+    .await;
+    assert_eq!(invalid.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = test::read_body_json(invalid).await;
+    assert!(body["error"].is_string());
+
+    let count: i64 = benchmarks::table
+        .count()
+        .get_result(&mut conn)
+        .expect("benchmarks should be countable");
+    assert_eq!(count, 1);
+}
@@ -0,0 +1,62 @@
+use diesel::prelude::*;
+use pushkind_common::services::errors::ServiceError;
+use pushkind_dantes::domain::types::HubId;
+use pushkind_dantes::repository::{DieselRepository, ProcessingStateReader};
+use pushkind_dantes::schema::crawlers;
+use pushkind_dantes::services::categories::{
+    CATEGORY_MATCH_PROCESSING_MESSAGE, match_product_categories,
+};
+use pushkind_dantes::services::rate_limit::RateLimiter;
+use pushkind_dantes::services::retry::SendRetryPolicy;
+use pushkind_dantes::zmq::RecordingSender;
+
+mod common;
+
+#[actix_web::test]
+async fn category_match_is_blocked_while_crawler_is_processing() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let user = common::sample_user();
+    let sender = RecordingSender::default();
+    let limiter = RateLimiter::per_minute(0);
+    let retry = SendRetryPolicy::none();
+
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+    diesel::update(crawlers::table.filter(crawlers::id.eq(1)))
+        .set(crawlers::processing.eq(true))
+        .execute(&mut conn)
+        .expect("should mark crawler as processing");
+
+    assert!(
+        repo.has_active_processing(hub_id)
+            .expect("should read processing state")
+    );
+
+    let result = match_product_categories(&limiter, &retry, &user, &repo, &sender).await;
+    match result {
+        Err(ServiceError::Form(message)) => {
+            assert_eq!(message, CATEGORY_MATCH_PROCESSING_MESSAGE);
+        }
+        other => panic!("expected form error, got {other:?}"),
+    }
+
+    diesel::update(crawlers::table.filter(crawlers::id.eq(1)))
+        .set(crawlers::processing.eq(false))
+        .execute(&mut conn)
+        .expect("should clear crawler processing flag");
+
+    assert!(
+        !repo
+            .has_active_processing(hub_id)
+            .expect("should read processing state")
+    );
+
+    let result = match_product_categories(&limiter, &retry, &user, &repo, &sender)
+        .await
+        .expect("matching should proceed once processing is cleared");
+    assert!(result);
+}
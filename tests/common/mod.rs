@@ -1,11 +1,27 @@
 //! Helpers for integration tests.
 
+use actix_identity::{Identity, IdentityMiddleware};
+use actix_session::SessionMiddleware;
+use actix_session::storage::CookieSessionStore;
+use actix_web::body::MessageBody;
+use actix_web::cookie::Key;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::{App, HttpRequest, HttpResponse, test, web};
+use actix_web_flash_messages::FlashMessagesFramework;
+use actix_web_flash_messages::storage::CookieMessageStore;
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use pushkind_common::db::{DbPool, establish_connection_pool};
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::models::config::CommonServerConfig;
+use pushkind_dantes::SERVICE_ACCESS_ROLE;
 use tempfile::NamedTempFile;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!(); // assumes migrations/ exists
 
+/// Secret the route test app signs session tokens with.
+#[allow(dead_code)]
+pub const SECRET: &str = "test-secret";
+
 /// Temporary database used in integration tests.
 pub struct TestDb {
     _tempfile: NamedTempFile,
@@ -32,3 +48,64 @@ impl TestDb {
         self.pool.clone()
     }
 }
+
+/// Service user of hub 1, the hub `TestDb` tests seed their data in.
+// Each test binary compiles this module; not all of them need a user.
+#[allow(dead_code)]
+pub fn sample_user() -> AuthenticatedUser {
+    AuthenticatedUser {
+        sub: "1".into(),
+        email: "test@example.com".into(),
+        hub_id: 1,
+        name: "Test".into(),
+        roles: vec![SERVICE_ACCESS_ROLE.into()],
+        exp: 0,
+    }
+}
+
+/// App wrapped in the middleware `run` installs, with `POST /login` signing
+/// in [`sample_user`]. Tests register the routes under test and their data.
+#[allow(dead_code)]
+pub fn test_app() -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    let key = Key::generate();
+    App::new()
+        .wrap(
+            FlashMessagesFramework::builder(CookieMessageStore::builder(key.clone()).build())
+                .build(),
+        )
+        .wrap(IdentityMiddleware::default())
+        .wrap(SessionMiddleware::new(CookieSessionStore::default(), key))
+        .route("/login", web::post().to(login))
+        .app_data(web::Data::new(CommonServerConfig {
+            auth_service_url: "http://auth.example.com".to_string(),
+            secret: SECRET.to_string(),
+        }))
+}
+
+async fn login(req: HttpRequest) -> HttpResponse {
+    let mut user = sample_user();
+    user.exp = (chrono::Utc::now().timestamp() + 3600) as _;
+    let token = user.to_jwt(SECRET).expect("token should encode");
+    Identity::login(&req.extensions(), token).expect("identity should be stored");
+    HttpResponse::Ok().finish()
+}
+
+/// Carries the session cookies set by a `/login` response over to `request`.
+#[allow(dead_code)]
+pub fn signed_in<B>(
+    mut request: test::TestRequest,
+    login: &ServiceResponse<B>,
+) -> test::TestRequest {
+    for cookie in login.response().cookies() {
+        request = request.cookie(cookie.into_owned());
+    }
+    request
+}
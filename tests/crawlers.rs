@@ -0,0 +1,64 @@
+use std::io::Write;
+
+use actix_multipart::form::tempfile::TempFile;
+use actix_multipart::form::text::Text;
+use diesel::prelude::*;
+use pushkind_dantes::forms::import_export::UploadImportForm;
+use pushkind_dantes::repository::DieselRepository;
+use pushkind_dantes::schema::crawlers;
+use pushkind_dantes::services::crawlers::import_crawlers;
+
+mod common;
+
+fn csv_upload_form(content: &[u8]) -> UploadImportForm {
+    let mut file = tempfile::NamedTempFile::new().expect("temp file should be created");
+    file.write_all(content)
+        .expect("temp file should be writable");
+    UploadImportForm {
+        file: TempFile {
+            file,
+            content_type: None,
+            file_name: Some("crawlers.csv".to_string()),
+            size: content.len(),
+        },
+        format: Text("csv".to_string()),
+        mode: Text("full".to_string()),
+    }
+}
+
+#[test]
+fn crawler_import_creates_new_crawlers_and_skips_known_urls() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+
+    let mut form = csv_upload_form(
+        b"name,url,selector\n\
+          Green shop,https://green.example.com,green\n\
+          101tea copy,https://101tea.ru,101tea\n\
+          Green again,https://green.example.com,green\n",
+    );
+    let report =
+        import_crawlers(&mut form, &common::sample_user(), &repo).expect("import should succeed");
+
+    assert_eq!(report.created, 1);
+    assert_eq!(report.skipped, 2);
+    assert_eq!(
+        report
+            .errors
+            .iter()
+            .map(|error| error.row_number)
+            .collect::<Vec<_>>(),
+        vec![3, 4]
+    );
+
+    let mut conn = test_db.pool().get().expect("should acquire DB connection");
+    let created: Vec<(i32, String, String)> = crawlers::table
+        .filter(crawlers::url.eq("https://green.example.com"))
+        .select((crawlers::hub_id, crawlers::name, crawlers::selector))
+        .load(&mut conn)
+        .expect("should load crawlers");
+    assert_eq!(
+        created,
+        vec![(1, "Green shop".to_string(), "green".to_string())]
+    );
+}
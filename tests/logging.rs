@@ -0,0 +1,111 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use chrono::TimeDelta;
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_dantes::SERVICE_ACCESS_ROLE;
+use pushkind_dantes::logging::{LogFormat, subscriber};
+use pushkind_dantes::repository::DieselRepository;
+use pushkind_dantes::services::ServiceError;
+use pushkind_dantes::services::api::{ApiV1ProductsQueryParams, api_v1_products};
+use pushkind_dantes::services::main::show_index;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+
+mod common;
+
+#[derive(Clone, Default)]
+struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+impl Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'writer> MakeWriter<'writer> for CaptureWriter {
+    type Writer = CaptureWriter;
+
+    fn make_writer(&'writer self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[test]
+fn json_log_format_emits_parseable_lines_for_instrumented_service() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let user = AuthenticatedUser {
+        sub: "1".into(),
+        email: "test@example.com".into(),
+        hub_id: 1,
+        name: "Test".into(),
+        roles: vec![],
+        exp: 0,
+    };
+
+    let writer = CaptureWriter::default();
+    let format = LogFormat::parse("json");
+    assert_eq!(format, LogFormat::Json);
+
+    let subscriber = subscriber(format, EnvFilter::new("info"), writer.clone());
+    tracing::subscriber::with_default(subscriber, || {
+        let _ = show_index(TimeDelta::hours(24), &user, &repo);
+    });
+
+    let output = String::from_utf8(writer.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("log line should be valid JSON"))
+        .collect();
+
+    assert!(!lines.is_empty());
+    assert!(
+        lines
+            .iter()
+            .any(|line| line["span"]["name"] == "show_index" && line["span"]["hub_id"] == 1)
+    );
+}
+
+#[test]
+fn cross_hub_product_request_is_logged_but_not_leaked() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let user = AuthenticatedUser {
+        sub: "42".into(),
+        email: "intruder@example.com".into(),
+        hub_id: 2,
+        name: "Intruder".into(),
+        roles: vec![SERVICE_ACCESS_ROLE.into()],
+        exp: 0,
+    };
+    let params = ApiV1ProductsQueryParams {
+        crawler_id: 1,
+        query: None,
+        page: None,
+    };
+
+    let writer = CaptureWriter::default();
+    let subscriber = subscriber(LogFormat::Json, EnvFilter::new("warn"), writer.clone());
+    let result =
+        tracing::subscriber::with_default(subscriber, || api_v1_products(params, 2, &user, &repo));
+
+    assert!(matches!(result, Err(ServiceError::NotFound)));
+
+    let output = String::from_utf8(writer.0.lock().unwrap().clone()).unwrap();
+    let warning = output
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("valid JSON"))
+        .find(|line| line["fields"]["message"] == "Cross-hub crawler access attempt")
+        .expect("cross-hub access should be logged");
+    assert_eq!(warning["level"], "WARN");
+    assert_eq!(warning["fields"]["user"], "42");
+    assert_eq!(warning["fields"]["hub_id"], 2);
+    assert_eq!(warning["fields"]["crawler_id"], 1);
+    assert_eq!(warning["fields"]["owner_hub_id"], 1);
+}
@@ -0,0 +1,409 @@
+use std::io::Write;
+
+use actix_multipart::form::tempfile::TempFile;
+use actix_multipart::form::text::Text;
+use diesel::prelude::*;
+use pushkind_dantes::domain::types::{CategoryId, CrawlerId, ProductId};
+use pushkind_dantes::forms::categories::BulkSetCategoryFormPayload;
+use pushkind_dantes::forms::import_export::UploadImportForm;
+use pushkind_dantes::forms::products::{UpdateProductForm, UpdateProductFormPayload};
+use pushkind_dantes::repository::{DieselRepository, ProductListQuery, ProductReader};
+use pushkind_dantes::schema::{
+    benchmarks, categories, crawlers, product_benchmark, product_images, products,
+};
+use pushkind_dantes::services::ServiceError;
+use pushkind_dantes::services::api::api_v1_product_by_id;
+use pushkind_dantes::services::categories::bulk_set_product_categories;
+use pushkind_dantes::services::import_export::{
+    ImportSession, begin_import_session, commit_import_session,
+};
+use pushkind_dantes::services::products::{
+    delete_product, preview_import_products, update_product,
+};
+
+mod common;
+
+fn csv_upload_form(content: &[u8], mode: &str) -> UploadImportForm {
+    let mut file = tempfile::NamedTempFile::new().expect("temp file should be created");
+    file.write_all(content)
+        .expect("temp file should be writable");
+    UploadImportForm {
+        file: TempFile {
+            file,
+            content_type: None,
+            file_name: Some("products.csv".to_string()),
+            size: content.len(),
+        },
+        format: Text("csv".to_string()),
+        mode: Text(mode.to_string()),
+    }
+}
+
+#[test]
+fn update_product_with_only_price_keeps_other_fields() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Green Tea"),
+            products::sku.eq("SKU-EDIT-1"),
+            products::category.eq(Some("Tea")),
+            products::units.eq(Some("g")),
+            products::price.eq(10.0_f64),
+            products::amount.eq(Some(100.0_f64)),
+            products::description.eq(Some("Fresh leaves")),
+            products::url.eq(Some("https://example.com/edit-1")),
+        ))
+        .execute(&mut conn)
+        .expect("should create product");
+
+    let product_id: i32 = products::table
+        .filter(products::sku.eq("SKU-EDIT-1"))
+        .select(products::id)
+        .first(&mut conn)
+        .expect("inserted product id should be readable");
+
+    let payload: UpdateProductFormPayload = UpdateProductForm {
+        price: Some(15.5),
+        ..Default::default()
+    }
+    .try_into()
+    .expect("price-only form should be valid");
+
+    let updated = update_product(product_id, payload, &common::sample_user(), &repo)
+        .expect("update should succeed");
+    assert!(updated);
+
+    let product = repo
+        .get_product_by_id(ProductId::new(product_id).expect("valid product id"))
+        .expect("should read product")
+        .expect("product should exist");
+
+    assert_eq!(product.price.get(), 15.5);
+    assert_eq!(product.name.as_str(), "Green Tea");
+    assert_eq!(product.sku.as_str(), "SKU-EDIT-1");
+    assert_eq!(product.category.as_ref().map(|c| c.as_str()), Some("Tea"));
+    assert_eq!(product.units.as_ref().map(|u| u.as_str()), Some("g"));
+    assert_eq!(product.amount.map(|a| a.get()), Some(100.0));
+    assert_eq!(
+        product.description.as_ref().map(|d| d.as_str()),
+        Some("Fresh leaves")
+    );
+    assert_eq!(
+        product.url.as_ref().map(|u| u.as_str()),
+        Some("https://example.com/edit-1")
+    );
+}
+
+#[test]
+fn import_preview_counts_rows_without_writing() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Green Tea"),
+            products::sku.eq("SKU-EXISTING"),
+            products::price.eq(10.0_f64),
+        ))
+        .execute(&mut conn)
+        .expect("should create product");
+
+    let content =
+        b"sku,name,price\nSKU-EXISTING,Green Tea,12.0\nSKU-NEW,Black Tea,5.0\n,Oolong,1.0\n";
+    let mut form = csv_upload_form(content, "partial");
+
+    let before: i64 = products::table
+        .count()
+        .get_result(&mut conn)
+        .expect("should count products");
+
+    let result = preview_import_products(1, &mut form, &common::sample_user(), &repo)
+        .expect("preview should succeed");
+
+    assert_eq!(result.would_update, 1);
+    assert_eq!(result.would_create, 1);
+    assert_eq!(result.rows_with_errors.len(), 1);
+
+    let after: i64 = products::table
+        .count()
+        .get_result(&mut conn)
+        .expect("should count products");
+    assert_eq!(before, after);
+    let price: f64 = products::table
+        .filter(products::sku.eq("SKU-EXISTING"))
+        .select(products::price)
+        .first(&mut conn)
+        .expect("existing product should be readable");
+    assert_eq!(price, 10.0);
+}
+
+#[test]
+fn import_preview_reports_rows_that_would_fail_validation() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Green Tea"),
+            products::sku.eq("SKU-EXISTING"),
+            products::price.eq(10.0_f64),
+        ))
+        .execute(&mut conn)
+        .expect("should create product");
+
+    let content =
+        b"sku,name,price\nSKU-EXISTING,Green Tea,cheap\nSKU-NEW,Black Tea,-1\nSKU-OK,Oolong,3.0\n";
+    let mut form = csv_upload_form(content, "full");
+
+    let result = preview_import_products(1, &mut form, &common::sample_user(), &repo)
+        .expect("preview should succeed");
+
+    assert_eq!(result.would_update, 0);
+    assert_eq!(result.would_create, 1);
+    let failed: Vec<Option<&str>> = result
+        .rows_with_errors
+        .iter()
+        .map(|row| row.sku.as_deref())
+        .collect();
+    assert_eq!(failed, vec![Some("SKU-EXISTING"), Some("SKU-NEW")]);
+}
+
+#[test]
+fn import_session_round_trips_and_commits_rows() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    let content =
+        b"sku,name,price,description\nSKU-A,Green Tea,5.0,\"Loose, leaf\"\nSKU-B,Black Tea,7.5,\n";
+    let mut form = csv_upload_form(content, "partial");
+    let user = common::sample_user();
+
+    let session = begin_import_session(1, &mut form, &user, &repo).expect("begin should succeed");
+    assert_eq!(session.upload_report.total_rows, 2);
+    assert_eq!(session.upload_report.created, 2);
+    let count: i64 = products::table
+        .count()
+        .get_result(&mut conn)
+        .expect("should count products");
+    assert_eq!(count, 0);
+
+    let json = serde_json::to_string(&session).expect("session should serialize");
+    let restored: ImportSession = serde_json::from_str(&json).expect("session should deserialize");
+    assert_eq!(restored, session);
+
+    let report = commit_import_session(1, restored, &user, &repo).expect("commit should succeed");
+    assert_eq!(report.created, 2);
+    assert!(report.errors.is_empty());
+
+    let description: Option<String> = products::table
+        .filter(products::sku.eq("SKU-A"))
+        .select(products::description)
+        .first(&mut conn)
+        .expect("committed product should be readable");
+    assert_eq!(description.as_deref(), Some("Loose, leaf"));
+}
+
+#[test]
+fn import_session_commit_rejects_other_crawler() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut form = csv_upload_form(b"sku,name,price\nSKU-A,Green Tea,5.0\n", "partial");
+    let user = common::sample_user();
+
+    let session = begin_import_session(1, &mut form, &user, &repo).expect("begin should succeed");
+    let result = commit_import_session(2, session, &user, &repo);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn bulk_category_set_skips_products_outside_the_hub() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(crawlers::table)
+        .values((
+            crawlers::id.eq(100),
+            crawlers::hub_id.eq(2),
+            crawlers::name.eq("Other hub"),
+            crawlers::url.eq("https://other.example.com"),
+            crawlers::selector.eq("other"),
+        ))
+        .execute(&mut conn)
+        .expect("should create crawler");
+    let category_id: i32 = diesel::insert_into(categories::table)
+        .values((categories::hub_id.eq(1), categories::name.eq("Tea")))
+        .returning(categories::id)
+        .get_result(&mut conn)
+        .expect("should create category");
+
+    let mut product_ids = Vec::new();
+    for (crawler_id, sku) in [(1, "SKU-HUB"), (100, "SKU-OTHER")] {
+        let id: i32 = diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(crawler_id),
+                products::name.eq("Green Tea"),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+            ))
+            .returning(products::id)
+            .get_result(&mut conn)
+            .expect("should create product");
+        product_ids.push(ProductId::new(id).expect("valid product id"));
+    }
+
+    let payload = BulkSetCategoryFormPayload {
+        product_ids: product_ids.clone(),
+        category_id: CategoryId::new(category_id).expect("valid category id"),
+    };
+    let outcome = bulk_set_product_categories(payload, &common::sample_user(), &repo)
+        .expect("bulk set should succeed");
+
+    assert_eq!(outcome.updated, 1);
+    assert_eq!(outcome.skipped, vec![product_ids[1].get()]);
+}
+
+#[test]
+fn product_by_id_hides_products_of_other_hubs() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(crawlers::table)
+        .values((
+            crawlers::id.eq(100),
+            crawlers::hub_id.eq(2),
+            crawlers::name.eq("Other hub"),
+            crawlers::url.eq("https://other.example.com"),
+            crawlers::selector.eq("other"),
+        ))
+        .execute(&mut conn)
+        .expect("should create crawler");
+
+    let mut product_ids = Vec::new();
+    for (crawler_id, sku) in [(1, "SKU-HUB"), (100, "SKU-OTHER")] {
+        let id: i32 = diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(crawler_id),
+                products::name.eq("Green Tea"),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+            ))
+            .returning(products::id)
+            .get_result(&mut conn)
+            .expect("should create product");
+        product_ids.push(id);
+    }
+    diesel::insert_into(product_images::table)
+        .values((
+            product_images::product_id.eq(product_ids[0]),
+            product_images::url.eq("https://example.com/tea.jpg"),
+        ))
+        .execute(&mut conn)
+        .expect("should create product image");
+
+    let product = api_v1_product_by_id(product_ids[0], &common::sample_user(), &repo)
+        .expect("hub product should be returned");
+    assert_eq!(product.images.len(), 1);
+    assert!(product.embedding.is_none());
+
+    let result = api_v1_product_by_id(product_ids[1], &common::sample_user(), &repo);
+    assert!(matches!(result, Err(ServiceError::NotFound)));
+}
+
+#[test]
+fn delete_product_hides_product_and_removes_associations() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    let product_id: i32 = diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Green Tea"),
+            products::sku.eq("SKU-1"),
+            products::price.eq(10.0_f64),
+        ))
+        .returning(products::id)
+        .get_result(&mut conn)
+        .expect("should create product");
+    let benchmark_id: i32 = diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .returning(benchmarks::id)
+        .get_result(&mut conn)
+        .expect("should create benchmark");
+    diesel::insert_into(product_benchmark::table)
+        .values((
+            product_benchmark::benchmark_id.eq(benchmark_id),
+            product_benchmark::product_id.eq(product_id),
+            product_benchmark::distance.eq(0.1_f32),
+        ))
+        .execute(&mut conn)
+        .expect("should create association");
+
+    let deleted =
+        delete_product(product_id, &common::sample_user(), &repo).expect("delete should succeed");
+    assert!(deleted);
+
+    let (total, items) = repo
+        .list_products(
+            ProductListQuery::default().crawler(CrawlerId::new(1).expect("valid crawler id")),
+        )
+        .expect("products should list");
+    assert_eq!(total, 0);
+    assert!(items.is_empty());
+
+    let associations: i64 = product_benchmark::table
+        .filter(product_benchmark::product_id.eq(product_id))
+        .count()
+        .get_result(&mut conn)
+        .expect("associations should count");
+    assert_eq!(associations, 0);
+
+    let deleted_at: Option<chrono::NaiveDateTime> = products::table
+        .filter(products::id.eq(product_id))
+        .select(products::deleted_at)
+        .first(&mut conn)
+        .expect("product row should be kept");
+    assert!(deleted_at.is_some());
+}
@@ -1,13 +1,26 @@
 use chrono::Utc;
 use diesel::prelude::*;
+use pushkind_common::pagination::Pagination;
+use pushkind_common::repository::errors::RepositoryError;
+use pushkind_dantes::domain::audit::{AuditAction, AuditResource, NewAuditEntry};
+use pushkind_dantes::domain::benchmark::NewBenchmark;
 use pushkind_dantes::domain::category::NewCategory;
+use pushkind_dantes::domain::crawler::UpdateCrawlerFields;
+use pushkind_dantes::domain::product::NewProduct;
 use pushkind_dantes::domain::types::{
-    CategoryAssignmentSource, CategoryName, HubId, ProductId, ProductUrl,
+    BenchmarkId, BenchmarkName, BenchmarkSku, CategoryAssignmentSource, CategoryId, CategoryName,
+    CrawlerId, CrawlerName, CrawlerSelectorValue, CrawlerUrl, HubId, ImageUrl, ProductAmount,
+    ProductDescription, ProductId, ProductName, ProductPrice, ProductSku, ProductUnits, ProductUrl,
+    SimilarityDistance,
 };
+use pushkind_dantes::pagination::DEFAULT_ITEMS_PER_PAGE;
 use pushkind_dantes::repository::{
-    CategoryListQuery, CategoryReader, CategoryWriter, DieselRepository, ProductWriter,
+    AuditLogReader, AuditLogWriter, BenchmarkListQuery, BenchmarkReader, BenchmarkWriter,
+    CategoryListQuery, CategoryReader, CategoryWriter, CrawlerListOrder, CrawlerListQuery,
+    CrawlerReader, CrawlerWriter, DieselRepository, ProductListQuery, ProductReader, ProductWriter,
+    SortDirection, UpsertOutcome,
 };
-use pushkind_dantes::schema::products;
+use pushkind_dantes::schema::{benchmarks, categories, crawlers, product_benchmark, products};
 
 mod common;
 
@@ -82,6 +95,94 @@ fn delete_category_resets_linked_products_to_automatic() {
     assert_eq!(row.1, CategoryAssignmentSource::Automatic.as_str());
 }
 
+#[test]
+fn create_category_reports_duplicate_name_as_conflict() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let category = |name: &str| NewCategory {
+        hub_id: HubId::new(1).expect("valid hub id"),
+        name: CategoryName::new(name).expect("valid category name"),
+        embedding: None,
+        created_at: Utc::now().naive_utc(),
+        updated_at: Utc::now().naive_utc(),
+    };
+
+    repo.create_category(&category("Tea/Green"))
+        .expect("should create category");
+    let duplicate = repo.create_category(&category("tea/green"));
+
+    assert!(matches!(duplicate, Err(RepositoryError::ConflictError)));
+}
+
+#[test]
+fn get_category_by_path_ignores_case() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    repo.create_category(&NewCategory {
+        hub_id,
+        name: CategoryName::new("Tea/Green").expect("valid category name"),
+        embedding: None,
+        created_at: Utc::now().naive_utc(),
+        updated_at: Utc::now().naive_utc(),
+    })
+    .expect("should create category");
+
+    let found = repo
+        .get_category_by_path(
+            &CategoryName::new("tea/GREEN").expect("valid category name"),
+            hub_id,
+        )
+        .expect("should look up category");
+
+    assert_eq!(
+        found.map(|category| category.name.as_str().to_string()),
+        Some("Tea/Green".to_string())
+    );
+}
+
+#[test]
+fn update_category_embedding_leaves_name_and_updated_at() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let created_at = Utc::now().naive_utc() - chrono::Duration::days(1);
+    repo.create_category(&NewCategory {
+        hub_id,
+        name: CategoryName::new("Tea/Black").expect("valid category name"),
+        embedding: None,
+        created_at,
+        updated_at: created_at,
+    })
+    .expect("should create category");
+    let (_, categories) = repo
+        .list_categories(CategoryListQuery::new(hub_id))
+        .expect("should list categories");
+    let before = categories
+        .into_iter()
+        .find(|c| c.name.as_str() == "Tea/Black")
+        .expect("inserted category should exist");
+
+    for embedding in [[1_u8, 2, 3, 4], [5, 6, 7, 8]] {
+        let affected = repo
+            .update_category_embedding(before.id, hub_id, &embedding)
+            .expect("should update embedding");
+        assert_eq!(affected, 1);
+    }
+    let other_hub = repo
+        .update_category_embedding(before.id, HubId::new(2).expect("valid hub id"), &[9])
+        .expect("should run update");
+    assert_eq!(other_hub, 0);
+
+    let after = repo
+        .get_category_by_id(before.id, hub_id)
+        .expect("should load category")
+        .expect("category should exist");
+    assert_eq!(after.embedding, Some(vec![5, 6, 7, 8]));
+    assert_eq!(after.name, before.name);
+    assert_eq!(after.updated_at, before.updated_at);
+}
+
 #[test]
 fn migration_allows_null_product_urls() {
     let test_db = common::TestDb::new();
@@ -102,6 +203,41 @@ fn migration_allows_null_product_urls() {
         .expect("product insert with null url should succeed");
 }
 
+#[test]
+fn list_products_without_url_returns_only_null_url_products() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    for (crawler_id, sku, url) in [
+        (1, "SKU-NO-URL", None),
+        (1, "SKU-WITH-URL", Some("https://example.com/with-url")),
+        (2, "SKU-OTHER-CRAWLER", None),
+    ] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(crawler_id),
+                products::name.eq(format!("Product {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+                products::url.eq(url),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+    }
+
+    let items = repo
+        .list_products_without_url(CrawlerId::new(1).expect("valid crawler id"))
+        .expect("should list products without url");
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].sku.as_str(), "SKU-NO-URL");
+    assert!(items[0].url.is_none());
+}
+
 #[test]
 fn non_null_product_urls_remain_unique_per_crawler() {
     let test_db = common::TestDb::new();
@@ -134,3 +270,1627 @@ fn non_null_product_urls_remain_unique_per_crawler() {
 
     assert!(duplicate_insert.is_err());
 }
+
+#[test]
+fn mark_reviewed_excludes_product_from_unreviewed_listing() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    for (sku, url) in [
+        ("SKU-R-1", "https://example.com/reviewed-1"),
+        ("SKU-R-2", "https://example.com/reviewed-2"),
+    ] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(1),
+                products::name.eq(format!("Product {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+                products::url.eq(Some(url)),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+    }
+
+    let product_id: i32 = products::table
+        .filter(products::sku.eq("SKU-R-1"))
+        .select(products::id)
+        .first(&mut conn)
+        .expect("inserted product id should be readable");
+    let product_id = ProductId::new(product_id).expect("valid product id");
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let crawler_id = CrawlerId::new(1).expect("valid crawler id");
+
+    let affected = repo
+        .mark_reviewed(product_id, hub_id)
+        .expect("should mark product as reviewed");
+    assert_eq!(affected, 1);
+
+    let (total, items) = repo
+        .list_products(ProductListQuery::default().crawler(crawler_id))
+        .expect("should list products");
+    assert_eq!(total, 2);
+    assert!(
+        items
+            .iter()
+            .any(|p| p.id == product_id && p.reviewed_at.is_some())
+    );
+
+    let (total, items) = repo
+        .list_products(
+            ProductListQuery::default()
+                .crawler(crawler_id)
+                .only_unreviewed(),
+        )
+        .expect("should list unreviewed products");
+    assert_eq!(total, 1);
+    assert_eq!(items[0].sku.as_str(), "SKU-R-2");
+
+    let other_hub = HubId::new(2).expect("valid hub id");
+    let affected = repo
+        .mark_reviewed(product_id, other_hub)
+        .expect("cross-hub mark should not fail");
+    assert_eq!(affected, 0);
+}
+
+#[test]
+fn soft_deleted_product_is_listed_as_deleted_until_restored() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    let product_id: i32 = diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Deleted Tea"),
+            products::sku.eq("SKU-D-1"),
+            products::price.eq(10.0_f64),
+        ))
+        .returning(products::id)
+        .get_result(&mut conn)
+        .expect("should create product");
+    let product_id = ProductId::new(product_id).expect("valid product id");
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let crawler_id = CrawlerId::new(1).expect("valid crawler id");
+
+    let affected = repo
+        .soft_delete_product(product_id, hub_id)
+        .expect("should soft-delete product");
+    assert_eq!(affected, 1);
+
+    let deleted = repo
+        .list_deleted_products(crawler_id)
+        .expect("should list deleted products");
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].id, product_id);
+
+    let (total, _) = repo
+        .list_products(ProductListQuery::default().crawler(crawler_id))
+        .expect("should list products");
+    assert_eq!(total, 0);
+
+    let other_hub = HubId::new(2).expect("valid hub id");
+    let affected = repo
+        .restore_product(product_id, other_hub)
+        .expect("cross-hub restore should not fail");
+    assert_eq!(affected, 0);
+
+    let affected = repo
+        .restore_product(product_id, hub_id)
+        .expect("should restore product");
+    assert_eq!(affected, 1);
+
+    let deleted = repo
+        .list_deleted_products(crawler_id)
+        .expect("should list deleted products");
+    assert!(deleted.is_empty());
+
+    let (total, items) = repo
+        .list_products(ProductListQuery::default().crawler(crawler_id))
+        .expect("should list products");
+    assert_eq!(total, 1);
+    assert_eq!(items[0].id, product_id);
+}
+
+/// Create one benchmark and `count` products on crawler 1 for distance tests.
+fn seed_distance_fixture(
+    conn: &mut SqliteConnection,
+    sku: &str,
+    count: usize,
+) -> (BenchmarkId, Vec<ProductId>) {
+    diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq(sku),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .execute(conn)
+        .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .filter(benchmarks::sku.eq(sku))
+        .select(benchmarks::id)
+        .first(conn)
+        .expect("inserted benchmark id should be readable");
+
+    let rows = (0..count)
+        .map(|index| {
+            (
+                products::crawler_id.eq(1),
+                products::name.eq(format!("Product {index}")),
+                products::sku.eq(format!("{sku}-P-{index}")),
+                products::price.eq(10.0_f64),
+            )
+        })
+        .collect::<Vec<_>>();
+    diesel::insert_into(products::table)
+        .values(rows)
+        .execute(conn)
+        .expect("should create products");
+    let product_ids: Vec<i32> = products::table
+        .filter(products::sku.like(format!("{sku}-P-%")))
+        .order(products::id.asc())
+        .select(products::id)
+        .load(conn)
+        .expect("should load product ids");
+
+    (
+        BenchmarkId::new(benchmark_id).expect("valid benchmark id"),
+        product_ids
+            .into_iter()
+            .map(|id| ProductId::new(id).expect("valid product id"))
+            .collect(),
+    )
+}
+
+fn distance_rows(
+    benchmark_id: BenchmarkId,
+    product_ids: &[ProductId],
+    distance: f32,
+) -> Vec<(BenchmarkId, ProductId, SimilarityDistance)> {
+    product_ids
+        .iter()
+        .map(|product_id| {
+            (
+                benchmark_id,
+                *product_id,
+                SimilarityDistance::new(distance).expect("valid distance"),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn bulk_update_benchmark_distances_upserts_across_batches() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+    let (benchmark_id, product_ids) = seed_distance_fixture(&mut conn, "BENCH-BULK", 1000);
+
+    let inserted = repo
+        .bulk_update_benchmark_distances(&distance_rows(benchmark_id, &product_ids, 0.5))
+        .expect("should insert distances");
+    assert_eq!(inserted, 1000);
+
+    repo.bulk_update_benchmark_distances(&distance_rows(benchmark_id, &product_ids[..10], 0.2))
+        .expect("should update distances");
+
+    let distances: Vec<f32> = product_benchmark::table
+        .filter(product_benchmark::benchmark_id.eq(benchmark_id.get()))
+        .order(product_benchmark::product_id.asc())
+        .select(product_benchmark::distance)
+        .load(&mut conn)
+        .expect("should load distances");
+    assert_eq!(distances.len(), 1000);
+    assert!(distances[..10].iter().all(|distance| *distance == 0.2));
+    assert!(distances[10..].iter().all(|distance| *distance == 0.5));
+}
+
+#[test]
+fn transaction_rolls_back_when_the_closure_fails() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+
+    let result: Result<(), RepositoryError> = repo.transaction(|conn| {
+        diesel::insert_into(benchmarks::table)
+            .values((
+                benchmarks::hub_id.eq(1),
+                benchmarks::name.eq("Benchmark"),
+                benchmarks::sku.eq("BENCH-ROLLBACK"),
+                benchmarks::category.eq("Tea"),
+                benchmarks::units.eq("g"),
+                benchmarks::price.eq(10.0_f64),
+                benchmarks::amount.eq(100.0_f64),
+                benchmarks::description.eq("Benchmark tea"),
+            ))
+            .execute(conn)?;
+        Err(RepositoryError::ValidationError(
+            "fail after the insert".to_string(),
+        ))
+    });
+    assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+
+    let mut conn = test_db.pool().get().expect("should acquire DB connection");
+    let remaining: i64 = benchmarks::table
+        .filter(benchmarks::sku.eq("BENCH-ROLLBACK"))
+        .count()
+        .get_result(&mut conn)
+        .expect("should count benchmarks");
+    assert_eq!(remaining, 0);
+}
+
+#[test]
+fn hub_scoped_association_checks_ownership_before_writing() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+    let (benchmark_id, product_ids) = seed_distance_fixture(&mut conn, "BENCH-HUB", 1);
+    let distance = SimilarityDistance::new(0.3).expect("valid distance");
+
+    let other_hub = HubId::new(2).expect("valid hub id");
+    let rejected =
+        repo.set_benchmark_association_in_hub(benchmark_id, product_ids[0], other_hub, distance);
+    assert!(matches!(rejected, Err(RepositoryError::NotFound)));
+
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let created = repo
+        .set_benchmark_association_in_hub(benchmark_id, product_ids[0], hub_id, distance)
+        .expect("should associate within the hub");
+    assert_eq!(created, 1);
+
+    let removed = repo
+        .remove_benchmark_association_in_hub(benchmark_id, product_ids[0], hub_id)
+        .expect("should remove within the hub");
+    assert_eq!(removed, 1);
+}
+
+#[test]
+fn bulk_update_benchmark_distances_matches_single_inserts() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+    let (single_id, single_products) = seed_distance_fixture(&mut conn, "BENCH-SINGLE", 1000);
+    let (bulk_id, bulk_products) = seed_distance_fixture(&mut conn, "BENCH-BULK", 1000);
+
+    for (benchmark_id, product_id, distance) in distance_rows(single_id, &single_products, 0.5) {
+        repo.set_benchmark_association(benchmark_id, product_id, distance)
+            .expect("should insert distance");
+    }
+    let inserted = repo
+        .bulk_update_benchmark_distances(&distance_rows(bulk_id, &bulk_products, 0.5))
+        .expect("should insert distances");
+    assert_eq!(inserted, 1000);
+
+    let load_distances = |conn: &mut SqliteConnection, benchmark_id: BenchmarkId| {
+        product_benchmark::table
+            .filter(product_benchmark::benchmark_id.eq(benchmark_id.get()))
+            .order(product_benchmark::product_id.asc())
+            .select(product_benchmark::distance)
+            .load::<f32>(conn)
+            .expect("should load distances")
+    };
+    let single = load_distances(&mut conn, single_id);
+    let bulk = load_distances(&mut conn, bulk_id);
+    assert_eq!(single.len(), 1000);
+    assert_eq!(bulk, single);
+    assert!(bulk.iter().all(|distance| *distance == 0.5));
+}
+
+#[test]
+fn list_distances_orders_by_requested_direction() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .execute(&mut conn)
+        .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .filter(benchmarks::sku.eq("BENCH-1"))
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+
+    let mut product_ids = vec![];
+    for (sku, distance) in [("SKU-D-1", 0.5_f32), ("SKU-D-2", 0.1), ("SKU-D-3", 0.9)] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(1),
+                products::name.eq(format!("Product {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+        let product_id: i32 = products::table
+            .filter(products::sku.eq(sku))
+            .select(products::id)
+            .first(&mut conn)
+            .expect("inserted product id should be readable");
+        diesel::insert_into(product_benchmark::table)
+            .values((
+                product_benchmark::product_id.eq(*product_id),
+                product_benchmark::benchmark_id.eq(benchmark_id),
+                product_benchmark::distance.eq(distance),
+            ))
+            .execute(&mut conn)
+            .expect("should create association");
+        product_ids.push(product_id);
+    }
+
+    let benchmark_id = BenchmarkId::new(benchmark_id).expect("valid benchmark id");
+
+    let ascending: Vec<f32> = repo
+        .list_distances(benchmark_id, SortDirection::Asc)
+        .expect("should list distances")
+        .into_iter()
+        .map(|(_, distance)| distance.get())
+        .collect();
+    assert_eq!(ascending, vec![0.1, 0.5, 0.9]);
+
+    let descending = repo
+        .list_distances(benchmark_id, SortDirection::Desc)
+        .expect("should list distances");
+    let descending_ids: Vec<i32> = descending.iter().map(|(id, _)| id.get()).collect();
+    assert_eq!(
+        descending_ids,
+        vec![product_ids[2], product_ids[0], product_ids[1]]
+    );
+}
+
+#[test]
+fn list_benchmark_matches_orders_by_distance_before_paginating() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .execute(&mut conn)
+        .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .filter(benchmarks::sku.eq("BENCH-1"))
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+
+    // Names sort the closest matches first, so a name-ordered page would
+    // never reach the furthest ones.
+    let total = DEFAULT_ITEMS_PER_PAGE + 5;
+    for (crawler_id, index) in (0..total).map(|i| (1, i)).chain([(2, total)]) {
+        let sku = format!("SKU-M-{index:03}");
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(crawler_id),
+                products::name.eq(format!("Product {index:03}")),
+                products::sku.eq(&sku),
+                products::price.eq(10.0_f64),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+        let product_id: i32 = products::table
+            .filter(products::sku.eq(&sku))
+            .select(products::id)
+            .first(&mut conn)
+            .expect("inserted product id should be readable");
+        diesel::insert_into(product_benchmark::table)
+            .values((
+                product_benchmark::product_id.eq(product_id),
+                product_benchmark::benchmark_id.eq(benchmark_id),
+                product_benchmark::distance.eq(index as f32 / 100.0),
+            ))
+            .execute(&mut conn)
+            .expect("should create association");
+    }
+
+    let benchmark_id = BenchmarkId::new(benchmark_id).expect("valid benchmark id");
+    let crawler_id = CrawlerId::new(1).expect("valid crawler id");
+    let first_page = Pagination {
+        page: 1,
+        per_page: DEFAULT_ITEMS_PER_PAGE,
+    };
+
+    let (count, furthest) = repo
+        .list_benchmark_matches(benchmark_id, crawler_id, SortDirection::Desc, first_page)
+        .expect("should list matches");
+    assert_eq!(count, total);
+    assert_eq!(furthest.len(), DEFAULT_ITEMS_PER_PAGE);
+    assert_eq!(
+        furthest[0].0.sku.as_str(),
+        format!("SKU-M-{:03}", total - 1)
+    );
+    assert!(
+        furthest
+            .windows(2)
+            .all(|pair| pair[0].1.get() >= pair[1].1.get())
+    );
+
+    let (_, closest) = repo
+        .list_benchmark_matches(benchmark_id, crawler_id, SortDirection::Asc, first_page)
+        .expect("should list matches");
+    assert_eq!(closest[0].0.sku.as_str(), "SKU-M-000");
+    assert_eq!(closest[0].1.get(), 0.0);
+}
+
+#[test]
+fn count_products_by_crawler_groups_counts_per_crawler() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    for (crawler_id, sku) in [(1, "SKU-C-1"), (1, "SKU-C-2"), (3, "SKU-C-3")] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(crawler_id),
+                products::name.eq(format!("Product {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+    }
+
+    let counts = repo
+        .count_products_by_crawler(HubId::new(1).expect("valid hub id"))
+        .expect("should count products");
+    assert_eq!(counts.get(&1), Some(&2));
+    assert_eq!(counts.get(&3), Some(&1));
+    assert_eq!(counts.get(&2), None);
+
+    let other_hub = repo
+        .count_products_by_crawler(HubId::new(2).expect("valid hub id"))
+        .expect("should count products");
+    assert!(other_hub.is_empty());
+}
+
+#[test]
+fn list_product_categories_groups_counts_with_uncategorized_bucket() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    let tea_id: i32 = diesel::insert_into(categories::table)
+        .values((categories::hub_id.eq(1), categories::name.eq("Tea")))
+        .returning(categories::id)
+        .get_result(&mut conn)
+        .expect("should create category");
+    let coffee_id: i32 = diesel::insert_into(categories::table)
+        .values((categories::hub_id.eq(1), categories::name.eq("Coffee")))
+        .returning(categories::id)
+        .get_result(&mut conn)
+        .expect("should create category");
+
+    for (crawler_id, sku, category_id) in [
+        (1, "SKU-G-1", Some(tea_id)),
+        (1, "SKU-G-2", Some(tea_id)),
+        (1, "SKU-G-3", Some(tea_id)),
+        (1, "SKU-G-4", Some(coffee_id)),
+        (1, "SKU-G-5", None),
+        (1, "SKU-G-6", None),
+        (3, "SKU-G-7", Some(coffee_id)),
+    ] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(crawler_id),
+                products::name.eq(format!("Product {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+                products::category_id.eq(category_id),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+    }
+
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let counts = repo
+        .list_product_categories(CrawlerId::new(1).expect("valid crawler id"), hub_id)
+        .expect("should group products by category");
+    assert_eq!(
+        counts,
+        vec![
+            (Some(CategoryName::new("Tea").expect("valid category")), 3),
+            (None, 2),
+            (
+                Some(CategoryName::new("Coffee").expect("valid category")),
+                1
+            ),
+        ]
+    );
+
+    let other_hub = repo
+        .list_product_categories(
+            CrawlerId::new(1).expect("valid crawler id"),
+            HubId::new(2).expect("valid hub id"),
+        )
+        .expect("should group products by category");
+    assert!(other_hub.is_empty());
+}
+
+#[test]
+fn list_benchmarks_missing_embeddings_returns_only_empty_embeddings() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    for (sku, embedding) in [
+        ("BENCH-NULL", None),
+        ("BENCH-EMPTY", Some(Vec::<u8>::new())),
+        ("BENCH-FULL", Some(vec![1_u8, 2, 3])),
+    ] {
+        diesel::insert_into(benchmarks::table)
+            .values((
+                benchmarks::hub_id.eq(1),
+                benchmarks::name.eq("Benchmark"),
+                benchmarks::sku.eq(sku),
+                benchmarks::category.eq("Tea"),
+                benchmarks::units.eq("g"),
+                benchmarks::price.eq(10.0_f64),
+                benchmarks::amount.eq(100.0_f64),
+                benchmarks::description.eq("Benchmark tea"),
+                benchmarks::embedding.eq(embedding),
+            ))
+            .execute(&mut conn)
+            .expect("should create benchmark");
+    }
+
+    let missing = repo
+        .list_benchmarks_missing_embeddings(HubId::new(1).expect("valid hub id"))
+        .expect("should list benchmarks");
+    let skus: Vec<&str> = missing.iter().map(|b| b.sku.as_str()).collect();
+    assert_eq!(skus, vec!["BENCH-NULL", "BENCH-EMPTY"]);
+}
+
+fn new_benchmark(sku: &str, name: &str, price: f64) -> NewBenchmark {
+    let now = Utc::now().naive_utc();
+    NewBenchmark {
+        hub_id: HubId::new(1).expect("valid hub id"),
+        name: BenchmarkName::new(name).expect("valid name"),
+        sku: BenchmarkSku::new(sku).expect("valid sku"),
+        category: CategoryName::new("Tea").expect("valid category"),
+        units: ProductUnits::new("g").expect("valid units"),
+        price: ProductPrice::new(price).expect("valid price"),
+        amount: ProductAmount::new(100.0).expect("valid amount"),
+        description: ProductDescription::new("Benchmark tea").expect("valid description"),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+#[test]
+fn benchmark_sku_is_unique_per_hub() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+
+    repo.create_benchmark(
+        &[new_benchmark("BENCH-1", "First", 10.0)],
+        HubId::new(1).expect("valid hub id"),
+    )
+    .expect("should create benchmark");
+    let duplicate = repo.create_benchmark(
+        &[new_benchmark("BENCH-1", "Second", 20.0)],
+        HubId::new(1).expect("valid hub id"),
+    );
+    assert!(matches!(duplicate, Err(RepositoryError::ConflictError)));
+}
+
+#[test]
+fn create_benchmark_rejects_records_from_another_hub() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut foreign = new_benchmark("BENCH-2", "Foreign", 20.0);
+    foreign.hub_id = HubId::new(2).expect("valid hub id");
+
+    let result = repo.create_benchmark(
+        &[new_benchmark("BENCH-1", "Local", 10.0), foreign],
+        HubId::new(1).expect("valid hub id"),
+    );
+
+    assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+    let (total, _) = repo
+        .list_benchmarks(BenchmarkListQuery::new(
+            HubId::new(1).expect("valid hub id"),
+        ))
+        .expect("should list benchmarks");
+    assert_eq!(total, 0);
+}
+
+#[test]
+fn list_benchmarks_for_product_returns_only_in_hub_benchmarks() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Green tea"),
+            products::sku.eq("SKU-1"),
+            products::price.eq(10.0_f64),
+        ))
+        .execute(&mut conn)
+        .expect("should create product");
+    let product_id: i32 = products::table
+        .select(products::id)
+        .first(&mut conn)
+        .expect("inserted product id should be readable");
+
+    let mut foreign = new_benchmark("BENCH-FOREIGN", "Foreign", 20.0);
+    foreign.hub_id = HubId::new(2).expect("valid hub id");
+    repo.create_benchmark(
+        &[
+            new_benchmark("BENCH-LINKED", "Linked", 10.0),
+            new_benchmark("BENCH-FREE", "Free", 10.0),
+        ],
+        HubId::new(1).expect("valid hub id"),
+    )
+    .expect("should create benchmarks");
+    repo.create_benchmark(&[foreign], HubId::new(2).expect("valid hub id"))
+        .expect("should create foreign benchmark");
+    let linked: Vec<i32> = benchmarks::table
+        .filter(benchmarks::sku.eq_any(["BENCH-LINKED", "BENCH-FOREIGN"]))
+        .select(benchmarks::id)
+        .load(&mut conn)
+        .expect("inserted benchmark ids should be readable");
+    for benchmark_id in linked {
+        diesel::insert_into(product_benchmark::table)
+            .values((
+                product_benchmark::product_id.eq(*product_id),
+                product_benchmark::benchmark_id.eq(benchmark_id),
+                product_benchmark::distance.eq(0.1_f32),
+            ))
+            .execute(&mut conn)
+            .expect("should create association");
+    }
+
+    let items = repo
+        .list_benchmarks_for_product(
+            ProductId::new(product_id).expect("valid product id"),
+            HubId::new(1).expect("valid hub id"),
+        )
+        .expect("should list product benchmarks");
+
+    let skus: Vec<&str> = items.iter().map(|b| b.sku.as_str()).collect();
+    assert_eq!(skus, vec!["BENCH-LINKED"]);
+}
+
+#[test]
+fn get_benchmark_by_sku_is_scoped_to_hub() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let sku = BenchmarkSku::new("BENCH-1").expect("valid sku");
+
+    let ids = repo
+        .create_benchmark(
+            &[new_benchmark("BENCH-1", "First", 10.0)],
+            HubId::new(1).expect("valid hub id"),
+        )
+        .expect("should create benchmark");
+
+    let found = repo
+        .get_benchmark_by_sku(&sku, HubId::new(1).expect("valid hub id"))
+        .expect("should query benchmark")
+        .expect("benchmark should exist");
+    assert_eq!(ids, vec![found.id]);
+    assert_eq!(found.name.as_str(), "First");
+    let other_hub = repo
+        .get_benchmark_by_sku(&sku, HubId::new(2).expect("valid hub id"))
+        .expect("should query benchmark");
+    assert!(other_hub.is_none());
+}
+
+#[test]
+fn upsert_benchmark_updates_existing_sku_instead_of_duplicating() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let first = repo
+        .upsert_benchmark(&new_benchmark("BENCH-1", "First", 10.0))
+        .expect("should insert benchmark");
+    assert_eq!(first, UpsertOutcome::Created);
+
+    let second = repo
+        .upsert_benchmark(&new_benchmark("BENCH-1", "Second", 20.0))
+        .expect("should update benchmark");
+    assert_eq!(second, UpsertOutcome::Updated);
+
+    let (total, items) = repo
+        .list_benchmarks(BenchmarkListQuery::new(hub_id))
+        .expect("should list benchmarks");
+    assert_eq!(total, 1);
+    assert_eq!(items[0].name.as_str(), "Second");
+    assert_eq!(items[0].price.get(), 20.0);
+}
+
+#[test]
+fn upsert_benchmarks_stores_all_rows_or_none() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.upsert_benchmark(&new_benchmark("BENCH-1", "First", 10.0))
+        .expect("should insert benchmark");
+
+    let outcomes = repo
+        .upsert_benchmarks(
+            &[
+                new_benchmark("BENCH-1", "Renamed", 15.0),
+                new_benchmark("BENCH-2", "Second", 20.0),
+            ],
+            hub_id,
+        )
+        .expect("should upsert benchmarks");
+    assert_eq!(
+        outcomes,
+        vec![UpsertOutcome::Updated, UpsertOutcome::Created]
+    );
+
+    let mut foreign = new_benchmark("BENCH-4", "Foreign", 40.0);
+    foreign.hub_id = HubId::new(2).expect("valid hub id");
+    let result =
+        repo.upsert_benchmarks(&[new_benchmark("BENCH-3", "Third", 30.0), foreign], hub_id);
+    assert!(result.is_err());
+
+    let (total, items) = repo
+        .list_benchmarks(BenchmarkListQuery::new(hub_id))
+        .expect("should list benchmarks");
+    assert_eq!(total, 2);
+    assert!(items.iter().any(|b| b.name.as_str() == "Renamed"));
+    assert!(items.iter().all(|b| b.sku.as_str() != "BENCH-3"));
+}
+
+#[test]
+fn upsert_benchmark_clears_embedding_only_when_text_changes() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+    let stored_embedding = |conn: &mut SqliteConnection| {
+        benchmarks::table
+            .filter(benchmarks::sku.eq("BENCH-1"))
+            .select(benchmarks::embedding)
+            .first::<Option<Vec<u8>>>(conn)
+            .expect("should load embedding")
+    };
+    let store_embedding = |conn: &mut SqliteConnection| {
+        diesel::update(benchmarks::table.filter(benchmarks::sku.eq("BENCH-1")))
+            .set(benchmarks::embedding.eq(Some(vec![1_u8, 2, 3])))
+            .execute(conn)
+            .expect("should store embedding");
+    };
+
+    repo.upsert_benchmark(&new_benchmark("BENCH-1", "First", 10.0))
+        .expect("should insert benchmark");
+    store_embedding(&mut conn);
+
+    repo.upsert_benchmark(&new_benchmark("BENCH-1", "First", 12.0))
+        .expect("should update price");
+    assert_eq!(stored_embedding(&mut conn), Some(vec![1, 2, 3]));
+
+    repo.upsert_benchmark(&new_benchmark("BENCH-1", "Renamed", 12.0))
+        .expect("should update name");
+    assert_eq!(stored_embedding(&mut conn), None);
+
+    store_embedding(&mut conn);
+    repo.upsert_benchmarks(&[new_benchmark("BENCH-1", "Renamed again", 12.0)], hub_id)
+        .expect("should upsert batch");
+    assert_eq!(stored_embedding(&mut conn), None);
+}
+
+#[test]
+fn update_benchmark_clears_embedding_only_when_text_changes() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    repo.create_benchmark(
+        &[new_benchmark("BENCH-1", "First", 10.0)],
+        HubId::new(1).expect("valid hub id"),
+    )
+    .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("benchmark should exist");
+    let stored_embedding = |conn: &mut SqliteConnection| {
+        benchmarks::table
+            .filter(benchmarks::id.eq(benchmark_id))
+            .select(benchmarks::embedding)
+            .first::<Option<Vec<u8>>>(conn)
+            .expect("should load embedding")
+    };
+    let id = BenchmarkId::new(benchmark_id).expect("valid benchmark id");
+
+    diesel::update(benchmarks::table.filter(benchmarks::id.eq(benchmark_id)))
+        .set(benchmarks::embedding.eq(Some(vec![1_u8, 2, 3])))
+        .execute(&mut conn)
+        .expect("should store embedding");
+
+    repo.update_benchmark(id, &new_benchmark("BENCH-1", "First", 12.0))
+        .expect("should update price");
+    assert_eq!(stored_embedding(&mut conn), Some(vec![1, 2, 3]));
+
+    let mut described = new_benchmark("BENCH-1", "First", 12.0);
+    described.description =
+        ProductDescription::new("Smoky lapsang souchong").expect("valid description");
+    repo.update_benchmark(id, &described)
+        .expect("should update description");
+    assert_eq!(stored_embedding(&mut conn), None);
+}
+
+#[test]
+fn archived_benchmarks_are_hidden_unless_requested() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_benchmark(
+        &[
+            new_benchmark("BENCH-ACTIVE", "Active", 10.0),
+            new_benchmark("BENCH-ARCHIVED", "Archived", 20.0),
+        ],
+        hub_id,
+    )
+    .expect("should insert benchmarks");
+    let (_, items) = repo
+        .list_benchmarks(BenchmarkListQuery::new(hub_id))
+        .expect("should list benchmarks");
+    let archived = items
+        .iter()
+        .find(|b| b.sku.as_str() == "BENCH-ARCHIVED")
+        .expect("benchmark should exist");
+
+    let other_hub = HubId::new(2).expect("valid hub id");
+    assert_eq!(
+        repo.archive_benchmark(archived.id, other_hub)
+            .expect("should run archive"),
+        0
+    );
+    assert_eq!(
+        repo.archive_benchmark(archived.id, hub_id)
+            .expect("should archive benchmark"),
+        1
+    );
+
+    let (total, items) = repo
+        .list_benchmarks(BenchmarkListQuery::new(hub_id))
+        .expect("should list benchmarks");
+    assert_eq!(total, 1);
+    assert_eq!(items[0].sku.as_str(), "BENCH-ACTIVE");
+
+    let (total, items) = repo
+        .list_benchmarks(BenchmarkListQuery::new(hub_id).include_archived())
+        .expect("should list benchmarks");
+    assert_eq!(total, 2);
+    let archived = items
+        .iter()
+        .find(|b| b.sku.as_str() == "BENCH-ARCHIVED")
+        .expect("archived benchmark should be listed");
+    assert!(archived.archived_at.is_some());
+}
+
+#[test]
+fn list_benchmarks_only_unmatched_skips_benchmarks_with_associations() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Green tea"),
+            products::sku.eq("SKU-1"),
+            products::price.eq(10.0_f64),
+        ))
+        .execute(&mut conn)
+        .expect("should create product");
+    let product_id: i32 = products::table
+        .select(products::id)
+        .first(&mut conn)
+        .expect("inserted product id should be readable");
+    repo.create_benchmark(
+        &[
+            new_benchmark("BENCH-MATCHED", "Matched", 10.0),
+            new_benchmark("BENCH-UNMATCHED", "Unmatched", 10.0),
+        ],
+        hub_id,
+    )
+    .expect("should create benchmarks");
+    let matched_id: i32 = benchmarks::table
+        .filter(benchmarks::sku.eq("BENCH-MATCHED"))
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+    diesel::insert_into(product_benchmark::table)
+        .values((
+            product_benchmark::product_id.eq(product_id),
+            product_benchmark::benchmark_id.eq(matched_id),
+            product_benchmark::distance.eq(0.1_f32),
+        ))
+        .execute(&mut conn)
+        .expect("should create association");
+
+    let (total, items) = repo
+        .list_benchmarks(BenchmarkListQuery::new(hub_id).only_unmatched())
+        .expect("should list benchmarks");
+
+    assert_eq!(total, 1);
+    assert_eq!(items[0].sku.as_str(), "BENCH-UNMATCHED");
+    let (total, _) = repo
+        .list_benchmarks(BenchmarkListQuery::new(hub_id))
+        .expect("should list benchmarks");
+    assert_eq!(total, 2);
+}
+
+#[test]
+fn list_benchmarks_filters_by_exact_or_nested_category() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let mut benchmarks = Vec::new();
+    for (sku, category) in [
+        ("BENCH-TEA", "Tea"),
+        ("BENCH-GREEN", "Tea/Green"),
+        ("BENCH-TEAPOT", "Teapots"),
+        ("BENCH-COFFEE", "Coffee"),
+    ] {
+        let mut benchmark = new_benchmark(sku, sku, 10.0);
+        benchmark.category = CategoryName::new(category).expect("valid category");
+        benchmarks.push(benchmark);
+    }
+    repo.create_benchmark(&benchmarks, hub_id)
+        .expect("should insert benchmarks");
+
+    let tea = CategoryName::new("Tea").expect("valid category");
+    let skus = |query: BenchmarkListQuery| {
+        let mut skus: Vec<String> = repo
+            .list_benchmarks(query)
+            .expect("should list benchmarks")
+            .1
+            .into_iter()
+            .map(|b| b.sku.as_str().to_string())
+            .collect();
+        skus.sort();
+        skus
+    };
+
+    assert_eq!(
+        skus(BenchmarkListQuery::new(hub_id).category(tea.clone())),
+        vec!["BENCH-TEA"]
+    );
+    assert_eq!(
+        skus(
+            BenchmarkListQuery::new(hub_id)
+                .category(tea)
+                .include_subcategories(true)
+        ),
+        vec!["BENCH-GREEN", "BENCH-TEA"]
+    );
+}
+
+#[test]
+fn list_crawlers_orders_by_num_products_descending() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    for (id, num_products) in [(1, 5), (2, 50)] {
+        diesel::update(crawlers::table.filter(crawlers::id.eq(id)))
+            .set(crawlers::num_products.eq(num_products))
+            .execute(&mut conn)
+            .expect("should update crawler");
+    }
+
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let (_, by_products) = repo
+        .list_crawlers(CrawlerListQuery::new(hub_id).order(CrawlerListOrder::ByNumProducts))
+        .expect("should list crawlers");
+    let ids: Vec<i32> = by_products.iter().map(|c| c.id.get()).collect();
+    assert_eq!(&ids[..2], &[2, 1]);
+
+    let (_, by_id) = repo
+        .list_crawlers(CrawlerListQuery::new(hub_id))
+        .expect("should list crawlers");
+    let ids: Vec<i32> = by_id.iter().map(|c| c.id.get()).collect();
+    assert_eq!(&ids[..2], &[1, 2]);
+}
+
+#[test]
+fn list_crawlers_applies_search_and_pagination() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let (total, all) = repo
+        .list_crawlers(CrawlerListQuery::new(hub_id))
+        .expect("should list crawlers");
+    assert_eq!(total, all.len());
+    let (total, second_page) = repo
+        .list_crawlers(CrawlerListQuery::new(hub_id).paginate(2, 1))
+        .expect("should list second page");
+    assert_eq!(total, all.len());
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0].id, all[1].id);
+
+    let (total, found) = repo
+        .list_crawlers(CrawlerListQuery::new(hub_id).search("GUTENBERG"))
+        .expect("should search crawlers");
+    let ids: Vec<i32> = found.iter().map(|c| c.id.get()).collect();
+    assert_eq!(ids, vec![3]);
+    assert_eq!(total, 1);
+
+    let (total, none) = repo
+        .list_crawlers(CrawlerListQuery::new(hub_id).search("100%"))
+        .expect("should search crawlers");
+    assert!(none.is_empty());
+    assert_eq!(total, 0);
+}
+
+#[test]
+fn update_crawler_rejects_duplicate_url_and_updates_otherwise() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let crawler_id = CrawlerId::new(1).expect("valid crawler id");
+    let fields = |url: &str| UpdateCrawlerFields {
+        name: CrawlerName::new("101 Tea").expect("valid name"),
+        url: CrawlerUrl::new(url).expect("valid url"),
+        selector: CrawlerSelectorValue::new("101tea").expect("valid selector"),
+    };
+
+    let duplicate = repo.update_crawler(crawler_id, hub_id, &fields("https://shop.rusteaco.ru"));
+    assert!(matches!(duplicate, Err(RepositoryError::ConflictError)));
+
+    let affected = repo
+        .update_crawler(crawler_id, hub_id, &fields("https://101tea.ru/catalog"))
+        .expect("should update crawler");
+    assert_eq!(affected, 1);
+
+    let crawler = repo
+        .get_crawler_by_id(crawler_id, hub_id)
+        .expect("should load crawler")
+        .expect("crawler should exist");
+    assert_eq!(crawler.name.as_str(), "101 Tea");
+    assert_eq!(crawler.url.as_str(), "https://101tea.ru/catalog");
+}
+
+#[test]
+fn only_unassociated_excludes_products_matched_to_benchmarks() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    for sku in ["SKU-MATCHED", "SKU-FREE"] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(1),
+                products::name.eq(format!("Green tea {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+    }
+    let matched_id: i32 = products::table
+        .filter(products::sku.eq("SKU-MATCHED"))
+        .select(products::id)
+        .first(&mut conn)
+        .expect("inserted product id should be readable");
+
+    repo.create_benchmark(
+        &[new_benchmark("BENCH-1", "Benchmark", 10.0)],
+        HubId::new(1).expect("valid hub id"),
+    )
+    .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+    diesel::insert_into(product_benchmark::table)
+        .values((
+            product_benchmark::product_id.eq(matched_id),
+            product_benchmark::benchmark_id.eq(benchmark_id),
+            product_benchmark::distance.eq(0.1_f32),
+        ))
+        .execute(&mut conn)
+        .expect("should create association");
+
+    let crawler_id = CrawlerId::new(1).expect("valid crawler id");
+    let (total, items) = repo
+        .list_products(
+            ProductListQuery::default()
+                .crawler(crawler_id)
+                .only_unassociated(),
+        )
+        .expect("should list products");
+    assert_eq!(total, 1);
+    assert_eq!(items[0].sku.as_str(), "SKU-FREE");
+
+    let (total, items) = repo
+        .search_products(
+            ProductListQuery::default()
+                .crawler(crawler_id)
+                .search("Green")
+                .only_unassociated(),
+        )
+        .expect("should search products");
+    assert_eq!(total, 1);
+    assert_eq!(items[0].sku.as_str(), "SKU-FREE");
+}
+
+#[test]
+fn stream_products_yields_all_crawler_products_across_batches() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    for (crawler_id, sku) in [
+        (1, "SKU-STREAM-1"),
+        (2, "SKU-STREAM-OTHER"),
+        (1, "SKU-STREAM-2"),
+        (1, "SKU-STREAM-3"),
+        (1, "SKU-STREAM-4"),
+        (1, "SKU-STREAM-5"),
+    ] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(crawler_id),
+                products::name.eq(format!("Product {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+    }
+    drop(conn);
+
+    let crawler_id = CrawlerId::new(1).expect("valid crawler id");
+    let skus = repo
+        .stream_products(crawler_id, 2)
+        .expect("should start stream")
+        .map(|product| product.map(|product| product.sku.as_str().to_string()))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("should stream products");
+
+    assert_eq!(
+        skus,
+        vec![
+            "SKU-STREAM-1",
+            "SKU-STREAM-2",
+            "SKU-STREAM-3",
+            "SKU-STREAM-4",
+            "SKU-STREAM-5",
+        ]
+    );
+}
+
+#[test]
+fn get_products_by_ids_returns_only_existing_products() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    let mut ids = Vec::new();
+    for (crawler_id, sku) in [(1, "SKU-IDS-1"), (3, "SKU-IDS-2")] {
+        let id: i32 = diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(crawler_id),
+                products::name.eq(format!("Product {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+            ))
+            .returning(products::id)
+            .get_result(&mut conn)
+            .expect("should create product");
+        ids.push(ProductId::new(id).expect("valid product id"));
+    }
+
+    let mut requested = vec![
+        ids[1],
+        ProductId::new(9999).expect("valid product id"),
+        ids[0],
+    ];
+    let found = repo
+        .get_products_by_ids(&requested)
+        .expect("should load products");
+    assert_eq!(
+        found.iter().map(|product| product.id).collect::<Vec<_>>(),
+        ids
+    );
+
+    requested.clear();
+    assert!(
+        repo.get_products_by_ids(&requested)
+            .expect("empty lookup should succeed")
+            .is_empty()
+    );
+}
+
+#[test]
+fn reassign_products_leaves_url_conflicts_on_source_crawler() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    for (crawler_id, sku, url) in [
+        (1, "SKU-MOVE-1", Some("https://example.com/shared")),
+        (1, "SKU-MOVE-2", Some("https://example.com/unique")),
+        (1, "SKU-MOVE-3", None),
+        (3, "SKU-KEEP-1", Some("https://example.com/shared")),
+    ] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(crawler_id),
+                products::name.eq(format!("Product {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+                products::url.eq(url),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+    }
+
+    let reassignment = repo
+        .reassign_products(
+            CrawlerId::new(1).expect("valid crawler id"),
+            CrawlerId::new(3).expect("valid crawler id"),
+            HubId::new(1).expect("valid hub id"),
+        )
+        .expect("reassign should not fail on url conflicts");
+
+    assert_eq!(reassignment.moved, 2);
+    assert_eq!(reassignment.skipped_url_conflicts, 1);
+    let left_on_source: Vec<String> = products::table
+        .filter(products::crawler_id.eq(1))
+        .select(products::sku)
+        .load(&mut conn)
+        .expect("should load source products");
+    assert_eq!(left_on_source, vec!["SKU-MOVE-1".to_string()]);
+}
+
+#[test]
+fn delete_crawler_removes_products_and_associations() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(crawlers::table)
+        .values((
+            crawlers::id.eq(50),
+            crawlers::hub_id.eq(1),
+            crawlers::name.eq("Doomed"),
+            crawlers::url.eq("https://doomed.example.com"),
+            crawlers::selector.eq("doomed"),
+        ))
+        .execute(&mut conn)
+        .expect("should create crawler");
+    for sku in ["SKU-D1", "SKU-D2", "SKU-D3"] {
+        diesel::insert_into(products::table)
+            .values((
+                products::crawler_id.eq(50),
+                products::name.eq(format!("Tea {sku}")),
+                products::sku.eq(sku),
+                products::price.eq(10.0_f64),
+            ))
+            .execute(&mut conn)
+            .expect("should create product");
+    }
+    let product_ids: Vec<i32> = products::table
+        .filter(products::crawler_id.eq(50))
+        .select(products::id)
+        .load(&mut conn)
+        .expect("inserted product ids should be readable");
+
+    repo.create_benchmark(
+        &[new_benchmark("BENCH-1", "Benchmark", 10.0)],
+        HubId::new(1).expect("valid hub id"),
+    )
+    .expect("should create benchmark");
+    let benchmark_id: i32 = benchmarks::table
+        .select(benchmarks::id)
+        .first(&mut conn)
+        .expect("inserted benchmark id should be readable");
+    for product_id in &product_ids[..2] {
+        diesel::insert_into(product_benchmark::table)
+            .values((
+                product_benchmark::product_id.eq(*product_id),
+                product_benchmark::benchmark_id.eq(benchmark_id),
+                product_benchmark::distance.eq(0.1_f32),
+            ))
+            .execute(&mut conn)
+            .expect("should create association");
+    }
+
+    let crawler_id = CrawlerId::new(50).expect("valid crawler id");
+    let foreign = repo
+        .delete_crawler(crawler_id, HubId::new(2).expect("valid hub id"))
+        .expect("should run delete");
+    assert_eq!(foreign, 0);
+
+    let deleted = repo
+        .delete_crawler(crawler_id, HubId::new(1).expect("valid hub id"))
+        .expect("should delete crawler");
+
+    assert_eq!(deleted, 1);
+    let crawlers_left: i64 = crawlers::table
+        .filter(crawlers::id.eq(50))
+        .count()
+        .get_result(&mut conn)
+        .expect("should count crawlers");
+    let products_left: i64 = products::table
+        .filter(products::crawler_id.eq(50))
+        .count()
+        .get_result(&mut conn)
+        .expect("should count products");
+    let associations_left: i64 = product_benchmark::table
+        .count()
+        .get_result(&mut conn)
+        .expect("should count associations");
+    assert_eq!(crawlers_left, 0);
+    assert_eq!(products_left, 0);
+    assert_eq!(associations_left, 0);
+}
+
+#[test]
+fn reassign_products_rejects_crawler_from_another_hub() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(crawlers::table)
+        .values((
+            crawlers::id.eq(100),
+            crawlers::hub_id.eq(2),
+            crawlers::name.eq("Other hub"),
+            crawlers::url.eq("https://other.example.com"),
+            crawlers::selector.eq("other"),
+        ))
+        .execute(&mut conn)
+        .expect("should create crawler");
+
+    let result = repo.reassign_products(
+        CrawlerId::new(1).expect("valid crawler id"),
+        CrawlerId::new(100).expect("valid crawler id"),
+        HubId::new(1).expect("valid hub id"),
+    );
+
+    assert!(matches!(result, Err(RepositoryError::NotFound)));
+}
+
+#[test]
+fn new_product_round_trips_through_the_insertable_model() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let crawler_id = CrawlerId::new(1).expect("valid crawler id");
+
+    let full = NewProduct {
+        crawler_id,
+        name: ProductName::new("Sencha").expect("valid name"),
+        sku: ProductSku::new("NP-FULL").expect("valid sku"),
+        category: Some(CategoryName::new("Tea/Green").expect("valid category")),
+        units: Some(ProductUnits::new("g").expect("valid units")),
+        price: ProductPrice::new(12.5).expect("valid price"),
+        amount: Some(ProductAmount::new(100.0).expect("valid amount")),
+        description: Some(ProductDescription::new("Green tea").expect("valid description")),
+        url: Some(ProductUrl::new("https://101tea.ru/np-full").expect("valid url")),
+        images: vec![],
+    };
+    let bare = NewProduct {
+        sku: ProductSku::new("NP-BARE").expect("valid sku"),
+        category: None,
+        units: None,
+        amount: None,
+        description: None,
+        url: None,
+        ..full.clone()
+    };
+
+    for new_product in [&full, &bare] {
+        assert_eq!(repo.create_product(new_product).expect("should insert"), 1);
+
+        let stored = repo
+            .list_products_by_crawler_and_sku(crawler_id, &new_product.sku)
+            .expect("should load product")
+            .pop()
+            .expect("product should exist");
+        assert_eq!(stored.crawler_id, new_product.crawler_id);
+        assert_eq!(stored.name, new_product.name);
+        assert_eq!(stored.sku, new_product.sku);
+        assert_eq!(stored.category, new_product.category);
+        assert_eq!(stored.units, new_product.units);
+        assert_eq!(stored.price, new_product.price);
+        assert_eq!(stored.amount, new_product.amount);
+        assert_eq!(stored.description, new_product.description);
+        assert_eq!(stored.url, new_product.url);
+    }
+}
+
+#[test]
+fn set_product_images_replaces_the_previous_set() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+    let (_, product_ids) = seed_distance_fixture(&mut conn, "BENCH-IMG", 1);
+    let product_id = product_ids[0];
+    let image = |url: &str| ImageUrl::new(url).expect("valid image url");
+
+    let inserted = repo
+        .set_product_images(
+            product_id,
+            &[
+                image("https://101tea.ru/old-1.png"),
+                image("https://101tea.ru/old-2.png"),
+            ],
+        )
+        .expect("should store images");
+    assert_eq!(inserted, 2);
+
+    let inserted = repo
+        .set_product_images(product_id, &[image("https://101tea.ru/new.png")])
+        .expect("should replace images");
+    assert_eq!(inserted, 1);
+
+    let stored = repo
+        .get_product_by_id(product_id)
+        .expect("should load product")
+        .expect("product should exist");
+    assert_eq!(stored.images, vec![image("https://101tea.ru/new.png")]);
+
+    assert_eq!(
+        repo.set_product_images(product_id, &[])
+            .expect("should clear images"),
+        0
+    );
+    let stored = repo
+        .get_product_by_id(product_id)
+        .expect("should load product")
+        .expect("product should exist");
+    assert!(stored.images.is_empty());
+}
+
+#[test]
+fn audit_entries_are_listed_newest_first_per_hub() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let now = Utc::now().naive_utc();
+    let entry = |hub_id: HubId, resource: AuditResource, seconds: i64| NewAuditEntry {
+        hub_id,
+        user_sub: "42".to_string(),
+        action: AuditAction::Delete,
+        resource,
+        created_at: now + chrono::TimeDelta::seconds(seconds),
+    };
+
+    repo.log_action(&entry(
+        hub_id,
+        AuditResource::Category(CategoryId::new(7).expect("valid category id")),
+        0,
+    ))
+    .expect("should log category delete");
+    repo.log_action(&entry(
+        hub_id,
+        AuditResource::Benchmark(BenchmarkSku::new("AUD-1").expect("valid sku")),
+        1,
+    ))
+    .expect("should log benchmark delete");
+    repo.log_action(&entry(
+        HubId::new(2).expect("valid hub id"),
+        AuditResource::Crawler(CrawlerId::new(1).expect("valid crawler id")),
+        2,
+    ))
+    .expect("should log other hub entry");
+
+    let entries = repo
+        .list_audit_entries(hub_id, 10)
+        .expect("should list audit entries");
+    let listed: Vec<(&str, &str, &str)> = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.action.as_str(),
+                entry.resource_type.as_str(),
+                entry.resource_id.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        listed,
+        [
+            ("delete", "benchmark", "AUD-1"),
+            ("delete", "category", "7")
+        ]
+    );
+}
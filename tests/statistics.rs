@@ -0,0 +1,163 @@
+use diesel::prelude::*;
+use pushkind_dantes::repository::DieselRepository;
+use pushkind_dantes::schema::{benchmarks, categories, products};
+use pushkind_dantes::services::statistics::{show_hub_statistics, show_hub_summary};
+
+mod common;
+
+#[test]
+fn hub_statistics_body_contains_all_fields() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    diesel::insert_into(products::table)
+        .values((
+            products::crawler_id.eq(1),
+            products::name.eq("Product"),
+            products::sku.eq("SKU-1"),
+            products::price.eq(10.0_f64),
+        ))
+        .execute(&mut conn)
+        .expect("should create product");
+    diesel::insert_into(benchmarks::table)
+        .values((
+            benchmarks::hub_id.eq(1),
+            benchmarks::name.eq("Benchmark"),
+            benchmarks::sku.eq("BENCH-1"),
+            benchmarks::category.eq("Tea"),
+            benchmarks::units.eq("g"),
+            benchmarks::price.eq(10.0_f64),
+            benchmarks::amount.eq(100.0_f64),
+            benchmarks::description.eq("Benchmark tea"),
+        ))
+        .execute(&mut conn)
+        .expect("should create benchmark");
+
+    let statistics =
+        show_hub_statistics(&common::sample_user(), &repo).expect("statistics should load");
+    let body = serde_json::to_value(&statistics).expect("statistics should serialize");
+
+    for field in [
+        "crawler_count",
+        "product_count",
+        "benchmark_count",
+        "category_count",
+        "unembedded_product_count",
+        "unmatched_benchmark_count",
+        "uncategorized_product_count",
+    ] {
+        assert!(body.get(field).is_some(), "missing field {field}");
+    }
+    assert_eq!(body["product_count"], 1);
+    assert_eq!(body["unembedded_product_count"], 1);
+    assert_eq!(body["uncategorized_product_count"], 1);
+    assert_eq!(body["benchmark_count"], 1);
+    assert_eq!(body["unmatched_benchmark_count"], 1);
+}
+
+/// Seeds hub 1 with one category, two products (one uncategorized) and two
+/// benchmarks (one archived), plus data of hub 2 that must not be counted.
+fn seed_hub_data(test_db: &common::TestDb) {
+    let mut conn = test_db
+        .pool()
+        .get()
+        .expect("should acquire DB connection for setup");
+
+    let category_id: i32 = diesel::insert_into(categories::table)
+        .values((categories::hub_id.eq(1), categories::name.eq("Tea")))
+        .returning(categories::id)
+        .get_result(&mut conn)
+        .expect("should create category");
+    diesel::insert_into(categories::table)
+        .values((categories::hub_id.eq(2), categories::name.eq("Coffee")))
+        .execute(&mut conn)
+        .expect("should create foreign category");
+
+    diesel::insert_into(products::table)
+        .values(vec![
+            (
+                products::crawler_id.eq(1),
+                products::name.eq("Categorized"),
+                products::sku.eq("SKU-1"),
+                products::price.eq(10.0_f64),
+                products::category_id.eq(Some(category_id)),
+            ),
+            (
+                products::crawler_id.eq(2),
+                products::name.eq("Uncategorized"),
+                products::sku.eq("SKU-2"),
+                products::price.eq(12.0_f64),
+                products::category_id.eq(None),
+            ),
+        ])
+        .execute(&mut conn)
+        .expect("should create products");
+
+    for (hub_id, sku, archived) in [
+        (1, "BENCH-1", false),
+        (1, "BENCH-2", true),
+        (2, "BENCH-3", false),
+    ] {
+        diesel::insert_into(benchmarks::table)
+            .values((
+                benchmarks::hub_id.eq(hub_id),
+                benchmarks::name.eq("Benchmark"),
+                benchmarks::sku.eq(sku),
+                benchmarks::category.eq("Tea"),
+                benchmarks::units.eq("g"),
+                benchmarks::price.eq(10.0_f64),
+                benchmarks::amount.eq(100.0_f64),
+                benchmarks::description.eq("Benchmark tea"),
+                benchmarks::archived_at.eq(archived.then(|| chrono::Utc::now().naive_utc())),
+            ))
+            .execute(&mut conn)
+            .expect("should create benchmark");
+    }
+}
+
+#[test]
+fn hub_summary_counts_each_entity_for_the_hub() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    seed_hub_data(&test_db);
+
+    let summary = show_hub_summary(&common::sample_user(), &repo).expect("summary should load");
+    let body = serde_json::to_value(&summary).expect("summary should serialize");
+
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "crawlers": 3,
+            "products": 2,
+            "benchmarks": 1,
+            "categories": 1,
+            "uncategorized_products": 1,
+        })
+    );
+}
+
+#[test]
+fn hub_summary_agrees_with_hub_statistics() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    seed_hub_data(&test_db);
+
+    let user = common::sample_user();
+    let statistics = show_hub_statistics(&user, &repo).expect("statistics should load");
+    let summary = show_hub_summary(&user, &repo).expect("summary should load");
+
+    assert_eq!(summary.crawlers, statistics.crawler_count);
+    assert_eq!(summary.products, statistics.product_count);
+    assert_eq!(summary.benchmarks, statistics.benchmark_count);
+    assert_eq!(summary.categories, statistics.category_count);
+    assert_eq!(
+        summary.uncategorized_products,
+        statistics.uncategorized_product_count
+    );
+    assert_eq!(statistics.benchmark_count, 1);
+    assert_eq!(statistics.unmatched_benchmark_count, 1);
+}
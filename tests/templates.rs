@@ -0,0 +1,51 @@
+use serde_json::json;
+use tera::{Context, Tera};
+
+#[test]
+fn benchmark_fragment_renders_crawler_products() {
+    let tera = Tera::new("templates/**/*").expect("templates should parse");
+
+    let mut context = Context::new();
+    context.insert(
+        "benchmark",
+        &json!({ "id": 5, "price": 10.0, "name": "Benchmark" }),
+    );
+    context.insert(
+        "crawler_products",
+        &json!([[
+            { "id": 1, "name": "Crawler One" },
+            {
+                "items": [{
+                    "id": 42,
+                    "crawler_id": 1,
+                    "name": "Green Tea",
+                    "sku": "SKU-42",
+                    "category": null,
+                    "associated_category": null,
+                    "units": "pcs",
+                    "price": 12.5,
+                    "amount": 1.0,
+                    "description": "Fresh",
+                    "url": null,
+                    "updated_at": "2026-01-01T00:00:00",
+                    "category_id": null,
+                    "category_assignment_source": "automatic",
+                    "reviewed_at": null,
+                    "images": []
+                }],
+                "page": 1,
+                "pages": 1
+            }
+        ]]),
+    );
+    context.insert("distances", &json!({ "42": 0.25 }));
+
+    let html = tera
+        .render("benchmarks/fragment.html", &context)
+        .expect("fragment should render");
+
+    assert!(html.contains("Товары Crawler One"));
+    assert!(html.contains("Green Tea"));
+    assert!(html.contains("SKU-42"));
+    assert!(!html.contains("<html"));
+}